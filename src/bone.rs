@@ -1,5 +1,7 @@
 //a Imports
 use geo_nd::matrix;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{Mat4, Transformation};
 
@@ -28,16 +30,21 @@ use crate::{Mat4, Transformation};
 ///  bone_relative = C.ptb * B.ptb * A.ptb * mesh
 ///  root = A.btp * B.btp * C.btp * C_bone_relative
 ///  animated(t) = A.btp(t) * B.btp(t) * C.btp(t) * C.ptb * B.ptb * A.ptb * mesh
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Bone {
     /// rest transform - translation, scale, rotation
     pub transformation: Transformation,
     /// The parent-to-bone mapping Matrix at rest; updated when the
     /// transformation is changed
+    ///
+    /// This is not serialized, as it is derived
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) ptb: Mat4,
     /// The mesh-to-bone mapping Matrix at rest, derived from the
     /// hierarchy root; updated when any transformation is changed in
     /// the hierarchy at this bone or above
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) mtb: Mat4,
     ///  Index into matrix array to put this bones animated mtm
     pub matrix_index: usize,
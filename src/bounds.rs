@@ -0,0 +1,318 @@
+//a Documentation
+/*!
+
+This provides bounding volumes - axis-aligned boxes and spheres - for
+the positions of [Vertices] (all of them, or just those used by a
+[Primitive]), and for an [Object], by combining the bounds of the
+primitives of its components with the transformations of the
+component hierarchy applied.
+
+The bounds of an [Object] are kept by the [Instantiable] it is
+deconstructed into, and an [Instance] can provide its bounds in world
+space, with its transformation applied.
+
+Transforming a box gives the axis-aligned box that contains the
+transformed box, which may be larger than the box of the transformed
+positions; hence the sphere may be the tighter bound after rotations.
+
+[Instantiable]: crate::Instantiable
+[Instance]: crate::Instance
+
+!*/
+
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::transformation::mat4_transform_point;
+use crate::{
+    AccessorError, Mat4, Material, Object, Primitive, RenderRecipe, Renderable, Vec3, Vertices,
+};
+
+//a Functions
+//fi distance
+fn distance(a: &Vec3, b: &Vec3) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+//a BoundingBox
+//tp BoundingBox
+/// An axis-aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox {
+    /// The minimum of each coordinate
+    pub min: Vec3,
+    /// The maximum of each coordinate
+    pub max: Vec3,
+}
+
+//ip BoundingBox
+impl BoundingBox {
+    //fp new
+    /// Create a new [BoundingBox] from its minimum and maximum
+    pub fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    //fp of_points
+    /// Create the [BoundingBox] of some points, or `None` if there are
+    /// none
+    pub fn of_points<'a, I: IntoIterator<Item = &'a Vec3>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bbox = Self::new(*first, *first);
+        for p in points {
+            bbox.min = [0, 1, 2].map(|i| bbox.min[i].min(p[i]));
+            bbox.max = [0, 1, 2].map(|i| bbox.max[i].max(p[i]));
+        }
+        Some(bbox)
+    }
+
+    //ap center
+    /// Get the center of the box
+    pub fn center(&self) -> Vec3 {
+        [0, 1, 2].map(|i| (self.min[i] + self.max[i]) / 2.)
+    }
+
+    //ap size
+    /// Get the size of the box along each axis
+    pub fn size(&self) -> Vec3 {
+        [0, 1, 2].map(|i| self.max[i] - self.min[i])
+    }
+
+    //ap contains
+    /// Return true if a point is within the box
+    pub fn contains(&self, p: &Vec3) -> bool {
+        (0..3).all(|i| self.min[i] <= p[i] && p[i] <= self.max[i])
+    }
+
+    //mp union
+    /// Get the box containing this box and another
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            [0, 1, 2].map(|i| self.min[i].min(other.min[i])),
+            [0, 1, 2].map(|i| self.max[i].max(other.max[i])),
+        )
+    }
+
+    //mp transformed
+    /// Get the axis-aligned box containing this box transformed by a
+    /// matrix
+    pub fn transformed(&self, m: &Mat4) -> Self {
+        let mut min = [m[12], m[13], m[14]];
+        let mut max = min;
+        for i in 0..3 {
+            for j in 0..3 {
+                let a = m[4 * j + i] * self.min[j];
+                let b = m[4 * j + i] * self.max[j];
+                min[i] += a.min(b);
+                max[i] += a.max(b);
+            }
+        }
+        Self::new(min, max)
+    }
+}
+
+//a BoundingSphere
+//tp BoundingSphere
+/// A bounding sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoundingSphere {
+    /// The center of the sphere
+    pub center: Vec3,
+    /// The radius of the sphere
+    pub radius: f32,
+}
+
+//ip BoundingSphere
+impl BoundingSphere {
+    //fp new
+    /// Create a new [BoundingSphere] from its center and radius
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    //fp of_points
+    /// Create the [BoundingSphere] of some points about a center
+    pub fn of_points<'a, I: IntoIterator<Item = &'a Vec3>>(center: Vec3, points: I) -> Self {
+        let radius = points
+            .into_iter()
+            .fold(0_f32, |r, p| r.max(distance(&center, p)));
+        Self::new(center, radius)
+    }
+
+    //ap contains
+    /// Return true if a point is within the sphere
+    pub fn contains(&self, p: &Vec3) -> bool {
+        distance(&self.center, p) <= self.radius
+    }
+
+    //mp union
+    /// Get the smallest sphere containing this sphere and another
+    pub fn union(&self, other: &Self) -> Self {
+        let d = distance(&self.center, &other.center);
+        if d + other.radius <= self.radius {
+            *self
+        } else if d + self.radius <= other.radius {
+            *other
+        } else {
+            let radius = (d + self.radius + other.radius) / 2.;
+            let t = (radius - self.radius) / d;
+            let center = [0, 1, 2].map(|i| self.center[i] + (other.center[i] - self.center[i]) * t);
+            Self::new(center, radius)
+        }
+    }
+
+    //mp transformed
+    /// Get a sphere containing this sphere transformed by a matrix
+    ///
+    /// The radius is scaled by the largest scaling of any axis
+    pub fn transformed(&self, m: &Mat4) -> Self {
+        let scale = (0..3)
+            .map(|j| distance(&[m[4 * j], m[4 * j + 1], m[4 * j + 2]], &[0.; 3]))
+            .fold(0_f32, f32::max);
+        Self::new(mat4_transform_point(m, &self.center), self.radius * scale)
+    }
+}
+
+//a Bounds
+//tp Bounds
+/// The bounds of a set of positions, as both an axis-aligned box and
+/// a sphere
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Bounds {
+    /// The axis-aligned bounding box
+    pub bbox: BoundingBox,
+    /// The bounding sphere
+    pub sphere: BoundingSphere,
+}
+
+//ip Bounds
+impl Bounds {
+    //fp of_points
+    /// Create the [Bounds] of some points, or `None` if there are none
+    ///
+    /// The sphere is centered on the center of the box
+    pub fn of_points(points: &[Vec3]) -> Option<Self> {
+        let bbox = BoundingBox::of_points(points)?;
+        let sphere = BoundingSphere::of_points(bbox.center(), points);
+        Some(Self { bbox, sphere })
+    }
+
+    //mp union
+    /// Get the bounds containing these bounds and another
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            bbox: self.bbox.union(&other.bbox),
+            sphere: self.sphere.union(&other.sphere),
+        }
+    }
+
+    //mp transformed
+    /// Get bounds containing these bounds transformed by a matrix
+    pub fn transformed(&self, m: &Mat4) -> Self {
+        Self {
+            bbox: self.bbox.transformed(m),
+            sphere: self.sphere.transformed(m),
+        }
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mi all_positions
+    /// Read all the positions of the [Vertices] (the `count` of the
+    /// position accessor)
+    fn all_positions(&self) -> Result<Vec<Vec3>, AccessorError> {
+        let position = self.borrow_position();
+        Ok(position.iter_elements(position.count as usize)?.collect())
+    }
+
+    //mi bounds_of_primitive
+    /// Get the [Bounds] of the positions (already read) used by a
+    /// [Primitive]
+    fn bounds_of_primitive(
+        &self,
+        positions: &[Vec3],
+        primitive: &Primitive,
+    ) -> Result<Option<Bounds>, AccessorError> {
+        let n = positions.len();
+        let positions = self
+            .primitive_indices(primitive)?
+            .into_iter()
+            .map(|i| {
+                positions
+                    .get(i as usize)
+                    .copied()
+                    .ok_or(AccessorError::BadIndex(i, n))
+            })
+            .collect::<Result<Vec<Vec3>, _>>()?;
+        Ok(Bounds::of_points(&positions))
+    }
+
+    //mp bounds
+    /// Get the [Bounds] of all the positions of the [Vertices], or
+    /// `None` if it has no vertices
+    ///
+    /// An error is returned if the positions cannot be read
+    pub fn bounds(&self) -> Result<Option<Bounds>, AccessorError> {
+        Ok(Bounds::of_points(&self.all_positions()?))
+    }
+
+    //mp primitive_bounds
+    /// Get the [Bounds] of the positions of the vertices drawn by a
+    /// [Primitive] that uses this [Vertices], or `None` if it draws no
+    /// vertices
+    ///
+    /// An error is returned if the indices or positions cannot be
+    /// read, or if an index is beyond the vertices of the position
+    /// accessor
+    pub fn primitive_bounds(&self, primitive: &Primitive) -> Result<Option<Bounds>, AccessorError> {
+        self.bounds_of_primitive(&self.all_positions()?, primitive)
+    }
+}
+
+//a Object
+//ip Object
+impl<'object, M, R> Object<'object, M, R>
+where
+    M: Material + 'object,
+    R: Renderable,
+{
+    //mp bounds
+    /// Get the [Bounds] of the object - of the primitives of all of its
+    /// components, with the transformations of the component hierarchy
+    /// applied - or `None` if it draws no vertices
+    ///
+    /// The object must have been analyzed. Primitives without vertices
+    /// are ignored; an error is returned if the bounds of a primitive
+    /// cannot be found.
+    ///
+    /// The positions of each [Vertices] are read only once, however
+    /// many primitives use it.
+    pub fn bounds(&self) -> Result<Option<Bounds>, AccessorError> {
+        let recipe = RenderRecipe::from_component_hierarchy(&self.components);
+        let mut positions: Vec<Option<Vec<Vec3>>> = vec![None; self.vertices.len()];
+        let mut bounds: Option<Bounds> = None;
+        for (p, m) in recipe.primitives.iter().zip(&recipe.matrix_for_primitives) {
+            let Some(v) = p.vertices().0.filter(|v| *v < self.vertices.len()) else {
+                continue;
+            };
+            let vertices = &self.vertices[v];
+            if positions[v].is_none() {
+                positions[v] = Some(vertices.all_positions()?);
+            }
+            let Some(b) = vertices.bounds_of_primitive(positions[v].as_ref().unwrap(), p)? else {
+                continue;
+            };
+            let b = b.transformed(&recipe.matrices[*m]);
+            bounds = Some(bounds.map_or(b, |bounds| bounds.union(&b)));
+        }
+        Ok(bounds)
+    }
+}
@@ -1,7 +1,8 @@
 //a Imports
-use std::cell::RefCell;
+use std::sync::RwLockReadGuard;
 
-use crate::{BufferData, BufferElementType, Renderable, VertexAttr};
+use crate::shared::ClientCell;
+use crate::{AccessorError, BufferData, BufferElementType, Renderable, Shared, VertexAttr};
 
 //a BufferAccessor
 //tp BufferAccessor
@@ -12,26 +13,42 @@ use crate::{BufferData, BufferElementType, Renderable, VertexAttr};
 /// Position or Normal.
 pub struct BufferAccessor<'a, R: Renderable + ?Sized> {
     /// The `BufferData` that contains the actual vertex attribute data
-    pub data: &'a BufferData<'a, R>,
+    ///
+    /// This is borrowed, or shared through an `Arc`
+    pub data: Shared<'a, BufferData<'a, R>>,
     /// For attributes: number of elements per vertex (1 to 4, or 4, 9 or 16)
     /// For indices: number of indices in the buffer
     pub elements_per_data: u32,
     /// The type of each element
     ///
-    /// For indices this must be Int8, Int16 or Int32
+    /// For indices this must be UInt8, UInt16 or UInt32
     pub ele_type: BufferElementType,
     /// Offset from start of buffer to first byte of data
     pub byte_offset: u32,
     /// Stride of data in the buffer - 0 for count*sizeof(ele_type)
     /// Unused for indices
     pub stride: u32,
+    /// True if integer elements are normalized when accessed - to 0.0
+    /// to 1.0 for unsigned types, and -1.0 to 1.0 for signed types
+    pub normalized: bool,
+    /// Number of elements (vertices) in the accessor
+    ///
+    /// For indices this is 1, the indices being a single element of
+    /// `elements_per_data` values
+    pub count: u32,
+    /// Minimum value of each of the `elements_per_data` components,
+    /// if known
+    pub min: Option<Vec<f64>>,
+    /// Maximum value of each of the `elements_per_data` components,
+    /// if known
+    pub max: Option<Vec<f64>>,
     /// The client bound to data\[byte_offset\] .. + byte_length
     ///
-    /// This must be held as a [RefCell] as the [BufferData] is
+    /// This must be held as a [ClientCell] as the [BufferData] is
     /// created early in the process, prior to any `BufferAccessor`s using
     /// it - which then have shared references to the daata - but the
     /// client is created afterwards
-    rc_client: RefCell<R::Accessor>,
+    rc_client: ClientCell<R::Accessor>,
 }
 
 //ip Display for Object
@@ -42,10 +59,12 @@ where
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
             fmt,
-            "BufferAccessor{{ {:?}:{:?} #{}@{}+*{}}}",
+            "BufferAccessor{{ {:?}:{:?}{} #{}x{}@{}+*{}}}",
             self.data,
             self.ele_type,
+            if self.normalized { " (norm)" } else { "" },
             self.elements_per_data,
+            self.count,
             self.byte_offset,
             self.stride,
             //  self.rc_client
@@ -57,25 +76,97 @@ where
 impl<'a, R: Renderable> BufferAccessor<'a, R> {
     //fp new
     /// Create a new view of a `BufferData`
-    pub fn new(
-        data: &'a BufferData<'a, R>,
+    ///
+    /// The `BufferData` may be borrowed or be an `Arc<BufferData>`.
+    ///
+    /// The accessor is not normalized, has no minimum or maximum, and
+    /// has as many elements as fit within the `BufferData`; use
+    /// `with_count` to set the actual number of elements
+    pub fn new<D: Into<Shared<'a, BufferData<'a, R>>>>(
+        data: D,
         count: u32, // count is number of ele_type in an attribute
         ele_type: BufferElementType,
         byte_offset: u32, // offset in bytes from start of data
         stride: u32,      /* stride between elements
                            * (0->count*sizeof(ele_type)) */
     ) -> Self {
-        let rc_client = RefCell::new(R::Accessor::default());
+        let data = data.into();
+        let rc_client = ClientCell::default();
+        let element_size = count as usize * ele_type.byte_length();
+        let step = if stride == 0 {
+            element_size
+        } else {
+            stride as usize
+        };
+        let available = (data.byte_length as usize).saturating_sub(byte_offset as usize);
+        let elements = {
+            if available < element_size || element_size == 0 {
+                0
+            } else {
+                ((available - element_size) / step + 1) as u32
+            }
+        };
         Self {
             data,
             elements_per_data: count,
             ele_type,
             byte_offset,
             stride,
+            normalized: false,
+            count: elements,
+            min: None,
+            max: None,
             rc_client,
         }
     }
 
+    //cp with_normalized
+    /// Set whether the integer elements of the accessor are
+    /// normalized
+    pub fn with_normalized(mut self, normalized: bool) -> Self {
+        self.normalized = normalized;
+        self
+    }
+
+    //cp with_count
+    /// Set the number of elements in the accessor
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = count;
+        self
+    }
+
+    //cp with_bounds
+    /// Set the minimum and maximum of each component of the accessor
+    pub fn with_bounds(mut self, min: Vec<f64>, max: Vec<f64>) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    //mp validate
+    /// Validate the accessor - that it is normalized only if it has
+    /// integer elements, that its minimum and maximum (if any) have
+    /// `elements_per_data` components, and that all `count` of its
+    /// elements lie within the [BufferData]
+    pub fn validate(&self) -> Result<(), AccessorError> {
+        if self.normalized && !self.ele_type.is_integer() {
+            return Err(AccessorError::NotNormalizable(self.ele_type));
+        }
+        for bounds in [&self.min, &self.max].into_iter().flatten() {
+            if bounds.len() != self.elements_per_data as usize {
+                return Err(AccessorError::BadBounds(
+                    bounds.len(),
+                    self.elements_per_data,
+                ));
+            }
+        }
+        self.check_range(
+            self.elements_per_data as usize,
+            self.element_stride(),
+            self.count as usize,
+        )
+    }
+
     //mp create_client
     /// Create the render buffer required by the BufferAccessor
     pub fn create_client(&self, attr: VertexAttr, renderable: &mut R) {
@@ -83,9 +174,22 @@ impl<'a, R: Renderable> BufferAccessor<'a, R> {
         renderable.init_buffer_view_client(self.rc_client.borrow_mut().deref_mut(), self, attr);
     }
 
+    //mp create_morph_target_client
+    /// Create the render buffer required by the BufferAccessor, as
+    /// the deltas of an attribute for a morph target
+    pub fn create_morph_target_client(&self, target: usize, attr: VertexAttr, renderable: &mut R) {
+        use std::ops::DerefMut;
+        renderable.init_morph_target_client(
+            self.rc_client.borrow_mut().deref_mut(),
+            self,
+            target,
+            attr,
+        );
+    }
+
     //ap borrow_client
     /// Borrow the client
-    pub fn borrow_client(&self) -> std::cell::Ref<R::Accessor> {
+    pub fn borrow_client(&self) -> RwLockReadGuard<'_, R::Accessor> {
         self.rc_client.borrow()
     }
 
@@ -97,8 +201,13 @@ impl<'a, R: Renderable> std::fmt::Display for BufferAccessor<'a, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "BufferAccessor[{:?}#{}]\n  {}+{}+n*{}\n",
-            self.ele_type, self.elements_per_data, self.data, self.byte_offset, self.stride
+            "BufferAccessor[{:?}#{}x{}]\n  {}+{}+n*{}\n",
+            self.ele_type,
+            self.elements_per_data,
+            self.count,
+            self.data,
+            self.byte_offset,
+            self.stride
         )
     }
 }
@@ -1,7 +1,8 @@
 //a Imports
-use std::cell::RefCell;
+use std::sync::RwLockReadGuard;
 
-use crate::{ByteBuffer, Renderable};
+use crate::shared::ClientCell;
+use crate::{ByteBuffer, ByteData, Renderable};
 
 //a BufferData
 //tp BufferData
@@ -24,20 +25,24 @@ use crate::{ByteBuffer, Renderable};
 /// primitives may have their own individual `BufferViews`.
 ///
 /// A client may have one copy of the data for all the primitives and models.
+///
+/// The data buffer may be borrowed, or it may be owned (shared with
+/// other [BufferData]s) as a [ByteData], in which case the
+/// [BufferData] can be `'static`.
 pub struct BufferData<'a, R: Renderable> {
     /// Data buffer itself
-    data: &'a [u8],
+    data: ByteData<'a>,
     /// Offset in to the data buffer for the first byte
     pub byte_offset: u32,
     /// Length of data used in the buffer
     pub byte_length: u32,
     /// The client bound to data\[byte_offset\] .. + byte_length
     ///
-    /// This must be held as a [RefCell] as the [BufferData] is
+    /// This must be held as a [ClientCell] as the [BufferData] is
     /// created early in the process, prior to any `BufferView`s using
     /// it - which then have shared references to the data - but the
     /// client is created afterwards
-    rc_client: RefCell<R::Buffer>,
+    rc_client: ClientCell<R::Buffer>,
 }
 
 //ip Debug for BufferData
@@ -45,7 +50,7 @@ impl<'a, R: Renderable> std::fmt::Debug for BufferData<'a, R> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
         let (data, cont) = {
             if self.data.len() < 8 {
-                (self.data, "")
+                (&self.data[..], "")
             } else {
                 (&self.data[0..8], "...")
             }
@@ -71,15 +76,26 @@ impl<'a, R: Renderable> BufferData<'a, R> {
     ///
     /// If offset and length are both zero, then all the data is used
     pub fn new<B: ByteBuffer + ?Sized>(data: &'a B, byte_offset: u32, byte_length: u32) -> Self {
+        Self::of_bytes(data.borrow_bytes(), byte_offset, byte_length)
+    }
+
+    //fp of_bytes
+    /// Create a new [BufferData] given borrowed or owned bytes, an
+    /// offset and length, as for `new`
+    ///
+    /// With owned bytes (such as an `Arc<[u8]>` or a `Vec<u8>`) the
+    /// [BufferData] does not borrow anything, and clones of the
+    /// [ByteData] can be used by many [BufferData]s
+    pub fn of_bytes<D: Into<ByteData<'a>>>(data: D, byte_offset: u32, byte_length: u32) -> Self {
+        let data = data.into();
         let byte_length = {
             if byte_length == 0 {
-                (data.byte_length() as u32) - byte_offset
+                (data.len() as u32) - byte_offset
             } else {
                 byte_length
             }
         };
-        let rc_client = RefCell::new(R::Buffer::default());
-        let data = data.borrow_bytes();
+        let rc_client = ClientCell::default();
         assert!(
             byte_offset + byte_length <= data.len() as u32,
             "Buffer is not large enough for data {} + #{} [ got {}]",
@@ -104,10 +120,17 @@ impl<'a, R: Renderable> BufferData<'a, R> {
 
     //ap borrow_client
     /// Borrow the client
-    pub fn borrow_client(&self) -> std::cell::Ref<R::Buffer> {
+    pub fn borrow_client(&self) -> RwLockReadGuard<'_, R::Buffer> {
         self.rc_client.borrow()
     }
 
+    //ap data
+    /// Get the whole of the underlying data buffer, of which this
+    /// [BufferData] is a portion
+    pub fn data(&self) -> &ByteData<'a> {
+        &self.data
+    }
+
     //mp as_ptr
     /// Get a const u8 ptr to the data itself
     pub fn as_ptr(&self) -> *const u8 {
@@ -58,7 +58,7 @@ impl<T> ByteBuffer for Vec<T> {
 
     //fp as_u8_ptr
     fn as_u8_ptr(&self) -> *const u8 {
-        let data: *const T = &self[0];
+        let data: *const T = self.as_ptr();
         unsafe { std::mem::transmute::<_, *const u8>(data) }
     }
 
@@ -0,0 +1,47 @@
+//a Documentation
+/*!
+
+Checksums used by the file formats
+
+!*/
+
+//a CRC-32
+//ci CRC32_TABLE
+/// Table of the CRC-32 (IEEE, reflected polynomial 0xedb88320) of
+/// each byte value
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0_u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut i = 0;
+        while i < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb88320
+            } else {
+                crc >> 1
+            };
+            i += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+};
+
+//fp crc32
+/// Calculate the CRC-32 (as used by PNG and zlib) of some bytes
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    crc32_extend(0, bytes)
+}
+
+//fp crc32_extend
+/// Extend the CRC-32 of some bytes with more bytes, to get the CRC-32
+/// of their concatenation
+pub(crate) fn crc32_extend(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for b in bytes {
+        crc = CRC32_TABLE[((crc ^ *b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -1,5 +1,7 @@
 //a Imports
 use crate::{Mesh, Transformation};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a Component
 //tp Component
@@ -7,7 +9,8 @@ use crate::{Mesh, Transformation};
 ///
 /// Frequently an object will contain a single [Component] with no
 /// transformation, just the mesh
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Component {
     /// The transformation to apply to the whole mesh
     pub transformation: Option<Transformation>,
@@ -0,0 +1,468 @@
+//a Documentation
+/*!
+
+This provides safe reading of the elements of a [BufferAccessor]
+
+Each element of an accessor is `elements_per_data` components of
+its `ele_type`, starting at `byte_offset` within the [BufferData]
+and separated by `stride` bytes (or tightly packed if the stride is
+zero). The elements can be read as arrays of [f32], [u16], [u32] or
+[i32] (anything supporting the [Element] trait), with a check that
+each element lies within the range of the [BufferData].
+
+Elements can also be written, to create an accessor (with its own
+[BufferData]) of any element type from (for example) [f32] data;
+this permits [BufferElementType::Float16] and normalized integer
+accessors to be built from [f32] sources.
+
+!*/
+
+//a Imports
+use std::sync::Arc;
+
+use crate::{
+    f16_to_f32, f32_to_f16, BufferAccessor, BufferData, BufferElementType, Renderable, VertexAttr,
+};
+
+//a AccessorError
+//tp AccessorError
+/// An error in reading the elements of a [BufferAccessor]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessorError {
+    /// The element type of the accessor cannot be read as the
+    /// requested type (named)
+    Unsupported(BufferElementType, &'static str),
+    /// An element (by index) would end at the given byte offset, which
+    /// is beyond the length of the [BufferData]
+    OutOfRange(usize, usize, usize),
+    /// The accessor is normalized but its element type is not an
+    /// integer type
+    NotNormalizable(BufferElementType),
+    /// The minimum or maximum of the accessor has the given number of
+    /// components, which is not `elements_per_data`
+    BadBounds(usize, u32),
+    /// An index is not less than the number of vertices
+    BadIndex(u32, usize),
+    /// The [crate::Vertices] has no accessor for an attribute that is
+    /// required
+    MissingAttr(VertexAttr),
+}
+
+//ip Display for AccessorError
+impl std::fmt::Display for AccessorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(ele_type, name) => {
+                write!(f, "accessor of {ele_type:?} cannot be read as {name}")
+            }
+            Self::OutOfRange(n, end, length) => write!(
+                f,
+                "element {n} ends at byte {end}, beyond the {length} bytes of the buffer data"
+            ),
+            Self::NotNormalizable(ele_type) => {
+                write!(f, "accessor of {ele_type:?} cannot be normalized")
+            }
+            Self::BadBounds(n, components) => write!(
+                f,
+                "accessor bounds have {n} components but its elements have {components}"
+            ),
+            Self::BadIndex(index, n) => {
+                write!(f, "index {index} is beyond the {n} vertices")
+            }
+            Self::MissingAttr(attr) => write!(f, "vertices have no {attr:?} attribute"),
+        }
+    }
+}
+
+//ip Error for AccessorError
+impl std::error::Error for AccessorError {}
+
+//a Element
+//tt Element
+/// A type that components of a [BufferAccessor] can be read as
+pub trait Element: Copy + Default + std::fmt::Debug {
+    /// The name of the type, for errors
+    const NAME: &'static str;
+
+    /// Return true if components of the element type can be read as
+    /// this type
+    fn is_readable(ele_type: BufferElementType) -> bool;
+
+    /// Read a component from its bytes (which are the length of the
+    /// element type), normalizing integers if required and if
+    /// supported by the type
+    fn of_bytes(ele_type: BufferElementType, bytes: &[u8], normalized: bool) -> Self;
+
+    /// Return true if components of this type can be written as the
+    /// element type
+    fn is_writable(ele_type: BufferElementType) -> bool {
+        Self::is_readable(ele_type)
+    }
+
+    /// Append the bytes of the component as the element type,
+    /// normalizing if required and if supported by the type; integers
+    /// are clamped to the range of the element type
+    fn write_bytes(self, ele_type: BufferElementType, normalized: bool, bytes: &mut Vec<u8>);
+}
+
+//fi read_uint
+/// Read an unsigned integer component
+fn read_uint(ele_type: BufferElementType, bytes: &[u8]) -> u32 {
+    match ele_type {
+        BufferElementType::UInt8 => bytes[0] as u32,
+        BufferElementType::UInt16 => u16::from_le_bytes(bytes.try_into().unwrap()) as u32,
+        _ => u32::from_le_bytes(bytes.try_into().unwrap()),
+    }
+}
+
+//fi read_int
+/// Read a signed integer component
+fn read_int(ele_type: BufferElementType, bytes: &[u8]) -> i32 {
+    match ele_type {
+        BufferElementType::Int8 => bytes[0] as i8 as i32,
+        BufferElementType::Int16 => i16::from_le_bytes(bytes.try_into().unwrap()) as i32,
+        _ => i32::from_le_bytes(bytes.try_into().unwrap()),
+    }
+}
+
+//fi int_range
+/// The range of values of an integer element type
+fn int_range(ele_type: BufferElementType) -> (i64, i64) {
+    use BufferElementType::*;
+    match ele_type {
+        Int8 => (i8::MIN as i64, i8::MAX as i64),
+        Int16 => (i16::MIN as i64, i16::MAX as i64),
+        Int32 => (i32::MIN as i64, i32::MAX as i64),
+        UInt8 => (0, u8::MAX as i64),
+        UInt16 => (0, u16::MAX as i64),
+        _ => (0, u32::MAX as i64),
+    }
+}
+
+//fi write_int
+/// Write an integer component, clamped to the range of the element type
+fn write_int(ele_type: BufferElementType, value: i64, bytes: &mut Vec<u8>) {
+    let (min, max) = int_range(ele_type);
+    let value = value.clamp(min, max);
+    let size = ele_type.byte_length();
+    bytes.extend_from_slice(&value.to_le_bytes()[0..size]);
+}
+
+//ip Element for f32
+/// Floats can be read from any element type; if normalized, unsigned
+/// integers are scaled to 0.0 to 1.0 and signed integers to -1.0 to
+/// 1.0
+impl Element for f32 {
+    const NAME: &'static str = "f32";
+    fn is_readable(_ele_type: BufferElementType) -> bool {
+        true
+    }
+    fn of_bytes(ele_type: BufferElementType, bytes: &[u8], normalized: bool) -> Self {
+        use BufferElementType::*;
+        match ele_type {
+            Float32 => f32::from_le_bytes(bytes.try_into().unwrap()),
+            Float16 => f16_to_f32(u16::from_le_bytes(bytes.try_into().unwrap())),
+            UInt8 if normalized => bytes[0] as f32 / 255.0,
+            UInt16 if normalized => read_uint(ele_type, bytes) as f32 / 65535.0,
+            UInt32 if normalized => (read_uint(ele_type, bytes) as f64 / u32::MAX as f64) as f32,
+            Int8 if normalized => (read_int(ele_type, bytes) as f32 / 127.0).max(-1.0),
+            Int16 if normalized => (read_int(ele_type, bytes) as f32 / 32767.0).max(-1.0),
+            Int32 if normalized => {
+                ((read_int(ele_type, bytes) as f64 / i32::MAX as f64) as f32).max(-1.0)
+            }
+            UInt8 | UInt16 | UInt32 => read_uint(ele_type, bytes) as f32,
+            Int8 | Int16 | Int32 => read_int(ele_type, bytes) as f32,
+        }
+    }
+    fn write_bytes(self, ele_type: BufferElementType, normalized: bool, bytes: &mut Vec<u8>) {
+        match ele_type {
+            BufferElementType::Float32 => bytes.extend(self.to_le_bytes()),
+            BufferElementType::Float16 => bytes.extend(f32_to_f16(self).to_le_bytes()),
+            _ if normalized => {
+                let max = int_range(ele_type).1;
+                let value = (self as f64 * max as f64).round() as i64;
+                write_int(ele_type, value.max(-max), bytes);
+            }
+            _ => write_int(ele_type, self.round() as i64, bytes),
+        }
+    }
+}
+
+//ip Element for u16
+/// 16-bit unsigned integers can be read from 8-bit and 16-bit
+/// unsigned integers
+impl Element for u16 {
+    const NAME: &'static str = "u16";
+    fn is_readable(ele_type: BufferElementType) -> bool {
+        matches!(
+            ele_type,
+            BufferElementType::UInt8 | BufferElementType::UInt16
+        )
+    }
+    fn of_bytes(ele_type: BufferElementType, bytes: &[u8], _normalized: bool) -> Self {
+        read_uint(ele_type, bytes) as u16
+    }
+    fn write_bytes(self, ele_type: BufferElementType, _normalized: bool, bytes: &mut Vec<u8>) {
+        write_int(ele_type, self as i64, bytes);
+    }
+}
+
+//ip Element for u32
+/// 32-bit unsigned integers can be read from any unsigned integer
+/// type
+impl Element for u32 {
+    const NAME: &'static str = "u32";
+    fn is_readable(ele_type: BufferElementType) -> bool {
+        ele_type.is_unsigned()
+    }
+    fn of_bytes(ele_type: BufferElementType, bytes: &[u8], _normalized: bool) -> Self {
+        read_uint(ele_type, bytes)
+    }
+    fn write_bytes(self, ele_type: BufferElementType, _normalized: bool, bytes: &mut Vec<u8>) {
+        write_int(ele_type, self as i64, bytes);
+    }
+}
+
+//ip Element for i32
+/// 32-bit signed integers can be read from any signed integer type
+impl Element for i32 {
+    const NAME: &'static str = "i32";
+    fn is_readable(ele_type: BufferElementType) -> bool {
+        ele_type.is_integer() && !ele_type.is_unsigned()
+    }
+    fn of_bytes(ele_type: BufferElementType, bytes: &[u8], _normalized: bool) -> Self {
+        read_int(ele_type, bytes)
+    }
+    fn write_bytes(self, ele_type: BufferElementType, _normalized: bool, bytes: &mut Vec<u8>) {
+        write_int(ele_type, self as i64, bytes);
+    }
+}
+
+//a Elements
+//tp Elements
+/// An iterator over the elements of a [BufferAccessor], each an
+/// array of N components
+///
+/// The range of the elements is checked when the iterator is
+/// created, so the iteration itself cannot fail
+#[derive(Debug, Clone)]
+pub struct Elements<'a, T: Element, const N: usize> {
+    /// The data of the [BufferData]
+    data: &'a [u8],
+    /// The element type of the accessor
+    ele_type: BufferElementType,
+    /// Number of components to read for each element (up to N)
+    components: usize,
+    /// Byte offset of the next element
+    offset: usize,
+    /// Byte step between elements
+    stride: usize,
+    /// Number of elements remaining
+    remaining: usize,
+    /// True if integer components should be normalized
+    normalized: bool,
+    /// Phantom for the element type
+    phantom: std::marker::PhantomData<T>,
+}
+
+//ip Iterator for Elements
+impl<'a, T: Element, const N: usize> Iterator for Elements<'a, T, N> {
+    type Item = [T; N];
+    fn next(&mut self) -> Option<[T; N]> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let size = self.ele_type.byte_length();
+        let mut result = [T::default(); N];
+        let bytes = &self.data[self.offset..self.offset + self.components * size];
+        for (r, b) in result.iter_mut().zip(bytes.chunks_exact(size)) {
+            *r = T::of_bytes(self.ele_type, b, self.normalized);
+        }
+        self.offset += self.stride;
+        self.remaining -= 1;
+        Some(result)
+    }
+    fn nth(&mut self, n: usize) -> Option<[T; N]> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.offset += n * self.stride;
+        self.remaining -= n;
+        self.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+//ip ExactSizeIterator for Elements
+impl<'a, T: Element, const N: usize> ExactSizeIterator for Elements<'a, T, N> {}
+
+//a BufferAccessor readers
+//ip BufferAccessor
+impl<'a, R: Renderable> BufferAccessor<'a, R> {
+    //ap element_size
+    /// Get the size in bytes of the data of one element
+    pub fn element_size(&self) -> usize {
+        self.elements_per_data as usize * self.ele_type.byte_length()
+    }
+
+    //ap element_stride
+    /// Get the number of bytes from one element to the next; this is
+    /// the stride, or the element size if the stride is zero (tightly
+    /// packed)
+    pub fn element_stride(&self) -> usize {
+        if self.stride == 0 {
+            self.element_size()
+        } else {
+            self.stride as usize
+        }
+    }
+
+    //mp check_range
+    /// Check that `count` elements of `components` components each,
+    /// separated by `stride` bytes, lie within the [BufferData]
+    pub(crate) fn check_range(
+        &self,
+        components: usize,
+        stride: usize,
+        count: usize,
+    ) -> Result<(), AccessorError> {
+        let length = self.data.byte_length as usize;
+        if count > 0 {
+            let end = self.byte_offset as usize
+                + (count - 1) * stride
+                + components * self.ele_type.byte_length();
+            if end > length {
+                return Err(AccessorError::OutOfRange(count - 1, end, length));
+            }
+        }
+        Ok(())
+    }
+
+    //mi elements
+    /// Create an iterator over `count` elements of `components`
+    /// components each, separated by `stride` bytes, checking their
+    /// range
+    fn elements<T: Element, const N: usize>(
+        &self,
+        components: usize,
+        stride: usize,
+        count: usize,
+    ) -> Result<Elements<'_, T, N>, AccessorError> {
+        if !T::is_readable(self.ele_type) {
+            return Err(AccessorError::Unsupported(self.ele_type, T::NAME));
+        }
+        let components = components.min(N);
+        self.check_range(components, stride, count)?;
+        Ok(Elements {
+            data: self.data.as_slice(),
+            ele_type: self.ele_type,
+            components,
+            offset: self.byte_offset as usize,
+            stride,
+            remaining: count,
+            normalized: self.normalized,
+            phantom: std::marker::PhantomData,
+        })
+    }
+
+    //mp iter_elements
+    /// Iterate over the first `count` elements of the accessor, each
+    /// as an array of N components
+    ///
+    /// If N is less than `elements_per_data` then only the first N
+    /// components are read; if it is greater then the remaining
+    /// components are zero. Integer components are normalized (for
+    /// [f32]) if the accessor is normalized.
+    ///
+    /// An error is returned if the element type cannot be read as T,
+    /// or if the elements run past the end of the [BufferData]
+    pub fn iter_elements<T: Element, const N: usize>(
+        &self,
+        count: usize,
+    ) -> Result<Elements<'_, T, N>, AccessorError> {
+        self.elements(
+            self.elements_per_data as usize,
+            self.element_stride(),
+            count,
+        )
+    }
+
+    //mp iter_all
+    /// Iterate over all `count` elements of the accessor, as for
+    /// `iter_elements`
+    pub fn iter_all<T: Element, const N: usize>(
+        &self,
+    ) -> Result<Elements<'_, T, N>, AccessorError> {
+        self.iter_elements(self.count as usize)
+    }
+
+    //mp read_element
+    /// Read element `n` of the accessor as an array of N components,
+    /// as for `iter_elements`
+    pub fn read_element<T: Element, const N: usize>(
+        &self,
+        n: usize,
+    ) -> Result<[T; N], AccessorError> {
+        let mut elements = self.iter_elements::<T, N>(n + 1)?;
+        Ok(elements.nth(n).unwrap())
+    }
+
+    //mp iter_indices
+    /// Iterate over the indices of an indices accessor (for which
+    /// `elements_per_data` is the number of indices, which are tightly
+    /// packed)
+    pub fn iter_indices(&self) -> Result<impl Iterator<Item = u32> + '_, AccessorError> {
+        let size = self.ele_type.byte_length();
+        let elements = self.elements::<u32, 1>(1, size, self.elements_per_data as usize)?;
+        Ok(elements.map(|[i]| i))
+    }
+
+    //mp read_index
+    /// Read index `n` of an indices accessor
+    pub fn read_index(&self, n: usize) -> Result<u32, AccessorError> {
+        let size = self.ele_type.byte_length();
+        let mut elements = self.elements::<u32, 1>(1, size, n + 1)?;
+        Ok(elements.nth(n).unwrap()[0])
+    }
+
+    //zz All done
+}
+
+//a BufferAccessor writers
+//ip BufferAccessor
+impl<'a, R: Renderable> BufferAccessor<'a, R> {
+    //fp of_elements
+    /// Create an accessor that owns its data, by writing elements of N
+    /// components as the element type (tightly packed)
+    ///
+    /// Integer components are normalized (for [f32]) if `normalized`
+    /// is true; an error is returned if the element type cannot be
+    /// written from T, or if it cannot be normalized
+    pub fn of_elements<T: Element, const N: usize>(
+        elements: &[[T; N]],
+        ele_type: BufferElementType,
+        normalized: bool,
+    ) -> Result<Self, AccessorError> {
+        if !T::is_writable(ele_type) {
+            return Err(AccessorError::Unsupported(ele_type, T::NAME));
+        }
+        if normalized && !ele_type.is_integer() {
+            return Err(AccessorError::NotNormalizable(ele_type));
+        }
+        let mut bytes = Vec::with_capacity(elements.len() * N * ele_type.byte_length());
+        for element in elements {
+            for c in element {
+                c.write_bytes(ele_type, normalized, &mut bytes);
+            }
+        }
+        let data = Arc::new(BufferData::of_bytes(bytes, 0, 0));
+        Ok(Self::new(data, N as u32, ele_type, 0, 0)
+            .with_normalized(normalized)
+            .with_count(elements.len() as u32))
+    }
+
+    //zz All done
+}
@@ -1,5 +1,5 @@
 //a Imports
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::{
     AccessorClient, BufferAccessor, BufferClient, BufferData, Material, MaterialClient, Renderable,
@@ -15,7 +15,7 @@ use crate::{
 /// there are N views then (after deconstruction of the object) a
 /// Buffer will have a strong count of the number of views upon it
 #[derive(Debug, Clone)]
-pub struct Buffer(Rc<u32>);
+pub struct Buffer(Arc<u32>);
 
 //ip Display for Buffer
 impl std::fmt::Display for Buffer {
@@ -27,7 +27,7 @@ impl std::fmt::Display for Buffer {
 //ip Default for Buffer
 impl Default for Buffer {
     fn default() -> Self {
-        Self(Rc::new(0))
+        Self(Arc::new(0))
     }
 }
 
@@ -80,8 +80,13 @@ impl Renderable for Id {
         buffer_view.data.create_client(self);
         *client = buffer_view.data.borrow_client().clone();
     }
-    fn create_vertices_client(&mut self, _vertices: &Vertices<Self>) -> Self::Vertices {
-        Self::Vertices::default()
+    /// The vertices client records the number of indices, or the
+    /// number of vertices if the [Vertices] is drawn without indices
+    fn create_vertices_client(&mut self, vertices: &Vertices<Self>) -> Self::Vertices {
+        match vertices.borrow_indices() {
+            Some(indices) => Id(indices.elements_per_data),
+            None => Id(vertices.borrow_position().count),
+        }
     }
     fn create_texture_client(&mut self, _vertices: &Texture<Self>) -> Self::Texture {
         Self::Texture::default()
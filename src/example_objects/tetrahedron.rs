@@ -69,7 +69,7 @@ pub fn new<R: Renderable>(eg: &mut ExampleVertices<R>, size: f32) {
     let data_indices = eg.push_byte_buffer(Box::new(index_data));
     let data_vertices = eg.push_byte_buffer(Box::new(vertex_data));
 
-    let indices = eg.push_accessor(data_indices, 6, BufferElementType::Int8, 0, 0);
+    let indices = eg.push_accessor(data_indices, 6, BufferElementType::UInt8, 0, 0);
     let normals = eg.push_accessor(data_vertices, 3, BufferElementType::Float32, 3 * 4, 6 * 4);
     let vertices = eg.push_accessor(data_vertices, 3, BufferElementType::Float32, 0, 6 * 4);
 
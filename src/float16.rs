@@ -0,0 +1,103 @@
+//a Documentation
+/*!
+
+This provides conversion between [f32] and IEEE 754 binary16
+(half-precision) floats, as used for [BufferElementType::Float16]
+accessors and textures.
+
+A half-precision float is held as its bits in a [u16]. Conversion to
+[f32] is exact (including subnormals, infinities and NaNs);
+conversion from [f32] rounds to nearest (with ties to even), with
+values too large for a half-precision float becoming infinities, and
+values too small becoming subnormals or (signed) zero.
+
+!*/
+
+//a Imports
+#[cfg(doc)]
+use crate::BufferElementType;
+
+//a Conversions
+//fp f16_to_f32
+/// Convert the bits of an IEEE 754 half-precision float to an [f32]
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h as u32) & 0x8000) << 16;
+    let exponent = ((h >> 10) & 0x1f) as u32;
+    let mantissa = (h & 0x3ff) as u32;
+    let bits = match exponent {
+        0 if mantissa == 0 => sign,
+        0 => {
+            // Subnormal; renormalize the mantissa
+            let shift = mantissa.leading_zeros() - 21;
+            let mantissa = (mantissa << shift) & 0x3ff;
+            sign | ((113 - shift) << 23) | (mantissa << 13)
+        }
+        31 => sign | 0x7f80_0000 | (mantissa << 13),
+        _ => sign | ((exponent + 112) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(bits)
+}
+
+//fi round_shift
+/// Shift a value right, rounding to nearest (with ties to even)
+fn round_shift(value: u32, shift: u32) -> u32 {
+    let half = 1 << (shift - 1);
+    let remainder = value & ((1 << shift) - 1);
+    let result = value >> shift;
+    if remainder > half || (remainder == half && (result & 1) != 0) {
+        result + 1
+    } else {
+        result
+    }
+}
+
+//fp f32_to_f16
+/// Convert an [f32] to the bits of an IEEE 754 half-precision float,
+/// rounding to nearest (with ties to even)
+///
+/// Values too large for a half-precision float become infinities, and
+/// NaNs remain (quiet) NaNs
+pub fn f32_to_f16(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan | (mantissa >> 13) as u16;
+    }
+    let exponent = exponent - 112;
+    if exponent >= 31 {
+        sign | 0x7c00
+    } else if exponent > 0 {
+        // Rounding may carry into the exponent, up to infinity
+        let h = ((exponent as u32) << 10) + round_shift(mantissa, 13);
+        sign | h.min(0x7c00) as u16
+    } else if exponent >= -10 {
+        // Subnormal; this may round up to the smallest normal
+        let mantissa = mantissa | 0x80_0000;
+        sign | round_shift(mantissa, (14 - exponent) as u32) as u16
+    } else {
+        sign
+    }
+}
+
+//fp f32s_to_f16_bytes
+/// Convert a slice of [f32] to the (little-endian) bytes of
+/// half-precision floats
+pub fn f32s_to_f16_bytes(values: &[f32]) -> Vec<u8> {
+    values
+        .iter()
+        .flat_map(|f| f32_to_f16(*f).to_le_bytes())
+        .collect()
+}
+
+//fp f16_bytes_to_f32s
+/// Convert the (little-endian) bytes of half-precision floats to a
+/// [Vec] of [f32]; any odd trailing byte is ignored
+pub fn f16_bytes_to_f32s(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(2)
+        .map(|b| f16_to_f32(u16::from_le_bytes([b[0], b[1]])))
+        .collect()
+}
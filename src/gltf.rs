@@ -0,0 +1,89 @@
+//a Documentation
+/*!
+
+# glTF 2.0 import and export
+
+This module (enabled with the `gltf` feature) reads glTF 2.0 files
+('.gltf' JSON files with separate or embedded binary buffers, and
+'.glb' binary files) and builds an [crate::Object] from them.
+
+A '.glb' file is read as a [Glb], which checks the header and finds
+the JSON and BIN chunks; the BIN chunk is provided as a
+[crate::ByteBuffer] so that the [crate::BufferData] refer directly to
+the file contents.
+
+The JSON document is parsed into a [Gltf]; this, together with the
+data for each of its buffers, is then validated and analyzed to
+produce a [GltfAsset]. The mapping from glTF to the 3D model library
+is:
+
+* each glTF buffer view that is used becomes a [crate::BufferData]
+
+* each glTF accessor that is used becomes a [crate::BufferAccessor];
+  an accessor used for indices has the number of indices as its
+  elements per data
+
+* each distinct set of indices, attributes and morph targets used by
+  a glTF mesh primitive becomes a [crate::Vertices], with a
+  [crate::MorphTarget] for each of its morph targets
+
+* each glTF mesh primitive becomes a [crate::Primitive] in a
+  [crate::Mesh], which has the weights of the glTF mesh (or those of
+  the node that uses it) as its morph target weights
+
+* each glTF node in the default scene becomes a [crate::Component],
+  with a [crate::Transformation] for its TRS or matrix
+
+* each glTF material becomes a [crate::PbrMaterial]
+
+* the first skin used in the scene becomes the [crate::Skeleton]
+
+Malformed files are reported with an [Error], which indicates the
+part of the file that is at fault.
+
+An [crate::Object] can be written out using an [ObjectExporter]; this
+packs the data of its vertices into a single binary buffer (each
+[crate::BufferData] once), with accessors for the attributes and for
+the indices of each primitive (and for the deltas of any morph
+targets). Components become nodes (with TRS from
+their transformations), materials become pbrMetallicRoughness
+materials, textures become PNG images in the binary buffer, and any
+[crate::Skeleton] becomes a skin (with inverse bind matrices) for the
+nodes whose meshes have joints. The result can be written as a GLB
+file or as glTF JSON (with the binary buffer embedded or separate).
+
+# Example
+
+```ignore
+use mod3d_base::gltf::GltfAsset;
+use mod3d_base::example_client::Renderable;
+
+let asset = GltfAsset::from_file("model.gltf")?;
+let data = asset.make_buffer_data::<Renderable>();
+let accessors = asset.make_accessors(&data);
+let vertices = asset.make_vertices(&accessors);
+let mut object = asset.make_object(&vertices);
+object.analyze();
+```
+
+!*/
+
+//a Imports and exports
+mod asset;
+mod error;
+mod export;
+mod glb;
+mod json;
+mod png;
+mod uri;
+
+pub use asset::GltfAsset;
+pub use asset::{element_type, node_transformation, primitive_type, semantic, vertex_attr};
+pub use error::{Error, Result};
+pub use export::{component_type, ObjectExporter};
+pub use glb::{Glb, GLB_MAGIC};
+pub use json::{
+    Accessor, Asset, Buffer, BufferView, Gltf, Image, Material, Mesh, Node, PbrMetallicRoughness,
+    Primitive, Scene, Skin, Texture, TextureInfo,
+};
+pub use uri::{base64_decode, base64_encode, load_uri};
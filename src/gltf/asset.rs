@@ -0,0 +1,837 @@
+//a Imports
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::json;
+use super::{load_uri, Error, Glb, Gltf, Result};
+use crate::hierarchy::Hierarchy;
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, ByteBuffer, Component, MaterialAspect, Mesh,
+    MorphTarget, Object, PbrMaterial, Primitive, PrimitiveType, Renderable, Skeleton,
+    Transformation, VertexAttr, Vertices,
+};
+
+//a Constants
+/// Vertex attribute semantics supported, and the [VertexAttr] they map to
+const ATTRIBUTES: &[(&str, VertexAttr)] = &[
+    ("NORMAL", VertexAttr::Normal),
+    ("TANGENT", VertexAttr::Tangent),
+    ("COLOR_0", VertexAttr::Color),
+    ("JOINTS_0", VertexAttr::Joints),
+    ("WEIGHTS_0", VertexAttr::Weights),
+    ("TEXCOORD_0", VertexAttr::TexCoords0),
+    ("TEXCOORD_1", VertexAttr::TexCoords1),
+    ("TEXCOORD_2", VertexAttr::TexCoords2),
+];
+
+//a Helper functions
+//fp element_type
+/// Get the [BufferElementType] for a glTF accessor component type
+pub fn element_type(component_type: u32) -> Result<BufferElementType> {
+    use BufferElementType::*;
+    match component_type {
+        5120 => Ok(Int8),
+        5121 => Ok(UInt8),
+        5122 => Ok(Int16),
+        5123 => Ok(UInt16),
+        5125 => Ok(UInt32),
+        5126 => Ok(Float32),
+        _ => Err(Error::Malformed(format!(
+            "bad accessor component type {component_type}"
+        ))),
+    }
+}
+
+//fp vertex_attr
+/// Get the [VertexAttr] for a glTF attribute semantic (other than
+/// POSITION), if it is supported
+pub fn vertex_attr(semantic: &str) -> Option<VertexAttr> {
+    ATTRIBUTES
+        .iter()
+        .find(|(s, _)| *s == semantic)
+        .map(|(_, attr)| *attr)
+}
+
+//fp semantic
+/// Get the glTF attribute semantic for a [VertexAttr], if it has one
+pub fn semantic(attr: VertexAttr) -> Option<&'static str> {
+    if attr == VertexAttr::Position {
+        return Some("POSITION");
+    }
+    ATTRIBUTES.iter().find(|(_, a)| *a == attr).map(|(s, _)| *s)
+}
+
+//fp primitive_type
+/// Get the [PrimitiveType] for a glTF primitive mode
+pub fn primitive_type(mode: u8) -> Result<PrimitiveType> {
+    use PrimitiveType::*;
+    match mode {
+        0 => Ok(Points),
+        1 => Ok(Lines),
+        2 => Ok(LineLoop),
+        3 => Ok(LineStrip),
+        4 => Ok(Triangles),
+        5 => Ok(TriangleStrip),
+        6 => Ok(TriangleFan),
+        _ => Err(Error::Malformed(format!("bad primitive mode {mode}"))),
+    }
+}
+
+//fp node_transformation
+/// Get the [Transformation] for a node, if it has one
+pub fn node_transformation(node: &json::Node) -> Option<Transformation> {
+    if let Some(matrix) = node.matrix {
+        let mut t = Transformation::new();
+        t.from_mat4(matrix);
+        Some(t)
+    } else if node.translation.is_none() && node.rotation.is_none() && node.scale.is_none() {
+        None
+    } else {
+        let t = Transformation::new()
+            .with_translation(node.translation.unwrap_or([0.; 3]))
+            .with_rotation(node.rotation.unwrap_or([0., 0., 0., 1.]))
+            .with_scale(node.scale.unwrap_or([1.; 3]));
+        Some(t)
+    }
+}
+
+//fi color_u8
+/// Convert a color component from 0.0 to 1.0 to a u8
+fn color_u8(c: f32) -> u8 {
+    (c.clamp(0., 1.) * 255.0).round() as u8
+}
+
+//a Plans
+//ti DataPlan
+/// The region of a buffer used for a [BufferData]
+#[derive(Debug)]
+struct DataPlan {
+    buffer: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+//ti AccessorPlan
+/// The information required to create a [BufferAccessor]
+#[derive(Debug)]
+struct AccessorPlan {
+    data: usize,
+    elements_per_data: u32,
+    ele_type: BufferElementType,
+    byte_offset: u32,
+    stride: u32,
+    normalized: bool,
+    count: u32,
+    bounds: Option<(Vec<f64>, Vec<f64>)>,
+}
+
+//ti VerticesPlan
+/// The accessors required to create a [Vertices]
+#[derive(Debug, PartialEq, Eq)]
+struct VerticesPlan {
+    indices: Option<usize>,
+    position: usize,
+    attrs: Vec<(VertexAttr, usize)>,
+    targets: Vec<Vec<(VertexAttr, usize)>>,
+}
+
+//a GltfAsset
+//tp GltfAsset
+/// A glTF document with its binary buffers, validated and analyzed
+/// so that an [Object] can be created from it
+///
+/// An [Object] borrows its [Vertices] and materials, which in turn
+/// borrow their [BufferAccessor]s and [BufferData]; so these are
+/// created in steps, each step borrowing the results of the
+/// previous:
+///
+/// ```ignore
+/// let asset = GltfAsset::from_file("model.gltf")?;
+/// let data = asset.make_buffer_data::<R>();
+/// let accessors = asset.make_accessors(&data);
+/// let vertices = asset.make_vertices(&accessors);
+/// let object = asset.make_object(&vertices);
+/// ```
+///
+/// All the validation of the document is performed when the
+/// [GltfAsset] is created, so these steps cannot fail.
+///
+/// The materials of the object are [PbrMaterial]s whose texture
+/// indices are the indices of the glTF textures; the glTF images are
+/// not decoded, so the client must add the textures to the object (in
+/// the order of the glTF textures) if it needs them.
+///
+/// A glTF primitive with no material uses a default material, which
+/// is added after the glTF materials.
+///
+/// The component hierarchy is that of the nodes of the default scene
+/// (or of all the root nodes if there are no scenes); the skeleton is
+/// that of the first skin used by a node in that hierarchy. The bone
+/// matrices of the skeleton are derived from the rest transformations
+/// of the joints.
+pub struct GltfAsset {
+    /// The glTF document
+    gltf: Gltf,
+    /// The binary buffers, one per glTF buffer
+    buffers: Vec<Box<dyn ByteBuffer>>,
+    /// The regions of the buffers required for [BufferData]
+    data: Vec<DataPlan>,
+    /// The buffer view index to data plan mapping
+    view_data: HashMap<usize, usize>,
+    /// The [BufferAccessor]s required
+    accessors: Vec<AccessorPlan>,
+    /// The (accessor index, is indices) to accessor plan mapping
+    accessor_map: HashMap<(usize, bool), usize>,
+    /// The [Vertices] required
+    vertices: Vec<VerticesPlan>,
+    /// The materials
+    materials: Vec<PbrMaterial>,
+    /// The meshes, one per glTF mesh
+    meshes: Vec<Mesh>,
+    /// The component hierarchy
+    components: Hierarchy<Component>,
+    /// The skeleton, if any
+    skeleton: Option<Skeleton>,
+}
+
+//ip Debug for GltfAsset
+impl std::fmt::Debug for GltfAsset {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            fmt,
+            "GltfAsset {{ buffers:{}, data:{:?}, accessors:{:?}, vertices:{:?}, materials:{:?}, components:{:?} }}",
+            self.buffers.len(),
+            self.data,
+            self.accessors,
+            self.vertices,
+            self.materials,
+            self.components,
+        )
+    }
+}
+
+//ip GltfAsset
+impl GltfAsset {
+    //fp new
+    /// Create a new [GltfAsset] from a glTF document and the data for
+    /// each of its buffers, validating the document
+    pub fn new(gltf: Gltf, buffers: Vec<Box<dyn ByteBuffer>>) -> Result<Self> {
+        if buffers.len() != gltf.buffers.len() {
+            return Err(Error::Malformed(format!(
+                "document has {} buffers but {} were provided",
+                gltf.buffers.len(),
+                buffers.len()
+            )));
+        }
+        for (i, (b, data)) in gltf.buffers.iter().zip(buffers.iter()).enumerate() {
+            if data.byte_length() < b.byte_length {
+                return Err(Error::BadRange(format!(
+                    "buffer {i} has byteLength {} but only {} bytes of data",
+                    b.byte_length,
+                    data.byte_length()
+                )));
+            }
+        }
+        let mut asset = Self {
+            gltf,
+            buffers,
+            data: Vec::new(),
+            view_data: HashMap::new(),
+            accessors: Vec::new(),
+            accessor_map: HashMap::new(),
+            vertices: Vec::new(),
+            materials: Vec::new(),
+            meshes: Vec::new(),
+            components: Hierarchy::new(),
+            skeleton: None,
+        };
+        asset.analyze()?;
+        Ok(asset)
+    }
+
+    //fp from_json
+    /// Create a new [GltfAsset] from a glTF JSON string, loading any
+    /// buffers relative to the base directory (if given)
+    pub fn from_json(json: &str, base: Option<&Path>) -> Result<Self> {
+        let gltf = Gltf::from_json(json)?;
+        let buffers = Self::load_buffers(&gltf, base, None)?;
+        Self::new(gltf, buffers)
+    }
+
+    //fp from_glb
+    /// Create a new [GltfAsset] from the contents of a GLB file,
+    /// loading any buffers with URIs relative to the base directory
+    /// (if given)
+    ///
+    /// The first buffer (if it has no URI) is the BIN chunk of the
+    /// file; the [crate::BufferData] created for the asset refer
+    /// directly to the file contents
+    pub fn from_glb(bytes: Vec<u8>, base: Option<&Path>) -> Result<Self> {
+        let glb = Glb::from_bytes(bytes)?;
+        let gltf = Gltf::from_json(glb.json_str()?)?;
+        let buffers = Self::load_buffers(&gltf, base, Some(glb))?;
+        Self::new(gltf, buffers)
+    }
+
+    //fp from_file
+    /// Read a '.gltf' or '.glb' file, and the buffers it refers to,
+    /// and create a [GltfAsset]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        if bytes.starts_with(b"glTF") {
+            Self::from_glb(bytes, path.parent())
+        } else {
+            let json = String::from_utf8(bytes)
+                .map_err(|_| Error::Malformed("glTF file is not UTF-8".into()))?;
+            Self::from_json(&json, path.parent())
+        }
+    }
+
+    //fi load_buffers
+    /// Load the buffers for a glTF document; the first buffer may be
+    /// the BIN chunk of a GLB file
+    fn load_buffers(
+        gltf: &Gltf,
+        base: Option<&Path>,
+        mut glb: Option<Glb>,
+    ) -> Result<Vec<Box<dyn ByteBuffer>>> {
+        let mut buffers: Vec<Box<dyn ByteBuffer>> = Vec::new();
+        for (i, b) in gltf.buffers.iter().enumerate() {
+            if let Some(uri) = &b.uri {
+                buffers.push(Box::new(load_uri(uri, base)?));
+            } else if i == 0 && glb.as_ref().is_some_and(|g| g.has_bin()) {
+                buffers.push(Box::new(glb.take().unwrap()));
+            } else {
+                return Err(Error::Malformed(format!("buffer {i} has no URI")));
+            }
+        }
+        Ok(buffers)
+    }
+
+    //ap gltf
+    /// Borrow the glTF document
+    pub fn gltf(&self) -> &Gltf {
+        &self.gltf
+    }
+
+    //ap materials
+    /// Borrow the materials
+    pub fn materials(&self) -> &[PbrMaterial] {
+        &self.materials
+    }
+
+    //ap components
+    /// Borrow the component hierarchy
+    pub fn components(&self) -> &Hierarchy<Component> {
+        &self.components
+    }
+
+    //ap skeleton
+    /// Borrow the skeleton, if there is one
+    pub fn skeleton(&self) -> Option<&Skeleton> {
+        self.skeleton.as_ref()
+    }
+
+    //mp make_buffer_data
+    /// Create the [BufferData]s for the asset
+    pub fn make_buffer_data<R: Renderable>(&self) -> Vec<BufferData<'_, R>> {
+        self.data
+            .iter()
+            .map(|d| {
+                BufferData::new(
+                    self.buffers[d.buffer].as_ref(),
+                    d.byte_offset,
+                    d.byte_length,
+                )
+            })
+            .collect()
+    }
+
+    //mp make_accessors
+    /// Create the [BufferAccessor]s for the asset, given the
+    /// [BufferData]s created by `make_buffer_data`
+    pub fn make_accessors<'a, R: Renderable>(
+        &self,
+        data: &'a [BufferData<'a, R>],
+    ) -> Vec<BufferAccessor<'a, R>> {
+        self.accessors
+            .iter()
+            .map(|a| {
+                let accessor = BufferAccessor::new(
+                    &data[a.data],
+                    a.elements_per_data,
+                    a.ele_type,
+                    a.byte_offset,
+                    a.stride,
+                )
+                .with_normalized(a.normalized)
+                .with_count(a.count);
+                match &a.bounds {
+                    Some((min, max)) => accessor.with_bounds(min.clone(), max.clone()),
+                    None => accessor,
+                }
+            })
+            .collect()
+    }
+
+    //mp make_vertices
+    /// Create the [Vertices] for the asset, given the
+    /// [BufferAccessor]s created by `make_accessors`
+    pub fn make_vertices<'a, R: Renderable>(
+        &self,
+        accessors: &'a [BufferAccessor<'a, R>],
+    ) -> Vec<Vertices<'a, R>> {
+        self.vertices
+            .iter()
+            .map(|v| {
+                let indices = v.indices.map(|i| (&accessors[i]).into());
+                let mut vertices = Vertices::of_indices(indices, &accessors[v.position]);
+                for (attr, a) in &v.attrs {
+                    vertices.add_attr(*attr, &accessors[*a]);
+                }
+                for t in &v.targets {
+                    let mut target = MorphTarget::new();
+                    for (attr, a) in t {
+                        target.add_delta(*attr, &accessors[*a]);
+                    }
+                    vertices.add_morph_target(target);
+                }
+                vertices
+            })
+            .collect()
+    }
+
+    //mp make_object
+    /// Create the [Object] for the asset, given the [Vertices]
+    /// created by `make_vertices`
+    pub fn make_object<'a, R: Renderable>(
+        &'a self,
+        vertices: &'a [Vertices<'a, R>],
+    ) -> Object<'a, PbrMaterial, R> {
+        let mut object = Object::new();
+        for v in vertices {
+            object.add_vertices(v);
+        }
+        for m in &self.materials {
+            object.add_material(m);
+        }
+        object.components = self.components.clone();
+        object.skeleton = self.skeleton.clone();
+        object
+    }
+
+    //mi analyze
+    /// Validate the document and create the plans for the object
+    fn analyze(&mut self) -> Result<()> {
+        self.validate_views()?;
+        self.analyze_materials()?;
+        let mut default_material = None;
+        for m in 0..self.gltf.meshes.len() {
+            let mesh = self.analyze_mesh(m, &mut default_material)?;
+            self.meshes.push(mesh);
+        }
+        if default_material.is_some() {
+            self.materials.push(PbrMaterial::default());
+        }
+        self.analyze_nodes()
+    }
+
+    //mi validate_views
+    /// Validate the buffer views against the buffers
+    fn validate_views(&self) -> Result<()> {
+        for (i, v) in self.gltf.buffer_views.iter().enumerate() {
+            let Some(b) = self.gltf.buffers.get(v.buffer) else {
+                return Err(Error::BadIndex("buffer", v.buffer));
+            };
+            let end = v.byte_offset.checked_add(v.byte_length);
+            if v.byte_length == 0 || end.is_none_or(|end| end > b.byte_length) {
+                return Err(Error::BadRange(format!(
+                    "buffer view {i} ({}+{}) does not fit within buffer {} ({} bytes)",
+                    v.byte_offset, v.byte_length, v.buffer, b.byte_length
+                )));
+            }
+            if let Some(stride) = v.byte_stride {
+                if !(4..=252).contains(&stride) || stride % 4 != 0 {
+                    return Err(Error::Malformed(format!(
+                        "buffer view {i} has bad byteStride {stride}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //mi analyze_materials
+    /// Create the [PbrMaterial]s for the glTF materials
+    fn analyze_materials(&mut self) -> Result<()> {
+        let num_textures = self.gltf.textures.len();
+        let texture = |t: &Option<json::TextureInfo>| -> Result<Option<usize>> {
+            match t {
+                Some(t) if t.index >= num_textures => Err(Error::BadIndex("texture", t.index)),
+                Some(t) => Ok(Some(t.index)),
+                None => Ok(None),
+            }
+        };
+        for m in &self.gltf.materials {
+            let mut material = PbrMaterial::default();
+            let pbr = m.pbr_metallic_roughness.clone().unwrap_or_default();
+            let c = pbr.base_color_factor;
+            material.set_rgba((
+                color_u8(c[0]),
+                color_u8(c[1]),
+                color_u8(c[2]),
+                color_u8(c[3]),
+            ));
+            let e = m.emissive_factor;
+            material.set_emissive_rgb((color_u8(e[0]), color_u8(e[1]), color_u8(e[2])));
+            material.set_mr(pbr.metallic_factor, pbr.roughness_factor);
+            for (aspect, t) in [
+                (MaterialAspect::Color, &pbr.base_color_texture),
+                (
+                    MaterialAspect::MetallicRoughness,
+                    &pbr.metallic_roughness_texture,
+                ),
+                (MaterialAspect::Normal, &m.normal_texture),
+                (MaterialAspect::Occlusion, &m.occlusion_texture),
+                (MaterialAspect::Emission, &m.emissive_texture),
+            ] {
+                if let Some(t) = texture(t)? {
+                    material.set_texture(aspect, t.into());
+                }
+            }
+            self.materials.push(material);
+        }
+        Ok(())
+    }
+
+    //mi accessor
+    /// Get the accessor plan index for a glTF accessor, creating it if
+    /// required
+    ///
+    /// For indices the number of elements is the number of indices
+    fn accessor(&mut self, index: usize, is_indices: bool) -> Result<usize> {
+        if let Some(a) = self.accessor_map.get(&(index, is_indices)) {
+            return Ok(*a);
+        }
+        let Some(accessor) = self.gltf.accessors.get(index) else {
+            return Err(Error::BadIndex("accessor", index));
+        };
+        let Some(view_index) = accessor.buffer_view else {
+            return Err(Error::Unsupported(format!(
+                "accessor {index} has no buffer view (sparse or zero accessors)"
+            )));
+        };
+        let Some(view) = self.gltf.buffer_views.get(view_index) else {
+            return Err(Error::BadIndex("buffer view", view_index));
+        };
+        let ele_type = element_type(accessor.component_type)?;
+        let components = accessor.components()?;
+        if is_indices && (components != 1 || !ele_type.is_unsigned()) {
+            return Err(Error::Malformed(format!(
+                "accessor {index} used for indices must be unsigned SCALAR"
+            )));
+        }
+        let element_size = ele_type.byte_length() * (components as usize);
+        let stride = view.byte_stride.unwrap_or(0);
+        let step = if stride == 0 { element_size } else { stride };
+        let end = accessor
+            .count
+            .checked_sub(1)
+            .and_then(|n| step.checked_mul(n))
+            .and_then(|n| n.checked_add(accessor.byte_offset))
+            .and_then(|n| n.checked_add(element_size));
+        if end.is_none_or(|end| end > view.byte_length) {
+            return Err(Error::BadRange(format!(
+                "accessor {index} ({} elements of {element_size} bytes at offset {}) does not fit within buffer view {view_index} ({} bytes)",
+                accessor.count, accessor.byte_offset, view.byte_length
+            )));
+        }
+        let data = match self.view_data.get(&view_index) {
+            Some(d) => *d,
+            None => {
+                let (Ok(byte_offset), Ok(byte_length)) = (
+                    u32::try_from(view.byte_offset),
+                    u32::try_from(view.byte_length),
+                ) else {
+                    return Err(Error::BadRange(format!(
+                        "buffer view {view_index} ({}+{}) is beyond the 4GB addressable by an accessor",
+                        view.byte_offset, view.byte_length
+                    )));
+                };
+                let d = self.data.len();
+                self.data.push(DataPlan {
+                    buffer: view.buffer,
+                    byte_offset,
+                    byte_length,
+                });
+                self.view_data.insert(view_index, d);
+                d
+            }
+        };
+        if accessor.normalized && (is_indices || !ele_type.is_integer()) {
+            return Err(Error::Malformed(format!(
+                "accessor {index} cannot be normalized"
+            )));
+        }
+        let (elements_per_data, count) = {
+            if is_indices {
+                (accessor.count as u32, 1)
+            } else {
+                (components, accessor.count as u32)
+            }
+        };
+        let has_bounds =
+            accessor.min.len() == components as usize && accessor.max.len() == components as usize;
+        let bounds =
+            (!is_indices && has_bounds).then(|| (accessor.min.clone(), accessor.max.clone()));
+        let a = self.accessors.len();
+        self.accessors.push(AccessorPlan {
+            data,
+            elements_per_data,
+            ele_type,
+            byte_offset: accessor.byte_offset as u32,
+            stride: stride as u32,
+            normalized: accessor.normalized,
+            count,
+            bounds,
+        });
+        self.accessor_map.insert((index, is_indices), a);
+        Ok(a)
+    }
+
+    //mi analyze_mesh
+    /// Create the [Mesh] for a glTF mesh
+    fn analyze_mesh(&mut self, m: usize, default_material: &mut Option<usize>) -> Result<Mesh> {
+        let mut mesh = Mesh::default();
+        let primitives = self.gltf.meshes[m].primitives.clone();
+        let num_targets = primitives.first().map_or(0, |p| p.targets.len());
+        for (p, prim) in primitives.iter().enumerate() {
+            let primitive_type = primitive_type(prim.mode)?;
+            let Some(position) = prim.attributes.get("POSITION") else {
+                return Err(Error::Malformed(format!(
+                    "mesh {m} primitive {p} has no POSITION attribute"
+                )));
+            };
+            let position = self.accessor(*position, false)?;
+            let (indices, index_count) = match prim.indices {
+                Some(indices) => {
+                    let count = self.gltf.accessors.get(indices).map_or(0, |a| a.count) as u32;
+                    (Some(self.accessor(indices, true)?), count)
+                }
+                None => (None, self.accessors[position].count),
+            };
+            let mut attrs = Vec::new();
+            for (name, attr) in ATTRIBUTES {
+                if let Some(a) = prim.attributes.get(*name) {
+                    attrs.push((*attr, self.accessor(*a, false)?));
+                }
+            }
+            if prim.targets.len() != num_targets {
+                return Err(Error::Malformed(format!(
+                    "mesh {m} primitive {p} has {} morph targets, not {num_targets}",
+                    prim.targets.len()
+                )));
+            }
+            let mut targets = Vec::new();
+            for target in &prim.targets {
+                let mut deltas = Vec::new();
+                for (name, a) in target {
+                    let attr = match name.as_str() {
+                        "POSITION" => VertexAttr::Position,
+                        "NORMAL" => VertexAttr::Normal,
+                        "TANGENT" => VertexAttr::Tangent,
+                        _ => continue,
+                    };
+                    deltas.push((attr, self.accessor(*a, false)?));
+                }
+                targets.push(deltas);
+            }
+            let plan = VerticesPlan {
+                indices,
+                position,
+                attrs,
+                targets,
+            };
+            let vertices_index = match self.vertices.iter().position(|v| *v == plan) {
+                Some(v) => v,
+                None => {
+                    self.vertices.push(plan);
+                    self.vertices.len() - 1
+                }
+            };
+            let material_index = match prim.material {
+                Some(material) if material >= self.materials.len() => {
+                    return Err(Error::BadIndex("material", material));
+                }
+                Some(material) => material,
+                None => *default_material.get_or_insert(self.gltf.materials.len()),
+            };
+            let primitive = {
+                if indices.is_some() {
+                    Primitive::new
+                } else {
+                    Primitive::of_vertex_range
+                }
+            };
+            mesh.add_primitive(primitive(
+                primitive_type,
+                vertices_index.into(),
+                0,
+                index_count,
+                material_index.into(),
+            ));
+        }
+        let weights = &self.gltf.meshes[m].weights;
+        if !weights.is_empty() && weights.len() != num_targets {
+            return Err(Error::Malformed(format!(
+                "mesh {m} has {} weights for {num_targets} morph targets",
+                weights.len()
+            )));
+        }
+        mesh.weights = weights.clone();
+        if mesh.weights.is_empty() {
+            mesh.weights = vec![0.; num_targets];
+        }
+        Ok(mesh)
+    }
+
+    //mi analyze_nodes
+    /// Create the component hierarchy and skeleton from the nodes of
+    /// the default scene
+    fn analyze_nodes(&mut self) -> Result<()> {
+        let nodes = &self.gltf.nodes;
+        let mut parents = vec![None; nodes.len()];
+        for (i, n) in nodes.iter().enumerate() {
+            for c in &n.children {
+                if *c >= nodes.len() {
+                    return Err(Error::BadIndex("node", *c));
+                }
+                if parents[*c].is_some() {
+                    return Err(Error::Malformed(format!(
+                        "node {c} has more than one parent"
+                    )));
+                }
+                parents[*c] = Some(i);
+            }
+        }
+        // With at most one parent each, the nodes form a forest unless
+        // following the parents from some node returns to it
+        let mut checked = vec![false; nodes.len()];
+        let mut on_path = vec![false; nodes.len()];
+        for n in 0..nodes.len() {
+            let mut path = vec![];
+            let mut node = Some(n);
+            while let Some(p) = node {
+                if checked[p] {
+                    break;
+                }
+                if on_path[p] {
+                    return Err(Error::Malformed(format!("node {p} is its own ancestor")));
+                }
+                on_path[p] = true;
+                path.push(p);
+                node = parents[p];
+            }
+            for p in path {
+                checked[p] = true;
+                on_path[p] = false;
+            }
+        }
+        let roots: Vec<usize> = {
+            if self.gltf.scenes.is_empty() {
+                (0..nodes.len()).filter(|n| parents[*n].is_none()).collect()
+            } else {
+                let scene = self.gltf.scene.unwrap_or(0);
+                let Some(scene) = self.gltf.scenes.get(scene) else {
+                    return Err(Error::BadIndex("scene", scene));
+                };
+                scene.nodes.clone()
+            }
+        };
+
+        let mut skin = None;
+        let mut component_of_node = vec![None; nodes.len()];
+        let mut stack: Vec<(Option<usize>, usize)> =
+            roots.iter().rev().map(|r| (None, *r)).collect();
+        while let Some((parent, n)) = stack.pop() {
+            let Some(node) = nodes.get(n) else {
+                return Err(Error::BadIndex("node", n));
+            };
+            if component_of_node[n].is_some() {
+                return Err(Error::Malformed(format!(
+                    "node {n} appears more than once in the scene"
+                )));
+            }
+            let mut mesh = match node.mesh {
+                Some(m) if m >= self.meshes.len() => return Err(Error::BadIndex("mesh", m)),
+                Some(m) => self.meshes[m].clone(),
+                None => Mesh::default(),
+            };
+            if !node.weights.is_empty() {
+                if node.weights.len() != mesh.weights.len() {
+                    return Err(Error::Malformed(format!(
+                        "node {n} has {} weights for {} morph targets",
+                        node.weights.len(),
+                        mesh.weights.len()
+                    )));
+                }
+                mesh.weights = node.weights.clone();
+            }
+            if let Some(s) = node.skin {
+                if s >= self.gltf.skins.len() {
+                    return Err(Error::BadIndex("skin", s));
+                }
+                skin.get_or_insert(s);
+            }
+            let c = self
+                .components
+                .add_node(Component::new(node_transformation(node), mesh));
+            if let Some(parent) = parent {
+                self.components.relate(parent, c);
+            }
+            component_of_node[n] = Some(c);
+            for child in node.children.iter().rev() {
+                stack.push((Some(c), *child));
+            }
+        }
+        self.components.find_roots();
+
+        if let Some(skin) = skin {
+            self.skeleton = Some(self.make_skeleton(skin, &parents)?);
+        }
+        Ok(())
+    }
+
+    //mi make_skeleton
+    /// Create a [Skeleton] from a glTF skin
+    ///
+    /// Each joint is related to its nearest ancestor node that is
+    /// also a joint
+    fn make_skeleton(&self, skin: usize, parents: &[Option<usize>]) -> Result<Skeleton> {
+        let joints = &self.gltf.skins[skin].joints;
+        let mut bone_of_node = HashMap::new();
+        let mut skeleton = Skeleton::new();
+        for (i, j) in joints.iter().enumerate() {
+            let Some(node) = self.gltf.nodes.get(*j) else {
+                return Err(Error::BadIndex("node", *j));
+            };
+            let transformation = node_transformation(node).unwrap_or_default();
+            bone_of_node.insert(*j, skeleton.add_bone(transformation, i));
+        }
+        for j in joints {
+            let mut parent = parents[*j];
+            while let Some(p) = parent {
+                if let Some(pb) = bone_of_node.get(&p) {
+                    skeleton.relate(*pb, bone_of_node[j]);
+                    break;
+                }
+                parent = parents[p];
+            }
+        }
+        skeleton.resolve();
+        Ok(skeleton)
+    }
+
+    //zz All done
+}
@@ -0,0 +1,80 @@
+//a Imports
+use crate::AccessorError;
+
+//a Error
+//tp Error
+/// An error in reading or interpreting a glTF file
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error in reading the file or one of its buffers
+    Io(std::io::Error),
+    /// The JSON could not be parsed as a glTF document
+    Json(serde_json::Error),
+    /// A reference (of the given kind) to an index that does not exist
+    BadIndex(&'static str, usize),
+    /// Data that does not fit within its buffer, buffer view or accessor
+    BadRange(String),
+    /// The file is not a valid glTF file
+    Malformed(String),
+    /// The file uses a feature that is not supported
+    Unsupported(String),
+}
+
+//tp Result
+/// The [Result] type for the glTF module
+pub type Result<T> = std::result::Result<T, Error>;
+
+//ip Display for Error
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Json(e) => write!(f, "JSON error: {e}"),
+            Self::BadIndex(kind, index) => {
+                write!(f, "reference to {kind} {index} which does not exist")
+            }
+            Self::BadRange(s) => write!(f, "data out of range: {s}"),
+            Self::Malformed(s) => write!(f, "malformed glTF: {s}"),
+            Self::Unsupported(s) => write!(f, "unsupported glTF feature: {s}"),
+        }
+    }
+}
+
+//ip Error for Error
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+//ip From<AccessorError> for Error
+impl From<AccessorError> for Error {
+    fn from(e: AccessorError) -> Self {
+        match e {
+            AccessorError::Unsupported(..)
+            | AccessorError::NotNormalizable(..)
+            | AccessorError::MissingAttr(..) => Self::Unsupported(e.to_string()),
+            AccessorError::OutOfRange(..)
+            | AccessorError::BadBounds(..)
+            | AccessorError::BadIndex(..) => Self::BadRange(e.to_string()),
+        }
+    }
+}
+
+//ip From<std::io::Error> for Error
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+//ip From<serde_json::Error> for Error
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
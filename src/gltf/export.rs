@@ -0,0 +1,719 @@
+//a Imports
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use super::{base64_encode, semantic, Error, Glb, Gltf, Result};
+use super::{json, png};
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, Material, MaterialAspect, Object, Renderable,
+    Skeleton, Texture, Transformation, VertexAttr, VertexLayout, Vertices,
+};
+
+//a Helper functions
+//fp component_type
+/// Get the glTF accessor component type for a [BufferElementType]
+pub fn component_type(ele_type: BufferElementType) -> Result<u32> {
+    use BufferElementType::*;
+    match ele_type {
+        Int8 => Ok(5120),
+        UInt8 => Ok(5121),
+        Int16 => Ok(5122),
+        UInt16 => Ok(5123),
+        UInt32 => Ok(5125),
+        Float32 => Ok(5126),
+        Int32 => Err(Error::Unsupported("signed 32-bit integer accessors".into())),
+        Float16 => Err(Error::Unsupported("16-bit float accessors".into())),
+    }
+}
+
+//fi accessor_type
+/// Get the glTF accessor type for a number of components
+fn accessor_type(components: u32) -> Result<&'static str> {
+    match components {
+        1 => Ok("SCALAR"),
+        2 => Ok("VEC2"),
+        3 => Ok("VEC3"),
+        4 => Ok("VEC4"),
+        9 => Ok("MAT3"),
+        16 => Ok("MAT4"),
+        _ => Err(Error::Unsupported(format!(
+            "accessor with {components} components"
+        ))),
+    }
+}
+
+//fi color_f32
+/// Convert a u8 color component to a float from 0.0 to 1.0
+fn color_f32(c: u8) -> f32 {
+    (c as f32) / 255.0
+}
+
+//fi set_node_trs
+/// Set the translation, rotation and scale of a node from a
+/// [Transformation]
+fn set_node_trs(node: &mut json::Node, mut t: Transformation) {
+    node.translation = Some(t.translation());
+    node.rotation = Some(t.rotation());
+    node.scale = Some(t.scale());
+}
+
+//fi address
+/// Get the address of a [BufferData], used to identify it when it is
+/// shared between accessors
+fn address<R: Renderable>(data: &BufferData<R>) -> usize {
+    data as *const BufferData<R> as usize
+}
+
+//fi exportable_layout
+/// Return true if the byte offset and stride of a vertex attribute
+/// are allowed by glTF - multiples of 4, with a stride of at most 252
+fn exportable_layout<R: Renderable>(accessor: &BufferAccessor<R>) -> bool {
+    accessor.byte_offset.is_multiple_of(4)
+        && accessor.element_stride().is_multiple_of(4)
+        && accessor.stride <= 252
+}
+
+//fi float_bounds
+/// Find the per-component minimum and maximum of a float accessor
+/// for a number of elements
+fn float_bounds<R: Renderable>(
+    accessor: &BufferAccessor<R>,
+    count: usize,
+) -> Result<(Vec<f64>, Vec<f64>)> {
+    let components = accessor.elements_per_data as usize;
+    let mut min = vec![f64::MAX; components];
+    let mut max = vec![f64::MIN; components];
+    for element in accessor.iter_elements::<f32, 4>(count)? {
+        for c in 0..components {
+            min[c] = min[c].min(element[c] as f64);
+            max[c] = max[c].max(element[c] as f64);
+        }
+    }
+    Ok((min, max))
+}
+
+//a ObjectExporter
+//tp Attributes
+/// A glTF attributes map, from semantic to accessor index
+type Attributes = BTreeMap<String, usize>;
+
+//tp ObjectExporter
+/// An exporter of [Object]s to glTF
+///
+/// Each [Object] added to the exporter becomes a glTF scene; its
+/// components become nodes (with their meshes), its materials become
+/// glTF materials (with textures as PNG images), its skeleton
+/// becomes a skin, and the data of its [Vertices] is packed into a
+/// single binary buffer.
+///
+/// Each [BufferData] used by the object is copied once in to the
+/// binary buffer, and the [BufferAccessor]s become accessors on
+/// buffer views of that data; the indices for each [crate::Primitive]
+/// become an accessor for just the range of indices that the
+/// primitive uses.
+///
+/// Once all the objects have been added, the glTF can be written as
+/// a GLB file or as glTF JSON
+#[derive(Debug, Default)]
+pub struct ObjectExporter {
+    /// The glTF document being built
+    gltf: Gltf,
+    /// The binary buffer being built
+    bin: Vec<u8>,
+}
+
+//ti ObjectState
+/// State used in adding a single object to the exporter
+struct ObjectState {
+    /// Offset in the binary buffer for each [BufferData], by address
+    data_offsets: HashMap<usize, usize>,
+    /// Buffer view for (data address, stride, is indices)
+    views: HashMap<(usize, u32, bool), usize>,
+    /// Accessor for (attribute accessor address, count)
+    accessors: HashMap<(usize, usize), usize>,
+}
+
+//ip ObjectExporter
+impl ObjectExporter {
+    //fp new
+    /// Create a new [ObjectExporter]
+    pub fn new() -> Self {
+        let gltf = Gltf {
+            asset: json::Asset {
+                version: "2.0".into(),
+                generator: Some(format!("mod3d-base {}", env!("CARGO_PKG_VERSION"))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        Self {
+            gltf,
+            bin: Vec::new(),
+        }
+    }
+
+    //ap gltf
+    /// Borrow the glTF document as it stands
+    pub fn gltf(&self) -> &Gltf {
+        &self.gltf
+    }
+
+    //ap bin
+    /// Borrow the binary buffer as it stands
+    pub fn bin(&self) -> &[u8] {
+        &self.bin
+    }
+
+    //mp add_object
+    /// Add an [Object] to the glTF as a new scene, returning the scene
+    /// index
+    ///
+    /// The first scene added is the default scene
+    ///
+    /// [Vertices] whose attributes are not aligned as glTF requires are
+    /// exported repacked; [Vertices] with no vertices, and primitives
+    /// with no indices, are omitted (as glTF accessors cannot be
+    /// empty)
+    pub fn add_object<M, R>(&mut self, object: &Object<M, R>) -> Result<usize>
+    where
+        M: Material,
+        R: Renderable,
+    {
+        let mut state = ObjectState {
+            data_offsets: HashMap::new(),
+            views: HashMap::new(),
+            accessors: HashMap::new(),
+        };
+        let texture_base = self.gltf.textures.len();
+        for t in &object.textures {
+            self.add_texture(t)?;
+        }
+        let material_base = self.gltf.materials.len();
+        for m in &object.materials {
+            self.add_material(&**m, texture_base, object.textures.len())?;
+        }
+        // The repacked vertices must live until the object is added, as
+        // the state identifies data and accessors by address
+        let mut repacked: Vec<Option<Vertices<R>>> = Vec::new();
+        for v in &object.vertices {
+            let aligned = std::iter::once(v.borrow_position())
+                .chain(v.iter_attrs().map(|(_, a)| a))
+                .chain(
+                    v.iter_morph_targets()
+                        .flat_map(|t| t.iter_deltas().map(|(_, a)| a)),
+                )
+                .all(exportable_layout);
+            repacked.push(if aligned {
+                None
+            } else {
+                Some(v.repack(VertexLayout::Planar)?)
+            });
+        }
+        let vertices: Vec<&Vertices<R>> = object
+            .vertices
+            .iter()
+            .zip(&repacked)
+            .map(|(v, r)| r.as_ref().unwrap_or(&**v))
+            .collect();
+        let mut attributes = Vec::new();
+        for v in &vertices {
+            attributes.push(self.add_vertices(&mut state, v)?);
+        }
+        let skin = object.skeleton.as_ref().map(|_| self.gltf.skins.len());
+
+        let components = &object.components;
+        let elements = components.borrow_elements();
+        let node_base = self.gltf.nodes.len();
+        for e in elements {
+            let component = &e.data;
+            let mut node = json::Node {
+                children: e.children().iter().map(|c| node_base + c).collect(),
+                ..Default::default()
+            };
+            if let Some(t) = component.transformation {
+                set_node_trs(&mut node, t);
+            }
+            if !component.mesh.primitives.is_empty() {
+                let mut mesh = json::Mesh::default();
+                for p in &component.mesh.primitives {
+                    let Some(v) = p.vertices_index().into() else {
+                        continue;
+                    };
+                    let v: usize = v;
+                    let Some((attributes, targets)) = &attributes[v] else {
+                        continue;
+                    };
+                    let indices = match p.vertex_range() {
+                        None if p.index_count() == 0 => continue,
+                        None => Some(self.add_indices(
+                            &mut state,
+                            vertices[v],
+                            p.byte_offset(),
+                            p.index_count(),
+                        )?),
+                        Some(range) => {
+                            Self::check_vertex_range(vertices[v], range)?;
+                            None
+                        }
+                    };
+                    if attributes.contains_key("JOINTS_0") {
+                        node.skin = skin;
+                    }
+                    let material: Option<usize> = p.material_index().into();
+                    mesh.primitives.push(json::Primitive {
+                        attributes: attributes.clone(),
+                        indices,
+                        material: material.map(|m| m + material_base),
+                        mode: p.primitive_type() as u8,
+                        targets: targets.clone(),
+                    });
+                }
+                if !mesh.primitives.is_empty() {
+                    mesh.weights = component.mesh.weights.clone();
+                    node.mesh = Some(self.gltf.meshes.len());
+                    self.gltf.meshes.push(mesh);
+                }
+            }
+            self.gltf.nodes.push(node);
+        }
+
+        let mut scene = json::Scene::default();
+        for (root, e) in elements.iter().enumerate() {
+            if !e.has_parent() {
+                scene.nodes.push(node_base + root);
+            }
+        }
+        if let Some(skeleton) = &object.skeleton {
+            let roots = self.add_skeleton(skeleton)?;
+            scene.nodes.extend(roots);
+        }
+        let scene_index = self.gltf.scenes.len();
+        self.gltf.scenes.push(scene);
+        self.gltf.scene.get_or_insert(scene_index);
+        Ok(scene_index)
+    }
+
+    //mi add_bin
+    /// Add some bytes to the binary buffer, returning a new buffer view
+    /// for them
+    fn add_bin(&mut self, bytes: &[u8]) -> usize {
+        let byte_offset = self.bin.len();
+        self.bin.extend_from_slice(bytes);
+        self.bin.resize((self.bin.len() + 3) & !3, 0);
+        let view = self.gltf.buffer_views.len();
+        self.gltf.buffer_views.push(json::BufferView {
+            buffer: 0,
+            byte_offset,
+            byte_length: bytes.len(),
+            ..Default::default()
+        });
+        view
+    }
+
+    //mi add_texture
+    /// Add a texture to the glTF, with its image as a PNG in the
+    /// binary buffer
+    ///
+    /// Only 2D textures of 8-bit data can be exported
+    fn add_texture<R: Renderable>(&mut self, texture: &Texture<R>) -> Result<()> {
+        let (width, height, depth) = *texture.dims();
+        let (channels, ele_type) = texture.data_type();
+        if ele_type != BufferElementType::UInt8 || height == 0 || depth != 0 {
+            return Err(Error::Unsupported(format!(
+                "export of {width}x{height}x{depth} texture of {ele_type:?}"
+            )));
+        }
+        let png = png::encode(texture.data(), width, height, channels as usize)?;
+        let buffer_view = self.add_bin(&png);
+        let source = self.gltf.images.len();
+        self.gltf.images.push(json::Image {
+            mime_type: Some("image/png".into()),
+            buffer_view: Some(buffer_view),
+            ..Default::default()
+        });
+        self.gltf.textures.push(json::Texture {
+            source: Some(source),
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    //mi add_material
+    /// Add a material to the glTF, given the glTF index of the
+    /// object's first texture and the number of textures the object
+    /// has
+    fn add_material<M: Material>(
+        &mut self,
+        material: &M,
+        texture_base: usize,
+        num_textures: usize,
+    ) -> Result<()> {
+        let texture = |aspect| -> Result<Option<json::TextureInfo>> {
+            let index: Option<usize> = material.texture(aspect).into();
+            match index {
+                None => Ok(None),
+                Some(t) if t >= num_textures => Err(Error::BadIndex("texture", t)),
+                Some(t) => Ok(Some(json::TextureInfo {
+                    index: texture_base + t,
+                    ..Default::default()
+                })),
+            }
+        };
+        let base_data = material.base_data();
+        let (r, g, b, a) = base_data.rgba_tuple();
+        let (metallic, roughness) = base_data.metallic_roughness();
+        let e = base_data.emissive_rgb;
+        let pbr = json::PbrMetallicRoughness {
+            base_color_factor: [color_f32(r), color_f32(g), color_f32(b), color_f32(a)],
+            base_color_texture: texture(MaterialAspect::Color)?,
+            metallic_factor: metallic,
+            roughness_factor: roughness,
+            metallic_roughness_texture: texture(MaterialAspect::MetallicRoughness)?,
+        };
+        self.gltf.materials.push(json::Material {
+            pbr_metallic_roughness: Some(pbr),
+            normal_texture: texture(MaterialAspect::Normal)?,
+            occlusion_texture: texture(MaterialAspect::Occlusion)?,
+            emissive_texture: texture(MaterialAspect::Emission)?,
+            emissive_factor: [
+                color_f32(e as u8),
+                color_f32((e >> 8) as u8),
+                color_f32((e >> 16) as u8),
+            ],
+            ..Default::default()
+        });
+        Ok(())
+    }
+
+    //mi add_skeleton
+    /// Add the bones of a [Skeleton] as nodes, and a skin whose joints
+    /// are those nodes in matrix index order, returning the nodes for
+    /// the root bones
+    ///
+    /// The inverse bind matrices are the mesh-to-bone matrices of the
+    /// bones at rest
+    fn add_skeleton(&mut self, skeleton: &Skeleton) -> Result<Vec<usize>> {
+        let mut skeleton = skeleton.clone();
+        skeleton.resolve();
+        skeleton.derive_matrices();
+        let node_base = self.gltf.nodes.len();
+        let mut joints = vec![None; skeleton.skeleton.len()];
+        for (n, b) in skeleton.skeleton.borrow_elements().iter().enumerate() {
+            let mut node = json::Node {
+                children: b.children().iter().map(|c| node_base + c).collect(),
+                ..Default::default()
+            };
+            set_node_trs(&mut node, b.data.transformation);
+            self.gltf.nodes.push(node);
+            match joints.get_mut(b.data.matrix_index) {
+                Some(j @ None) => *j = Some(n),
+                _ => {
+                    return Err(Error::Unsupported(format!(
+                        "bone {n} has a matrix index {} that is duplicated or out of range",
+                        b.data.matrix_index
+                    )))
+                }
+            }
+        }
+        let mut ibms = Vec::with_capacity(joints.len() * 64);
+        for j in joints.iter().flatten() {
+            for f in skeleton.skeleton.borrow_node(*j).borrow_mtb() {
+                ibms.extend_from_slice(&f.to_le_bytes());
+            }
+        }
+        let buffer_view = self.add_bin(&ibms);
+        let inverse_bind_matrices = self.gltf.accessors.len();
+        self.gltf.accessors.push(json::Accessor {
+            buffer_view: Some(buffer_view),
+            component_type: 5126,
+            count: joints.len(),
+            type_: "MAT4".into(),
+            ..Default::default()
+        });
+        let roots: Vec<usize> = skeleton.iter_roots().map(|r| node_base + r).collect();
+        self.gltf.skins.push(json::Skin {
+            inverse_bind_matrices: Some(inverse_bind_matrices),
+            skeleton: if roots.len() == 1 {
+                Some(roots[0])
+            } else {
+                None
+            },
+            joints: joints.iter().flatten().map(|j| node_base + j).collect(),
+            name: None,
+        });
+        Ok(roots)
+    }
+
+    //mi add_data
+    /// Add a [BufferData] to the binary buffer (if it has not already
+    /// been added) and return its offset in the buffer
+    fn add_data<R: Renderable>(&mut self, state: &mut ObjectState, data: &BufferData<R>) -> usize {
+        *state.data_offsets.entry(address(data)).or_insert_with(|| {
+            let offset = self.bin.len();
+            self.bin.extend_from_slice(data.as_slice());
+            self.bin.resize((self.bin.len() + 3) & !3, 0);
+            offset
+        })
+    }
+
+    //mi add_view
+    /// Add a buffer view for a [BufferAccessor] (if there is not one
+    /// already for its data and stride) and return its index
+    fn add_view<R: Renderable>(
+        &mut self,
+        state: &mut ObjectState,
+        accessor: &BufferAccessor<R>,
+        is_indices: bool,
+    ) -> usize {
+        let stride = if is_indices { 0 } else { accessor.stride };
+        let key = (address(&accessor.data), stride, is_indices);
+        if let Some(view) = state.views.get(&key) {
+            return *view;
+        }
+        let byte_offset = self.add_data(state, &accessor.data);
+        let view = self.gltf.buffer_views.len();
+        self.gltf.buffer_views.push(json::BufferView {
+            buffer: 0,
+            byte_offset,
+            byte_length: accessor.data.byte_length as usize,
+            byte_stride: if stride == 0 {
+                None
+            } else {
+                Some(stride as usize)
+            },
+            target: Some(if is_indices { 34963 } else { 34962 }),
+            name: None,
+        });
+        state.views.insert(key, view);
+        view
+    }
+
+    //mi add_vertices
+    /// Add accessors for the attributes of a [Vertices] and the deltas
+    /// of its morph targets, returning the glTF attributes map and
+    /// targets for primitives that use it
+    ///
+    /// The number of vertices is one more than the largest index in
+    /// the indices of the [Vertices], or the count of the position
+    /// accessor if the [Vertices] has no indices; if there are no
+    /// vertices then nothing is added, and None is returned
+    fn add_vertices<R: Renderable>(
+        &mut self,
+        state: &mut ObjectState,
+        vertices: &Vertices<R>,
+    ) -> Result<Option<(Attributes, Vec<Attributes>)>> {
+        let count = match vertices.borrow_indices() {
+            Some(indices) => indices
+                .iter_indices()?
+                .map(|i| i as usize + 1)
+                .max()
+                .unwrap_or(0),
+            None => vertices.borrow_position().count as usize,
+        };
+        if count == 0 {
+            return Ok(None);
+        }
+        let position = vertices.borrow_position();
+        let all_attrs: Vec<_> = std::iter::once((VertexAttr::Position, position))
+            .chain(vertices.iter_attrs())
+            .collect();
+        let attributes = self.add_attributes(state, &all_attrs, count)?;
+        let mut targets = Vec::new();
+        for target in vertices.iter_morph_targets() {
+            let deltas: Vec<_> = target.iter_deltas().collect();
+            targets.push(self.add_attributes(state, &deltas, count)?);
+        }
+        Ok(Some((attributes, targets)))
+    }
+
+    //mi add_attributes
+    /// Add accessors for the first `count` elements of attributes,
+    /// returning the glTF attributes map for them
+    fn add_attributes<R: Renderable>(
+        &mut self,
+        state: &mut ObjectState,
+        attrs: &[(VertexAttr, &BufferAccessor<R>)],
+        count: usize,
+    ) -> Result<Attributes> {
+        let mut attributes = BTreeMap::new();
+        for (attr, accessor) in attrs.iter().copied() {
+            let Some(semantic) = semantic(attr) else {
+                continue;
+            };
+            let key = (accessor as *const BufferAccessor<R> as usize, count);
+            let index = match state.accessors.get(&key) {
+                Some(index) => *index,
+                None => {
+                    let buffer_view = self.add_view(state, accessor, false);
+                    let (min, max) = {
+                        if attr == VertexAttr::Position {
+                            float_bounds(accessor, count)?
+                        } else if count == accessor.count as usize {
+                            (
+                                accessor.min.clone().unwrap_or_default(),
+                                accessor.max.clone().unwrap_or_default(),
+                            )
+                        } else {
+                            (vec![], vec![])
+                        }
+                    };
+                    let index = self.gltf.accessors.len();
+                    self.gltf.accessors.push(json::Accessor {
+                        buffer_view: Some(buffer_view),
+                        byte_offset: accessor.byte_offset as usize,
+                        component_type: component_type(accessor.ele_type)?,
+                        normalized: accessor.normalized,
+                        count,
+                        type_: accessor_type(accessor.elements_per_data)?.into(),
+                        min,
+                        max,
+                        name: None,
+                    });
+                    state.accessors.insert(key, index);
+                    index
+                }
+            };
+            attributes.insert(semantic.to_string(), index);
+        }
+        Ok(attributes)
+    }
+
+    //mi add_indices
+    /// Add an accessor for the range of indices used by a primitive
+    fn add_indices<R: Renderable>(
+        &mut self,
+        state: &mut ObjectState,
+        vertices: &Vertices<R>,
+        byte_offset: u32,
+        index_count: u32,
+    ) -> Result<usize> {
+        let Some(indices) = vertices.borrow_indices() else {
+            return Err(Error::BadRange(
+                "indexed primitive uses vertices without indices".into(),
+            ));
+        };
+        if !indices.ele_type.is_unsigned() {
+            return Err(Error::Unsupported(format!(
+                "indices of {:?}",
+                indices.ele_type
+            )));
+        }
+        let size = indices.ele_type.byte_length() as u32;
+        if !byte_offset.is_multiple_of(size)
+            || byte_offset / size + index_count > indices.elements_per_data
+        {
+            return Err(Error::BadRange(format!(
+                "primitive indices {byte_offset}+#{index_count} are not within the {} indices of its vertices",
+                indices.elements_per_data
+            )));
+        }
+        let buffer_view = self.add_view(state, indices, true);
+        let index = self.gltf.accessors.len();
+        self.gltf.accessors.push(json::Accessor {
+            buffer_view: Some(buffer_view),
+            byte_offset: (indices.byte_offset + byte_offset) as usize,
+            component_type: component_type(indices.ele_type)?,
+            count: index_count as usize,
+            type_: "SCALAR".into(),
+            ..Default::default()
+        });
+        Ok(index)
+    }
+
+    //mi check_vertex_range
+    /// Check that a vertex range primitive draws all of the vertices
+    /// of a [Vertices] without indices
+    ///
+    /// A glTF primitive without indices draws every vertex of its
+    /// attribute accessors, so other ranges cannot be exported
+    fn check_vertex_range<R: Renderable>(
+        vertices: &Vertices<R>,
+        (first, count): (u32, u32),
+    ) -> Result<()> {
+        if vertices.is_indexed() {
+            return Err(Error::Unsupported(
+                "vertex range primitive of indexed vertices".into(),
+            ));
+        }
+        if first != 0 || count != vertices.borrow_position().count {
+            return Err(Error::Unsupported(format!(
+                "vertex range {first}+#{count} that is not all {} vertices",
+                vertices.borrow_position().count
+            )));
+        }
+        Ok(())
+    }
+
+    //mi complete
+    /// Complete the glTF by adding the binary buffer (if it is not
+    /// empty) with the given URI
+    fn complete(&mut self, uri: Option<String>) {
+        if !self.bin.is_empty() {
+            self.gltf.buffers = vec![json::Buffer {
+                uri,
+                byte_length: self.bin.len(),
+                ..Default::default()
+            }];
+        }
+    }
+
+    //dp into_glb
+    /// Complete the glTF and generate the contents of a GLB file
+    pub fn into_glb(mut self) -> Result<Vec<u8>> {
+        self.complete(None);
+        let json = self.gltf.to_json()?;
+        Ok(Glb::write(&json, &self.bin))
+    }
+
+    //dp into_gltf
+    /// Complete the glTF and generate the JSON of a '.gltf' file and
+    /// the contents of its binary buffer
+    ///
+    /// If a URI for the binary buffer is given then the binary data
+    /// must be written there by the caller; otherwise it is embedded
+    /// in the JSON as a base64 'data:' URI (and the returned binary
+    /// data may be ignored)
+    pub fn into_gltf(mut self, bin_uri: Option<&str>) -> Result<(String, Vec<u8>)> {
+        let uri = match bin_uri {
+            Some(uri) => uri.to_string(),
+            None => format!(
+                "data:application/octet-stream;base64,{}",
+                base64_encode(&self.bin)
+            ),
+        };
+        self.complete(Some(uri));
+        let json = self.gltf.to_json_pretty()?;
+        Ok((json, self.bin))
+    }
+
+    //dp write_file
+    /// Complete the glTF and write it to a file
+    ///
+    /// If the file has a '.glb' extension then a GLB file is written;
+    /// otherwise a '.gltf' JSON file is written, with its binary
+    /// buffer in a '.bin' file alongside it
+    pub fn write_file<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let is_glb = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("glb"));
+        if is_glb {
+            std::fs::write(path, self.into_glb()?)?;
+            return Ok(());
+        }
+        let bin_path = path.with_extension("bin");
+        let bin_uri = bin_path
+            .file_name()
+            .and_then(|f| f.to_str())
+            .ok_or_else(|| Error::Malformed(format!("bad glTF path {}", path.display())))?
+            .to_string();
+        let (json, bin) = self.into_gltf(Some(&bin_uri))?;
+        std::fs::write(path, json)?;
+        if !bin.is_empty() {
+            std::fs::write(bin_path, bin)?;
+        }
+        Ok(())
+    }
+
+    //zz All done
+}
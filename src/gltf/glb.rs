@@ -0,0 +1,171 @@
+//a Imports
+use std::ops::Range;
+
+use super::{Error, Result};
+use crate::ByteBuffer;
+
+//a Constants
+/// Magic number at the start of a GLB file ('glTF')
+pub const GLB_MAGIC: u32 = 0x46546C67;
+/// Chunk type of the JSON chunk ('JSON')
+const CHUNK_JSON: u32 = 0x4E4F534A;
+/// Chunk type of the binary chunk ('BIN\0')
+const CHUNK_BIN: u32 = 0x004E4942;
+
+//fi read_u32
+/// Read a little-endian u32 from a byte slice at an offset
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+//a Glb
+//tp Glb
+/// The contents of a GLB (binary glTF) file
+///
+/// This owns the bytes of the whole file, and records where the JSON
+/// and (optional) BIN chunks are within it.
+///
+/// The [ByteBuffer] implementation provides the BIN chunk, so that
+/// [crate::BufferData] can refer directly to the file contents
+/// without copying
+#[derive(Debug)]
+pub struct Glb {
+    /// The complete file contents
+    bytes: Vec<u8>,
+    /// The range of the JSON chunk data
+    json: Range<usize>,
+    /// The range of the BIN chunk data, if there is one
+    bin: Option<Range<usize>>,
+}
+
+//ip Glb
+impl Glb {
+    //fp from_bytes
+    /// Parse the contents of a GLB file, checking the 12-byte header
+    /// and the chunk structure
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        if bytes.len() < 20 {
+            return Err(Error::Malformed("GLB file is too short".into()));
+        }
+        if read_u32(&bytes, 0) != GLB_MAGIC {
+            return Err(Error::Malformed(
+                "GLB file does not start with 'glTF'".into(),
+            ));
+        }
+        let version = read_u32(&bytes, 4);
+        if version != 2 {
+            return Err(Error::Unsupported(format!("GLB version {version}")));
+        }
+        let length = read_u32(&bytes, 8) as usize;
+        if length > bytes.len() {
+            return Err(Error::BadRange(format!(
+                "GLB header length {length} but the file has only {} bytes",
+                bytes.len()
+            )));
+        }
+        let mut chunks = Vec::new();
+        let mut offset = 12;
+        while offset < length {
+            if offset + 8 > length {
+                return Err(Error::BadRange(format!(
+                    "GLB chunk header at {offset} extends beyond the end of the file"
+                )));
+            }
+            let chunk_length = read_u32(&bytes, offset) as usize;
+            let chunk_type = read_u32(&bytes, offset + 4);
+            let start = offset + 8;
+            if start + chunk_length > length {
+                return Err(Error::BadRange(format!(
+                    "GLB chunk at {offset} of length {chunk_length} extends beyond the end of the file"
+                )));
+            }
+            chunks.push((chunk_type, start..start + chunk_length));
+            offset = start + chunk_length;
+        }
+        let json = match chunks.first() {
+            Some((CHUNK_JSON, range)) => range.clone(),
+            _ => return Err(Error::Malformed("GLB first chunk is not JSON".into())),
+        };
+        let bin = match chunks.get(1) {
+            Some((CHUNK_BIN, range)) => Some(range.clone()),
+            _ => None,
+        };
+        Ok(Self { bytes, json, bin })
+    }
+
+    //ap json_str
+    /// Get the JSON chunk as a string
+    pub fn json_str(&self) -> Result<&str> {
+        std::str::from_utf8(&self.bytes[self.json.clone()])
+            .map_err(|_| Error::Malformed("GLB JSON chunk is not UTF-8".into()))
+    }
+
+    //ap has_bin
+    /// Return true if the GLB file has a BIN chunk
+    pub fn has_bin(&self) -> bool {
+        self.bin.is_some()
+    }
+
+    //ap bin
+    /// Borrow the BIN chunk data (empty if there is none)
+    pub fn bin(&self) -> &[u8] {
+        match &self.bin {
+            Some(range) => &self.bytes[range.clone()],
+            None => &[],
+        }
+    }
+
+    //fp write
+    /// Create the contents of a GLB file given the JSON and the BIN
+    /// chunk data (which may be empty, in which case no BIN chunk is
+    /// written)
+    ///
+    /// The JSON is padded with spaces, and the binary data with
+    /// zeros, to a four byte boundary
+    pub fn write(json: &str, bin: &[u8]) -> Vec<u8> {
+        let json_length = (json.len() + 3) & !3;
+        let bin_length = (bin.len() + 3) & !3;
+        let mut length = 12 + 8 + json_length;
+        if !bin.is_empty() {
+            length += 8 + bin_length;
+        }
+        let mut bytes = Vec::with_capacity(length);
+        bytes.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&2_u32.to_le_bytes());
+        bytes.extend_from_slice(&(length as u32).to_le_bytes());
+        bytes.extend_from_slice(&(json_length as u32).to_le_bytes());
+        bytes.extend_from_slice(&CHUNK_JSON.to_le_bytes());
+        bytes.extend_from_slice(json.as_bytes());
+        bytes.resize(20 + json_length, b' ');
+        if !bin.is_empty() {
+            bytes.extend_from_slice(&(bin_length as u32).to_le_bytes());
+            bytes.extend_from_slice(&CHUNK_BIN.to_le_bytes());
+            bytes.extend_from_slice(bin);
+            bytes.resize(length, 0);
+        }
+        bytes
+    }
+
+    //zz All done
+}
+
+//ip ByteBuffer for Glb
+/// The [ByteBuffer] of a [Glb] is its BIN chunk
+impl ByteBuffer for Glb {
+    //fp byte_length
+    fn byte_length(&self) -> usize {
+        self.bin().len()
+    }
+
+    //fp borrow_bytes
+    fn borrow_bytes(&self) -> &[u8] {
+        self.bin()
+    }
+
+    //fp as_u8_ptr
+    fn as_u8_ptr(&self) -> *const u8 {
+        self.bin().as_ptr()
+    }
+
+    //zz All done
+}
@@ -0,0 +1,479 @@
+//a Documentation
+/*!
+
+The glTF 2.0 JSON document model
+
+These types mirror the glTF 2.0 schema for the properties that the 3D
+model library makes use of; other properties (animations, cameras,
+extensions, extras) are ignored when the JSON is parsed.
+
+Indices within the document are plain [usize] values, as in the JSON
+itself; they are validated when a [crate::gltf::GltfAsset] is
+created.
+
+!*/
+
+//a Imports
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Error, Result};
+
+//a Helper functions
+//fi is_default
+/// Return true if the value is the default for its type (for skipping on serialization)
+fn is_default<T: Default + PartialEq>(t: &T) -> bool {
+    *t == T::default()
+}
+
+//fi is_one
+/// Return true if the value is 1.0 (for skipping on serialization)
+fn is_one(f: &f32) -> bool {
+    *f == 1.0
+}
+
+//fi one
+/// Default value of 1.0
+fn one() -> f32 {
+    1.0
+}
+
+//fi ones4
+/// Default value of [1.0; 4]
+fn ones4() -> [f32; 4] {
+    [1.0; 4]
+}
+
+//fi is_ones4
+/// Return true if the value is [1.0; 4] (for skipping on serialization)
+fn is_ones4(f: &[f32; 4]) -> bool {
+    *f == [1.0; 4]
+}
+
+//fi triangles_mode
+/// Default primitive mode (triangles)
+fn triangles_mode() -> u8 {
+    4
+}
+
+//fi is_triangles_mode
+/// Return true if the mode is the default (triangles)
+fn is_triangles_mode(m: &u8) -> bool {
+    *m == 4
+}
+
+//a Asset
+//tp Asset
+/// The 'asset' property of a glTF document
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Asset {
+    /// The glTF version that the asset targets - must be 2.x
+    pub version: String,
+    /// Tool that generated the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    /// Minimum glTF version required to load the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_version: Option<String>,
+    /// Copyright message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+}
+
+//a Buffers
+//tp Buffer
+/// A glTF buffer - a reference to binary data
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Buffer {
+    /// URI of the data (relative path, or a 'data:' URI); None for
+    /// the binary chunk of a GLB file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// Length of the buffer in bytes
+    pub byte_length: usize,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp BufferView
+/// A glTF buffer view - a contiguous region of a [Buffer]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BufferView {
+    /// Index of the buffer
+    pub buffer: usize,
+    /// Offset in to the buffer in bytes
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub byte_offset: usize,
+    /// Length of the view in bytes
+    pub byte_length: usize,
+    /// Stride between vertex attributes, if not tightly packed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub byte_stride: Option<usize>,
+    /// Intended GPU buffer type (34962 for vertex data, 34963 for indices)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<u32>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp Accessor
+/// A glTF accessor - a typed view in to a [BufferView]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Accessor {
+    /// Index of the buffer view; if None the data is all zeros
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer_view: Option<usize>,
+    /// Offset in to the buffer view in bytes
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub byte_offset: usize,
+    /// The component type (5120 to 5126)
+    pub component_type: u32,
+    /// True if integer data is normalized on access
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub normalized: bool,
+    /// Number of elements
+    pub count: usize,
+    /// Type of each element - "SCALAR", "VEC2", ..., "MAT4"
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Maximum value of each component
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub max: Vec<f64>,
+    /// Minimum value of each component
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub min: Vec<f64>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//ip Accessor
+impl Accessor {
+    //ap components
+    /// Get the number of components per element given the accessor type
+    pub fn components(&self) -> Result<u32> {
+        match self.type_.as_str() {
+            "SCALAR" => Ok(1),
+            "VEC2" => Ok(2),
+            "VEC3" => Ok(3),
+            "VEC4" => Ok(4),
+            "MAT2" => Ok(4),
+            "MAT3" => Ok(9),
+            "MAT4" => Ok(16),
+            t => Err(Error::Malformed(format!("unknown accessor type '{t}'"))),
+        }
+    }
+}
+
+//a Meshes
+//tp Primitive
+/// A glTF mesh primitive
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Primitive {
+    /// Map from attribute semantic (e.g. "POSITION") to accessor index
+    pub attributes: BTreeMap<String, usize>,
+    /// Accessor index of the indices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indices: Option<usize>,
+    /// Material index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<usize>,
+    /// Primitive mode; 4 (triangles) by default
+    #[serde(default = "triangles_mode", skip_serializing_if = "is_triangles_mode")]
+    pub mode: u8,
+    /// Morph targets, each a map from attribute semantic to the
+    /// accessor index of its deltas
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<BTreeMap<String, usize>>,
+}
+
+//tp Mesh
+/// A glTF mesh - a set of primitives
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mesh {
+    /// The primitives that make up the mesh
+    pub primitives: Vec<Primitive>,
+    /// Default weights of the morph targets of the primitives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<f32>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//a Nodes and scenes
+//tp Node
+/// A glTF node in the node hierarchy
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    /// Indices of the child nodes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<usize>,
+    /// Index of the mesh for the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mesh: Option<usize>,
+    /// Index of the skin for the node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skin: Option<usize>,
+    /// Weights of the morph targets of the mesh, overriding those of
+    /// the mesh
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<f32>,
+    /// Column-major local transformation matrix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<[f32; 16]>,
+    /// Rotation quaternion (x, y, z, w)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<[f32; 4]>,
+    /// Scale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<[f32; 3]>,
+    /// Translation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<[f32; 3]>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp Scene
+/// A glTF scene - a set of root nodes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scene {
+    /// Indices of the root nodes
+    #[serde(default)]
+    pub nodes: Vec<usize>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp Skin
+/// A glTF skin - a set of joints
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Skin {
+    /// Accessor (of MAT4) of the inverse bind matrices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inverse_bind_matrices: Option<usize>,
+    /// Index of the node used as the skeleton root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skeleton: Option<usize>,
+    /// Node indices of the joints; the joint at index 'n' is
+    /// referred to as 'n' in a JOINTS attribute
+    pub joints: Vec<usize>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//a Materials and textures
+//tp TextureInfo
+/// Reference to a texture from a material
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextureInfo {
+    /// Index of the texture
+    pub index: usize,
+    /// The TEXCOORD_<n> set to use
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub tex_coord: usize,
+    /// Normal texture scale, or occlusion texture strength
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f32>,
+    /// Occlusion texture strength
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<f32>,
+}
+
+//tp PbrMetallicRoughness
+/// The PBR metallic-roughness parameters of a material
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PbrMetallicRoughness {
+    /// Linear RGBA base color factor
+    #[serde(default = "ones4", skip_serializing_if = "is_ones4")]
+    pub base_color_factor: [f32; 4],
+    /// Base color texture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_color_texture: Option<TextureInfo>,
+    /// Metalness factor
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub metallic_factor: f32,
+    /// Roughness factor
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub roughness_factor: f32,
+    /// Metallic-roughness texture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metallic_roughness_texture: Option<TextureInfo>,
+}
+
+//ip Default for PbrMetallicRoughness
+impl Default for PbrMetallicRoughness {
+    fn default() -> Self {
+        Self {
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+        }
+    }
+}
+
+//tp Material
+/// A glTF material
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Material {
+    /// PBR metallic-roughness parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pbr_metallic_roughness: Option<PbrMetallicRoughness>,
+    /// Normal texture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal_texture: Option<TextureInfo>,
+    /// Occlusion texture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occlusion_texture: Option<TextureInfo>,
+    /// Emissive texture
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emissive_texture: Option<TextureInfo>,
+    /// Linear RGB emissive factor
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub emissive_factor: [f32; 3],
+    /// Alpha mode - "OPAQUE", "MASK" or "BLEND"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha_mode: Option<String>,
+    /// Alpha cutoff for MASK mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha_cutoff: Option<f32>,
+    /// True if the material is double sided
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub double_sided: bool,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp Texture
+/// A glTF texture - an image and a sampler
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Texture {
+    /// Index of the sampler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampler: Option<usize>,
+    /// Index of the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<usize>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//tp Image
+/// A glTF image - either a URI or a buffer view, with a MIME type
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Image {
+    /// URI of the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    /// MIME type of the image (if it is in a buffer view)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Buffer view containing the image
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer_view: Option<usize>,
+    /// Optional name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+//a Gltf
+//tp Gltf
+/// The root of a glTF 2.0 JSON document
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gltf {
+    /// Metadata about the asset
+    pub asset: Asset,
+    /// Index of the default scene
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene: Option<usize>,
+    /// Scenes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scenes: Vec<Scene>,
+    /// Nodes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<Node>,
+    /// Meshes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub meshes: Vec<Mesh>,
+    /// Accessors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accessors: Vec<Accessor>,
+    /// Buffer views
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buffer_views: Vec<BufferView>,
+    /// Buffers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub buffers: Vec<Buffer>,
+    /// Materials
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub materials: Vec<Material>,
+    /// Textures
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub textures: Vec<Texture>,
+    /// Images
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<Image>,
+    /// Skins
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skins: Vec<Skin>,
+}
+
+//ip Gltf
+impl Gltf {
+    //fp from_json
+    /// Parse a glTF JSON document from a string
+    ///
+    /// This checks only the syntax and the asset version; the
+    /// references between the parts of the document are checked
+    /// when a [crate::gltf::GltfAsset] is created
+    pub fn from_json(json: &str) -> Result<Self> {
+        let gltf: Self = serde_json::from_str(json)?;
+        if !gltf.asset.version.starts_with("2.") {
+            return Err(Error::Unsupported(format!(
+                "glTF version '{}' (only 2.x is supported)",
+                gltf.asset.version
+            )));
+        }
+        Ok(gltf)
+    }
+
+    //mp to_json
+    /// Generate a JSON string for the document
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    //mp to_json_pretty
+    /// Generate an indented JSON string for the document
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    //zz All done
+}
@@ -0,0 +1,94 @@
+//a Imports
+use super::{Error, Result};
+use crate::checksum::crc32;
+
+//a Checksums
+//fi adler32
+/// Calculate the Adler-32 checksum (as used by zlib) of some bytes
+fn adler32(bytes: &[u8]) -> u32 {
+    let mut a = 1_u32;
+    let mut b = 0_u32;
+    for chunk in bytes.chunks(5552) {
+        for x in chunk {
+            a += *x as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+//fi push_chunk
+/// Append a PNG chunk with its length and CRC
+fn push_chunk(png: &mut Vec<u8>, chunk_type: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(chunk_type);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+//fi zlib_stored
+/// Wrap data in a zlib stream of uncompressed ('stored') deflate
+/// blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut z = Vec::with_capacity(data.len() + data.len() / 65535 * 5 + 11);
+    z.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(65535).peekable();
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none() as u8;
+        let len = block.len() as u16;
+        z.push(last);
+        z.extend_from_slice(&len.to_le_bytes());
+        z.extend_from_slice(&(!len).to_le_bytes());
+        z.extend_from_slice(block);
+    }
+    z.extend_from_slice(&adler32(data).to_be_bytes());
+    z
+}
+
+//a Encoding
+//fp encode
+/// Encode 8-bit image data as an (uncompressed) PNG
+///
+/// The data is rows of `width` pixels, each of `channels` bytes (1
+/// for greyscale, 2 for greyscale with alpha, 3 for RGB and 4 for
+/// RGBA)
+pub fn encode(data: &[u8], width: usize, height: usize, channels: usize) -> Result<Vec<u8>> {
+    let color_type = match channels {
+        1 => 0,
+        2 => 4,
+        3 => 2,
+        4 => 6,
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "texture with {channels} channels"
+            )))
+        }
+    };
+    let row_length = width * channels;
+    if width == 0 || height == 0 || data.len() < row_length * height {
+        return Err(Error::BadRange(format!(
+            "texture data of {} bytes is too small for {width}x{height}x{channels}",
+            data.len()
+        )));
+    }
+    let mut raw = Vec::with_capacity((row_length + 1) * height);
+    for row in data.chunks_exact(row_length).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&(width as u32).to_be_bytes());
+    ihdr.extend_from_slice(&(height as u32).to_be_bytes());
+    ihdr.extend_from_slice(&[8, color_type, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    push_chunk(&mut png, b"IHDR", &ihdr);
+    push_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    push_chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
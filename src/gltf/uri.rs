@@ -0,0 +1,120 @@
+//a Imports
+use std::path::Path;
+
+use super::{Error, Result};
+
+//a Base64
+//fi base64_value
+/// Get the 6-bit value of a base64 character
+fn base64_value(c: u8) -> Option<u32> {
+    match c {
+        b'A'..=b'Z' => Some((c - b'A') as u32),
+        b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+        b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+//fp base64_decode
+/// Decode a base64 string (standard or URL-safe alphabet, with
+/// optional padding) into bytes
+pub fn base64_decode(s: &str) -> Result<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len() * 3 / 4);
+    let mut acc = 0_u32;
+    let mut bits = 0;
+    for c in s.bytes() {
+        if c == b'=' {
+            break;
+        }
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        let Some(v) = base64_value(c) else {
+            return Err(Error::Malformed(format!(
+                "bad character '{}' in base64 data",
+                c as char
+            )));
+        };
+        acc = (acc << 6) | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            result.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(result)
+}
+
+//fp base64_encode
+/// Encode bytes as base64 (standard alphabet, with padding)
+pub fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut acc = 0_u32;
+        for (i, b) in chunk.iter().enumerate() {
+            acc |= (*b as u32) << (16 - 8 * i);
+        }
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[((acc >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+//a URI loading
+//fi percent_decode
+/// Decode a percent-encoded relative URI into a path string
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(v) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                result.push(v);
+                i += 3;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+//fp load_uri
+/// Load the data for a URI found in a glTF file
+///
+/// This supports 'data:' URIs with base64 encoding, and paths
+/// relative to the directory containing the glTF file (if a base
+/// directory is given)
+pub fn load_uri(uri: &str, base: Option<&Path>) -> Result<Vec<u8>> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let Some((media, payload)) = data.split_once(',') else {
+            return Err(Error::Malformed("data URI without ','".into()));
+        };
+        if !media.ends_with(";base64") {
+            return Err(Error::Unsupported(format!(
+                "data URI that is not base64 encoded ('{media}')"
+            )));
+        }
+        base64_decode(payload)
+    } else if uri.contains("://") {
+        Err(Error::Unsupported(format!("non-local URI '{uri}'")))
+    } else if let Some(base) = base {
+        Ok(std::fs::read(base.join(percent_decode(uri)))?)
+    } else {
+        Err(Error::Unsupported(format!(
+            "URI '{uri}' with no base directory to load it from"
+        )))
+    }
+}
@@ -5,6 +5,8 @@ This module provides a hierarchy of nodes and iterators over them
 
 //a Imports
 use indent_display::{IndentedDisplay, IndentedOptions, Indenter};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a Constants
 /// Compile-time setting for adding extra debugging information
@@ -14,6 +16,7 @@ const DEBUG_ITERATOR: bool = false;
 //tp Node
 /// A node in the hierarchy
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Node<T>
 where
     T: std::fmt::Debug,
@@ -84,6 +87,12 @@ where
         !self.children.is_empty()
     }
 
+    //ap children
+    /// Borrow the indices of the children of this node
+    pub fn children(&self) -> &[usize] {
+        &self.children
+    }
+
     //zz All done
 }
 
@@ -92,6 +101,7 @@ where
 /// A hierarchy of nodes, each of which has a data of the type of the
 /// tree
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Hierarchy<T>
 where
     T: std::fmt::Debug,
@@ -301,7 +311,7 @@ impl<T> NodeEnumOp<T> {
 /// The recipe is a [Vec] of [NodeEnumOp]s which describe entirely how
 /// to traverse the hierarchy; essentially it is a record of an
 /// enumeration of a hierarchy or part of a hierarchy
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Recipe {
     /// The [NodeEnumOp]s that make up the traversal
     ops: Vec<NodeEnumOp<usize>>,
@@ -1,5 +1,5 @@
 //a Imports
-use crate::{Instantiable, Mat4, Renderable, SkeletonPose, Transformation};
+use crate::{Bounds, Instantiable, Mat4, Renderable, SkeletonPose, Transformation};
 
 //a Instance
 //tp Instance
@@ -29,6 +29,12 @@ where
     pub bone_poses: Vec<SkeletonPose<'a>>,
     /// Transformation matrices for the bones
     pub bone_matrices: Vec<Mat4>,
+    /// The level of detail each mesh with levels of detail in the
+    /// render recipe was last drawn at
+    pub lod_levels: Vec<usize>,
+    /// The morph target weights of each mesh with weights in the
+    /// render recipe, initially the default weights of the meshes
+    pub morph_weights: Vec<Vec<f32>>,
 }
 
 impl<'a, R> Instance<'a, R>
@@ -51,12 +57,30 @@ where
         for _ in 0..num_bone_matrices {
             bone_matrices.push([0.; 16]);
         }
+        let lod_levels = Vec::new();
+        let morph_weights = instantiable
+            .render_recipe
+            .morph_meshes
+            .iter()
+            .map(|m| m.weights.clone())
+            .collect();
         Self {
             instantiable,
             transformation,
             trans_mat,
             bone_poses,
             bone_matrices,
+            lod_levels,
+            morph_weights,
         }
     }
+
+    //mp world_bounds
+    /// Get the bounds of the instance in world space - the bounds of
+    /// its [Instantiable] with its transformation applied - if the
+    /// [Instantiable] has bounds
+    pub fn world_bounds(&self) -> Option<Bounds> {
+        let m = self.transformation.mat4();
+        self.instantiable.bounds.map(|b| b.transformed(&m))
+    }
 }
@@ -2,7 +2,9 @@
 use crate::hierarchy;
 use hierarchy::Hierarchy;
 
-use crate::{Component, Instance, Material, RenderRecipe, Renderable, Skeleton, Texture, Vertices};
+use crate::{
+    Bounds, Component, Instance, Material, RenderRecipe, Renderable, Skeleton, Texture, Vertices,
+};
 
 //a Instantiable
 //tp Instantiable
@@ -38,6 +40,10 @@ where
     pub render_recipe: RenderRecipe,
     /// Number of bone matrices required for all the bone sets in this structure
     pub num_bone_matrices: usize,
+    /// The bounds of the object (in model space), if known; this is
+    /// set when an [crate::Object] is deconstructed into the
+    /// [Instantiable]
+    pub bounds: Option<Bounds>,
 }
 
 //ip Instantiable
@@ -71,6 +77,7 @@ where
             .into_iter()
             .map(|t| t.borrow_client().clone())
             .collect();
+        let bounds = None;
         Self {
             skeleton,
             vertices,
@@ -78,6 +85,7 @@ where
             materials,
             render_recipe,
             num_bone_matrices,
+            bounds,
         }
     }
 
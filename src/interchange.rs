@@ -0,0 +1,54 @@
+//a Documentation
+/*!
+
+# STL, PLY and OFF import and export
+
+This module (enabled with the `interchange` feature) reads and writes
+the simple mesh interchange formats used for 3D printing and
+scanning:
+
+* [stl] - ASCII and binary STL
+
+* [ply] - ASCII and binary PLY, with per-vertex normals and colors
+
+* [off] - OFF, with per-vertex normals and colors
+
+Each format is read into, and written from, a [TriangleMesh]. A
+[TriangleMesh] is turned into the data for an [crate::Object] (with
+a single [crate::Component] and a single [crate::Primitive] of
+triangles) with a [TriangleAsset]; and any [crate::Vertices] and
+[crate::Primitive] combination can be converted to a [TriangleMesh]
+(expanding triangle strips and fans) using
+[TriangleMesh::of_primitive] so that it can be written.
+
+# Example
+
+```ignore
+use mod3d_base::interchange::{stl, TriangleAsset, TriangleMesh};
+use mod3d_base::example_client::Renderable;
+
+let asset = TriangleAsset::from_file("model.stl")?;
+let data = asset.make_buffer_data::<Renderable>();
+let accessors = asset.make_accessors(&data);
+let vertices = asset.make_vertices(&accessors);
+let object = asset.make_object(&vertices);
+
+let primitive = &object.components.borrow_node(0).mesh.primitives[0];
+let mesh = TriangleMesh::of_primitive(&vertices[0], primitive)?;
+std::fs::write("copy.stl", stl::write_binary(&mesh)?)?;
+```
+
+!*/
+
+//a Imports and exports
+mod asset;
+mod error;
+mod triangle_mesh;
+
+pub mod off;
+pub mod ply;
+pub mod stl;
+
+pub use asset::TriangleAsset;
+pub use error::{Error, Result};
+pub use triangle_mesh::TriangleMesh;
@@ -0,0 +1,231 @@
+//a Imports
+use std::path::Path;
+
+use super::{off, ply, stl, Error, Result, TriangleMesh};
+use crate::hierarchy::Hierarchy;
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, Component, Mesh, Object, PbrMaterial, Primitive,
+    PrimitiveType, Renderable, VertexAttr, Vertices,
+};
+
+//a TriangleAsset
+//tp TriangleAsset
+/// A [TriangleMesh] (as read from an STL, PLY or OFF file) converted
+/// to the data for an [Object]
+///
+/// The vertex data is packed into a single buffer of floats -
+/// position, then normal and color (RGBA) if the mesh has them - and
+/// the triangles into a single buffer of 32-bit indices.
+///
+/// The [Object] has a single [Component], with a single [Primitive]
+/// of triangles using a default [PbrMaterial]. As with
+/// [crate::gltf::GltfAsset] it is created in stages, each borrowing
+/// the previous:
+///
+/// ```ignore
+/// let asset = TriangleAsset::from_file("model.stl")?;
+/// let data = asset.make_buffer_data::<R>();
+/// let accessors = asset.make_accessors(&data);
+/// let vertices = asset.make_vertices(&accessors);
+/// let object = asset.make_object(&vertices);
+/// ```
+#[derive(Debug)]
+pub struct TriangleAsset {
+    /// The vertex data
+    vertex_data: Vec<f32>,
+    /// The index data
+    index_data: Vec<u32>,
+    /// Number of floats per vertex
+    floats_per_vertex: usize,
+    /// True if the vertices have normals
+    has_normals: bool,
+    /// True if the vertices have colors
+    has_colors: bool,
+    /// The material
+    material: PbrMaterial,
+    /// The component hierarchy
+    components: Hierarchy<Component>,
+}
+
+//ip TriangleAsset
+impl TriangleAsset {
+    //fp new
+    /// Create a new [TriangleAsset] from a [TriangleMesh], validating
+    /// the mesh
+    pub fn new(mesh: &TriangleMesh) -> Result<Self> {
+        mesh.validate()?;
+        let has_normals = mesh.normals.is_some();
+        let has_colors = mesh.colors.is_some();
+        let floats_per_vertex = 3 + (has_normals as usize) * 3 + (has_colors as usize) * 4;
+        let mut vertex_data = Vec::with_capacity(mesh.positions.len() * floats_per_vertex);
+        for (n, p) in mesh.positions.iter().enumerate() {
+            vertex_data.extend_from_slice(p);
+            if let Some(normals) = &mesh.normals {
+                vertex_data.extend_from_slice(&normals[n]);
+            }
+            if let Some(colors) = &mesh.colors {
+                vertex_data.extend_from_slice(&colors[n]);
+            }
+        }
+        let index_data: Vec<u32> = mesh.triangles.iter().flatten().copied().collect();
+
+        let mut components = Hierarchy::new();
+        if !index_data.is_empty() {
+            let mut mesh = Mesh::default();
+            mesh.add_primitive(Primitive::new(
+                PrimitiveType::Triangles,
+                0.into(),
+                0,
+                index_data.len() as u32,
+                0.into(),
+            ));
+            components.add_node(Component::new(None, mesh));
+            components.find_roots();
+        }
+        Ok(Self {
+            vertex_data,
+            index_data,
+            floats_per_vertex,
+            has_normals,
+            has_colors,
+            material: PbrMaterial::default(),
+            components,
+        })
+    }
+
+    //fp from_file
+    /// Read an STL, PLY or OFF file (by its extension) and create a
+    /// [TriangleAsset]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let read = match extension.as_deref() {
+            Some("stl") => stl::read,
+            Some("ply") => ply::read,
+            Some("off") => off::read,
+            _ => {
+                return Err(Error::Unsupported(format!(
+                    "file type of {}",
+                    path.display()
+                )))
+            }
+        };
+        let bytes = std::fs::read(path)?;
+        Self::new(&read(&bytes)?)
+    }
+
+    //ap components
+    /// Borrow the component hierarchy
+    pub fn components(&self) -> &Hierarchy<Component> {
+        &self.components
+    }
+
+    //mp make_buffer_data
+    /// Create the [BufferData]s for the asset - the indices and the
+    /// vertex data (or none, if there are no triangles)
+    pub fn make_buffer_data<R: Renderable>(&self) -> Vec<BufferData<'_, R>> {
+        if self.index_data.is_empty() {
+            return vec![];
+        }
+        vec![
+            BufferData::new(&self.index_data, 0, 0),
+            BufferData::new(&self.vertex_data, 0, 0),
+        ]
+    }
+
+    //mp make_accessors
+    /// Create the [BufferAccessor]s for the asset, given the
+    /// [BufferData]s created by `make_buffer_data`
+    ///
+    /// These are the indices, positions, and then normals and colors
+    /// if the vertices have them
+    pub fn make_accessors<'a, R: Renderable>(
+        &self,
+        data: &'a [BufferData<'a, R>],
+    ) -> Vec<BufferAccessor<'a, R>> {
+        let mut accessors = Vec::new();
+        if data.is_empty() {
+            return accessors;
+        }
+        let stride = (self.floats_per_vertex * 4) as u32;
+        let count = self.index_data.len() as u32;
+        accessors.push(BufferAccessor::new(
+            &data[0],
+            count,
+            BufferElementType::UInt32,
+            0,
+            0,
+        ));
+        accessors.push(BufferAccessor::new(
+            &data[1],
+            3,
+            BufferElementType::Float32,
+            0,
+            stride,
+        ));
+        let mut offset = 12;
+        if self.has_normals {
+            accessors.push(BufferAccessor::new(
+                &data[1],
+                3,
+                BufferElementType::Float32,
+                offset,
+                stride,
+            ));
+            offset += 12;
+        }
+        if self.has_colors {
+            accessors.push(BufferAccessor::new(
+                &data[1],
+                4,
+                BufferElementType::Float32,
+                offset,
+                stride,
+            ));
+        }
+        accessors
+    }
+
+    //mp make_vertices
+    /// Create the [Vertices] for the asset, given the
+    /// [BufferAccessor]s created by `make_accessors`
+    pub fn make_vertices<'a, R: Renderable>(
+        &self,
+        accessors: &'a [BufferAccessor<'a, R>],
+    ) -> Vec<Vertices<'a, R>> {
+        if accessors.is_empty() {
+            return vec![];
+        }
+        let mut vertices = Vertices::new(&accessors[0], &accessors[1]);
+        let mut n = 2;
+        if self.has_normals {
+            vertices.add_attr(VertexAttr::Normal, &accessors[n]);
+            n += 1;
+        }
+        if self.has_colors {
+            vertices.add_attr(VertexAttr::Color, &accessors[n]);
+        }
+        vec![vertices]
+    }
+
+    //mp make_object
+    /// Create the [Object] for the asset, given the [Vertices]
+    /// created by `make_vertices`
+    pub fn make_object<'a, R: Renderable>(
+        &'a self,
+        vertices: &'a [Vertices<'a, R>],
+    ) -> Object<'a, PbrMaterial, R> {
+        let mut object = Object::new();
+        for v in vertices {
+            object.add_vertices(v);
+        }
+        object.add_material(&self.material);
+        object.components = self.components.clone();
+        object
+    }
+
+    //zz All done
+}
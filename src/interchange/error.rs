@@ -0,0 +1,70 @@
+//a Imports
+use crate::AccessorError;
+
+//a Error
+//tp Error
+/// An error in reading or writing an STL, PLY or OFF file
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error in reading or writing the file
+    Io(std::io::Error),
+    /// The file could not be parsed
+    Syntax(String),
+    /// A reference (of the given kind) to an index that does not exist
+    BadIndex(&'static str, usize),
+    /// Data that does not fit within its file or buffer
+    BadRange(String),
+    /// The file (or the mesh to be written) uses a feature that is not
+    /// supported
+    Unsupported(String),
+}
+
+//tp Result
+/// The [Result] type for the interchange module
+pub type Result<T> = std::result::Result<T, Error>;
+
+//ip Display for Error
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Syntax(s) => write!(f, "syntax error: {s}"),
+            Self::BadIndex(kind, index) => {
+                write!(f, "reference to {kind} {index} which does not exist")
+            }
+            Self::BadRange(s) => write!(f, "data out of range: {s}"),
+            Self::Unsupported(s) => write!(f, "unsupported: {s}"),
+        }
+    }
+}
+
+//ip Error for Error
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+//ip From<AccessorError> for Error
+impl From<AccessorError> for Error {
+    fn from(e: AccessorError) -> Self {
+        match e {
+            AccessorError::Unsupported(..)
+            | AccessorError::NotNormalizable(..)
+            | AccessorError::MissingAttr(..) => Self::Unsupported(e.to_string()),
+            AccessorError::OutOfRange(..)
+            | AccessorError::BadBounds(..)
+            | AccessorError::BadIndex(..) => Self::BadRange(e.to_string()),
+        }
+    }
+}
+
+//ip From<std::io::Error> for Error
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,206 @@
+//a Documentation
+/*!
+
+Reading and writing of OFF (Object File Format) files
+
+An OFF file starts with a keyword - 'OFF', or 'COFF', 'NOFF' or
+'CNOFF' if the vertices have colors and/or normals - followed by the
+numbers of vertices, faces and edges. Each vertex is then a line with
+its position (followed by its normal and color, if present), and each
+face a line with its number of vertices and their indices (optionally
+followed by a face color, which is ignored). Comments start with '#'.
+
+Vertex colors may be integers from 0 to 255 or floats from 0.0 to
+1.0, with three or four components; when written they are floats
+with four components. Faces are triangulated as fans.
+
+!*/
+
+//a Imports
+use std::fmt::Write;
+
+use super::{Error, Result, TriangleMesh};
+
+//a Reading
+//fi parse_numbers
+/// Parse the words of a line as numbers
+fn parse_numbers(line: usize, l: &str) -> Result<Vec<f64>> {
+    l.split_whitespace()
+        .map(|w| {
+            w.parse()
+                .map_err(|_| Error::Syntax(format!("line {line}: bad number '{w}'")))
+        })
+        .collect()
+}
+
+//fi count
+/// Convert a count from the header to a [usize], which must be a
+/// non-negative integer
+fn count(what: &str, v: f64) -> Result<usize> {
+    if !v.is_finite() || v < 0.0 || v.fract() != 0.0 {
+        return Err(Error::BadRange(format!("OFF file has {what} count {v}")));
+    }
+    Ok(v as usize)
+}
+
+//fi vertex_index
+/// Convert a vertex index of a face to a [u32], which must be a
+/// non-negative integer
+fn vertex_index(line: usize, v: f64) -> Result<u32> {
+    if !(0.0..=u32::MAX as f64).contains(&v) || v.fract() != 0.0 {
+        return Err(Error::BadRange(format!(
+            "line {line}: bad vertex index {v}"
+        )));
+    }
+    Ok(v as u32)
+}
+
+//fp read
+/// Read the contents of an OFF file
+pub fn read(bytes: &[u8]) -> Result<TriangleMesh> {
+    let text =
+        std::str::from_utf8(bytes).map_err(|_| Error::Syntax("OFF file is not UTF-8".into()))?;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(n, l)| (n + 1, l.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, l)| !l.is_empty());
+
+    let Some((_, first)) = lines.next() else {
+        return Err(Error::Syntax("OFF file is empty".into()));
+    };
+    let (keyword, rest) = first.split_at(first.find(char::is_whitespace).unwrap_or(first.len()));
+    let (has_colors, has_normals) = match keyword {
+        "OFF" => (false, false),
+        "COFF" => (true, false),
+        "NOFF" => (false, true),
+        "CNOFF" => (true, true),
+        _ => {
+            return Err(Error::Unsupported(format!(
+                "OFF file with keyword '{keyword}'"
+            )))
+        }
+    };
+    let counts = {
+        if rest.trim().is_empty() {
+            let Some((line, l)) = lines.next() else {
+                return Err(Error::Syntax("OFF file has no counts".into()));
+            };
+            parse_numbers(line, l)?
+        } else {
+            parse_numbers(1, rest)?
+        }
+    };
+    if counts.len() < 2 {
+        return Err(Error::Syntax("OFF file has no face count".into()));
+    }
+    let num_vertices = count("vertex", counts[0])?;
+    let num_faces = count("face", counts[1])?;
+
+    // The counts are not trusted for preallocation; a file with fewer
+    // vertices or faces is an error
+    let mut mesh = TriangleMesh::default();
+    if has_normals {
+        mesh.normals = Some(Vec::new());
+    }
+    let mut colors = Vec::new();
+    for _ in 0..num_vertices {
+        let Some((line, l)) = lines.next() else {
+            return Err(Error::BadRange("OFF file has too few vertices".into()));
+        };
+        let v = parse_numbers(line, l)?;
+        let needed = 3 + (has_normals as usize) * 3 + (has_colors as usize) * 3;
+        if v.len() < needed {
+            return Err(Error::Syntax(format!(
+                "line {line}: vertex needs {needed} numbers"
+            )));
+        }
+        mesh.positions.push([v[0] as f32, v[1] as f32, v[2] as f32]);
+        let mut rest = &v[3..];
+        if let Some(normals) = &mut mesh.normals {
+            normals.push([rest[0] as f32, rest[1] as f32, rest[2] as f32]);
+            rest = &rest[3..];
+        }
+        if has_colors {
+            // Alpha is optional, and is marked as missing with -1
+            colors.push([
+                rest[0],
+                rest[1],
+                rest[2],
+                rest.get(3).copied().unwrap_or(-1.0),
+            ]);
+        }
+    }
+    if has_colors {
+        // Integer colors are 0 to 255; otherwise they are 0.0 to 1.0
+        let scale = {
+            if colors.iter().flatten().any(|c| *c > 1.0) {
+                1.0 / 255.0
+            } else {
+                1.0
+            }
+        };
+        mesh.colors = Some(
+            colors
+                .iter()
+                .map(|c| {
+                    let a = if c[3] < 0.0 { 1.0 } else { c[3] * scale };
+                    [
+                        (c[0] * scale) as f32,
+                        (c[1] * scale) as f32,
+                        (c[2] * scale) as f32,
+                        a as f32,
+                    ]
+                })
+                .collect(),
+        );
+    }
+
+    for _ in 0..num_faces {
+        let Some((line, l)) = lines.next() else {
+            return Err(Error::BadRange("OFF file has too few faces".into()));
+        };
+        let f = parse_numbers(line, l)?;
+        let n = count("face vertex", f.first().copied().unwrap_or(0.0))?;
+        if n < 3 || f.len() <= n {
+            return Err(Error::Syntax(format!("line {line}: bad face")));
+        }
+        let face = f[1..n + 1]
+            .iter()
+            .map(|v| vertex_index(line, *v))
+            .collect::<Result<Vec<u32>>>()?;
+        for i in 1..n - 1 {
+            mesh.triangles.push([face[0], face[i], face[i + 1]]);
+        }
+    }
+    mesh.validate()?;
+    Ok(mesh)
+}
+
+//a Writing
+//fp write
+/// Write a [TriangleMesh] as an OFF file
+pub fn write(mesh: &TriangleMesh) -> Result<String> {
+    mesh.validate()?;
+    let mut s = String::new();
+    let c = if mesh.colors.is_some() { "C" } else { "" };
+    let n = if mesh.normals.is_some() { "N" } else { "" };
+    writeln!(s, "{c}{n}OFF").unwrap();
+    writeln!(s, "{} {} 0", mesh.positions.len(), mesh.triangles.len()).unwrap();
+    for (i, [x, y, z]) in mesh.positions.iter().enumerate() {
+        write!(s, "{x} {y} {z}").unwrap();
+        if let Some(normals) = &mesh.normals {
+            let [nx, ny, nz] = normals[i];
+            write!(s, " {nx} {ny} {nz}").unwrap();
+        }
+        if let Some(colors) = &mesh.colors {
+            let [r, g, b, a] = colors[i];
+            write!(s, " {r} {g} {b} {a}").unwrap();
+        }
+        writeln!(s).unwrap();
+    }
+    for [a, b, c] in &mesh.triangles {
+        writeln!(s, "3 {a} {b} {c}").unwrap();
+    }
+    Ok(s)
+}
@@ -0,0 +1,489 @@
+//a Documentation
+/*!
+
+Reading and writing of ASCII and binary PLY files
+
+A PLY file has a header describing a number of elements, each with a
+count and a list of properties, followed by the data for each element
+(as ASCII text or as little- or big-endian binary).
+
+When read, the 'vertex' element provides the positions ('x', 'y' and
+'z'), the normals ('nx', 'ny' and 'nz', if present) and colors
+('red', 'green', 'blue' and optionally 'alpha', if present; integer
+colors are scaled from 0 to 255); the 'face' element provides the
+faces as a list property 'vertex_indices' (or 'vertex_index'), which
+are triangulated as fans. Other elements and properties are skipped.
+
+When written, the vertices have float positions (and normals, if the
+mesh has them) and 8-bit colors (if the mesh has them), and the faces
+have a list of 32-bit vertex indices.
+
+!*/
+
+//a Imports
+use std::fmt::Write;
+
+use super::{Error, Result, TriangleMesh};
+
+//a PLY types
+//ti Type
+/// The type of a PLY scalar property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    /// Signed 8-bit integer
+    I8,
+    /// Unsigned 8-bit integer
+    U8,
+    /// Signed 16-bit integer
+    I16,
+    /// Unsigned 16-bit integer
+    U16,
+    /// Signed 32-bit integer
+    I32,
+    /// Unsigned 32-bit integer
+    U32,
+    /// 32-bit float
+    F32,
+    /// 64-bit float
+    F64,
+}
+
+//ii Type
+impl Type {
+    //fi parse
+    /// Parse a PLY type name
+    fn parse(name: &str) -> Result<Self> {
+        use Type::*;
+        match name {
+            "char" | "int8" => Ok(I8),
+            "uchar" | "uint8" => Ok(U8),
+            "short" | "int16" => Ok(I16),
+            "ushort" | "uint16" => Ok(U16),
+            "int" | "int32" => Ok(I32),
+            "uint" | "uint32" => Ok(U32),
+            "float" | "float32" => Ok(F32),
+            "double" | "float64" => Ok(F64),
+            _ => Err(Error::Syntax(format!("unknown PLY property type '{name}'"))),
+        }
+    }
+
+    //fi size
+    /// Size in bytes of the type
+    fn size(self) -> usize {
+        use Type::*;
+        match self {
+            I8 | U8 => 1,
+            I16 | U16 => 2,
+            I32 | U32 | F32 => 4,
+            F64 => 8,
+        }
+    }
+
+    //fi is_integer
+    /// Return true if the type is an integer
+    fn is_integer(self) -> bool {
+        !matches!(self, Type::F32 | Type::F64)
+    }
+}
+
+//ti Property
+/// A property of a PLY element, with its type (and the type of the
+/// list count, if it is a list)
+#[derive(Debug)]
+struct Property {
+    /// Name of the property
+    name: String,
+    /// Type of the property (or of each element of the list)
+    value: Type,
+    /// Type of the count of the list, if the property is a list
+    list: Option<Type>,
+}
+
+//ti Element
+/// An element of a PLY file, with its count and properties
+#[derive(Debug)]
+struct Element {
+    /// Name of the element
+    name: String,
+    /// Number of instances of the element
+    count: usize,
+    /// Properties of the element
+    properties: Vec<Property>,
+}
+
+//ii Element
+impl Element {
+    //fi property
+    /// Find the index of a property by name
+    fn property(&self, name: &str) -> Option<usize> {
+        self.properties.iter().position(|p| p.name == name)
+    }
+}
+
+//ti Format
+/// The format of the data of a PLY file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// ASCII text
+    Ascii,
+    /// Little-endian binary
+    LittleEndian,
+    /// Big-endian binary
+    BigEndian,
+}
+
+//a Reading the data
+//ti Body
+/// A reader for the data of a PLY file following the header
+struct Body<'a> {
+    /// The format of the data
+    format: Format,
+    /// The data
+    bytes: &'a [u8],
+    /// The current position in the data
+    pos: usize,
+}
+
+//ii Body
+impl Body<'_> {
+    //fi read_ascii
+    /// Read the next whitespace-separated word of ASCII data as a
+    /// number
+    fn read_ascii(&mut self) -> Result<f64> {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        let start = self.pos;
+        while self.pos < self.bytes.len() && !self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(Error::BadRange("PLY data ends early".into()));
+        }
+        let word = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or("");
+        word.parse()
+            .map_err(|_| Error::Syntax(format!("bad number '{word}' in PLY data")))
+    }
+
+    //fi read
+    /// Read a value of the given type
+    fn read(&mut self, t: Type) -> Result<f64> {
+        if self.format == Format::Ascii {
+            return self.read_ascii();
+        }
+        let size = t.size();
+        let Some(b) = self.bytes.get(self.pos..self.pos + size) else {
+            return Err(Error::BadRange("PLY data ends early".into()));
+        };
+        self.pos += size;
+        let mut buf = [0_u8; 8];
+        buf[..size].copy_from_slice(b);
+        if self.format == Format::BigEndian {
+            buf[..size].reverse();
+        }
+        use Type::*;
+        Ok(match t {
+            I8 => buf[0] as i8 as f64,
+            U8 => buf[0] as f64,
+            I16 => i16::from_le_bytes([buf[0], buf[1]]) as f64,
+            U16 => u16::from_le_bytes([buf[0], buf[1]]) as f64,
+            I32 => i32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            U32 => u32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            F32 => f32::from_le_bytes(buf[..4].try_into().unwrap()) as f64,
+            F64 => f64::from_le_bytes(buf),
+        })
+    }
+
+    //fi read_element
+    /// Read the values of the properties of one element instance
+    ///
+    /// Scalar properties are pushed to `values`, and list properties
+    /// to `lists` (with an empty list for each scalar property)
+    fn read_element(
+        &mut self,
+        element: &Element,
+        values: &mut Vec<f64>,
+        lists: &mut Vec<Vec<f64>>,
+    ) -> Result<()> {
+        values.clear();
+        for (i, p) in element.properties.iter().enumerate() {
+            if i >= lists.len() {
+                lists.push(Vec::new());
+            }
+            lists[i].clear();
+            if let Some(count_type) = p.list {
+                let count = self.read(count_type)?;
+                if count < 0.0 {
+                    return Err(Error::BadRange(format!("PLY list of length {count}")));
+                }
+                for _ in 0..count as usize {
+                    let v = self.read(p.value)?;
+                    lists[i].push(v);
+                }
+                values.push(0.0);
+            } else {
+                values.push(self.read(p.value)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+//a Reading
+//fi parse_header
+/// Parse the header of a PLY file, returning the format, elements,
+/// and the offset of the data
+fn parse_header(bytes: &[u8]) -> Result<(Format, Vec<Element>, usize)> {
+    let Some(end) = bytes.windows(10).position(|w| w == b"end_header") else {
+        return Err(Error::Syntax("PLY file has no end_header".into()));
+    };
+    let mut data_start = end + 10;
+    while data_start < bytes.len() && bytes[data_start] != b'\n' {
+        data_start += 1;
+    }
+    let header = std::str::from_utf8(&bytes[..end])
+        .map_err(|_| Error::Syntax("PLY header is not UTF-8".into()))?;
+    let mut lines = header.lines();
+    if lines.next().map(|l| l.trim()) != Some("ply") {
+        return Err(Error::Syntax("file does not start with 'ply'".into()));
+    }
+    let mut format = None;
+    let mut elements: Vec<Element> = Vec::new();
+    for l in lines {
+        let words: Vec<&str> = l.split_whitespace().collect();
+        match words.as_slice() {
+            ["format", f, _] => {
+                format = Some(match *f {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::LittleEndian,
+                    "binary_big_endian" => Format::BigEndian,
+                    _ => return Err(Error::Unsupported(format!("PLY format '{f}'"))),
+                })
+            }
+            ["element", name, count] => {
+                let count = count
+                    .parse()
+                    .map_err(|_| Error::Syntax(format!("bad element count '{count}'")))?;
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", "list", count_type, value, name] => {
+                let Some(e) = elements.last_mut() else {
+                    return Err(Error::Syntax("PLY property before element".into()));
+                };
+                e.properties.push(Property {
+                    name: name.to_string(),
+                    value: Type::parse(value)?,
+                    list: Some(Type::parse(count_type)?),
+                });
+            }
+            ["property", value, name] => {
+                let Some(e) = elements.last_mut() else {
+                    return Err(Error::Syntax("PLY property before element".into()));
+                };
+                e.properties.push(Property {
+                    name: name.to_string(),
+                    value: Type::parse(value)?,
+                    list: None,
+                });
+            }
+            [] | ["comment", ..] | ["obj_info", ..] => (),
+            _ => return Err(Error::Syntax(format!("bad PLY header line '{l}'"))),
+        }
+    }
+    let Some(format) = format else {
+        return Err(Error::Syntax("PLY header has no format".into()));
+    };
+    Ok((format, elements, (data_start + 1).min(bytes.len())))
+}
+
+//fi vertex_index
+/// Convert a vertex index of a face to a [u32], which must be a
+/// non-negative integer
+fn vertex_index(v: f64) -> Result<u32> {
+    if !(0.0..=u32::MAX as f64).contains(&v) || v.fract() != 0.0 {
+        return Err(Error::BadRange(format!("PLY face has vertex index {v}")));
+    }
+    Ok(v as u32)
+}
+
+//fp read
+/// Read the contents of an ASCII or binary PLY file
+pub fn read(bytes: &[u8]) -> Result<TriangleMesh> {
+    let (format, elements, data_start) = parse_header(bytes)?;
+    let mut body = Body {
+        format,
+        bytes: &bytes[data_start..],
+        pos: 0,
+    };
+    let mut mesh = TriangleMesh::default();
+    let mut values = Vec::new();
+    let mut lists = Vec::new();
+    for e in &elements {
+        match e.name.as_str() {
+            "vertex" => {
+                let find = |names: &[&str]| -> Option<Vec<usize>> {
+                    names.iter().map(|n| e.property(n)).collect()
+                };
+                let Some(xyz) = find(&["x", "y", "z"]) else {
+                    return Err(Error::Syntax("PLY vertex has no x, y and z".into()));
+                };
+                let normal = find(&["nx", "ny", "nz"]);
+                let rgb = find(&["red", "green", "blue"]);
+                let alpha = e.property("alpha");
+                let color_scale = match &rgb {
+                    Some(rgb) if e.properties[rgb[0]].value.is_integer() => 1.0 / 255.0,
+                    _ => 1.0,
+                };
+                // The count is not trusted for preallocation; a file
+                // with fewer vertices is an error
+                if normal.is_some() {
+                    mesh.normals = Some(Vec::new());
+                }
+                if rgb.is_some() {
+                    mesh.colors = Some(Vec::new());
+                }
+                for _ in 0..e.count {
+                    body.read_element(e, &mut values, &mut lists)?;
+                    mesh.positions.push([
+                        values[xyz[0]] as f32,
+                        values[xyz[1]] as f32,
+                        values[xyz[2]] as f32,
+                    ]);
+                    if let (Some(normal), Some(normals)) = (&normal, &mut mesh.normals) {
+                        normals.push([
+                            values[normal[0]] as f32,
+                            values[normal[1]] as f32,
+                            values[normal[2]] as f32,
+                        ]);
+                    }
+                    if let (Some(rgb), Some(colors)) = (&rgb, &mut mesh.colors) {
+                        let a = alpha.map(|a| values[a] * color_scale).unwrap_or(1.0);
+                        colors.push([
+                            (values[rgb[0]] * color_scale) as f32,
+                            (values[rgb[1]] * color_scale) as f32,
+                            (values[rgb[2]] * color_scale) as f32,
+                            a as f32,
+                        ]);
+                    }
+                }
+            }
+            "face" => {
+                let Some(indices) = e
+                    .property("vertex_indices")
+                    .or_else(|| e.property("vertex_index"))
+                else {
+                    return Err(Error::Syntax("PLY face has no vertex_indices".into()));
+                };
+                if e.properties[indices].list.is_none() {
+                    return Err(Error::Syntax(
+                        "PLY face vertex_indices is not a list".into(),
+                    ));
+                }
+                for _ in 0..e.count {
+                    body.read_element(e, &mut values, &mut lists)?;
+                    let face = lists[indices]
+                        .iter()
+                        .map(|v| vertex_index(*v))
+                        .collect::<Result<Vec<u32>>>()?;
+                    for i in 1..face.len().saturating_sub(1) {
+                        mesh.triangles.push([face[0], face[i], face[i + 1]]);
+                    }
+                }
+            }
+            _ => {
+                for _ in 0..e.count {
+                    body.read_element(e, &mut values, &mut lists)?;
+                }
+            }
+        }
+    }
+    mesh.validate()?;
+    Ok(mesh)
+}
+
+//a Writing
+//fi header
+/// Create the header for a [TriangleMesh]
+fn header(mesh: &TriangleMesh, format: &str) -> String {
+    let mut s = String::new();
+    writeln!(s, "ply").unwrap();
+    writeln!(s, "format {format} 1.0").unwrap();
+    writeln!(s, "element vertex {}", mesh.positions.len()).unwrap();
+    for p in ["x", "y", "z"] {
+        writeln!(s, "property float {p}").unwrap();
+    }
+    if mesh.normals.is_some() {
+        for p in ["nx", "ny", "nz"] {
+            writeln!(s, "property float {p}").unwrap();
+        }
+    }
+    if mesh.colors.is_some() {
+        for p in ["red", "green", "blue", "alpha"] {
+            writeln!(s, "property uchar {p}").unwrap();
+        }
+    }
+    writeln!(s, "element face {}", mesh.triangles.len()).unwrap();
+    writeln!(s, "property list uchar uint vertex_indices").unwrap();
+    writeln!(s, "end_header").unwrap();
+    s
+}
+
+//fi color_u8
+/// Convert a color component from 0.0 to 1.0 to a u8
+fn color_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+//fp write_ascii
+/// Write a [TriangleMesh] as an ASCII PLY file
+pub fn write_ascii(mesh: &TriangleMesh) -> Result<String> {
+    mesh.validate()?;
+    let mut s = header(mesh, "ascii");
+    for (n, [x, y, z]) in mesh.positions.iter().enumerate() {
+        write!(s, "{x} {y} {z}").unwrap();
+        if let Some(normals) = &mesh.normals {
+            let [nx, ny, nz] = normals[n];
+            write!(s, " {nx} {ny} {nz}").unwrap();
+        }
+        if let Some(colors) = &mesh.colors {
+            let [r, g, b, a] = colors[n].map(color_u8);
+            write!(s, " {r} {g} {b} {a}").unwrap();
+        }
+        writeln!(s).unwrap();
+    }
+    for [a, b, c] in &mesh.triangles {
+        writeln!(s, "3 {a} {b} {c}").unwrap();
+    }
+    Ok(s)
+}
+
+//fp write_binary
+/// Write a [TriangleMesh] as a binary (little-endian) PLY file
+pub fn write_binary(mesh: &TriangleMesh) -> Result<Vec<u8>> {
+    mesh.validate()?;
+    let mut bytes = header(mesh, "binary_little_endian").into_bytes();
+    for (n, p) in mesh.positions.iter().enumerate() {
+        for f in p {
+            bytes.extend_from_slice(&f.to_le_bytes());
+        }
+        if let Some(normals) = &mesh.normals {
+            for f in normals[n] {
+                bytes.extend_from_slice(&f.to_le_bytes());
+            }
+        }
+        if let Some(colors) = &mesh.colors {
+            bytes.extend_from_slice(&colors[n].map(color_u8));
+        }
+    }
+    for t in &mesh.triangles {
+        bytes.push(3);
+        for v in t {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    Ok(bytes)
+}
@@ -0,0 +1,229 @@
+//a Documentation
+/*!
+
+Reading and writing of ASCII and binary STL files
+
+An STL file is a list of triangles, each with a facet normal and the
+positions of its three corners; there is no sharing of vertices
+between triangles. When read, each corner becomes a separate vertex
+of the [TriangleMesh], with the facet normal as its normal (or the
+normal calculated from the corner positions, if the facet normal is
+zero).
+
+When written the facet normals are calculated from the corner
+positions, and any vertex normals or colors of the mesh are ignored.
+
+!*/
+
+//a Imports
+use std::fmt::Write;
+
+use super::{Error, Result, TriangleMesh};
+
+//a Constants
+/// Size of the header of a binary STL file
+const HEADER_SIZE: usize = 80;
+/// Size of each triangle in a binary STL file
+const TRIANGLE_SIZE: usize = 50;
+
+//a Helper functions
+//fi read_f32s
+/// Read little-endian f32 values from bytes
+fn read_f32s<const N: usize>(bytes: &[u8]) -> [f32; N] {
+    let mut result = [0.0; N];
+    for (r, b) in result.iter_mut().zip(bytes.chunks_exact(4)) {
+        *r = f32::from_le_bytes(b.try_into().unwrap());
+    }
+    result
+}
+
+//fi parse_vec3
+/// Parse the next three words of a line as floats
+fn parse_vec3<'a, I: Iterator<Item = &'a str>>(line: usize, words: &mut I) -> Result<[f32; 3]> {
+    let mut result = [0.; 3];
+    for r in result.iter_mut() {
+        let w = words.next().unwrap_or("");
+        *r = w
+            .parse()
+            .map_err(|_| Error::Syntax(format!("line {line}: bad number '{w}'")))?;
+    }
+    Ok(result)
+}
+
+//fi add_facet
+/// Add a facet to a mesh, with a separate vertex for each corner
+fn add_facet(mesh: &mut TriangleMesh, normal: [f32; 3], corners: &[[f32; 3]]) {
+    let base = mesh.positions.len() as u32;
+    mesh.positions.extend_from_slice(corners);
+    mesh.triangles.push([base, base + 1, base + 2]);
+    let normal = {
+        if normal == [0.; 3] {
+            mesh.face_normal(mesh.triangles.len() - 1)
+        } else {
+            normal
+        }
+    };
+    if let Some(normals) = &mut mesh.normals {
+        normals.extend_from_slice(&[normal; 3]);
+    }
+}
+
+//fi is_binary
+/// Determine if the contents of an STL file are binary
+///
+/// Some binary files start with 'solid', so a file is binary if its
+/// length matches the triangle count in its header, or if it does
+/// not start with 'solid'
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() >= HEADER_SIZE + 4 {
+        let count = u32::from_le_bytes(bytes[HEADER_SIZE..HEADER_SIZE + 4].try_into().unwrap());
+        if bytes.len() == HEADER_SIZE + 4 + count as usize * TRIANGLE_SIZE {
+            return true;
+        }
+    }
+    !bytes.trim_ascii_start().starts_with(b"solid")
+}
+
+//a Reading
+//fp read
+/// Read the contents of an ASCII or binary STL file
+pub fn read(bytes: &[u8]) -> Result<TriangleMesh> {
+    if is_binary(bytes) {
+        read_binary(bytes)
+    } else {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| Error::Syntax("ASCII STL file is not UTF-8".into()))?;
+        read_ascii(text)
+    }
+}
+
+//fp read_binary
+/// Read the contents of a binary STL file
+pub fn read_binary(bytes: &[u8]) -> Result<TriangleMesh> {
+    if bytes.len() < HEADER_SIZE + 4 {
+        return Err(Error::BadRange("binary STL file is too short".into()));
+    }
+    let count = u32::from_le_bytes(bytes[HEADER_SIZE..HEADER_SIZE + 4].try_into().unwrap());
+    let count = count as usize;
+    let data = &bytes[HEADER_SIZE + 4..];
+    if data.len() < count * TRIANGLE_SIZE {
+        return Err(Error::BadRange(format!(
+            "binary STL file has {count} triangles but only {} bytes of data",
+            data.len()
+        )));
+    }
+    let mut mesh = TriangleMesh {
+        normals: Some(Vec::with_capacity(count * 3)),
+        ..Default::default()
+    };
+    for t in data.chunks_exact(TRIANGLE_SIZE).take(count) {
+        let normal = read_f32s(&t[0..12]);
+        let corners = [
+            read_f32s(&t[12..24]),
+            read_f32s(&t[24..36]),
+            read_f32s(&t[36..48]),
+        ];
+        add_facet(&mut mesh, normal, &corners);
+    }
+    Ok(mesh)
+}
+
+//fp read_ascii
+/// Read the contents of an ASCII STL file
+pub fn read_ascii(text: &str) -> Result<TriangleMesh> {
+    let mut mesh = TriangleMesh {
+        normals: Some(Vec::new()),
+        ..Default::default()
+    };
+    let mut normal = [0.; 3];
+    let mut corners = Vec::with_capacity(3);
+    let mut in_solid = false;
+    for (n, l) in text.lines().enumerate() {
+        let line = n + 1;
+        let mut words = l.split_whitespace();
+        let Some(keyword) = words.next() else {
+            continue;
+        };
+        match keyword {
+            "solid" => in_solid = true,
+            "endsolid" => in_solid = false,
+            "facet" => {
+                if words.next() != Some("normal") {
+                    return Err(Error::Syntax(format!(
+                        "line {line}: expected 'facet normal'"
+                    )));
+                }
+                normal = parse_vec3(line, &mut words)?;
+                corners.clear();
+            }
+            "vertex" => corners.push(parse_vec3(line, &mut words)?),
+            "endfacet" => {
+                if corners.len() != 3 {
+                    return Err(Error::Syntax(format!(
+                        "line {line}: facet has {} vertices",
+                        corners.len()
+                    )));
+                }
+                add_facet(&mut mesh, normal, &corners);
+            }
+            "outer" | "endloop" => (),
+            _ => {
+                return Err(Error::Syntax(format!(
+                    "line {line}: unexpected '{keyword}'"
+                )))
+            }
+        }
+        if !in_solid && keyword != "endsolid" {
+            return Err(Error::Syntax(format!(
+                "line {line}: '{keyword}' outside of a solid"
+            )));
+        }
+    }
+    Ok(mesh)
+}
+
+//a Writing
+//fp write_ascii
+/// Write a [TriangleMesh] as an ASCII STL file with the given solid
+/// name
+pub fn write_ascii(mesh: &TriangleMesh, name: &str) -> Result<String> {
+    mesh.validate()?;
+    let mut s = String::new();
+    writeln!(s, "solid {name}").unwrap();
+    for (i, t) in mesh.triangles.iter().enumerate() {
+        let [nx, ny, nz] = mesh.face_normal(i);
+        writeln!(s, "  facet normal {nx} {ny} {nz}").unwrap();
+        writeln!(s, "    outer loop").unwrap();
+        for v in t {
+            let [x, y, z] = mesh.positions[*v as usize];
+            writeln!(s, "      vertex {x} {y} {z}").unwrap();
+        }
+        writeln!(s, "    endloop").unwrap();
+        writeln!(s, "  endfacet").unwrap();
+    }
+    writeln!(s, "endsolid {name}").unwrap();
+    Ok(s)
+}
+
+//fp write_binary
+/// Write a [TriangleMesh] as a binary STL file
+pub fn write_binary(mesh: &TriangleMesh) -> Result<Vec<u8>> {
+    mesh.validate()?;
+    let count = mesh.triangles.len();
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + 4 + count * TRIANGLE_SIZE);
+    bytes.resize(HEADER_SIZE, 0);
+    bytes[0..9].copy_from_slice(b"mod3d-stl");
+    bytes.extend_from_slice(&(count as u32).to_le_bytes());
+    for (i, t) in mesh.triangles.iter().enumerate() {
+        for f in mesh.face_normal(i) {
+            bytes.extend_from_slice(&f.to_le_bytes());
+        }
+        for v in t {
+            for f in mesh.positions[*v as usize] {
+                bytes.extend_from_slice(&f.to_le_bytes());
+            }
+        }
+        bytes.extend_from_slice(&[0, 0]);
+    }
+    Ok(bytes)
+}
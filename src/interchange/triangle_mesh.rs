@@ -0,0 +1,142 @@
+//a Imports
+use std::collections::HashMap;
+
+use geo_nd::vector;
+
+use super::{Error, Result};
+use crate::{list_indices, Primitive, PrimitiveType, Renderable, VertexAttr, Vertices};
+
+//a TriangleMesh
+//tp TriangleMesh
+/// A simple mesh of triangles, with positions and optional normals
+/// and colors (RGBA, 0.0 to 1.0) for each vertex
+///
+/// This is the form in which the STL, PLY and OFF files are read and
+/// written
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TriangleMesh {
+    /// The position of each vertex
+    pub positions: Vec<[f32; 3]>,
+    /// The normal of each vertex, if the mesh has normals
+    pub normals: Option<Vec<[f32; 3]>>,
+    /// The color of each vertex, if the mesh has colors
+    pub colors: Option<Vec<[f32; 4]>>,
+    /// The vertex indices of each triangle
+    pub triangles: Vec<[u32; 3]>,
+}
+
+//ip TriangleMesh
+impl TriangleMesh {
+    //fp of_primitive
+    /// Create a [TriangleMesh] from a [Primitive] using a [Vertices]
+    ///
+    /// Triangle strips and fans are expanded into separate triangles
+    /// (dropping any degenerate triangles); only the vertices used by
+    /// the primitive are included in the mesh. The normals and colors
+    /// are taken from the [Vertices] attributes if it has them.
+    pub fn of_primitive<R: Renderable>(
+        vertices: &Vertices<R>,
+        primitive: &Primitive,
+    ) -> Result<Self> {
+        if primitive.primitive_type().list_type() != PrimitiveType::Triangles {
+            return Err(Error::Unsupported(format!(
+                "{:?} primitive cannot be converted to triangles",
+                primitive.primitive_type()
+            )));
+        }
+        let corners = vertices.primitive_indices(primitive)?;
+        let triangles: Vec<[u32; 3]> = list_indices(primitive.primitive_type(), &corners)
+            .chunks_exact(3)
+            .map(|c| [c[0], c[1], c[2]])
+            .collect();
+
+        let mut vertex_map = HashMap::new();
+        let mut used = Vec::new();
+        let triangles = triangles
+            .into_iter()
+            .map(|t| {
+                t.map(|v| {
+                    *vertex_map.entry(v).or_insert_with(|| {
+                        used.push(v as usize);
+                        (used.len() - 1) as u32
+                    })
+                })
+            })
+            .collect();
+
+        let position = vertices.borrow_position();
+        let positions = used
+            .iter()
+            .map(|v| position.read_element(*v))
+            .collect::<std::result::Result<_, _>>()?;
+        let normals = match vertices.borrow_attr(VertexAttr::Normal) {
+            Some(normal) => Some(
+                used.iter()
+                    .map(|v| normal.read_element(*v))
+                    .collect::<std::result::Result<_, _>>()?,
+            ),
+            None => None,
+        };
+        let colors = match vertices.borrow_attr(VertexAttr::Color) {
+            Some(color) => Some(
+                used.iter()
+                    .map(|v| {
+                        // Colors without alpha are opaque
+                        let mut rgba: [f32; 4] = color.read_element(*v)?;
+                        if color.elements_per_data < 4 {
+                            rgba[3] = 1.0;
+                        }
+                        Ok(rgba)
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            positions,
+            normals,
+            colors,
+            triangles,
+        })
+    }
+
+    //mp validate
+    /// Check that the normals and colors (if present) match the
+    /// positions, and that the triangles refer to vertices that exist
+    pub fn validate(&self) -> Result<()> {
+        let n = self.positions.len();
+        if self.normals.as_ref().is_some_and(|x| x.len() != n) {
+            return Err(Error::BadRange(format!(
+                "mesh has {n} positions but a different number of normals"
+            )));
+        }
+        if self.colors.as_ref().is_some_and(|x| x.len() != n) {
+            return Err(Error::BadRange(format!(
+                "mesh has {n} positions but a different number of colors"
+            )));
+        }
+        for t in &self.triangles {
+            for v in t {
+                if *v as usize >= n {
+                    return Err(Error::BadIndex("vertex", *v as usize));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //mp face_normal
+    /// Calculate the normal of a triangle from its vertex positions
+    /// (zero if the triangle is degenerate)
+    pub fn face_normal(&self, triangle: usize) -> [f32; 3] {
+        let [a, b, c] = self.triangles[triangle].map(|v| self.positions[v as usize]);
+        let n = vector::cross_product3(&vector::sub(b, &a, 1.), &vector::sub(c, &a, 1.));
+        if vector::length_sq(&n) > 0. {
+            vector::normalize(n)
+        } else {
+            [0.; 3]
+        }
+    }
+
+    //zz All done
+}
@@ -3,12 +3,6 @@
 #![warn(rustdoc::missing_doc_code_examples)]
 
 /*!
-# TODO
-
-Replace ByteBuffer with T:AsRef<[u8]>
-
-Make Vertices have an option<indices> and update renderable vertices clients
-
 # 3D Model library
 
 This library provides structures and functions to support simple and
@@ -84,6 +78,16 @@ Underlying the data model is the [ByteBuffer] trait - any data that is
 used for the models must support this trait, and implementations are
 provided for slice <> and for Vec<>.
 
+Alternatively the data may be owned, as a [ByteData] - this holds an
+`Arc<[u8]>`, or any other `AsRef<[u8]>` (such as a `Vec<u8>` or a
+memory-mapped file) in an [std::sync::Arc], so that it can be cheaply
+shared. Similarly the [BufferData], [BufferAccessor], [Vertices],
+[Texture] and [Material] descriptors can be borrowed or shared with an
+`Arc` (as a [Shared]); if everything is shared then an [Object] is
+`'static`, and it can be moved between threads (if its material and
+client types permit), so it can be loaded at run time without
+borrowing from buffers that must outlive it.
+
 ### [BufferData]
 
 A type that borrows a sub slice of[u8], using an explicit offset and
@@ -128,7 +132,7 @@ more than one [Vertices] object.
 
 ### [Vertices]
 
-The [Vertices] type borrows at least one [BufferAccessor] for a vertex
+The [Vertices] type borrows an optional [BufferAccessor] for a vertex
 indices buffer, and at least one [BufferAccessor] for positions of the
 vertices; in addition it borrows more [BufferAccessor], one for each
 attribute [VertexAttr] that is part of a mesh or set of meshes.
@@ -139,7 +143,7 @@ a Vertices object becomes a set of OpenGL Buffers (and subsets
 thereof) and for a particular shader class it can be bound into a VAO.
 
 A [Vertices] object is a set of related [BufferAccessor]s, with at least a
-view for indices and a view for vertex positions; it may have more
+view for vertex positions (and usually a view for indices); it may have more
 views for additional attributes. It has a lifetime that is no longer
 than that of the [BufferData] from which the [BufferAccessor]s are made.
 
@@ -246,6 +250,9 @@ A [Primitive] contains:
 
 * a number of indices
 
+A [Primitive] of a [Vertices] without indices instead contains a
+vertex range - the first vertex and the number of vertices to draw.
+
 A [Primitive] does *not* contain any transformation information - all
 the [Primitive] that belong to a [Mesh] have the same transformation.
 
@@ -520,6 +527,15 @@ pub use buffer_accessor::BufferAccessor;
 pub use buffer_data::BufferData;
 pub use byte_buffer::ByteBuffer;
 
+mod shared;
+pub use shared::{ByteData, Shared};
+
+mod float16;
+pub use float16::{f16_bytes_to_f32s, f16_to_f32, f32_to_f16, f32s_to_f16_bytes};
+
+mod element_reader;
+pub use element_reader::{AccessorError, Element, Elements};
+
 mod traits;
 pub use traits::{
     AccessorClient, BufferClient, Material, MaterialClient, Renderable, TextureClient,
@@ -539,6 +555,32 @@ mod mesh;
 mod primitive;
 pub use mesh::Mesh;
 pub use primitive::Primitive;
+mod mesh_builder;
+pub use mesh_builder::{MeshBuilder, MeshBuilderError, MeshVertex, VertexLayout};
+mod repack;
+pub use repack::VERTEX_ALIGNMENT;
+mod quantize;
+pub use quantize::{Quantization, Quantized};
+mod weld;
+pub use weld::{Welded, Welding};
+mod optimize;
+pub use optimize::{CacheStatistics, Optimization, Optimized};
+mod strips;
+pub use strips::{list_indices, strip_indices};
+mod split;
+mod normals;
+pub use normals::{NormalGeneration, NormalWeighting};
+mod tangents;
+mod bounds;
+pub use bounds::{BoundingBox, BoundingSphere, Bounds};
+mod simplify;
+pub use simplify::{Lod, LodChain, Simplification, Simplified};
+mod lod;
+pub use lod::{LodMesh, LodSelection, LodThreshold, MeshLod, SelectedPrimitive};
+mod morph;
+pub use morph::{MorphMesh, MorphTarget};
+mod meshlet;
+pub use meshlet::{Meshlet, MeshletBuilder, Meshlets, NormalCone};
 
 mod component;
 pub use component::Component;
@@ -546,6 +588,13 @@ mod render_recipe;
 pub use render_recipe::RenderRecipe;
 mod object;
 pub use object::Object;
+#[cfg(feature = "serde")]
+mod object_description;
+#[cfg(feature = "serde")]
+pub use object_description::{
+    AccessorDescription, BufferDataDescription, ObjectDescription, TextureDescription,
+    VerticesDescription,
+};
 
 mod instantiable;
 pub use instantiable::Instantiable;
@@ -556,3 +605,15 @@ pub mod example_objects;
 pub use example_objects::ExampleVertices;
 
 pub mod example_client;
+
+#[cfg(any(feature = "gltf", feature = "native"))]
+mod checksum;
+
+#[cfg(feature = "gltf")]
+pub mod gltf;
+#[cfg(feature = "interchange")]
+pub mod interchange;
+#[cfg(feature = "native")]
+pub mod native;
+#[cfg(feature = "obj")]
+pub mod obj;
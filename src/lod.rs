@@ -0,0 +1,311 @@
+//a Documentation
+/*!
+
+This provides levels of detail for a [Mesh], and their selection for
+drawing from the position of a camera and its projection.
+
+A [Mesh] has its primitives (the full detail, level 0) and a list of
+[MeshLod]s in order of decreasing detail (levels 1 and on), each with
+its own primitives (which may, for example, be from a [LodChain]) and
+a [LodThreshold]: the screen size below which, or the distance beyond
+which, the level is used. The screen size of a mesh is the diameter of
+its bounding sphere as a fraction of the height of the viewport.
+
+Alternatively levels may be selected by their error (the distance of
+the level's surface from that of the full detail): the coarsest level
+whose error, projected on to the screen as a fraction of the height of
+the viewport, is at most a given screen space error is used.
+
+To stop a mesh flickering between two levels when it is near the
+threshold between them, a mesh only changes to a coarser level when its
+threshold is passed by a margin (the hysteresis), and only changes back
+when the threshold is missed by the margin; so the level that a mesh
+was last drawn at must be kept, which an [Instance] does.
+
+A [RenderRecipe] keeps the meshes of its components that have levels
+of detail, and provides the primitives to draw for a frame.
+
+[LodChain]: crate::LodChain
+
+!*/
+
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::transformation::mat4_compose;
+use crate::{BoundingSphere, Instance, Lod, Mat4, Mesh, Primitive, RenderRecipe, Renderable, Vec3};
+
+//a LodThreshold
+//tp LodThreshold
+/// The threshold at which a level of detail is used
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LodThreshold {
+    /// The level is used when the screen size of the mesh is at most
+    /// this
+    ScreenSize(f32),
+    /// The level is used when the distance of the mesh from the camera
+    /// is at least this
+    Distance(f32),
+}
+
+//a MeshLod
+//tp MeshLod
+/// A level of detail of a [Mesh]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MeshLod {
+    /// The primitives to draw for the level
+    pub primitives: Vec<Primitive>,
+    /// The threshold at which the level is used
+    pub threshold: LodThreshold,
+    /// The error of the level relative to the full detail; a distance
+    /// in the units of the positions of the mesh
+    pub error: f32,
+}
+
+//ip MeshLod
+impl MeshLod {
+    //fp new
+    /// Create a new [MeshLod] with an error of zero
+    pub fn new(primitives: Vec<Primitive>, threshold: LodThreshold) -> Self {
+        Self {
+            primitives,
+            threshold,
+            error: 0.,
+        }
+    }
+
+    //fp of_lod
+    /// Create a new [MeshLod] for a level of a [crate::LodChain],
+    /// drawing its primitive with its error
+    pub fn of_lod(lod: &Lod, threshold: LodThreshold) -> Self {
+        Self::new(vec![lod.primitive.clone()], threshold).with_error(lod.error)
+    }
+
+    //cp with_error
+    /// Set the error of the level
+    pub fn with_error(mut self, error: f32) -> Self {
+        self.error = error;
+        self
+    }
+}
+
+//a LodSelection
+//tp LodSelection
+/// The parameters for selecting the levels of detail of meshes to draw
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodSelection {
+    /// The position of the camera in world space
+    pub camera: Vec3,
+    /// The scale of the projection - the cotangent of half the
+    /// vertical field of view
+    pub projection_scale: f32,
+    /// If set then levels are selected by their error projected on to
+    /// the screen, which may be at most this, instead of by their
+    /// thresholds
+    pub max_screen_error: Option<f32>,
+    /// The fraction of the threshold by which it must be passed to
+    /// change to a coarser level, or missed to change back
+    pub hysteresis: f32,
+}
+
+//ip LodSelection
+impl LodSelection {
+    //fp new
+    /// Create a new [LodSelection] for a camera and its perspective
+    /// projection matrix, using the thresholds of the levels with a
+    /// hysteresis of 10%
+    pub fn new(camera: Vec3, projection: &Mat4) -> Self {
+        Self {
+            camera,
+            projection_scale: projection[5],
+            max_screen_error: None,
+            hysteresis: 0.1,
+        }
+    }
+
+    //cp with_max_screen_error
+    /// Set the screen space error to select levels by, or use the
+    /// thresholds of the levels
+    pub fn with_max_screen_error(mut self, max_screen_error: Option<f32>) -> Self {
+        self.max_screen_error = max_screen_error;
+        self
+    }
+
+    //cp with_hysteresis
+    /// Set the hysteresis
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+}
+
+//a Mesh
+//ip Mesh
+impl Mesh {
+    //mp select_lod
+    /// Select the level of detail (0 being the full detail) to draw the
+    /// [Mesh] at, with a matrix to world space, given the level it was
+    /// last drawn at
+    ///
+    /// The full detail is used if the camera is within the bounding
+    /// sphere of the mesh
+    pub fn select_lod(&self, selection: &LodSelection, m: &Mat4, current: usize) -> usize {
+        if self.lods.is_empty() {
+            return 0;
+        }
+        let sphere = self
+            .bounds
+            .map_or(BoundingSphere::new([0.; 3], 0.), |b| b.sphere);
+        let unit = BoundingSphere::new(sphere.center, 1.).transformed(m);
+        let (center, scale) = (unit.center, unit.radius);
+        let radius = sphere.radius * scale;
+        let d = [0, 1, 2].map(|i| center[i] - selection.camera[i]);
+        let distance = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        if distance <= radius || distance == 0. {
+            return 0;
+        }
+        let projected = selection.projection_scale / distance;
+
+        let mut level = 0;
+        for (i, lod) in self.lods.iter().enumerate() {
+            let margin = if i < current {
+                1. - selection.hysteresis
+            } else {
+                1. + selection.hysteresis
+            };
+            let used = match (selection.max_screen_error, lod.threshold) {
+                (Some(max), _) => lod.error * scale * projected / 2. * margin <= max,
+                (None, LodThreshold::ScreenSize(size)) => radius * projected * margin <= size,
+                (None, LodThreshold::Distance(d)) => distance >= d * margin,
+            };
+            if !used {
+                break;
+            }
+            level = i + 1;
+        }
+        level
+    }
+}
+
+//a LodMesh
+//tp LodMesh
+/// A mesh with levels of detail in a [RenderRecipe]
+#[derive(Debug, Clone)]
+pub struct LodMesh {
+    /// The index of the first primitive of the mesh (at full detail)
+    /// in the primitives of the recipe
+    pub first: usize,
+    /// The number of primitives of the mesh at full detail
+    pub count: usize,
+    /// The index of the matrix of the mesh in the recipe
+    pub matrix: usize,
+    /// The mesh
+    pub mesh: Mesh,
+}
+
+//a SelectedPrimitive
+//tp SelectedPrimitive
+/// A primitive selected to be drawn for a frame by a [RenderRecipe]
+#[derive(Debug, Clone, Copy)]
+pub struct SelectedPrimitive<'a> {
+    /// The index of the matrix for the primitive in the recipe
+    pub matrix: usize,
+    /// The index of the primitive in the primitives of the recipe; for
+    /// a primitive of a coarser level of detail, the index of the first
+    /// primitive (at full detail) of its mesh, so that the data of the
+    /// mesh - such as its morph target weights - may be found
+    pub index: usize,
+    /// The level of detail of the primitive (0 for full detail)
+    pub level: usize,
+    /// The index of the primitive in the primitives of its level of
+    /// detail of its mesh, if the mesh has levels of detail
+    pub lod_index: Option<usize>,
+    /// The primitive
+    pub primitive: &'a Primitive,
+}
+
+//ip SelectedPrimitive
+impl<'a> SelectedPrimitive<'a> {
+    //fi of_recipe
+    /// Create a [SelectedPrimitive] for a primitive of a recipe at full
+    /// detail
+    fn of_recipe(recipe: &'a RenderRecipe, index: usize) -> Self {
+        Self {
+            matrix: recipe.matrix_for_primitives[index],
+            index,
+            level: 0,
+            lod_index: None,
+            primitive: &recipe.primitives[index],
+        }
+    }
+}
+
+//a RenderRecipe
+//ip RenderRecipe
+impl RenderRecipe {
+    //mp select_primitives
+    /// Get the primitives to draw for a frame, with the index of the
+    /// matrix and of the recipe primitive for each, with the meshes
+    /// that have levels of detail drawn at the levels selected for them
+    ///
+    /// The matrix `m` is from the space of the recipe to world space;
+    /// `levels` holds the level each mesh was last drawn at, and is
+    /// updated with the levels selected
+    pub fn select_primitives(
+        &self,
+        m: &Mat4,
+        selection: &LodSelection,
+        levels: &mut Vec<usize>,
+    ) -> Vec<SelectedPrimitive<'_>> {
+        levels.resize(self.lod_meshes.len(), 0);
+        let mut primitives = Vec::with_capacity(self.primitives.len());
+        let mut next = 0;
+        for (lod_mesh, level) in self.lod_meshes.iter().zip(levels.iter_mut()) {
+            for i in next..lod_mesh.first {
+                primitives.push(SelectedPrimitive::of_recipe(self, i));
+            }
+            let world = mat4_compose(m, &self.matrices[lod_mesh.matrix]);
+            *level = lod_mesh.mesh.select_lod(selection, &world, *level);
+            for (j, p) in lod_mesh.mesh.lod_primitives(*level).iter().enumerate() {
+                let index = if *level == 0 {
+                    lod_mesh.first + j
+                } else {
+                    lod_mesh.first
+                };
+                primitives.push(SelectedPrimitive {
+                    matrix: lod_mesh.matrix,
+                    index,
+                    level: *level,
+                    lod_index: Some(j),
+                    primitive: p,
+                });
+            }
+            next = lod_mesh.first + lod_mesh.count;
+        }
+        for i in next..self.primitives.len() {
+            primitives.push(SelectedPrimitive::of_recipe(self, i));
+        }
+        primitives
+    }
+}
+
+//a Instance
+//ip Instance
+impl<'a, R: Renderable> Instance<'a, R> {
+    //mp select_primitives
+    /// Get the primitives to draw the instance for a frame, with the
+    /// index of the matrix and of the primitive (of the render recipe
+    /// of the [crate::Instantiable]) for each, selecting the levels of
+    /// detail of its meshes with its transformation
+    ///
+    /// The index of each primitive may be used to get the morph target
+    /// weights to draw it with, using `primitive_weights`
+    pub fn select_primitives(&mut self, selection: &LodSelection) -> Vec<SelectedPrimitive<'a>> {
+        let m = self.transformation.mat4();
+        let recipe = &self.instantiable.render_recipe;
+        recipe.select_primitives(&m, selection, &mut self.lod_levels)
+    }
+}
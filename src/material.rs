@@ -8,6 +8,8 @@ This provides for abstract Materials which can be used by any 3D model
 
 //a Imports
 use crate::{Material, MaterialAspect, ShortIndex};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a BaseData
 //tp BaseData
@@ -22,6 +24,7 @@ use crate::{Material, MaterialAspect, ShortIndex};
 ///
 /// Gltf
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BaseData {
     /// Color of the material
     ///
@@ -118,7 +121,8 @@ impl From<u32> for BaseData {
 //a BaseMaterial
 //tp BaseMaterial
 /// Base material that provides simply color and constant metallicness/roughness
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BaseMaterial {
     /// Base material data
     base_data: BaseData,
@@ -150,7 +154,8 @@ impl Material for BaseMaterial {
 //a PbrMaterial
 //tp PbrMaterial
 /// A physically-based rendered material with full set of textures
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PbrMaterial {
     base_data: BaseData,
     base_texture: ShortIndex,
@@ -1,5 +1,7 @@
 //a Imports
-use crate::Primitive;
+use crate::{Bounds, MeshLod, Primitive};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a Mesh
 //tp Mesh
@@ -8,10 +10,39 @@ use crate::Primitive;
 ///
 /// The [Mesh] depends on being in an 3D model object, as it is the
 /// object that contains the actual materials and vertices to use
-#[derive(Debug, Default)]
+///
+/// The primitives are the full detail of the mesh; it may also have
+/// levels of detail with fewer primitives or triangles, to be drawn in
+/// their place when the mesh is far from the camera (see [MeshLod])
+///
+/// If the [crate::Vertices] its primitives use have morph targets then
+/// the mesh holds the default weights for them
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Mesh {
     /// The primitive
     pub primitives: Vec<Primitive>,
+    /// The levels of detail after the full detail, in order of
+    /// decreasing detail
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub lods: Vec<MeshLod>,
+    /// The bounds of the mesh, used to select its level of detail; if
+    /// `None` then the mesh is treated as a point at its origin
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub bounds: Option<Bounds>,
+    /// The default weights of the morph targets of the vertices of the
+    /// primitives
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub weights: Vec<f32>,
 }
 
 //ip Mesh
@@ -22,5 +53,34 @@ impl Mesh {
         self.primitives.push(primitive);
     }
 
+    //mp add_lod
+    /// Add a level of detail to the [Mesh], with less detail than
+    /// those it already has
+    pub fn add_lod(&mut self, lod: MeshLod) {
+        self.lods.push(lod);
+    }
+
+    //ap num_lods
+    /// Get the number of levels of detail of the [Mesh], including the
+    /// full detail
+    pub fn num_lods(&self) -> usize {
+        self.lods.len() + 1
+    }
+
+    //ap lod_primitives
+    /// Get the primitives of a level of detail of the [Mesh], where
+    /// level 0 is the full detail
+    ///
+    /// Levels beyond the last give the primitives of the last
+    pub fn lod_primitives(&self, level: usize) -> &[Primitive] {
+        match level {
+            0 => &self.primitives,
+            _ => match self.lods.get(level - 1).or(self.lods.last()) {
+                Some(lod) => &lod.primitives,
+                None => &self.primitives,
+            },
+        }
+    }
+
     //zz All done
 }
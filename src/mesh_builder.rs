@@ -0,0 +1,502 @@
+//a Documentation
+/*!
+
+This provides a [MeshBuilder], which collects vertices (with named
+attributes) and primitives (triangles, strips, lines, ...) each with a
+material, and packs them in one step into the data required for an
+[Object] - a vertex buffer and an index buffer, the [BufferData] and
+[BufferAccessor]s for them, the [Vertices], and a [Mesh] of
+[Primitive]s.
+
+The data produced is owned (through [Arc]s), so the [Vertices] can
+have any lifetime (including `'static`) and can be added to any
+[Object].
+
+```ignore
+let mut builder = MeshBuilder::new().with_layout(VertexLayout::Planar);
+let a = builder.push_vertex(MeshVertex::new([0., 0., 0.]).with_normal([0., 0., 1.]));
+let b = builder.push_vertex(MeshVertex::new([1., 0., 0.]).with_normal([0., 0., 1.]));
+let c = builder.push_vertex(MeshVertex::new([0., 1., 0.]).with_normal([0., 0., 1.]));
+builder.push_triangles(&[a, b, c], m_id);
+let mesh = builder.add_to_object(&mut object)?;
+object.add_component(None, None, mesh);
+```
+
+!*/
+
+//a Imports
+use std::sync::Arc;
+
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, Material, Mesh, Object, Primitive,
+    PrimitiveType, Renderable, ShortIndex, VertexAttr, Vertices,
+};
+
+//a MeshBuilderError
+//tp MeshBuilderError
+/// An error in the data given to a [MeshBuilder]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshBuilderError {
+    /// An index (the first) refers to a vertex beyond the number pushed (the second)
+    IndexOutOfRange(u32, usize),
+    /// The number of vertices cannot be indexed with the index type
+    TooManyVertices(usize, BufferElementType),
+    /// The index type is not an unsigned integer type
+    BadIndexType(BufferElementType),
+    /// The number of indices is not valid for the primitive type
+    BadIndexCount(PrimitiveType, usize),
+}
+
+//ip Display for MeshBuilderError
+impl std::fmt::Display for MeshBuilderError {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::IndexOutOfRange(i, n) => {
+                write!(fmt, "index {i} is out of range for {n} vertices")
+            }
+            Self::TooManyVertices(n, t) => write!(fmt, "{n} vertices cannot be indexed by {t:?}"),
+            Self::BadIndexType(t) => write!(fmt, "{t:?} is not an index type"),
+            Self::BadIndexCount(p, n) => write!(fmt, "{n} indices is not valid for {p:?}"),
+        }
+    }
+}
+
+//ip Error for MeshBuilderError
+impl std::error::Error for MeshBuilderError {}
+
+//a VertexLayout
+//tp VertexLayout
+/// The layout of the vertex attributes in the vertex buffer
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VertexLayout {
+    /// All the attributes of a vertex are adjacent, with a stride of
+    /// the vertex size
+    #[default]
+    Interleaved,
+    /// Each attribute is in its own contiguous region of the buffer
+    Planar,
+}
+
+//a MeshVertex
+//tp MeshVertex
+/// A vertex pushed to a [MeshBuilder]
+///
+/// Only the position is required; if any vertex of a builder has an
+/// attribute then all the vertices have it, with those that do not
+/// provide it using zeros
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    /// Position of the vertex
+    pub position: [f32; 3],
+    /// Optional normal
+    pub normal: Option<[f32; 3]>,
+    /// Optional texture coordinates (for [VertexAttr::TexCoords0])
+    pub tex_coords: Option<[f32; 2]>,
+    /// Optional RGBA color
+    pub color: Option<[f32; 4]>,
+    /// Optional joint indices and weights for skinning
+    pub skin: Option<([u16; 4], [f32; 4])>,
+}
+
+//ip MeshVertex
+impl MeshVertex {
+    //fp new
+    /// Create a new [MeshVertex] at a position
+    pub fn new(position: [f32; 3]) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+
+    //cp with_normal
+    /// Set the normal of the vertex
+    pub fn with_normal(mut self, normal: [f32; 3]) -> Self {
+        self.normal = Some(normal);
+        self
+    }
+
+    //cp with_tex_coords
+    /// Set the texture coordinates of the vertex
+    pub fn with_tex_coords(mut self, tex_coords: [f32; 2]) -> Self {
+        self.tex_coords = Some(tex_coords);
+        self
+    }
+
+    //cp with_color
+    /// Set the color of the vertex
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    //cp with_skin
+    /// Set the joints and weights of the vertex
+    pub fn with_skin(mut self, joints: [u16; 4], weights: [f32; 4]) -> Self {
+        self.skin = Some((joints, weights));
+        self
+    }
+
+    //mi has_attr
+    /// Return true if the vertex has the attribute
+    fn has_attr(&self, attr: VertexAttr) -> bool {
+        match attr {
+            VertexAttr::Position => true,
+            VertexAttr::Normal => self.normal.is_some(),
+            VertexAttr::TexCoords0 => self.tex_coords.is_some(),
+            VertexAttr::Color => self.color.is_some(),
+            VertexAttr::Joints | VertexAttr::Weights => self.skin.is_some(),
+            _ => false,
+        }
+    }
+
+    //mi write_attr
+    /// Append the bytes of an attribute of the vertex to a buffer
+    fn write_attr(&self, attr: VertexAttr, bytes: &mut Vec<u8>) {
+        let floats = |bytes: &mut Vec<u8>, f: &[f32]| {
+            bytes.extend(f.iter().flat_map(|f| f.to_le_bytes()));
+        };
+        let skin = self.skin.unwrap_or_default();
+        match attr {
+            VertexAttr::Position => floats(bytes, &self.position),
+            VertexAttr::Normal => floats(bytes, &self.normal.unwrap_or_default()),
+            VertexAttr::TexCoords0 => floats(bytes, &self.tex_coords.unwrap_or_default()),
+            VertexAttr::Color => floats(bytes, &self.color.unwrap_or_default()),
+            VertexAttr::Joints => bytes.extend(skin.0.iter().flat_map(|j| j.to_le_bytes())),
+            VertexAttr::Weights => floats(bytes, &skin.1),
+            _ => (),
+        }
+    }
+}
+
+//a Attribute formats
+/// The attributes a [MeshBuilder] supports, in the order they are
+/// placed in the vertex buffer
+const ATTRIBUTES: [VertexAttr; 6] = [
+    VertexAttr::Position,
+    VertexAttr::Normal,
+    VertexAttr::TexCoords0,
+    VertexAttr::Color,
+    VertexAttr::Joints,
+    VertexAttr::Weights,
+];
+
+//fi attr_format
+/// The number of elements and element type used for an attribute
+fn attr_format(attr: VertexAttr) -> (u32, BufferElementType) {
+    match attr {
+        VertexAttr::Position | VertexAttr::Normal => (3, BufferElementType::Float32),
+        VertexAttr::TexCoords0 => (2, BufferElementType::Float32),
+        VertexAttr::Joints => (4, BufferElementType::UInt16),
+        _ => (4, BufferElementType::Float32),
+    }
+}
+
+//fi attr_size
+/// The size in bytes of an attribute of one vertex
+fn attr_size(attr: VertexAttr) -> usize {
+    let (n, ele_type) = attr_format(attr);
+    n as usize * ele_type.byte_length()
+}
+
+//a MeshBuilder
+//ti BuilderPrimitive
+/// A primitive pushed to a [MeshBuilder]; its indices are a range of
+/// the builder's indices
+#[derive(Debug, Clone, Copy)]
+struct BuilderPrimitive {
+    primitive_type: PrimitiveType,
+    first_index: usize,
+    index_count: usize,
+    material: ShortIndex,
+}
+
+//tp MeshBuilder
+/// A builder of the vertex and index data for a [Mesh]
+///
+/// Vertices are pushed (returning their index), and then primitives
+/// using those vertex indices are pushed, each with the index of a
+/// material in the [Object] that the mesh will be part of.
+///
+/// The builder then packs the vertices (interleaved or planar) into a
+/// vertex buffer and the indices (as 8-, 16- or 32-bit unsigned
+/// integers) into an index buffer, and creates the [Vertices] and
+/// [Mesh] from them.
+#[derive(Debug, Clone)]
+pub struct MeshBuilder {
+    layout: VertexLayout,
+    index_type: BufferElementType,
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
+    primitives: Vec<BuilderPrimitive>,
+}
+
+//ip Default for MeshBuilder
+impl std::default::Default for MeshBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//ip MeshBuilder
+impl MeshBuilder {
+    //fp new
+    /// Create a new [MeshBuilder], with an interleaved layout and
+    /// 32-bit indices
+    pub fn new() -> Self {
+        Self {
+            layout: VertexLayout::Interleaved,
+            index_type: BufferElementType::UInt32,
+            vertices: vec![],
+            indices: vec![],
+            primitives: vec![],
+        }
+    }
+
+    //cp with_layout
+    /// Set the layout of the vertex buffer
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    //cp with_index_type
+    /// Set the type of the indices - [BufferElementType::UInt16] or
+    /// [BufferElementType::UInt32] (or [BufferElementType::UInt8])
+    pub fn with_index_type(mut self, index_type: BufferElementType) -> Self {
+        self.index_type = index_type;
+        self
+    }
+
+    //ap num_vertices
+    /// Get the number of vertices pushed so far
+    pub fn num_vertices(&self) -> usize {
+        self.vertices.len()
+    }
+
+    //ap num_indices
+    /// Get the number of indices pushed so far (over all the primitives)
+    pub fn num_indices(&self) -> usize {
+        self.indices.len()
+    }
+
+    //mp push_vertex
+    /// Push a vertex, returning its index
+    pub fn push_vertex(&mut self, vertex: MeshVertex) -> u32 {
+        let n = self.vertices.len() as u32;
+        self.vertices.push(vertex);
+        n
+    }
+
+    //mp push_primitive
+    /// Push a primitive of a given type using a material
+    ///
+    /// The indices are of vertices pushed to the builder; they are
+    /// checked when the mesh is built
+    pub fn push_primitive(
+        &mut self,
+        primitive_type: PrimitiveType,
+        indices: &[u32],
+        material: ShortIndex,
+    ) {
+        self.primitives.push(BuilderPrimitive {
+            primitive_type,
+            first_index: self.indices.len(),
+            index_count: indices.len(),
+            material,
+        });
+        self.indices.extend_from_slice(indices);
+    }
+
+    //mp push_triangles
+    /// Push a list of triangles (three indices each) using a material
+    pub fn push_triangles(&mut self, indices: &[u32], material: ShortIndex) {
+        self.push_primitive(PrimitiveType::Triangles, indices, material);
+    }
+
+    //mp push_strip
+    /// Push a triangle strip using a material
+    pub fn push_strip(&mut self, indices: &[u32], material: ShortIndex) {
+        self.push_primitive(PrimitiveType::TriangleStrip, indices, material);
+    }
+
+    //mp push_lines
+    /// Push a list of lines (two indices each) using a material
+    pub fn push_lines(&mut self, indices: &[u32], material: ShortIndex) {
+        self.push_primitive(PrimitiveType::Lines, indices, material);
+    }
+
+    //ap attrs
+    /// Get the attributes that the vertices will have - the position
+    /// first, then any attribute that any vertex has
+    pub fn attrs(&self) -> Vec<VertexAttr> {
+        ATTRIBUTES
+            .into_iter()
+            .filter(|a| self.vertices.iter().any(|v| v.has_attr(*a)))
+            .collect()
+    }
+
+    //mp validate
+    /// Validate the data pushed to the builder
+    pub fn validate(&self) -> Result<(), MeshBuilderError> {
+        let max_vertices = match self.index_type {
+            BufferElementType::UInt8 => 1 << 8,
+            BufferElementType::UInt16 => 1 << 16,
+            BufferElementType::UInt32 => 1 << 32,
+            t => return Err(MeshBuilderError::BadIndexType(t)),
+        };
+        let n = self.vertices.len();
+        if n as u64 > max_vertices {
+            return Err(MeshBuilderError::TooManyVertices(n, self.index_type));
+        }
+        if let Some(i) = self.indices.iter().find(|i| **i as usize >= n) {
+            return Err(MeshBuilderError::IndexOutOfRange(*i, n));
+        }
+        for p in &self.primitives {
+            let count = p.index_count;
+            let valid = match p.primitive_type {
+                PrimitiveType::Points => true,
+                PrimitiveType::Lines => count % 2 == 0,
+                PrimitiveType::LineLoop | PrimitiveType::LineStrip => count != 1,
+                PrimitiveType::Triangles => count % 3 == 0,
+                PrimitiveType::TriangleStrip | PrimitiveType::TriangleFan => {
+                    count == 0 || count >= 3
+                }
+            };
+            if !valid {
+                return Err(MeshBuilderError::BadIndexCount(p.primitive_type, count));
+            }
+        }
+        Ok(())
+    }
+
+    //mi vertex_bytes
+    /// Pack the vertex data with the builder's layout, returning the
+    /// bytes and the byte offset and stride of each attribute
+    fn vertex_bytes(&self, attrs: &[VertexAttr]) -> (Vec<u8>, Vec<(usize, usize)>) {
+        let n = self.vertices.len();
+        let vertex_size: usize = attrs.iter().map(|a| attr_size(*a)).sum();
+        let mut bytes = Vec::with_capacity(n * vertex_size);
+        let mut offsets = vec![];
+        let mut offset = 0;
+        match self.layout {
+            VertexLayout::Interleaved => {
+                for a in attrs {
+                    offsets.push((offset, vertex_size));
+                    offset += attr_size(*a);
+                }
+                for v in &self.vertices {
+                    for a in attrs {
+                        v.write_attr(*a, &mut bytes);
+                    }
+                }
+            }
+            VertexLayout::Planar => {
+                for a in attrs {
+                    offsets.push((offset, 0));
+                    offset += attr_size(*a) * n;
+                    for v in &self.vertices {
+                        v.write_attr(*a, &mut bytes);
+                    }
+                }
+            }
+        }
+        (bytes, offsets)
+    }
+
+    //mi index_bytes
+    /// Pack the indices using the builder's index type
+    fn index_bytes(&self) -> Vec<u8> {
+        match self.index_type {
+            BufferElementType::UInt8 => self.indices.iter().map(|i| *i as u8).collect(),
+            BufferElementType::UInt16 => self
+                .indices
+                .iter()
+                .flat_map(|i| (*i as u16).to_le_bytes())
+                .collect(),
+            _ => self.indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+        }
+    }
+
+    //mi position_bounds
+    /// Get the minimum and maximum of the positions
+    fn position_bounds(&self) -> (Vec<f64>, Vec<f64>) {
+        let mut min = vec![f64::MAX; 3];
+        let mut max = vec![f64::MIN; 3];
+        for v in &self.vertices {
+            for (i, c) in v.position.iter().enumerate() {
+                min[i] = min[i].min(*c as f64);
+                max[i] = max[i].max(*c as f64);
+            }
+        }
+        (min, max)
+    }
+
+    //mp build
+    /// Build the [Vertices] and the [Mesh] that uses them, given the
+    /// index that the [Vertices] will have in the [Object]
+    pub fn build<'a, R: Renderable>(
+        &self,
+        vertices_index: ShortIndex,
+    ) -> Result<(Vertices<'a, R>, Mesh), MeshBuilderError> {
+        self.validate()?;
+        let attrs = self.attrs();
+        let n = self.vertices.len() as u32;
+        let (vertex_bytes, offsets) = self.vertex_bytes(&attrs);
+        let vertex_data = Arc::new(BufferData::of_bytes(vertex_bytes, 0, 0));
+        let index_data = Arc::new(BufferData::of_bytes(self.index_bytes(), 0, 0));
+
+        let indices =
+            BufferAccessor::new(index_data, self.indices.len() as u32, self.index_type, 0, 0);
+        let accessor = |i: usize| {
+            let (elements, ele_type) = attr_format(attrs[i]);
+            let (offset, stride) = offsets[i];
+            BufferAccessor::new(
+                vertex_data.clone(),
+                elements,
+                ele_type,
+                offset as u32,
+                stride as u32,
+            )
+            .with_count(n)
+        };
+        let (min, max) = self.position_bounds();
+        let position = accessor(0);
+        let position = if n > 0 {
+            position.with_bounds(min, max)
+        } else {
+            position
+        };
+        let mut vertices = Vertices::new(Arc::new(indices), Arc::new(position));
+        for (i, attr) in attrs.iter().enumerate().skip(1) {
+            vertices.add_attr(*attr, Arc::new(accessor(i)));
+        }
+
+        let index_size = self.index_type.byte_length();
+        let mut mesh = Mesh::default();
+        for p in &self.primitives {
+            mesh.add_primitive(Primitive::new(
+                p.primitive_type,
+                vertices_index,
+                (p.first_index * index_size) as u32,
+                p.index_count as u32,
+                p.material,
+            ));
+        }
+        Ok((vertices, mesh))
+    }
+
+    //mp add_to_object
+    /// Build the [Vertices] and add them to an [Object], returning the
+    /// [Mesh] that uses them (to be added to a component of the object)
+    pub fn add_to_object<M: Material, R: Renderable>(
+        &self,
+        object: &mut Object<'_, M, R>,
+    ) -> Result<Mesh, MeshBuilderError> {
+        let (vertices, mesh) = self.build(object.vertices.len().into())?;
+        object.add_vertices(Arc::new(vertices));
+        Ok(mesh)
+    }
+
+    //zz All done
+}
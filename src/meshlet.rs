@@ -0,0 +1,535 @@
+//a Documentation
+/*!
+
+This provides the splitting of the triangles of a [Primitive] into
+meshlets - small clusters of triangles with a limited number of
+vertices and triangles, as used by mesh shader and other cluster based
+renderers.
+
+Each meshlet has a vertex remap table (the indices in the [Vertices]
+of its local vertices) and a micro-index buffer (three local vertex
+indices, as bytes, for each of its triangles); the tables and buffers
+of all the meshlets of a primitive are held in one [Meshlets], with the
+offset and count of each meshlet's within them.
+
+Meshlets are built greedily: triangles are added to the current
+meshlet preferring those that add the fewest new vertices (and then
+those nearest its center, and optionally those facing most nearly its
+way), until no more fit, when a new meshlet is started.
+
+Each meshlet also has a bounding sphere, for frustum and occlusion
+culling, and a [NormalCone] - a cone containing the normals of its
+triangles - with which a meshlet whose triangles all face away from the
+camera may be culled.
+
+The [Meshlets] are plain data, which may be added to the [Vertices]
+whose vertices they use, for any [crate::Renderable] client to upload
+when its [Vertices] client is created.
+
+!*/
+
+//a Imports
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    list_indices, AccessorError, BoundingSphere, Bounds, Primitive, PrimitiveType, Renderable,
+    Vec3, Vertices,
+};
+
+//a Constants
+/// Marker for a vertex that is not in the current meshlet
+const NONE: u32 = u32::MAX;
+
+//a Helper functions
+//fi sub
+fn sub(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+//fi dot
+fn dot(a: &Vec3, b: &Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+//fi cross
+fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+//fi normalize
+/// Normalize a vector, leaving a zero vector as it is
+fn normalize(a: Vec3) -> Vec3 {
+    let l = dot(&a, &a).sqrt();
+    if l == 0. {
+        a
+    } else {
+        [a[0] / l, a[1] / l, a[2] / l]
+    }
+}
+
+//a MeshletBuilder
+//tp MeshletBuilder
+/// The parameters for building the meshlets of a [Primitive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshletBuilder {
+    /// The largest number of vertices of a meshlet; at least 3 and at
+    /// most 256 (as the micro-indices are bytes)
+    pub max_vertices: usize,
+    /// The largest number of triangles of a meshlet; at least 1
+    pub max_triangles: usize,
+    /// How strongly triangles facing the way of the meshlet are
+    /// preferred over those nearest it (0 for not at all, to 1), for
+    /// tighter normal cones
+    pub cone_weight: f32,
+}
+
+//ip Default for MeshletBuilder
+impl Default for MeshletBuilder {
+    fn default() -> Self {
+        Self {
+            max_vertices: 64,
+            max_triangles: 124,
+            cone_weight: 0.,
+        }
+    }
+}
+
+//ip MeshletBuilder
+impl MeshletBuilder {
+    //fp new
+    /// Create a new [MeshletBuilder] for meshlets of at most 64
+    /// vertices and 124 triangles
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with_max_vertices
+    /// Set the largest number of vertices of a meshlet
+    pub fn with_max_vertices(mut self, max_vertices: usize) -> Self {
+        self.max_vertices = max_vertices;
+        self
+    }
+
+    //cp with_max_triangles
+    /// Set the largest number of triangles of a meshlet
+    pub fn with_max_triangles(mut self, max_triangles: usize) -> Self {
+        self.max_triangles = max_triangles;
+        self
+    }
+
+    //cp with_cone_weight
+    /// Set how strongly triangles facing the way of the meshlet are
+    /// preferred
+    pub fn with_cone_weight(mut self, cone_weight: f32) -> Self {
+        self.cone_weight = cone_weight;
+        self
+    }
+}
+
+//a NormalCone, Meshlet, Meshlets
+//tp NormalCone
+/// A cone containing the normals of the triangles of a meshlet, for
+/// culling meshlets whose triangles all face away from the camera
+///
+/// A cutoff of 1 indicates that the normals are too spread for the
+/// meshlet ever to be culled
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NormalCone {
+    /// The apex of the cone; the triangles face away from any camera
+    /// within the cone with this apex and the opposite axis
+    pub apex: Vec3,
+    /// The (unit) axis of the cone - the mean direction of the normals
+    pub axis: Vec3,
+    /// The sine of the largest angle between a normal and the axis;
+    /// the cosine of the half-angle of the cone of camera positions
+    /// from which the triangles all face away
+    pub cutoff: f32,
+}
+
+//ip NormalCone
+impl NormalCone {
+    //ap is_backfacing
+    /// Return true if all the triangles face away from a camera at a
+    /// position (in the space of the vertices)
+    pub fn is_backfacing(&self, camera: &Vec3) -> bool {
+        if self.cutoff >= 1. {
+            return false;
+        }
+        let d = normalize(sub(&self.apex, camera));
+        dot(&d, &self.axis) >= self.cutoff
+    }
+}
+
+//tp Meshlet
+/// A meshlet of [Meshlets] - the ranges of its vertex remap table and
+/// micro-indices within those of the [Meshlets], with its bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Meshlet {
+    /// The offset of the meshlet's vertex remap table in the vertices
+    /// of the [Meshlets]
+    pub vertex_offset: u32,
+    /// The number of vertices of the meshlet
+    pub vertex_count: u32,
+    /// The offset of the meshlet's micro-indices in the triangles of
+    /// the [Meshlets]
+    pub triangle_offset: u32,
+    /// The number of triangles of the meshlet (a third of the number of
+    /// its micro-indices)
+    pub triangle_count: u32,
+    /// The bounding sphere of the vertices of the meshlet
+    pub sphere: BoundingSphere,
+    /// The cone of the normals of the triangles of the meshlet
+    pub cone: NormalCone,
+}
+
+//tp Meshlets
+/// The meshlets of a [Primitive], with their vertex remap tables and
+/// micro-index buffers
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Meshlets {
+    /// The primitive the meshlets were built from, which they may be
+    /// drawn in place of
+    pub primitive: Primitive,
+    /// The meshlets
+    pub meshlets: Vec<Meshlet>,
+    /// The vertex remap tables of all the meshlets - the indices in the
+    /// [Vertices] of the local vertices of each
+    pub vertices: Vec<u32>,
+    /// The micro-indices of all the meshlets - three local vertex
+    /// indices for each triangle
+    pub triangles: Vec<u8>,
+}
+
+//ip Meshlets
+impl Meshlets {
+    //fp empty
+    /// Create a new [Meshlets] for a [Primitive] with no meshlets
+    pub fn empty(primitive: &Primitive) -> Self {
+        Self {
+            primitive: primitive.clone(),
+            meshlets: Vec::new(),
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+        }
+    }
+
+    //ap vertices_of
+    /// Get the vertex remap table of a meshlet
+    pub fn vertices_of(&self, meshlet: &Meshlet) -> &[u32] {
+        let first = meshlet.vertex_offset as usize;
+        &self.vertices[first..first + meshlet.vertex_count as usize]
+    }
+
+    //ap triangles_of
+    /// Get the micro-indices of a meshlet
+    pub fn triangles_of(&self, meshlet: &Meshlet) -> &[u8] {
+        let first = meshlet.triangle_offset as usize;
+        &self.triangles[first..first + 3 * meshlet.triangle_count as usize]
+    }
+
+    //mp indices
+    /// Get the indices (in the [Vertices]) of the triangles of all the
+    /// meshlets, in order, as a triangle list
+    pub fn indices(&self) -> Vec<u32> {
+        let mut indices = Vec::with_capacity(self.triangles.len());
+        for m in &self.meshlets {
+            let vertices = self.vertices_of(m);
+            indices.extend(self.triangles_of(m).iter().map(|i| vertices[*i as usize]));
+        }
+        indices
+    }
+}
+
+//a Clusterer
+//ti Clusterer
+/// The state for building the meshlets of a triangle list
+struct Clusterer<'a> {
+    /// The positions of the vertices
+    positions: &'a [Vec3],
+    /// The triangles
+    triangles: Vec<[u32; 3]>,
+    /// The unit normal of each triangle (zero if it is degenerate)
+    normals: Vec<Vec3>,
+    /// The centroid of each triangle
+    centroids: Vec<Vec3>,
+    /// The start of the triangles of each vertex in `vertex_triangles`
+    first_triangle: Vec<usize>,
+    /// The triangles using each vertex
+    vertex_triangles: Vec<u32>,
+    /// True for each triangle in a meshlet
+    used: Vec<bool>,
+    /// The local index of each vertex in the current meshlet, or NONE
+    local: Vec<u32>,
+    /// The vertices of the current meshlet
+    vertices: Vec<u32>,
+    /// The triangles of the current meshlet
+    meshlet_triangles: Vec<u32>,
+    /// The micro-indices of the current meshlet
+    micro_indices: Vec<u8>,
+    /// The sum of the centroids of the triangles of the current meshlet
+    centroid_sum: Vec3,
+    /// The sum of the normals of the triangles of the current meshlet
+    normal_sum: Vec3,
+}
+
+//ii Clusterer
+impl<'a> Clusterer<'a> {
+    //fi new
+    /// Create a [Clusterer] for the triangles of a triangle list
+    fn new(positions: &'a [Vec3], indices: &[u32]) -> Self {
+        let triangles: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .collect();
+        let mut normals = Vec::with_capacity(triangles.len());
+        let mut centroids = Vec::with_capacity(triangles.len());
+        for t in &triangles {
+            let [a, b, c] = t.map(|v| positions[v as usize]);
+            normals.push(normalize(cross(&sub(&b, &a), &sub(&c, &a))));
+            centroids.push([0, 1, 2].map(|i| (a[i] + b[i] + c[i]) / 3.));
+        }
+
+        let n = positions.len();
+        let mut first_triangle = vec![0; n + 1];
+        for t in &triangles {
+            for v in t {
+                first_triangle[*v as usize + 1] += 1;
+            }
+        }
+        for v in 0..n {
+            first_triangle[v + 1] += first_triangle[v];
+        }
+        let mut fill = first_triangle.clone();
+        let mut vertex_triangles = vec![0; indices.len() / 3 * 3];
+        for (i, t) in triangles.iter().enumerate() {
+            for v in t {
+                vertex_triangles[fill[*v as usize]] = i as u32;
+                fill[*v as usize] += 1;
+            }
+        }
+
+        Self {
+            positions,
+            used: vec![false; triangles.len()],
+            triangles,
+            normals,
+            centroids,
+            first_triangle,
+            vertex_triangles,
+            local: vec![NONE; n],
+            vertices: Vec::new(),
+            meshlet_triangles: Vec::new(),
+            micro_indices: Vec::new(),
+            centroid_sum: [0.; 3],
+            normal_sum: [0.; 3],
+        }
+    }
+
+    //mi new_vertices
+    /// Get the number of vertices of a triangle not in the current
+    /// meshlet
+    fn new_vertices(&self, t: usize) -> usize {
+        let mut count = 0;
+        for (i, v) in self.triangles[t].iter().enumerate() {
+            if self.local[*v as usize] == NONE && !self.triangles[t][..i].contains(v) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    //mi best_adjacent
+    /// Find the best unused triangle that shares a vertex with the
+    /// current meshlet and fits within it
+    fn best_adjacent(&self, max_vertices: usize, cone_weight: f32) -> Option<usize> {
+        let count = self.meshlet_triangles.len() as f32;
+        let center = self.centroid_sum.map(|c| c / count);
+        let axis = normalize(self.normal_sum);
+        let mut best: Option<(usize, f32, usize)> = None;
+        for v in &self.vertices {
+            let v = *v as usize;
+            for t in &self.vertex_triangles[self.first_triangle[v]..self.first_triangle[v + 1]] {
+                let t = *t as usize;
+                if self.used[t] {
+                    continue;
+                }
+                let extra = self.new_vertices(t);
+                if self.vertices.len() + extra > max_vertices {
+                    continue;
+                }
+                let d = sub(&self.centroids[t], &center);
+                let spread = 1. - dot(&self.normals[t], &axis);
+                let cost = dot(&d, &d).sqrt() * (1. + cone_weight * spread);
+                let better = match best {
+                    None => true,
+                    Some((e, c, _)) => (extra, cost) < (e, c),
+                };
+                if better {
+                    best = Some((extra, cost, t));
+                }
+            }
+        }
+        best.map(|(_, _, t)| t)
+    }
+
+    //mi add
+    /// Add a triangle to the current meshlet
+    fn add(&mut self, t: usize) {
+        self.used[t] = true;
+        for v in self.triangles[t] {
+            let v = v as usize;
+            if self.local[v] == NONE {
+                self.local[v] = self.vertices.len() as u32;
+                self.vertices.push(v as u32);
+            }
+            self.micro_indices.push(self.local[v] as u8);
+        }
+        self.meshlet_triangles.push(t as u32);
+        let (centroid, normal) = (self.centroids[t], self.normals[t]);
+        self.centroid_sum = [0, 1, 2].map(|i| self.centroid_sum[i] + centroid[i]);
+        self.normal_sum = [0, 1, 2].map(|i| self.normal_sum[i] + normal[i]);
+    }
+
+    //mi cone
+    /// Get the [NormalCone] of the current meshlet, given the center of
+    /// its bounding sphere
+    fn cone(&self, center: Vec3) -> NormalCone {
+        let no_culling = NormalCone {
+            apex: center,
+            axis: [0.; 3],
+            cutoff: 1.,
+        };
+        let axis = normalize(self.normal_sum);
+        let normals = || {
+            self.meshlet_triangles
+                .iter()
+                .map(|t| *t as usize)
+                .filter(|t| self.normals[*t] != [0.; 3])
+        };
+        let min_dot = normals().fold(1_f32, |m, t| m.min(dot(&self.normals[t], &axis)));
+        if axis == [0.; 3] || min_dot <= 0.1 {
+            return no_culling;
+        }
+        // The apex is moved back along the axis until it is behind the
+        // planes of all the triangles
+        let mut max_t = 0_f32;
+        for t in normals() {
+            let n = &self.normals[t];
+            let corner = &self.positions[self.triangles[t][0] as usize];
+            max_t = max_t.max(dot(&sub(&center, corner), n) / dot(&axis, n));
+        }
+        NormalCone {
+            apex: [0, 1, 2].map(|i| center[i] - axis[i] * max_t),
+            axis,
+            cutoff: (1. - min_dot * min_dot).sqrt(),
+        }
+    }
+
+    //mi finish
+    /// Finish the current meshlet, if it has any triangles
+    fn finish(&mut self, meshlets: &mut Meshlets) {
+        if self.meshlet_triangles.is_empty() {
+            return;
+        }
+        let points: Vec<Vec3> = self
+            .vertices
+            .iter()
+            .map(|v| self.positions[*v as usize])
+            .collect();
+        let sphere = Bounds::of_points(&points).unwrap().sphere;
+        meshlets.meshlets.push(Meshlet {
+            vertex_offset: meshlets.vertices.len() as u32,
+            vertex_count: self.vertices.len() as u32,
+            triangle_offset: meshlets.triangles.len() as u32,
+            triangle_count: self.meshlet_triangles.len() as u32,
+            sphere,
+            cone: self.cone(sphere.center),
+        });
+        for v in self.vertices.drain(..) {
+            self.local[v as usize] = NONE;
+            meshlets.vertices.push(v);
+        }
+        meshlets.triangles.append(&mut self.micro_indices);
+        self.meshlet_triangles.clear();
+        self.centroid_sum = [0.; 3];
+        self.normal_sum = [0.; 3];
+    }
+
+    //mi build
+    /// Build the meshlets of all the triangles
+    ///
+    /// If no unused triangle shares a vertex with the current meshlet
+    /// then the next unused triangle (in order) is added if it fits
+    fn build(mut self, builder: &MeshletBuilder, primitive: &Primitive) -> Meshlets {
+        let max_vertices = builder.max_vertices.clamp(3, 256);
+        let max_triangles = builder.max_triangles.max(1);
+        let mut meshlets = Meshlets::empty(primitive);
+        let mut next = 0;
+        loop {
+            while next < self.triangles.len() && self.used[next] {
+                next += 1;
+            }
+            if next == self.triangles.len() {
+                break;
+            }
+            let t = {
+                if self.meshlet_triangles.is_empty() {
+                    Some(next)
+                } else if self.meshlet_triangles.len() == max_triangles {
+                    None
+                } else {
+                    self.best_adjacent(max_vertices, builder.cone_weight)
+                        .or_else(|| {
+                            (self.vertices.len() + self.new_vertices(next) <= max_vertices)
+                                .then_some(next)
+                        })
+                }
+            };
+            match t {
+                Some(t) => self.add(t),
+                None => self.finish(&mut meshlets),
+            }
+        }
+        self.finish(&mut meshlets);
+        meshlets
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp build_meshlets
+    /// Build the [Meshlets] of a [Primitive] that uses this [Vertices]
+    ///
+    /// Triangle strips and fans are split as lists; other primitives
+    /// have no meshlets
+    ///
+    /// An error is returned if the data cannot be read, or if an index
+    /// is beyond the vertices of the position accessor
+    pub fn build_meshlets(
+        &self,
+        primitive: &Primitive,
+        builder: &MeshletBuilder,
+    ) -> Result<Meshlets, AccessorError> {
+        let position = self.borrow_position();
+        let n = position.count as usize;
+        let indices = self.primitive_indices(primitive)?;
+        if let Some(i) = indices.iter().find(|i| **i as usize >= n) {
+            return Err(AccessorError::BadIndex(*i, n));
+        }
+        if primitive.primitive_type().list_type() != PrimitiveType::Triangles {
+            return Ok(Meshlets::empty(primitive));
+        }
+        let indices = list_indices(primitive.primitive_type(), &indices);
+        let positions: Vec<Vec3> = position.iter_elements(n)?.collect();
+        Ok(Clusterer::new(&positions, &indices).build(builder, primitive))
+    }
+}
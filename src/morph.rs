@@ -0,0 +1,206 @@
+//a Documentation
+/*!
+
+This provides morph targets (also known as blend shapes) for
+[Vertices], and their evaluation on the CPU.
+
+A [MorphTarget] is a set of [BufferAccessor]s of displacements (deltas)
+of some of the attributes of the vertices - generally the position,
+normal and tangent - with one element for each vertex. A [Vertices]
+may have a number of morph targets; the morphed value of an attribute
+of a vertex is its value plus the deltas of each target scaled by the
+weight for that target.
+
+A [crate::Mesh] holds the default weights for the morph targets of the
+[Vertices] its primitives use; a [RenderRecipe] keeps the meshes that
+have weights, and an [Instance] has its own (initially the default)
+weights for each of these, which may be animated.
+
+The deltas are generally applied by a renderer, which is given the
+accessors of the targets through the `init_morph_target_client` method
+of [Renderable]; the morphed positions and normals may also be
+evaluated here.
+
+!*/
+
+//a Imports
+use crate::{
+    AccessorError, BufferAccessor, Instance, RenderRecipe, Renderable, Shared, Vec3, VertexAttr,
+    Vertices,
+};
+
+//a Helper functions
+//fi normalize
+fn normalize(a: Vec3) -> Vec3 {
+    let l = (a[0] * a[0] + a[1] * a[1] + a[2] * a[2]).sqrt();
+    if l == 0. {
+        a
+    } else {
+        [a[0] / l, a[1] / l, a[2] / l]
+    }
+}
+
+//a MorphTarget
+//tp MorphTarget
+/// A morph target of a [Vertices] - [BufferAccessor]s of the deltas of
+/// some of its attributes (such as [VertexAttr::Position],
+/// [VertexAttr::Normal] and [VertexAttr::Tangent]), each with an
+/// element for every vertex
+#[derive(Debug)]
+pub struct MorphTarget<'vertices, R: Renderable> {
+    deltas: Vec<(VertexAttr, Shared<'vertices, BufferAccessor<'vertices, R>>)>,
+}
+
+//ip Clone for MorphTarget
+impl<'vertices, R: Renderable> Clone for MorphTarget<'vertices, R> {
+    fn clone(&self) -> Self {
+        Self {
+            deltas: self.deltas.clone(),
+        }
+    }
+}
+
+//ip Default for MorphTarget
+impl<'vertices, R: Renderable> Default for MorphTarget<'vertices, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//ip MorphTarget
+impl<'vertices, R: Renderable> MorphTarget<'vertices, R> {
+    //fp new
+    /// Create a new [MorphTarget] with no deltas
+    pub fn new() -> Self {
+        let deltas = Vec::new();
+        Self { deltas }
+    }
+
+    //mp add_delta
+    /// Add a [BufferAccessor] of the deltas of a particular [VertexAttr]
+    pub fn add_delta<A: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>>(
+        &mut self,
+        attr: VertexAttr,
+        accessor: A,
+    ) {
+        self.deltas.push((attr, accessor.into()));
+    }
+
+    //mp borrow_delta
+    /// Borrow the deltas [BufferAccessor] of an attribute if the target
+    /// has one
+    pub fn borrow_delta<'a>(
+        &'a self,
+        attr: VertexAttr,
+    ) -> Option<&'a BufferAccessor<'vertices, R>> {
+        self.deltas
+            .iter()
+            .find(|(a, _)| *a == attr)
+            .map(|(_, accessor)| &**accessor)
+    }
+
+    //mp iter_deltas
+    /// Iterate through the deltas of the attributes
+    pub fn iter_deltas(
+        &self,
+    ) -> impl Iterator<Item = (VertexAttr, &BufferAccessor<'vertices, R>)> + '_ {
+        self.deltas
+            .iter()
+            .map(|(attr, accessor)| (*attr, &**accessor))
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mi morphed
+    /// Get the values of an attribute of all the vertices (the `count`
+    /// of the position accessor), as three components, with the deltas
+    /// of the morph targets applied with the given weights
+    fn morphed(
+        &self,
+        attr: VertexAttr,
+        accessor: &BufferAccessor<'vertices, R>,
+        weights: &[f32],
+    ) -> Result<Vec<Vec3>, AccessorError> {
+        let n = self.borrow_position().count as usize;
+        let mut values: Vec<Vec3> = accessor.iter_elements(n)?.collect();
+        for (target, weight) in self.iter_morph_targets().zip(weights) {
+            let Some(deltas) = target.borrow_delta(attr) else {
+                continue;
+            };
+            if *weight == 0. {
+                continue;
+            }
+            for (v, d) in values.iter_mut().zip(deltas.iter_elements::<f32, 3>(n)?) {
+                *v = [0, 1, 2].map(|i| v[i] + weight * d[i]);
+            }
+        }
+        Ok(values)
+    }
+
+    //mp morphed_positions
+    /// Get the positions of all the vertices with the morph targets
+    /// applied with the given weights (one for each target)
+    ///
+    /// Targets without weights are not applied, and weights without
+    /// targets are ignored
+    pub fn morphed_positions(&self, weights: &[f32]) -> Result<Vec<Vec3>, AccessorError> {
+        self.morphed(VertexAttr::Position, self.borrow_position(), weights)
+    }
+
+    //mp morphed_normals
+    /// Get the normals of all the vertices with the morph targets
+    /// applied with the given weights (as for `morphed_positions`),
+    /// normalized
+    ///
+    /// An error is returned if the [Vertices] has no normals
+    pub fn morphed_normals(&self, weights: &[f32]) -> Result<Vec<Vec3>, AccessorError> {
+        let normals = self
+            .borrow_attr(VertexAttr::Normal)
+            .ok_or(AccessorError::MissingAttr(VertexAttr::Normal))?;
+        let normals = self.morphed(VertexAttr::Normal, normals, weights)?;
+        Ok(normals.into_iter().map(normalize).collect())
+    }
+}
+
+//a MorphMesh
+//tp MorphMesh
+/// A mesh with morph target weights in a [RenderRecipe]
+#[derive(Debug, Clone)]
+pub struct MorphMesh {
+    /// The index of the first primitive of the mesh in the primitives
+    /// of the recipe
+    pub first: usize,
+    /// The number of primitives of the mesh
+    pub count: usize,
+    /// The default weights of the mesh
+    pub weights: Vec<f32>,
+}
+
+//a RenderRecipe
+//ip RenderRecipe
+impl RenderRecipe {
+    //ap morph_mesh_of_primitive
+    /// Get the index of the mesh with morph target weights that a
+    /// primitive (by index) of the recipe belongs to, if any
+    pub fn morph_mesh_of_primitive(&self, primitive: usize) -> Option<usize> {
+        self.morph_meshes
+            .iter()
+            .position(|m| (m.first..m.first + m.count).contains(&primitive))
+    }
+}
+
+//a Instance
+//ip Instance
+impl<'a, R: Renderable> Instance<'a, R> {
+    //ap primitive_weights
+    /// Get the morph target weights of the instance to draw a primitive
+    /// (by index) of the render recipe of its [crate::Instantiable]
+    /// with, if its mesh has weights
+    pub fn primitive_weights(&self, primitive: usize) -> Option<&[f32]> {
+        let recipe = &self.instantiable.render_recipe;
+        let m = recipe.morph_mesh_of_primitive(primitive)?;
+        self.morph_weights.get(m).map(|w| &w[..])
+    }
+}
@@ -0,0 +1,62 @@
+//a Documentation
+/*!
+
+# Native binary model format
+
+This module (enabled with the `native` feature) provides a native
+binary container for an [crate::ObjectDescription], designed for fast
+loading of large models: the file can be memory-mapped (with the
+`mmap` feature), and the [crate::BufferData] and [crate::Texture]s of
+the [crate::Object] then use the mapping directly, so that no
+per-vertex parsing is required.
+
+A file consists of:
+
+* a [Header] of [HEADER_SIZE] bytes, with a magic, version, file
+  length and a CRC-32 of the region table and descriptor
+
+* a table of [Region]s, one per buffer of the description - the byte
+  offset, length and CRC-32 of its data within the file
+
+* the descriptor - the accessors, vertices, textures, materials,
+  component hierarchy and skeleton of the description (as JSON)
+
+* the data of each region, aligned to [REGION_ALIGNMENT] bytes
+
+When a file is loaded as a [NativeFile] the header, checksum, region
+table and descriptor are all validated, so that a truncated or
+corrupt file is reported as an [Error] rather than causing a panic
+when the object is built. The data of the regions is not read (so a
+large memory-mapped file is not paged in just to be opened); it is
+checked against the checksums in the region table by
+[NativeFile::verify].
+
+# Example
+
+```ignore
+use mod3d_base::native::{self, NativeFile};
+use mod3d_base::{ObjectDescription, PbrMaterial};
+
+native::write_file(&ObjectDescription::of_object(&object), "level.m3d")?;
+
+let file = NativeFile::<_, PbrMaterial>::map_file("level.m3d")?;
+let data = file.make_buffer_data::<R>();
+let accessors = file.make_accessors(&data);
+let vertices = file.make_vertices(&accessors);
+let textures = file.make_textures::<R>();
+let object = file.make_object(&vertices, &textures);
+```
+
+!*/
+
+//a Imports and exports
+mod error;
+mod format;
+mod native_file;
+mod write;
+
+pub use error::{Error, Result};
+pub use format::{Header, Region};
+pub use format::{HEADER_SIZE, NATIVE_MAGIC, NATIVE_VERSION, REGION_ALIGNMENT, REGION_SIZE};
+pub use native_file::NativeFile;
+pub use write::{write, write_file};
@@ -0,0 +1,68 @@
+//a Error
+//tp Error
+/// An error in reading or writing a native binary model file
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error in reading, writing or mapping the file
+    Io(std::io::Error),
+    /// The file is not a native model file (it has the wrong magic)
+    BadMagic,
+    /// The file has a version that is not supported
+    Version(u32),
+    /// The file is shorter than it should be; the required and
+    /// actual lengths in bytes
+    Truncated(u64, u64),
+    /// The checksum of the file contents is wrong; the checksum in the
+    /// header and that of the contents
+    Checksum(u32, u32),
+    /// The file contents are inconsistent
+    Corrupt(String),
+    /// The descriptor (of accessors, vertices, materials etc) could
+    /// not be serialized or deserialized
+    Descriptor(String),
+    /// The data would be too large for a native model file
+    TooLarge(u64),
+}
+
+//tp Result
+/// The [Result] type for the native module
+pub type Result<T> = std::result::Result<T, Error>;
+
+//ip Display for Error
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::BadMagic => write!(f, "not a native model file"),
+            Self::Version(v) => write!(f, "unsupported native model file version {v}"),
+            Self::Truncated(needed, found) => write!(
+                f,
+                "native model file is truncated: needed {needed} bytes but found {found}"
+            ),
+            Self::Checksum(expected, found) => write!(
+                f,
+                "native model file is corrupt: checksum {found:08x} does not match {expected:08x}"
+            ),
+            Self::Corrupt(s) => write!(f, "native model file is corrupt: {s}"),
+            Self::Descriptor(s) => write!(f, "bad native model descriptor: {s}"),
+            Self::TooLarge(n) => write!(f, "{n} bytes is too large for a native model file"),
+        }
+    }
+}
+
+//ip Error for Error
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+//ip From<std::io::Error> for Error
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,179 @@
+//a Imports
+use super::{Error, Result};
+
+//a Constants
+/// The magic at the start of every native model file
+pub const NATIVE_MAGIC: [u8; 8] = *b"MOD3DNAT";
+
+/// The version of the native model file format that is written (and
+/// the latest that can be read)
+pub const NATIVE_VERSION: u32 = 1;
+
+/// Size in bytes of the header
+pub const HEADER_SIZE: usize = 64;
+
+/// Size in bytes of each entry in the region table
+pub const REGION_SIZE: usize = 24;
+
+/// Alignment in bytes of each region from the start of the file
+pub const REGION_ALIGNMENT: usize = 16;
+
+//fi read_u32
+/// Read a little-endian u32 from a byte offset
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+//fi read_u64
+/// Read a little-endian u64 from a byte offset
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+//a Header
+//tp Header
+/// The header of a native model file
+///
+/// This is [HEADER_SIZE] bytes, little-endian:
+///
+/// | Offset | Size | Content                                      |
+/// |--------|------|----------------------------------------------|
+/// | 0      | 8    | Magic ('MOD3DNAT')                           |
+/// | 8      | 4    | Version                                      |
+/// | 12     | 4    | Flags (zero)                                 |
+/// | 16     | 8    | Length of the file in bytes                  |
+/// | 24     | 8    | Number of regions                            |
+/// | 32     | 8    | Offset of the descriptor                     |
+/// | 40     | 8    | Length of the descriptor                     |
+/// | 48     | 4    | CRC-32 of the region table and descriptor    |
+/// | 52     | 12   | Reserved (zero)                              |
+///
+/// The region table follows the header directly; the data of the
+/// regions is not covered by the checksum of the header, as each
+/// region has its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Header {
+    /// The version of the file format
+    pub version: u32,
+    /// Flags (currently always zero)
+    pub flags: u32,
+    /// The length of the file in bytes
+    pub file_length: u64,
+    /// The number of regions in the region table
+    pub region_count: u64,
+    /// The byte offset of the descriptor
+    pub descriptor_offset: u64,
+    /// The length in bytes of the descriptor
+    pub descriptor_length: u64,
+    /// The CRC-32 of the region table followed by the descriptor
+    pub checksum: u32,
+}
+
+//ip Header
+impl Header {
+    //fp parse
+    /// Parse the header from the start of a file, checking the magic,
+    /// version and length
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_SIZE {
+            return Err(Error::Truncated(HEADER_SIZE as u64, bytes.len() as u64));
+        }
+        if bytes[0..8] != NATIVE_MAGIC {
+            return Err(Error::BadMagic);
+        }
+        let header = Self {
+            version: read_u32(bytes, 8),
+            flags: read_u32(bytes, 12),
+            file_length: read_u64(bytes, 16),
+            region_count: read_u64(bytes, 24),
+            descriptor_offset: read_u64(bytes, 32),
+            descriptor_length: read_u64(bytes, 40),
+            checksum: read_u32(bytes, 48),
+        };
+        if header.version == 0 || header.version > NATIVE_VERSION {
+            return Err(Error::Version(header.version));
+        }
+        if header.file_length > bytes.len() as u64 {
+            return Err(Error::Truncated(header.file_length, bytes.len() as u64));
+        }
+        if header.file_length < HEADER_SIZE as u64 {
+            return Err(Error::Corrupt(format!(
+                "file length {} is shorter than the header",
+                header.file_length
+            )));
+        }
+        Ok(header)
+    }
+
+    //mp as_bytes
+    /// Get the bytes of the header as written to a file
+    pub fn as_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..8].copy_from_slice(&NATIVE_MAGIC);
+        bytes[8..12].copy_from_slice(&self.version.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.flags.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.file_length.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.region_count.to_le_bytes());
+        bytes[32..40].copy_from_slice(&self.descriptor_offset.to_le_bytes());
+        bytes[40..48].copy_from_slice(&self.descriptor_length.to_le_bytes());
+        bytes[48..52].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes
+    }
+
+    //zz All done
+}
+
+//a Region
+//tp Region
+/// An entry in the region table of a native model file - a byte
+/// offset and length within the file of the data for one buffer, and
+/// the CRC-32 of that data
+///
+/// This is [REGION_SIZE] bytes, little-endian:
+///
+/// | Offset | Size | Content                                      |
+/// |--------|------|----------------------------------------------|
+/// | 0      | 8    | Byte offset from the start of the file       |
+/// | 8      | 8    | Length in bytes                              |
+/// | 16     | 4    | CRC-32 of the data                           |
+/// | 20     | 4    | Reserved (zero)                              |
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Region {
+    /// Byte offset from the start of the file
+    pub offset: u64,
+    /// Length in bytes
+    pub length: u64,
+    /// The CRC-32 of the data
+    pub checksum: u32,
+}
+
+//ip Region
+impl Region {
+    //fp parse
+    /// Parse a region table entry
+    pub fn parse(bytes: &[u8]) -> Self {
+        Self {
+            offset: read_u64(bytes, 0),
+            length: read_u64(bytes, 8),
+            checksum: read_u32(bytes, 16),
+        }
+    }
+
+    //mp as_bytes
+    /// Get the bytes of the region table entry as written to a file
+    pub fn as_bytes(&self) -> [u8; REGION_SIZE] {
+        let mut bytes = [0; REGION_SIZE];
+        bytes[0..8].copy_from_slice(&self.offset.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.length.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.checksum.to_le_bytes());
+        bytes
+    }
+
+    //ap end
+    /// Get the byte offset of the end of the region
+    pub fn end(&self) -> u64 {
+        self.offset.saturating_add(self.length)
+    }
+
+    //zz All done
+}
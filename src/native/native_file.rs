@@ -0,0 +1,322 @@
+//a Imports
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+use super::{Error, Header, Region, Result};
+use super::{HEADER_SIZE, REGION_SIZE};
+use crate::checksum::{crc32, crc32_extend};
+use crate::{
+    BufferAccessor, BufferData, ByteBuffer, ByteData, Material, Object, ObjectDescription,
+    Renderable, Texture, Vertices,
+};
+
+//a NativeFile
+//tp NativeFile
+/// The contents of a native model file, held in a [ByteBuffer] (such
+/// as a `Vec<u8>` or, with the `mmap` feature, a memory mapping of
+/// the file)
+///
+/// When created the header, checksum, region table and descriptor
+/// are validated, but the data of the regions is not read; it may be
+/// checked with `verify` or `verify_region`. The [Object] is then
+/// built in stages, each
+/// borrowing the previous, with the [BufferData] and [Texture]s using
+/// the regions of the file directly:
+///
+/// ```ignore
+/// let file = NativeFile::<_, PbrMaterial>::map_file("level.m3d")?;
+/// let data = file.make_buffer_data::<R>();
+/// let accessors = file.make_accessors(&data);
+/// let vertices = file.make_vertices(&accessors);
+/// let textures = file.make_textures::<R>();
+/// let object = file.make_object(&vertices, &textures);
+/// ```
+///
+/// Alternatively the file can be converted into a `'static` [Object]
+/// that owns the contents of the file, with `into_object`.
+#[derive(Debug)]
+pub struct NativeFile<B, M>
+where
+    B: ByteBuffer,
+    M: Material,
+{
+    /// The contents of the file
+    data: B,
+    /// The header of the file
+    header: Header,
+    /// The regions of the file, one per buffer of the description
+    regions: Vec<Region>,
+    /// The description of the object, without its buffers
+    description: ObjectDescription<M>,
+}
+
+//ip NativeFile
+impl<B, M> NativeFile<B, M>
+where
+    B: ByteBuffer,
+    M: Material + DeserializeOwned,
+{
+    //fp new
+    /// Create a [NativeFile] from the contents of a file, validating
+    /// it
+    pub fn new(data: B) -> Result<Self> {
+        let bytes = data.borrow_bytes();
+        let header = Header::parse(bytes)?;
+        let contents = &bytes[..header.file_length as usize];
+
+        let table_end = (header.region_count)
+            .checked_mul(REGION_SIZE as u64)
+            .and_then(|n| n.checked_add(HEADER_SIZE as u64))
+            .filter(|n| *n <= header.file_length)
+            .ok_or_else(|| {
+                Error::Corrupt(format!(
+                    "region table of {} regions does not fit in the file",
+                    header.region_count
+                ))
+            })?;
+        let descriptor_end = header
+            .descriptor_offset
+            .saturating_add(header.descriptor_length);
+        if header.descriptor_offset < table_end || descriptor_end > header.file_length {
+            return Err(Error::Corrupt(format!(
+                "descriptor {}+#{} is not within the file",
+                header.descriptor_offset, header.descriptor_length
+            )));
+        }
+        let table = &contents[HEADER_SIZE..table_end as usize];
+        let descriptor = &contents[header.descriptor_offset as usize..descriptor_end as usize];
+        let checksum = crc32_extend(crc32(table), descriptor);
+        if checksum != header.checksum {
+            return Err(Error::Checksum(header.checksum, checksum));
+        }
+
+        let regions: Vec<Region> = table.chunks_exact(REGION_SIZE).map(Region::parse).collect();
+        for (i, r) in regions.iter().enumerate() {
+            if r.end() > header.file_length || r.end() > u32::MAX as u64 {
+                return Err(Error::Corrupt(format!(
+                    "region {i} {}+#{} is not within the file",
+                    r.offset, r.length
+                )));
+            }
+        }
+
+        let description: ObjectDescription<M> =
+            serde_json::from_slice(descriptor).map_err(|e| Error::Descriptor(e.to_string()))?;
+        if !description.buffers.is_empty() {
+            return Err(Error::Descriptor(
+                "buffers must be regions of the file".into(),
+            ));
+        }
+        let region_lengths: Vec<usize> = regions.iter().map(|r| r.length as usize).collect();
+        description
+            .validate_layout(&region_lengths)
+            .map_err(Error::Descriptor)?;
+        Ok(Self {
+            data,
+            header,
+            regions,
+            description,
+        })
+    }
+
+    //zz All done
+}
+
+//ip NativeFile<Vec<u8>>
+impl<M> NativeFile<Vec<u8>, M>
+where
+    M: Material + DeserializeOwned,
+{
+    //fp read_file
+    /// Read a native model file into memory and validate it
+    pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(std::fs::read(path)?)
+    }
+}
+
+//ip NativeFile<memmap2::Mmap>
+#[cfg(feature = "mmap")]
+impl<M> NativeFile<memmap2::Mmap, M>
+where
+    M: Material + DeserializeOwned,
+{
+    //fp map_file
+    /// Memory-map a native model file and validate it
+    ///
+    /// The file must not be modified while it is mapped; if it is,
+    /// the contents of the buffers may change underneath the object
+    pub fn map_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: the mapping is read-only, and the file is required
+        // (as documented) not to be modified while it is mapped
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::new(map)
+    }
+}
+
+//ip NativeFile
+impl<B, M> NativeFile<B, M>
+where
+    B: ByteBuffer,
+    M: Material,
+{
+    //ap header
+    /// Borrow the header of the file
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    //ap regions
+    /// Borrow the region table of the file
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    //ap description
+    /// Borrow the description of the object (which has no buffers)
+    pub fn description(&self) -> &ObjectDescription<M> {
+        &self.description
+    }
+
+    //ap region
+    /// Borrow the bytes of a region of the file
+    pub fn region(&self, n: usize) -> &[u8] {
+        let r = &self.regions[n];
+        &self.data.borrow_bytes()[r.offset as usize..r.end() as usize]
+    }
+
+    //mp verify_region
+    /// Check the data of a region of the file against the checksum in
+    /// the region table
+    pub fn verify_region(&self, n: usize) -> Result<()> {
+        let checksum = crc32(self.region(n));
+        if checksum != self.regions[n].checksum {
+            return Err(Error::Checksum(self.regions[n].checksum, checksum));
+        }
+        Ok(())
+    }
+
+    //mp verify
+    /// Check the data of all the regions of the file against the
+    /// checksums in the region table
+    ///
+    /// This reads the whole file (so, if it is memory-mapped, pages
+    /// all of it in)
+    pub fn verify(&self) -> Result<()> {
+        (0..self.regions.len()).try_for_each(|n| self.verify_region(n))
+    }
+
+    //mp make_buffer_data
+    /// Create the [BufferData]s for the object, directly using the
+    /// contents of the file
+    pub fn make_buffer_data<R: Renderable>(&self) -> Vec<BufferData<'_, R>> {
+        self.description
+            .buffer_data
+            .iter()
+            .map(|d| {
+                let offset = self.regions[d.buffer].offset as u32 + d.byte_offset;
+                BufferData::new(&self.data, offset, d.byte_length)
+            })
+            .collect()
+    }
+
+    //mp make_accessors
+    /// Create the [BufferAccessor]s for the object, given the
+    /// [BufferData]s created by `make_buffer_data`
+    pub fn make_accessors<'a, R: Renderable>(
+        &self,
+        data: &'a [BufferData<'a, R>],
+    ) -> Vec<BufferAccessor<'a, R>> {
+        self.description.make_accessors(data)
+    }
+
+    //mp make_vertices
+    /// Create the [Vertices] for the object, given the
+    /// [BufferAccessor]s created by `make_accessors`
+    pub fn make_vertices<'a, R: Renderable>(
+        &self,
+        accessors: &'a [BufferAccessor<'a, R>],
+    ) -> Vec<Vertices<'a, R>> {
+        self.description.make_vertices(accessors)
+    }
+
+    //mp make_textures
+    /// Create the [Texture]s for the object, directly using the
+    /// contents of the file
+    pub fn make_textures<R: Renderable>(&self) -> Vec<Texture<'_, R>> {
+        self.description
+            .textures
+            .iter()
+            .map(|t| {
+                Texture::new(
+                    self.region(t.buffer),
+                    t.dims,
+                    t.ele_type,
+                    t.elements_per_data,
+                )
+            })
+            .collect()
+    }
+
+    //mp make_object
+    /// Create the [Object], given the [Vertices] and [Texture]s
+    /// created by `make_vertices` and `make_textures`
+    pub fn make_object<'a, R: Renderable>(
+        &'a self,
+        vertices: &'a [Vertices<'a, R>],
+        textures: &'a [Texture<'a, R>],
+    ) -> Object<'a, M, R> {
+        self.description.make_object(vertices, textures)
+    }
+
+    //zz All done
+}
+
+//ip NativeFile
+impl<B, M> NativeFile<B, M>
+where
+    B: ByteBuffer + AsRef<[u8]> + Send + Sync + 'static,
+    M: Material + Clone,
+{
+    //dp into_object
+    /// Convert the file into an [Object] that owns the contents of the
+    /// file (such as the memory mapping), and so is `'static`
+    ///
+    /// The [BufferData]s and [Texture]s of the object share the
+    /// contents of the file directly
+    pub fn into_object<R: Renderable>(self) -> Object<'static, M, R> {
+        let contents = ByteData::owned(self.data);
+        let buffers: Vec<ByteData<'static>> = self
+            .regions
+            .iter()
+            .map(|r| contents.slice(r.offset as usize..r.end() as usize))
+            .collect();
+        self.description.into_shared_object(&buffers)
+    }
+
+    //zz All done
+}
+
+//ip ByteBuffer for memmap2::Mmap
+/// A memory mapping of a file can be used directly as the data for
+/// [BufferData]
+#[cfg(feature = "mmap")]
+impl ByteBuffer for memmap2::Mmap {
+    //fp byte_length
+    fn byte_length(&self) -> usize {
+        self.len()
+    }
+
+    //fp borrow_bytes
+    fn borrow_bytes(&self) -> &[u8] {
+        self
+    }
+
+    //fp as_u8_ptr
+    fn as_u8_ptr(&self) -> *const u8 {
+        self.as_ptr()
+    }
+
+    //zz All done
+}
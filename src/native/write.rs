@@ -0,0 +1,103 @@
+//a Imports
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{Error, Header, Region, Result};
+use super::{HEADER_SIZE, NATIVE_VERSION, REGION_ALIGNMENT, REGION_SIZE};
+use crate::checksum::{crc32, crc32_extend};
+use crate::hierarchy::Hierarchy;
+use crate::{
+    AccessorDescription, BufferDataDescription, Component, Material, ObjectDescription, Skeleton,
+    TextureDescription, VerticesDescription,
+};
+
+//a Descriptor
+//ti Descriptor
+/// The descriptor of a native model file - an [ObjectDescription]
+/// without its buffers, which are regions of the file instead
+#[derive(Serialize)]
+struct Descriptor<'a, M: Serialize> {
+    buffer_data: &'a [BufferDataDescription],
+    accessors: &'a [AccessorDescription],
+    vertices: &'a [VerticesDescription],
+    textures: &'a [TextureDescription],
+    materials: &'a [M],
+    skeleton: &'a Option<Skeleton>,
+    components: &'a Hierarchy<Component>,
+}
+
+//fi align
+/// Pad a Vec of bytes to the region alignment
+fn align(bytes: &mut Vec<u8>) {
+    let padding = bytes.len().next_multiple_of(REGION_ALIGNMENT) - bytes.len();
+    bytes.resize(bytes.len() + padding, 0);
+}
+
+//a Writing
+//fp write
+/// Write an [ObjectDescription] as the contents of a native model
+/// file
+///
+/// The buffers of the description become the regions of the file, in
+/// order, each aligned to [REGION_ALIGNMENT] bytes. As the regions are
+/// used directly as buffer data, the whole file must be less than
+/// 4GB.
+pub fn write<M: Material + Serialize>(description: &ObjectDescription<M>) -> Result<Vec<u8>> {
+    let descriptor = Descriptor {
+        buffer_data: &description.buffer_data,
+        accessors: &description.accessors,
+        vertices: &description.vertices,
+        textures: &description.textures,
+        materials: &description.materials,
+        skeleton: &description.skeleton,
+        components: &description.components,
+    };
+    let descriptor =
+        serde_json::to_vec(&descriptor).map_err(|e| Error::Descriptor(e.to_string()))?;
+
+    let region_count = description.buffers.len();
+    let descriptor_offset = HEADER_SIZE + region_count * REGION_SIZE;
+    let mut bytes = vec![0; descriptor_offset];
+    bytes.extend_from_slice(&descriptor);
+    let mut regions = Vec::with_capacity(region_count);
+    for b in &description.buffers {
+        align(&mut bytes);
+        regions.push(Region {
+            offset: bytes.len() as u64,
+            length: b.len() as u64,
+            checksum: crc32(b),
+        });
+        bytes.extend_from_slice(b);
+    }
+    align(&mut bytes);
+    if bytes.len() > u32::MAX as usize {
+        return Err(Error::TooLarge(bytes.len() as u64));
+    }
+
+    for (i, r) in regions.iter().enumerate() {
+        let start = HEADER_SIZE + i * REGION_SIZE;
+        bytes[start..start + REGION_SIZE].copy_from_slice(&r.as_bytes());
+    }
+    let header = Header {
+        version: NATIVE_VERSION,
+        flags: 0,
+        file_length: bytes.len() as u64,
+        region_count: region_count as u64,
+        descriptor_offset: descriptor_offset as u64,
+        descriptor_length: descriptor.len() as u64,
+        checksum: crc32_extend(crc32(&bytes[HEADER_SIZE..descriptor_offset]), &descriptor),
+    };
+    bytes[0..HEADER_SIZE].copy_from_slice(&header.as_bytes());
+    Ok(bytes)
+}
+
+//fp write_file
+/// Write an [ObjectDescription] to a native model file
+pub fn write_file<M: Material + Serialize, P: AsRef<Path>>(
+    description: &ObjectDescription<M>,
+    path: P,
+) -> Result<()> {
+    std::fs::write(path, write(description)?)?;
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//a Documentation
+/*!
+
+This provides generation of vertex normals for a [Vertices] from the
+triangles of the [Primitive]s that use it, for meshes (such as those
+generated procedurally, or imported from OBJ or STL files) that lack
+[VertexAttr::Normal].
+
+Each triangle has a face normal; the normal of a vertex is the sum of
+the face normals of the triangles that use it, weighted by the area of
+each triangle or by the angle of the triangle at the vertex (see
+[NormalWeighting]), normalized.
+
+With a crease angle, a triangle that uses a vertex only includes in its
+normal for that vertex the face normals of those other triangles using
+the vertex whose face normals are within the crease angle of its own;
+so the normals are smooth across shallow edges, but not across hard
+edges. Where the triangles that use a vertex then have different
+normals for it, the vertex is split, with a new vertex for each
+different normal. A crease angle of zero gives flat normals - each
+triangle having its own face normal (except that triangles in the same
+plane share their vertices).
+
+Vertices are only shared by triangles if they have the same index; a
+mesh whose triangles each have their own vertices (such as one
+imported from STL) should be welded (see [Vertices::weld]) first.
+
+!*/
+
+//a Imports
+use crate::{AccessorError, Primitive, Renderable, VertexAttr, VertexLayout, Vertices};
+
+//a NormalWeighting
+//tp NormalWeighting
+/// The weighting of the face normals of the triangles that use a
+/// vertex, for a smooth normal
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NormalWeighting {
+    /// Weight each face normal by the area of its triangle
+    Area,
+    /// Weight each face normal by the angle of its triangle at the
+    /// vertex; this is independent of how the surface is triangulated
+    #[default]
+    Angle,
+}
+
+//a NormalGeneration
+//tp NormalGeneration
+/// The parameters for generating the normals of a [Vertices]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NormalGeneration {
+    /// The weighting of the face normals
+    pub weighting: NormalWeighting,
+    /// The largest angle (in radians) between the face normals of
+    /// triangles that are smoothed together at a vertex; if `None`
+    /// the normals are smooth everywhere, and no vertices are split
+    pub crease_angle: Option<f32>,
+    /// The layout of the new vertex buffer
+    pub layout: VertexLayout,
+}
+
+//ip NormalGeneration
+impl NormalGeneration {
+    //fp new
+    /// Create a new [NormalGeneration] for smooth, angle-weighted
+    /// normals
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //fp flat
+    /// Create a new [NormalGeneration] for flat normals
+    pub fn flat() -> Self {
+        Self::new().with_crease_angle(Some(0.))
+    }
+
+    //cp with_weighting
+    /// Set the weighting of the face normals
+    pub fn with_weighting(mut self, weighting: NormalWeighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    //cp with_crease_angle
+    /// Set the crease angle, in radians
+    pub fn with_crease_angle(mut self, crease_angle: Option<f32>) -> Self {
+        self.crease_angle = crease_angle;
+        self
+    }
+
+    //cp with_layout
+    /// Set the layout of the new vertex buffer
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+//a Vector functions
+//fi sub
+fn sub(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+//fi dot
+fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+//fi cross
+fn cross(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+//fi normalize
+/// Normalize a vector, returning `None` if it has zero length
+fn normalize(a: &[f32; 3]) -> Option<[f32; 3]> {
+    let l = dot(a, a).sqrt();
+    (l > 0.).then(|| a.map(|c| c / l))
+}
+
+//fi angle
+/// The angle between two vectors, or zero if either has zero length
+fn angle(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    match (normalize(a), normalize(b)) {
+        (Some(a), Some(b)) => dot(&a, &b).clamp(-1., 1.).acos(),
+        _ => 0.,
+    }
+}
+
+//a Faces
+//tp Faces
+/// The face normals of the triangles of the primitives using a
+/// [Vertices], and the weight of each of their corners
+struct Faces {
+    /// The unit face normal of each triangle, or `None` if it is
+    /// degenerate
+    normals: Vec<Option<[f32; 3]>>,
+    /// The weight of each corner of each triangle
+    weights: Vec<[f32; 3]>,
+}
+
+//ip Faces
+impl Faces {
+    //fp new
+    /// Create the faces for triangles with the given vertex positions
+    fn new(triangles: &[[u32; 3]], positions: &[[f32; 3]], weighting: NormalWeighting) -> Self {
+        let mut normals = Vec::with_capacity(triangles.len());
+        let mut weights = Vec::with_capacity(triangles.len());
+        for t in triangles {
+            let p = t.map(|v| positions[v as usize]);
+            let n = cross(&sub(&p[1], &p[0]), &sub(&p[2], &p[0]));
+            normals.push(normalize(&n));
+            weights.push(match weighting {
+                NormalWeighting::Area => [dot(&n, &n).sqrt() / 2.; 3],
+                NormalWeighting::Angle => [0, 1, 2].map(|k| {
+                    let corner = &p[k];
+                    angle(&sub(&p[(k + 1) % 3], corner), &sub(&p[(k + 2) % 3], corner))
+                }),
+            });
+        }
+        Self { normals, weights }
+    }
+
+    //mp corner_normal
+    /// Get the normal of a corner (face * 3 + k), as the weighted sum
+    /// of the face normals of the corners of a vertex that are within
+    /// the crease of the corner's face
+    ///
+    /// The corners are summed in the same order for every corner, so
+    /// corners that include the same faces have identical normals
+    fn corner_normal(&self, corner: usize, corners: &[usize], min_cos: Option<f32>) -> [f32; 3] {
+        let face_normal = self.normals[corner / 3];
+        let mut sum = [0.; 3];
+        for c in corners {
+            let Some(n) = self.normals[c / 3] else {
+                continue;
+            };
+            let within = match (face_normal, min_cos) {
+                _ if c / 3 == corner / 3 => true,
+                (Some(f), Some(min_cos)) => dot(&f, &n) >= min_cos,
+                _ => true,
+            };
+            if within {
+                let w = self.weights[c / 3][c % 3];
+                sum = [0, 1, 2].map(|i| sum[i] + n[i] * w);
+            }
+        }
+        sum
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp generate_normals
+    /// Create a new [Vertices] with normals generated from the
+    /// triangles of the primitives (which must use this [Vertices]),
+    /// replacing any existing normals, and the primitives to use with it
+    ///
+    /// Where the crease angle requires vertices to be split, the new
+    /// vertices are added after the original ones, and the primitives
+    /// use a new index buffer, in which triangle strips and fans become
+    /// triangle lists; otherwise the indices (if any) are copied and
+    /// the primitives are unchanged. Vertices that are not used by any
+    /// triangle are given a normal of `[0., 0., 1.]`. The new
+    /// [Vertices] does not keep any [crate::Meshlets] of this one.
+    ///
+    /// An error is returned if the attributes or indices cannot be
+    /// read, or if an index is beyond the vertices of the position
+    /// accessor
+    pub fn generate_normals<'b>(
+        &self,
+        primitives: &[Primitive],
+        generation: &NormalGeneration,
+    ) -> Result<(Vertices<'b, R>, Vec<Primitive>), AccessorError> {
+        let triangulated = self.triangulate(primitives)?;
+        let n = triangulated.num_vertices;
+        let positions: Vec<[f32; 3]> = self.borrow_position().iter_elements(n)?.collect();
+        let faces = Faces::new(&triangulated.triangles, &positions, generation.weighting);
+
+        let mut vertex_corners = vec![vec![]; n];
+        for (c, v) in triangulated.triangles.iter().flatten().enumerate() {
+            vertex_corners[*v as usize].push(c);
+        }
+        let min_cos = generation.crease_angle.map(f32::cos);
+        let mut corner_normals = vec![[0.; 3]; triangulated.triangles.len() * 3];
+        for corners in &vertex_corners {
+            for c in corners {
+                let normal = faces.corner_normal(*c, corners, min_cos);
+                corner_normals[*c] = normalize(&normal).unwrap_or([0., 0., 1.]);
+            }
+        }
+        self.split_by_corners(
+            primitives,
+            triangulated,
+            VertexAttr::Normal,
+            &corner_normals,
+            [0., 0., 1.],
+            generation.layout,
+        )
+    }
+
+    //zz All done
+}
@@ -0,0 +1,47 @@
+//a Documentation
+/*!
+
+# Wavefront OBJ import
+
+This module (enabled with the `obj` feature) reads Wavefront OBJ
+files, with their MTL material libraries, and builds an
+[crate::Object] from them.
+
+The OBJ statements supported are `v`, `vt`, `vn` and `f` (with any
+mix of `v`, `v/vt`, `v//vn` and `v/vt/vn` index tuples, and with
+negative indices relative to the end of the data so far), `o` and
+`g` (which start a new [crate::Component]), `usemtl` (which starts a
+new [crate::Primitive]) and `mtllib`. Other statements (such as
+smoothing groups, lines and points) are ignored.
+
+The MTL files are read into an [MtlLibrary], which converts each
+material to a [crate::PbrMaterial]; see that type for the statements
+supported.
+
+Malformed files are reported with an [Error], which includes the
+line number that is at fault.
+
+# Example
+
+```ignore
+use mod3d_base::obj::ObjAsset;
+use mod3d_base::example_client::Renderable;
+
+let asset = ObjAsset::from_file("model.obj")?;
+let data = asset.make_buffer_data::<Renderable>();
+let accessors = asset.make_accessors(&data);
+let vertices = asset.make_vertices(&accessors);
+let mut object = asset.make_object(&vertices);
+object.analyze();
+```
+
+!*/
+
+//a Imports and exports
+mod asset;
+mod error;
+mod mtl;
+
+pub use asset::ObjAsset;
+pub use error::{Error, Result};
+pub use mtl::MtlLibrary;
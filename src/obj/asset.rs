@@ -0,0 +1,430 @@
+//a Imports
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{Error, MtlLibrary, Result};
+use crate::hierarchy::Hierarchy;
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, Component, Mesh, Object, PbrMaterial, Primitive,
+    PrimitiveType, Renderable, VertexAttr, Vertices,
+};
+
+//a Helper functions
+//fi parse_floats
+/// Parse at least `min` (and at most N) of the arguments of a
+/// statement as floats
+fn parse_floats<const N: usize>(line: usize, args: &[&str], min: usize) -> Result<[f32; N]> {
+    let mut result = [0.0; N];
+    if args.len() < min {
+        return Err(Error::Syntax(line, format!("expected {min} numbers")));
+    }
+    for (r, a) in result.iter_mut().zip(args.iter()) {
+        *r = a
+            .parse()
+            .map_err(|_| Error::Syntax(line, format!("bad number '{a}'")))?;
+    }
+    Ok(result)
+}
+
+//fi resolve_index
+/// Resolve a one-based (or, if negative, relative to the end) index
+/// into a list of `len` elements
+fn resolve_index(line: usize, kind: &'static str, s: &str, len: usize) -> Result<usize> {
+    let index: i64 = s
+        .parse()
+        .map_err(|_| Error::Syntax(line, format!("bad {kind} index '{s}'")))?;
+    let resolved = {
+        if index < 0 {
+            len as i64 + index
+        } else {
+            index - 1
+        }
+    };
+    if resolved < 0 || resolved >= len as i64 {
+        Err(Error::BadIndex(line, kind, index))
+    } else {
+        Ok(resolved as usize)
+    }
+}
+
+//fi mtllibs
+/// Find the material library filenames referred to by an OBJ file
+fn mtllibs(obj: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    for l in obj.lines() {
+        let mut words = l.split('#').next().unwrap_or("").split_whitespace();
+        if words.next() == Some("mtllib") {
+            result.extend(words);
+        }
+    }
+    result
+}
+
+//a Internal types
+//ti VertexKey
+/// The position, texture coordinate and normal indices of a face
+/// vertex
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+//ti Group
+/// A group (from 'o' or 'g') of faces, with the primitives as
+/// (material name index, first index, index count)
+#[derive(Debug, Default)]
+struct Group {
+    /// The primitives
+    primitives: Vec<(Option<usize>, u32, u32)>,
+}
+
+//a ObjAsset
+//tp ObjAsset
+/// A Wavefront OBJ file (with its MTL material libraries) converted
+/// to the data for an [Object]
+///
+/// The faces of the file are triangulated (as fans), and each
+/// distinct combination of position, texture coordinate and normal
+/// used by a face becomes a vertex. All of the vertices are packed
+/// into a single buffer of floats - position, then normal (if any
+/// face has normals) and texture coordinates (if any face has
+/// texture coordinates) - and all the indices into a single buffer
+/// of 32-bit indices. There is one [Vertices] for the whole file.
+///
+/// Texture coordinates are flipped vertically (v becomes 1-v), as
+/// the library follows glTF with the origin at the top left.
+///
+/// Each 'o' or 'g' group with faces becomes a root [Component], and
+/// within that each run of faces with the same 'usemtl' material
+/// becomes a [Primitive] of triangles. Faces with no material (or an
+/// unknown material) use a default material, which is added after
+/// those of the material libraries.
+///
+/// As with [crate::gltf::GltfAsset] the [Object] is created in
+/// stages, each borrowing the previous:
+///
+/// ```ignore
+/// let asset = ObjAsset::from_file("model.obj")?;
+/// let data = asset.make_buffer_data::<R>();
+/// let accessors = asset.make_accessors(&data);
+/// let vertices = asset.make_vertices(&accessors);
+/// let object = asset.make_object(&vertices);
+/// ```
+#[derive(Debug)]
+pub struct ObjAsset {
+    /// The vertex data
+    vertex_data: Vec<f32>,
+    /// The index data
+    index_data: Vec<u32>,
+    /// Number of floats per vertex
+    floats_per_vertex: usize,
+    /// True if the vertices have normals
+    has_normals: bool,
+    /// True if the vertices have texture coordinates
+    has_tex_coords: bool,
+    /// The materials
+    materials: Vec<PbrMaterial>,
+    /// The texture filenames
+    textures: Vec<String>,
+    /// The component hierarchy
+    components: Hierarchy<Component>,
+}
+
+//ip ObjAsset
+impl ObjAsset {
+    //fp new
+    /// Create a new [ObjAsset] from the contents of an OBJ file, given
+    /// the material library for it
+    pub fn new(obj: &str, library: &MtlLibrary) -> Result<Self> {
+        let mut positions: Vec<[f32; 3]> = Vec::new();
+        let mut tex_coords: Vec<[f32; 2]> = Vec::new();
+        let mut normals: Vec<[f32; 3]> = Vec::new();
+        let mut keys: Vec<VertexKey> = Vec::new();
+        let mut key_map: HashMap<VertexKey, u32> = HashMap::new();
+        let mut index_data: Vec<u32> = Vec::new();
+        let mut material_names: Vec<&str> = Vec::new();
+        let mut material = None;
+        let mut groups = vec![Group::default()];
+        let mut face = Vec::new();
+
+        for (n, l) in obj.lines().enumerate() {
+            let line = n + 1;
+            let l = l.split('#').next().unwrap_or("");
+            let mut words = l.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+            match keyword {
+                "v" => positions.push(parse_floats(line, &args, 3)?),
+                "vn" => normals.push(parse_floats(line, &args, 3)?),
+                "vt" => {
+                    let [u, v] = parse_floats(line, &args, 1)?;
+                    tex_coords.push([u, 1.0 - v]);
+                }
+                "o" | "g" if !groups.last().unwrap().primitives.is_empty() => {
+                    groups.push(Group::default());
+                }
+                "usemtl" => {
+                    let name = args.first().copied().unwrap_or("");
+                    material = Some(
+                        material_names
+                            .iter()
+                            .position(|m| *m == name)
+                            .unwrap_or_else(|| {
+                                material_names.push(name);
+                                material_names.len() - 1
+                            }),
+                    );
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(Error::Syntax(
+                            line,
+                            "face with fewer than 3 vertices".into(),
+                        ));
+                    }
+                    face.clear();
+                    for a in &args {
+                        let mut parts = a.split('/');
+                        let v =
+                            resolve_index(line, "vertex", parts.next().unwrap(), positions.len())?;
+                        let vt = match parts.next() {
+                            None | Some("") => None,
+                            Some(s) => Some(resolve_index(
+                                line,
+                                "texture coordinate",
+                                s,
+                                tex_coords.len(),
+                            )?),
+                        };
+                        let vn = match parts.next() {
+                            None | Some("") => None,
+                            Some(s) => Some(resolve_index(line, "normal", s, normals.len())?),
+                        };
+                        let key = (v, vt, vn);
+                        let index = *key_map.entry(key).or_insert_with(|| {
+                            keys.push(key);
+                            (keys.len() - 1) as u32
+                        });
+                        face.push(index);
+                    }
+                    let start = index_data.len() as u32;
+                    for i in 1..face.len() - 1 {
+                        index_data.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+                    }
+                    let count = index_data.len() as u32 - start;
+                    let primitives = &mut groups.last_mut().unwrap().primitives;
+                    match primitives.last_mut() {
+                        Some((m, s, c)) if *m == material && *s + *c == start => *c += count,
+                        _ => primitives.push((material, start, count)),
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let has_normals = keys.iter().any(|(_, _, vn)| vn.is_some());
+        let has_tex_coords = keys.iter().any(|(_, vt, _)| vt.is_some());
+        let floats_per_vertex = 3 + (has_normals as usize) * 3 + (has_tex_coords as usize) * 2;
+        let mut vertex_data = Vec::with_capacity(keys.len() * floats_per_vertex);
+        for (v, vt, vn) in &keys {
+            vertex_data.extend_from_slice(&positions[*v]);
+            if has_normals {
+                vertex_data.extend_from_slice(&vn.map(|n| normals[n]).unwrap_or_default());
+            }
+            if has_tex_coords {
+                vertex_data.extend_from_slice(&vt.map(|t| tex_coords[t]).unwrap_or_default());
+            }
+        }
+
+        let mut materials = library.pbr_materials();
+        let default_material = materials.len();
+        let material_index: Vec<usize> = material_names
+            .iter()
+            .map(|name| library.find(name).unwrap_or(default_material))
+            .collect();
+        let mut components = Hierarchy::new();
+        let mut uses_default = false;
+        for g in &groups {
+            if g.primitives.is_empty() {
+                continue;
+            }
+            let mut mesh = Mesh::default();
+            for (m, start, count) in &g.primitives {
+                let m = m.map(|m| material_index[m]).unwrap_or(default_material);
+                uses_default |= m == default_material;
+                mesh.add_primitive(Primitive::new(
+                    PrimitiveType::Triangles,
+                    0.into(),
+                    start * 4,
+                    *count,
+                    m.into(),
+                ));
+            }
+            components.add_node(Component::new(None, mesh));
+        }
+        components.find_roots();
+        if uses_default {
+            materials.push(PbrMaterial::default());
+        }
+
+        Ok(Self {
+            vertex_data,
+            index_data,
+            floats_per_vertex,
+            has_normals,
+            has_tex_coords,
+            materials,
+            textures: library.textures().to_vec(),
+            components,
+        })
+    }
+
+    //fp from_obj
+    /// Create a new [ObjAsset] from the contents of an OBJ file,
+    /// loading its material libraries relative to the base directory
+    /// (if given; otherwise the material libraries are ignored)
+    pub fn from_obj(obj: &str, base: Option<&Path>) -> Result<Self> {
+        let mut library = MtlLibrary::new();
+        if let Some(base) = base {
+            for filename in mtllibs(obj) {
+                library.add_mtl(&std::fs::read_to_string(base.join(filename))?)?;
+            }
+        }
+        Self::new(obj, &library)
+    }
+
+    //fp from_file
+    /// Read an OBJ file, and the material libraries it refers to, and
+    /// create an [ObjAsset]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let obj = std::fs::read_to_string(path)?;
+        Self::from_obj(&obj, Some(path.parent().unwrap_or(Path::new("."))))
+    }
+
+    //ap materials
+    /// Borrow the materials
+    pub fn materials(&self) -> &[PbrMaterial] {
+        &self.materials
+    }
+
+    //ap textures
+    /// Borrow the texture filenames, in the order of the texture
+    /// indices used by the materials
+    pub fn textures(&self) -> &[String] {
+        &self.textures
+    }
+
+    //ap components
+    /// Borrow the component hierarchy
+    pub fn components(&self) -> &Hierarchy<Component> {
+        &self.components
+    }
+
+    //mp make_buffer_data
+    /// Create the [BufferData]s for the asset - the indices and the
+    /// vertex data (or none, if there are no faces)
+    pub fn make_buffer_data<R: Renderable>(&self) -> Vec<BufferData<'_, R>> {
+        if self.index_data.is_empty() {
+            return vec![];
+        }
+        vec![
+            BufferData::new(&self.index_data, 0, 0),
+            BufferData::new(&self.vertex_data, 0, 0),
+        ]
+    }
+
+    //mp make_accessors
+    /// Create the [BufferAccessor]s for the asset, given the
+    /// [BufferData]s created by `make_buffer_data`
+    ///
+    /// These are the indices, positions, and then normals and texture
+    /// coordinates if the vertices have them
+    pub fn make_accessors<'a, R: Renderable>(
+        &self,
+        data: &'a [BufferData<'a, R>],
+    ) -> Vec<BufferAccessor<'a, R>> {
+        let mut accessors = Vec::new();
+        if data.is_empty() {
+            return accessors;
+        }
+        let stride = (self.floats_per_vertex * 4) as u32;
+        let count = self.index_data.len() as u32;
+        accessors.push(BufferAccessor::new(
+            &data[0],
+            count,
+            BufferElementType::UInt32,
+            0,
+            0,
+        ));
+        accessors.push(BufferAccessor::new(
+            &data[1],
+            3,
+            BufferElementType::Float32,
+            0,
+            stride,
+        ));
+        let mut offset = 12;
+        if self.has_normals {
+            accessors.push(BufferAccessor::new(
+                &data[1],
+                3,
+                BufferElementType::Float32,
+                offset,
+                stride,
+            ));
+            offset += 12;
+        }
+        if self.has_tex_coords {
+            accessors.push(BufferAccessor::new(
+                &data[1],
+                2,
+                BufferElementType::Float32,
+                offset,
+                stride,
+            ));
+        }
+        accessors
+    }
+
+    //mp make_vertices
+    /// Create the [Vertices] for the asset, given the
+    /// [BufferAccessor]s created by `make_accessors`
+    pub fn make_vertices<'a, R: Renderable>(
+        &self,
+        accessors: &'a [BufferAccessor<'a, R>],
+    ) -> Vec<Vertices<'a, R>> {
+        if accessors.is_empty() {
+            return vec![];
+        }
+        let mut vertices = Vertices::new(&accessors[0], &accessors[1]);
+        let mut n = 2;
+        if self.has_normals {
+            vertices.add_attr(VertexAttr::Normal, &accessors[n]);
+            n += 1;
+        }
+        if self.has_tex_coords {
+            vertices.add_attr(VertexAttr::TexCoords0, &accessors[n]);
+        }
+        vec![vertices]
+    }
+
+    //mp make_object
+    /// Create the [Object] for the asset, given the [Vertices]
+    /// created by `make_vertices`
+    pub fn make_object<'a, R: Renderable>(
+        &'a self,
+        vertices: &'a [Vertices<'a, R>],
+    ) -> Object<'a, PbrMaterial, R> {
+        let mut object = Object::new();
+        for v in vertices {
+            object.add_vertices(v);
+        }
+        for m in &self.materials {
+            object.add_material(m);
+        }
+        object.components = self.components.clone();
+        object
+    }
+
+    //zz All done
+}
@@ -0,0 +1,51 @@
+//a Error
+//tp Error
+/// An error in reading or interpreting a Wavefront OBJ or MTL file
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error in reading the file or one of its material libraries
+    Io(std::io::Error),
+    /// A line (numbered from 1) that could not be parsed
+    Syntax(usize, String),
+    /// A face on a line (numbered from 1) that refers to a vertex,
+    /// texture coordinate or normal (of the given kind) that does not
+    /// exist
+    BadIndex(usize, &'static str, i64),
+}
+
+//tp Result
+/// The [Result] type for the OBJ module
+pub type Result<T> = std::result::Result<T, Error>;
+
+//ip Display for Error
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Syntax(line, s) => write!(f, "line {line}: {s}"),
+            Self::BadIndex(line, kind, index) => {
+                write!(
+                    f,
+                    "line {line}: reference to {kind} {index} which does not exist"
+                )
+            }
+        }
+    }
+}
+
+//ip Error for Error
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+//ip From<std::io::Error> for Error
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,239 @@
+//a Imports
+use super::{Error, Result};
+use crate::{MaterialAspect, MaterialBaseData, PbrMaterial};
+
+//a Helper functions
+//fi color_u8
+/// Convert a color component from 0.0 to 1.0 to a u8
+fn color_u8(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+//fi parse_floats
+/// Parse the arguments of a statement as a fixed number of floats
+fn parse_floats<const N: usize>(line: usize, args: &[&str]) -> Result<[f32; N]> {
+    let mut result = [0.0; N];
+    if args.len() < N {
+        return Err(Error::Syntax(line, format!("expected {N} numbers")));
+    }
+    for (r, a) in result.iter_mut().zip(args.iter()) {
+        *r = a
+            .parse()
+            .map_err(|_| Error::Syntax(line, format!("bad number '{a}'")))?;
+    }
+    Ok(result)
+}
+
+//a MtlMaterial
+//ti MtlMaterial
+/// A material being built while parsing an MTL file
+#[derive(Debug)]
+struct MtlMaterial {
+    /// The name given by 'newmtl'
+    name: String,
+    /// Diffuse color
+    kd: [f32; 3],
+    /// Dissolve (alpha)
+    d: f32,
+    /// Emissive color
+    ke: [f32; 3],
+    /// Metallic and roughness (PBR extension)
+    mr: [f32; 2],
+    /// Texture indices for the material aspects
+    textures: Vec<(MaterialAspect, usize)>,
+}
+
+//ii MtlMaterial
+impl MtlMaterial {
+    //fi new
+    /// Create a new material with the MTL defaults
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kd: [1.0; 3],
+            d: 1.0,
+            ke: [0.0; 3],
+            mr: [0.0, 1.0],
+            textures: Vec::new(),
+        }
+    }
+
+    //fi pbr_material
+    /// Create the [PbrMaterial] for the material
+    fn pbr_material(&self) -> PbrMaterial {
+        let mut base_data = MaterialBaseData::default();
+        base_data.set_rgba((
+            color_u8(self.kd[0]),
+            color_u8(self.kd[1]),
+            color_u8(self.kd[2]),
+            color_u8(self.d),
+        ));
+        base_data.set_emissive_rgb((
+            color_u8(self.ke[0]),
+            color_u8(self.ke[1]),
+            color_u8(self.ke[2]),
+        ));
+        base_data.set_mr(self.mr[0], self.mr[1]);
+        let mut material = PbrMaterial::default();
+        material.set_base_data(&base_data);
+        for (aspect, t) in &self.textures {
+            material.set_texture(*aspect, (*t).into());
+        }
+        material
+    }
+}
+
+//a MtlLibrary
+//tp MtlLibrary
+/// A set of materials read from one or more MTL files
+///
+/// Each material is converted to a [PbrMaterial]:
+///
+/// * `Kd` and `d` (or `Tr`) provide the RGBA
+///
+/// * `Ke` provides the emissive color
+///
+/// * `Pm` and `Pr` (from the PBR extension to MTL) provide the
+///   metallic and roughness
+///
+/// * `map_Kd`, `map_Ke`, `norm` (or `map_Bump` or `bump`) and
+///   `map_Pr` provide the color, emission, normal and
+///   metallic-roughness texture slots
+///
+/// The textures are identified by the index of their filename in the
+/// library; the images are not loaded, so the client must add the
+/// textures to an object in that order if it needs them.
+#[derive(Debug, Default)]
+pub struct MtlLibrary {
+    /// The materials, in the order they were defined
+    materials: Vec<MtlMaterial>,
+    /// The texture filenames
+    textures: Vec<String>,
+}
+
+//ip MtlLibrary
+impl MtlLibrary {
+    //fp new
+    /// Create a new, empty, [MtlLibrary]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //ap textures
+    /// Borrow the texture filenames
+    pub fn textures(&self) -> &[String] {
+        &self.textures
+    }
+
+    //ap len
+    /// Get the number of materials
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    //ap is_empty
+    /// Return true if there are no materials
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    //ap find
+    /// Find the index of a material by name (the last to be defined,
+    /// if there is more than one)
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.materials.iter().rposition(|m| m.name == name)
+    }
+
+    //ap name
+    /// Get the name of a material
+    pub fn name(&self, n: usize) -> &str {
+        &self.materials[n].name
+    }
+
+    //mp pbr_materials
+    /// Create the [PbrMaterial]s for the library
+    pub fn pbr_materials(&self) -> Vec<PbrMaterial> {
+        self.materials.iter().map(|m| m.pbr_material()).collect()
+    }
+
+    //mi texture
+    /// Find or add a texture filename, returning its index
+    fn texture(&mut self, filename: &str) -> usize {
+        if let Some(n) = self.textures.iter().position(|t| t == filename) {
+            n
+        } else {
+            self.textures.push(filename.to_string());
+            self.textures.len() - 1
+        }
+    }
+
+    //mp add_mtl
+    /// Parse the contents of an MTL file, adding its materials to the
+    /// library
+    pub fn add_mtl(&mut self, mtl: &str) -> Result<()> {
+        let mut current = None;
+        for (n, l) in mtl.lines().enumerate() {
+            let line = n + 1;
+            let l = l.split('#').next().unwrap_or("");
+            let mut words = l.split_whitespace();
+            let Some(keyword) = words.next() else {
+                continue;
+            };
+            let args: Vec<&str> = words.collect();
+            if keyword == "newmtl" {
+                let Some(name) = args.first() else {
+                    return Err(Error::Syntax(line, "newmtl without a name".into()));
+                };
+                self.materials.push(MtlMaterial::new(name));
+                current = Some(self.materials.len() - 1);
+                continue;
+            }
+            let Some(m) = current else {
+                return Err(Error::Syntax(line, format!("'{keyword}' before newmtl")));
+            };
+            let aspect = match keyword {
+                "Kd" => {
+                    self.materials[m].kd = parse_floats(line, &args)?;
+                    None
+                }
+                "Ke" => {
+                    self.materials[m].ke = parse_floats(line, &args)?;
+                    None
+                }
+                "d" => {
+                    self.materials[m].d = parse_floats::<1>(line, &args)?[0];
+                    None
+                }
+                "Tr" => {
+                    self.materials[m].d = 1.0 - parse_floats::<1>(line, &args)?[0];
+                    None
+                }
+                "Pm" => {
+                    self.materials[m].mr[0] = parse_floats::<1>(line, &args)?[0];
+                    None
+                }
+                "Pr" => {
+                    self.materials[m].mr[1] = parse_floats::<1>(line, &args)?[0];
+                    None
+                }
+                "map_Kd" => Some(MaterialAspect::Color),
+                "map_Ke" => Some(MaterialAspect::Emission),
+                "map_Pr" => Some(MaterialAspect::MetallicRoughness),
+                "norm" | "map_Bump" | "map_bump" | "bump" => Some(MaterialAspect::Normal),
+                _ => None,
+            };
+            if let Some(aspect) = aspect {
+                // Texture options precede the filename
+                let Some(filename) = args.last() else {
+                    return Err(Error::Syntax(line, format!("{keyword} without a filename")));
+                };
+                let t = self.texture(filename);
+                self.materials[m].textures.retain(|(a, _)| *a != aspect);
+                self.materials[m].textures.push((aspect, t));
+            }
+        }
+        Ok(())
+    }
+
+    //zz All done
+}
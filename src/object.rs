@@ -2,7 +2,7 @@
 use crate::hierarchy;
 use crate::Renderable;
 use crate::{
-    Component, Instantiable, Material, Mesh, ShortIndex, Skeleton, Texture, Transformation,
+    Component, Instantiable, Material, Mesh, Shared, ShortIndex, Skeleton, Texture, Transformation,
     Vertices,
 };
 use hierarchy::Hierarchy;
@@ -12,6 +12,11 @@ use hierarchy::Hierarchy;
 /// A hierarchy of ObjectNode's
 ///
 /// This can be flattened in to an Instantiable
+///
+/// The vertices, textures and materials of the object are each
+/// borrowed or shared through an `Arc`; if they are all shared (with
+/// owned data) then the object is `'static`, and it can be sent
+/// between threads if the material and client types permit it.
 pub struct Object<'object, M, R>
 where
     R: Renderable,
@@ -20,11 +25,11 @@ where
     /// Skeleton
     pub skeleton: Option<Skeleton>,
     /// All the vertices used
-    pub vertices: Vec<&'object Vertices<'object, R>>,
+    pub vertices: Vec<Shared<'object, Vertices<'object, R>>>,
     /// All the vertices used
-    pub textures: Vec<&'object Texture<'object, R>>,
+    pub textures: Vec<Shared<'object, Texture<'object, R>>>,
     /// All the materials used
-    pub materials: Vec<&'object M>,
+    pub materials: Vec<Shared<'object, M>>,
     /// The meshes etc that make up the object
     pub components: Hierarchy<Component>,
     // The roots of the bones and hierarchical recipes for traversal
@@ -85,42 +90,48 @@ where
     //ap vertices
     /// Borrow one of the vertices
     pub fn vertices(&self, n: ShortIndex) -> &Vertices<'object, R> {
-        self.vertices[n.as_usize()]
+        &self.vertices[n.as_usize()]
     }
 
     //ap texture
     /// Borrow one of the textures
     pub fn texture(&self, n: ShortIndex) -> &Texture<'object, R> {
-        self.textures[n.as_usize()]
+        &self.textures[n.as_usize()]
     }
 
     //mp material
     /// Borrow a materiaal from the object
     pub fn material(&self, n: ShortIndex) -> &M {
-        self.materials[n.as_usize()]
+        &self.materials[n.as_usize()]
     }
 
     //mp add_vertices
-    /// Add vertices to the object
-    pub fn add_vertices(&mut self, vertices: &'object Vertices<'object, R>) -> ShortIndex {
+    /// Add vertices to the object, borrowed or as an `Arc`
+    pub fn add_vertices<V>(&mut self, vertices: V) -> ShortIndex
+    where
+        V: Into<Shared<'object, Vertices<'object, R>>>,
+    {
         let n = self.vertices.len();
-        self.vertices.push(vertices);
+        self.vertices.push(vertices.into());
         n.into()
     }
 
     //mp add_texture
-    /// Add texture to the object
-    pub fn add_texture(&mut self, texture: &'object Texture<'object, R>) -> ShortIndex {
+    /// Add texture to the object, borrowed or as an `Arc`
+    pub fn add_texture<T>(&mut self, texture: T) -> ShortIndex
+    where
+        T: Into<Shared<'object, Texture<'object, R>>>,
+    {
         let n = self.textures.len();
-        self.textures.push(texture);
+        self.textures.push(texture.into());
         n.into()
     }
 
     //fp add_material
-    /// Add a material to the object
-    pub fn add_material(&mut self, material: &'object M) -> ShortIndex {
+    /// Add a material to the object, borrowed or as an `Arc`
+    pub fn add_material<T: Into<Shared<'object, M>>>(&mut self, material: T) -> ShortIndex {
         let n = self.materials.len();
-        self.materials.push(material);
+        self.materials.push(material.into());
         n.into()
     }
 
@@ -164,8 +175,11 @@ where
     /// This permits (for exampl in OpenGL) the CPU-side buffers in
     /// the object to be dropped, but the GPU-side objects (created by
     /// create_client) can be maintained. The [Instantiable] contains
-    /// only instances of the types for the [Renderable].
+    /// only instances of the types for the [Renderable]; it does
+    /// include the bounds of the object, if they can be calculated
+    /// (they are `None` if the positions or indices cannot be read).
     pub fn into_instantiable(self, renderer: &mut R) -> Result<Instantiable<R>, (Self, String)> {
+        let bounds = self.bounds().ok().flatten();
         for v in &self.vertices {
             v.create_client(renderer);
         }
@@ -177,13 +191,17 @@ where
             .iter()
             .map(|m| renderer.create_material_client(&self, m))
             .collect();
-        Ok(Instantiable::<R>::new::<M>(
+        let vertices = self.vertices.iter().map(|v| &**v).collect();
+        let textures = self.textures.iter().map(|t| &**t).collect();
+        let mut instantiable = Instantiable::<R>::new::<M>(
             self.skeleton,
-            self.vertices,
-            self.textures,
+            vertices,
+            textures,
             materials,
             self.components,
-        ))
+        );
+        instantiable.bounds = bounds;
+        Ok(instantiable)
     }
 
     //zz All done
@@ -0,0 +1,603 @@
+//a Documentation
+/*!
+
+This provides an owned description of an [Object], that can be
+serialized and deserialized (with the `serde` feature)
+
+An [Object] borrows (or shares) its vertices, textures and materials,
+and the [Vertices] borrow [BufferAccessor]s which in turn borrow
+[BufferData]s and the underlying data buffers. An [ObjectDescription]
+instead owns copies of the data buffers, and describes the
+[BufferData]s, [BufferAccessor]s, [Vertices] and [Texture]s by index
+into the arrays of the description.
+
+An [Object] can be rebuilt from an [ObjectDescription] in stages,
+each borrowing the previous:
+
+```ignore
+let description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json)?;
+description.validate()?;
+let data = description.make_buffer_data::<R>();
+let accessors = description.make_accessors(&data);
+let vertices = description.make_vertices(&accessors);
+let textures = description.make_textures::<R>();
+let object = description.make_object(&vertices, &textures);
+```
+
+Alternatively the description can be converted into an [Object] that
+owns all of its data, which is `'static`:
+
+```ignore
+let object: Object<'static, PbrMaterial, R> = description.into_object();
+```
+
+!*/
+
+//a Imports
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hierarchy::Hierarchy;
+use crate::{
+    BufferAccessor, BufferData, BufferElementType, ByteData, Component, Material, MorphTarget,
+    Object, Renderable, Shared, Skeleton, Texture, VertexAttr, Vertices,
+};
+
+//a Descriptions
+//tp BufferDataDescription
+/// A description of a [BufferData] as a portion of one of the
+/// buffers of an [ObjectDescription]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferDataDescription {
+    /// Index of the buffer in the [ObjectDescription]
+    pub buffer: usize,
+    /// Offset in to the buffer for the first byte
+    pub byte_offset: u32,
+    /// Length of the data used in the buffer
+    pub byte_length: u32,
+}
+
+//tp AccessorDescription
+/// A description of a [BufferAccessor] onto one of the buffer datas
+/// of an [ObjectDescription]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessorDescription {
+    /// Index of the buffer data in the [ObjectDescription]
+    pub data: usize,
+    /// Number of elements per vertex (or the number of indices)
+    pub elements_per_data: u32,
+    /// The type of each element
+    pub ele_type: BufferElementType,
+    /// Offset from the start of the buffer data to the first byte
+    pub byte_offset: u32,
+    /// Stride of the data in the buffer data
+    pub stride: u32,
+    /// True if integer elements are normalized
+    #[serde(default)]
+    pub normalized: bool,
+    /// Number of elements; if not given then as many as fit in the
+    /// buffer data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u32>,
+    /// Minimum value of each component, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<Vec<f64>>,
+    /// Maximum value of each component, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<Vec<f64>>,
+}
+
+//ip AccessorDescription
+impl AccessorDescription {
+    //mi make_accessor
+    /// Create the [BufferAccessor] for the description given its
+    /// (borrowed or shared) [BufferData]
+    fn make_accessor<'a, R, D>(&self, data: D) -> BufferAccessor<'a, R>
+    where
+        R: Renderable,
+        D: Into<Shared<'a, BufferData<'a, R>>>,
+    {
+        let mut accessor = BufferAccessor::new(
+            data,
+            self.elements_per_data,
+            self.ele_type,
+            self.byte_offset,
+            self.stride,
+        )
+        .with_normalized(self.normalized);
+        if let Some(count) = self.count {
+            accessor.count = count;
+        }
+        accessor.min = self.min.clone();
+        accessor.max = self.max.clone();
+        accessor
+    }
+}
+
+//tp VerticesDescription
+/// A description of a [Vertices] by the indices of its accessors in
+/// an [ObjectDescription]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerticesDescription {
+    /// Index of the indices accessor, if the vertices are indexed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub indices: Option<usize>,
+    /// Index of the position accessor
+    pub position: usize,
+    /// Additional attributes and the indices of their accessors
+    pub attrs: Vec<(VertexAttr, usize)>,
+    /// The morph targets, each of attributes and the indices of the
+    /// accessors of their deltas
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<Vec<(VertexAttr, usize)>>,
+}
+
+//tp TextureDescription
+/// A description of a [Texture] whose data is one of the buffers of
+/// an [ObjectDescription]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextureDescription {
+    /// Index of the buffer in the [ObjectDescription]
+    pub buffer: usize,
+    /// Width, height, and depth of the texture
+    pub dims: (usize, usize, usize),
+    /// Number of elements per texture entry
+    pub elements_per_data: u32,
+    /// The type of each element
+    pub ele_type: BufferElementType,
+}
+
+//a ObjectDescription
+//tp ObjectDescription
+/// An owned description of an [Object], with buffers and accessors
+/// referred to by index rather than by reference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectDescription<M>
+where
+    M: Material,
+{
+    /// The data buffers
+    ///
+    /// These may be omitted when deserializing, as in the native file
+    /// format (where the buffers are regions of the file)
+    #[serde(default)]
+    pub buffers: Vec<Vec<u8>>,
+    /// Portions of the data buffers used by accessors
+    pub buffer_data: Vec<BufferDataDescription>,
+    /// Accessors onto the buffer data
+    pub accessors: Vec<AccessorDescription>,
+    /// The vertices of the object
+    pub vertices: Vec<VerticesDescription>,
+    /// The textures of the object
+    pub textures: Vec<TextureDescription>,
+    /// The materials of the object
+    pub materials: Vec<M>,
+    /// The skeleton of the object, if it has one
+    pub skeleton: Option<Skeleton>,
+    /// The component hierarchy of the object
+    pub components: Hierarchy<Component>,
+}
+
+//fi find_or_push
+/// Find the index of an item in a Vec of references, comparing by
+/// address (and length, for slices), adding it if not present
+fn find_or_push<'a, T: ?Sized>(v: &mut Vec<&'a T>, item: &'a T) -> usize {
+    if let Some(n) = v.iter().position(|x| std::ptr::eq(*x, item)) {
+        n
+    } else {
+        v.push(item);
+        v.len() - 1
+    }
+}
+
+//ip ObjectDescription
+impl<M> ObjectDescription<M>
+where
+    M: Material + Clone,
+{
+    //fp of_object
+    /// Create an [ObjectDescription] from an [Object], copying its
+    /// data buffers, materials, skeleton and components
+    ///
+    /// Buffers, buffer data and accessors that are shared (by
+    /// reference) within the [Object] are shared (by index) in the
+    /// description
+    pub fn of_object<R: Renderable>(object: &Object<M, R>) -> Self {
+        let mut buffers: Vec<&[u8]> = vec![];
+        let mut buffer_data: Vec<&BufferData<R>> = vec![];
+        let mut accessors: Vec<&BufferAccessor<R>> = vec![];
+        let mut vertices = vec![];
+        for v in &object.vertices {
+            let indices = v
+                .borrow_indices()
+                .map(|i| find_or_push(&mut accessors, i));
+            let position = find_or_push(&mut accessors, v.borrow_position());
+            let attrs = v
+                .iter_attrs()
+                .map(|(attr, a)| (attr, find_or_push(&mut accessors, a)))
+                .collect();
+            let targets = v
+                .iter_morph_targets()
+                .map(|t| {
+                    t.iter_deltas()
+                        .map(|(attr, a)| (attr, find_or_push(&mut accessors, a)))
+                        .collect()
+                })
+                .collect();
+            vertices.push(VerticesDescription {
+                indices,
+                position,
+                attrs,
+                targets,
+            });
+        }
+        let accessors: Vec<AccessorDescription> = accessors
+            .iter()
+            .map(|a| AccessorDescription {
+                data: find_or_push(&mut buffer_data, &a.data),
+                elements_per_data: a.elements_per_data,
+                ele_type: a.ele_type,
+                byte_offset: a.byte_offset,
+                stride: a.stride,
+                normalized: a.normalized,
+                count: Some(a.count),
+                min: a.min.clone(),
+                max: a.max.clone(),
+            })
+            .collect();
+        let buffer_data: Vec<BufferDataDescription> = buffer_data
+            .iter()
+            .map(|d| BufferDataDescription {
+                buffer: find_or_push(&mut buffers, &d.data()[..]),
+                byte_offset: d.byte_offset,
+                byte_length: d.byte_length,
+            })
+            .collect();
+        let textures: Vec<TextureDescription> = object
+            .textures
+            .iter()
+            .map(|t| {
+                let (elements_per_data, ele_type) = t.data_type();
+                TextureDescription {
+                    buffer: find_or_push(&mut buffers, t.data()),
+                    dims: *t.dims(),
+                    elements_per_data,
+                    ele_type,
+                }
+            })
+            .collect();
+        Self {
+            buffers: buffers.into_iter().map(|b| b.to_vec()).collect(),
+            buffer_data,
+            accessors,
+            vertices,
+            textures,
+            materials: object.materials.iter().map(|m| (**m).clone()).collect(),
+            skeleton: object.skeleton.clone(),
+            components: object.components.clone(),
+        }
+    }
+}
+
+//ip ObjectDescription
+impl<M> ObjectDescription<M>
+where
+    M: Material,
+{
+    //mp validate
+    /// Check that all the indices within the description are valid,
+    /// that the buffer data and textures lie within their buffers, and
+    /// that the components form a hierarchy
+    ///
+    /// This should be invoked after deserializing, before building an
+    /// [Object]; the `make_` methods panic if the description is not
+    /// valid
+    pub fn validate(&self) -> Result<(), String> {
+        let buffer_lengths: Vec<usize> = self.buffers.iter().map(|b| b.len()).collect();
+        self.validate_layout(&buffer_lengths)
+    }
+
+    //mp validate_layout
+    /// Validate the description given the lengths of its buffers,
+    /// which may be held outside the description
+    pub(crate) fn validate_layout(&self, buffer_lengths: &[usize]) -> Result<(), String> {
+        for (i, d) in self.buffer_data.iter().enumerate() {
+            let Some(length) = buffer_lengths.get(d.buffer) else {
+                return Err(format!("buffer data {i} uses unknown buffer {}", d.buffer));
+            };
+            if d.byte_offset as usize + d.byte_length as usize > *length {
+                return Err(format!(
+                    "buffer data {i} {}+#{} is not within the {length} bytes of buffer {}",
+                    d.byte_offset, d.byte_length, d.buffer
+                ));
+            }
+        }
+        for (i, a) in self.accessors.iter().enumerate() {
+            let Some(d) = self.buffer_data.get(a.data) else {
+                return Err(format!("accessor {i} uses unknown buffer data {}", a.data));
+            };
+            if a.normalized && !a.ele_type.is_integer() {
+                return Err(format!("accessor {i} of {:?} is normalized", a.ele_type));
+            }
+            let element_size = a.elements_per_data as usize * a.ele_type.byte_length();
+            let step = if a.stride == 0 {
+                element_size
+            } else {
+                a.stride as usize
+            };
+            let count = a.count.unwrap_or(0) as usize;
+            let end = a.byte_offset as usize + count.saturating_sub(1) * step + element_size;
+            if count > 0 && end > d.byte_length as usize {
+                return Err(format!(
+                    "accessor {i} of {count} elements is not within the {} bytes of buffer data {}",
+                    d.byte_length, a.data
+                ));
+            }
+        }
+        for (i, v) in self.vertices.iter().enumerate() {
+            let accessors = v
+                .indices
+                .into_iter()
+                .chain(std::iter::once(v.position))
+                .chain(v.attrs.iter().map(|(_, a)| *a))
+                .chain(v.targets.iter().flatten().map(|(_, a)| *a));
+            for a in accessors {
+                if a >= self.accessors.len() {
+                    return Err(format!("vertices {i} uses unknown accessor {a}"));
+                }
+            }
+        }
+        for (i, t) in self.textures.iter().enumerate() {
+            let Some(length) = buffer_lengths.get(t.buffer) else {
+                return Err(format!("texture {i} uses unknown buffer {}", t.buffer));
+            };
+            // A height or depth of zero is a 1D or 2D texture
+            let (w, h, d) = t.dims;
+            let size = [h.max(1), d.max(1), t.elements_per_data as usize]
+                .into_iter()
+                .try_fold(w, |acc, n| acc.checked_mul(n))
+                .and_then(|n| n.checked_mul(t.ele_type.byte_length()));
+            if size.is_none_or(|size| size > *length) {
+                return Err(format!(
+                    "texture {i} of {:?} is not within the {length} bytes of buffer {}",
+                    t.dims, t.buffer
+                ));
+            }
+        }
+        self.validate_components()?;
+        for c in self.components.borrow_elements() {
+            let mesh = &c.data.mesh;
+            let lods = mesh.lods.iter().flat_map(|lod| &lod.primitives);
+            for p in mesh.primitives.iter().chain(lods) {
+                if let Some(v) = Option::<usize>::from(p.vertices_index()) {
+                    if v >= self.vertices.len() {
+                        return Err(format!("primitive uses unknown vertices {v}"));
+                    }
+                }
+                if let Some(m) = Option::<usize>::from(p.material_index()) {
+                    if m >= self.materials.len() {
+                        return Err(format!("primitive uses unknown material {m}"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //mi validate_components
+    /// Check that the component hierarchy is a forest: its children
+    /// and roots are within the hierarchy, each node has at most one
+    /// parent, and no node is its own ancestor
+    fn validate_components(&self) -> Result<(), String> {
+        let elements = self.components.borrow_elements();
+        let n = elements.len();
+        for r in self.components.borrow_roots() {
+            if *r >= n {
+                return Err(format!("component root {r} is not a component"));
+            }
+        }
+        let mut parents = vec![None; n];
+        for (i, e) in elements.iter().enumerate() {
+            for c in e.children() {
+                if *c >= n {
+                    return Err(format!("component {i} has unknown child {c}"));
+                }
+                if let Some(p) = parents[*c] {
+                    return Err(format!("component {c} has parents {p} and {i}"));
+                }
+                parents[*c] = Some(i);
+            }
+        }
+        // Walk up from each node, marking the nodes on the walk; the
+        // walk ends at a root or a node already known to reach one
+        let mut checked = vec![false; n];
+        let mut on_path = vec![false; n];
+        for i in 0..n {
+            let mut path = vec![];
+            let mut c = Some(i);
+            while let Some(p) = c.filter(|p| !checked[*p]) {
+                if on_path[p] {
+                    return Err(format!("component {p} is its own ancestor"));
+                }
+                on_path[p] = true;
+                path.push(p);
+                c = parents[p];
+            }
+            for p in path {
+                checked[p] = true;
+            }
+        }
+        Ok(())
+    }
+
+    //mp make_buffer_data
+    /// Create the [BufferData]s for the description
+    pub fn make_buffer_data<R: Renderable>(&self) -> Vec<BufferData<'_, R>> {
+        self.buffer_data
+            .iter()
+            .map(|d| BufferData::new(&self.buffers[d.buffer], d.byte_offset, d.byte_length))
+            .collect()
+    }
+
+    //mp make_accessors
+    /// Create the [BufferAccessor]s for the description, given the
+    /// [BufferData]s created by `make_buffer_data`
+    pub fn make_accessors<'a, R: Renderable>(
+        &self,
+        data: &'a [BufferData<'a, R>],
+    ) -> Vec<BufferAccessor<'a, R>> {
+        self.accessors
+            .iter()
+            .map(|a| a.make_accessor(&data[a.data]))
+            .collect()
+    }
+
+    //mp make_vertices
+    /// Create the [Vertices] for the description, given the
+    /// [BufferAccessor]s created by `make_accessors`
+    pub fn make_vertices<'a, R: Renderable>(
+        &self,
+        accessors: &'a [BufferAccessor<'a, R>],
+    ) -> Vec<Vertices<'a, R>> {
+        self.vertices
+            .iter()
+            .map(|v| {
+                let indices = v.indices.map(|i| (&accessors[i]).into());
+                let mut vertices = Vertices::of_indices(indices, &accessors[v.position]);
+                for (attr, a) in &v.attrs {
+                    vertices.add_attr(*attr, &accessors[*a]);
+                }
+                for t in &v.targets {
+                    let mut target = MorphTarget::new();
+                    for (attr, a) in t {
+                        target.add_delta(*attr, &accessors[*a]);
+                    }
+                    vertices.add_morph_target(target);
+                }
+                vertices
+            })
+            .collect()
+    }
+
+    //mp make_textures
+    /// Create the [Texture]s for the description
+    pub fn make_textures<R: Renderable>(&self) -> Vec<Texture<'_, R>> {
+        self.textures
+            .iter()
+            .map(|t| {
+                Texture::new(
+                    &self.buffers[t.buffer],
+                    t.dims,
+                    t.ele_type,
+                    t.elements_per_data,
+                )
+            })
+            .collect()
+    }
+
+    //mp make_object
+    /// Create the [Object] for the description, given the [Vertices]
+    /// and [Texture]s created by `make_vertices` and `make_textures`
+    ///
+    /// If the object has a skeleton then it is resolved
+    pub fn make_object<'a, R: Renderable>(
+        &'a self,
+        vertices: &'a [Vertices<'a, R>],
+        textures: &'a [Texture<'a, R>],
+    ) -> Object<'a, M, R> {
+        let mut object = Object::new();
+        for v in vertices {
+            object.add_vertices(v);
+        }
+        for t in textures {
+            object.add_texture(t);
+        }
+        for m in &self.materials {
+            object.add_material(m);
+        }
+        object.components = self.components.clone();
+        if let Some(skeleton) = &self.skeleton {
+            let mut skeleton = skeleton.clone();
+            skeleton.resolve();
+            object.skeleton = Some(skeleton);
+        }
+        object
+    }
+
+    //dp into_object
+    /// Convert the description into an [Object] that owns all of its
+    /// data, and so is `'static`
+    ///
+    /// The buffers of the description are moved into reference
+    /// counted [ByteData]s, shared by the [BufferData]s and [Texture]s
+    /// of the object; its descriptors are shared by `Arc`
+    ///
+    /// If the object has a skeleton then it is resolved
+    pub fn into_object<R: Renderable>(mut self) -> Object<'static, M, R> {
+        let buffers: Vec<ByteData<'static>> = std::mem::take(&mut self.buffers)
+            .into_iter()
+            .map(ByteData::from)
+            .collect();
+        self.into_shared_object(&buffers)
+    }
+
+    //dp into_shared_object
+    /// Convert the description into an [Object] that owns all of its
+    /// data, given the (owned) data for each of its buffers
+    pub(crate) fn into_shared_object<R: Renderable>(
+        self,
+        buffers: &[ByteData<'static>],
+    ) -> Object<'static, M, R> {
+        let data: Vec<_> = self
+            .buffer_data
+            .iter()
+            .map(|d| {
+                let data =
+                    BufferData::of_bytes(buffers[d.buffer].clone(), d.byte_offset, d.byte_length);
+                Arc::new(data)
+            })
+            .collect();
+        let accessors: Vec<_> = self
+            .accessors
+            .iter()
+            .map(|a| Arc::new(a.make_accessor(data[a.data].clone())))
+            .collect();
+        let mut object = Object::new();
+        for v in &self.vertices {
+            let indices = v.indices.map(|i| accessors[i].clone().into());
+            let mut vertices = Vertices::of_indices(indices, accessors[v.position].clone());
+            for (attr, a) in &v.attrs {
+                vertices.add_attr(*attr, accessors[*a].clone());
+            }
+            for t in &v.targets {
+                let mut target = MorphTarget::new();
+                for (attr, a) in t {
+                    target.add_delta(*attr, accessors[*a].clone());
+                }
+                vertices.add_morph_target(target);
+            }
+            object.add_vertices(Arc::new(vertices));
+        }
+        for t in &self.textures {
+            let texture = Texture::of_bytes(
+                buffers[t.buffer].clone(),
+                t.dims,
+                t.ele_type,
+                t.elements_per_data,
+            );
+            object.add_texture(Arc::new(texture));
+        }
+        for m in self.materials {
+            object.add_material(Arc::new(m));
+        }
+        object.components = self.components;
+        if let Some(mut skeleton) = self.skeleton {
+            skeleton.resolve();
+            object.skeleton = Some(skeleton);
+        }
+        object
+    }
+
+    //zz All done
+}
@@ -0,0 +1,535 @@
+//a Documentation
+/*!
+
+This provides optimization of the indices and vertex data of a
+[Vertices] for rendering on a GPU, in three stages:
+
+* the triangles of each [PrimitiveType::Triangles] [Primitive] are
+  reordered for locality in the post-transform vertex cache, using the
+  Tipsify algorithm (Sander, Nehab and Barczak, 2007)
+
+* optionally, the clusters of triangles produced by Tipsify are split
+  further (where this costs little in cache efficiency) and sorted so
+  that outward-facing clusters are drawn first, reducing overdraw
+  independently of the view direction
+
+* the vertices are reordered in the order in which the indices first
+  use them, for locality of vertex fetches, and the indices remapped
+
+Primitives of other types keep the order of their indices (which
+matters for strips and fans), and their vertices are still reordered.
+If any of the primitives is a vertex range then the vertices cannot be
+reordered, and only the indices are optimized.
+
+The efficiency of the vertex cache is measured with
+[CacheStatistics], simulating a FIFO cache of a given size - giving
+the average cache miss ratio (ACMR, the number of vertices transformed
+per triangle, ideally 0.5 for large regular meshes and at most 3) and
+the average transform to vertex ratio (ATVR, the number of vertices
+transformed per distinct vertex, ideally 1).
+
+!*/
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{
+    AccessorError, BufferAccessor, Primitive, PrimitiveType, Renderable, Shared, VertexLayout,
+    Vertices,
+};
+
+//a CacheStatistics
+//tp FifoCache
+/// A simulated FIFO vertex cache
+///
+/// A vertex is in the cache if fewer than `size` other misses have
+/// occurred since it was last added
+struct FifoCache {
+    size: usize,
+    misses: usize,
+    added: HashMap<u32, usize>,
+}
+
+//ip FifoCache
+impl FifoCache {
+    //fp new
+    /// Create a new empty cache
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            misses: 0,
+            added: HashMap::new(),
+        }
+    }
+
+    //mp use_vertex
+    /// Use a vertex, returning true if it was a miss
+    fn use_vertex(&mut self, v: u32) -> bool {
+        match self.added.get(&v) {
+            Some(added) if self.misses - added <= self.size => false,
+            _ => {
+                self.added.insert(v, self.misses);
+                self.misses += 1;
+                true
+            }
+        }
+    }
+
+    //mp use_triangle
+    /// Use the vertices of a triangle, returning the number of misses
+    fn use_triangle(&mut self, triangle: &[u32]) -> usize {
+        triangle.iter().filter(|v| self.use_vertex(**v)).count()
+    }
+
+    //mp clear
+    /// Empty the cache
+    fn clear(&mut self) {
+        self.misses += self.size;
+    }
+}
+
+//tp CacheStatistics
+/// Statistics of the post-transform vertex cache for drawing a list of
+/// triangles, simulating a FIFO cache of a given size
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStatistics {
+    /// The number of triangles
+    pub triangles: usize,
+    /// The number of distinct vertices used by the triangles
+    pub vertices: usize,
+    /// The number of vertices transformed - the cache misses
+    pub misses: usize,
+}
+
+//ip CacheStatistics
+impl CacheStatistics {
+    //fp of_triangles
+    /// Simulate drawing a list of triangles (three indices each) with a
+    /// FIFO cache of `cache_size` vertices
+    pub fn of_triangles(indices: &[u32], cache_size: usize) -> Self {
+        let mut cache = FifoCache::new(cache_size);
+        let misses = indices.chunks_exact(3).map(|t| cache.use_triangle(t)).sum();
+        let mut distinct = indices.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        Self {
+            triangles: indices.len() / 3,
+            vertices: distinct.len(),
+            misses,
+        }
+    }
+
+    //ap acmr
+    /// The average cache miss ratio - vertices transformed per triangle
+    pub fn acmr(&self) -> f32 {
+        if self.triangles == 0 {
+            0.
+        } else {
+            self.misses as f32 / self.triangles as f32
+        }
+    }
+
+    //ap atvr
+    /// The average transform to vertex ratio - vertices transformed
+    /// per distinct vertex
+    pub fn atvr(&self) -> f32 {
+        if self.vertices == 0 {
+            0.
+        } else {
+            self.misses as f32 / self.vertices as f32
+        }
+    }
+}
+
+//a Reordering
+//fi tipsify
+/// Reorder a list of triangles for a vertex cache of `cache_size`
+/// vertices, using Tipsify; all the indices must be less than
+/// `num_vertices`
+///
+/// Returns the reordered triangles, and the first triangle of each
+/// cluster that starts with a cold cache
+fn tipsify(indices: &[u32], num_vertices: usize, cache_size: usize) -> (Vec<u32>, Vec<usize>) {
+    let num_triangles = indices.len() / 3;
+    let indices = &indices[0..num_triangles * 3];
+
+    // The triangles using each vertex, and the number not yet emitted
+    let mut live = vec![0_usize; num_vertices];
+    for v in indices {
+        live[*v as usize] += 1;
+    }
+    let mut offsets = vec![0; num_vertices + 1];
+    for v in 0..num_vertices {
+        offsets[v + 1] = offsets[v] + live[v];
+    }
+    let mut adjacency = vec![0; indices.len()];
+    let mut filled = offsets.clone();
+    for (i, v) in indices.iter().enumerate() {
+        adjacency[filled[*v as usize]] = i / 3;
+        filled[*v as usize] += 1;
+    }
+
+    let mut cache_time = vec![0_usize; num_vertices];
+    let mut time = cache_size + 1;
+    let mut emitted = vec![false; num_triangles];
+    let mut dead_end = vec![];
+    let mut cursor = 0;
+    let mut output = Vec::with_capacity(indices.len());
+    let mut clusters = vec![];
+    let mut fanning = None;
+    loop {
+        let f = match fanning {
+            Some(f) => f,
+            None => {
+                // Skip the dead end, or start a new cluster
+                while let Some(v) = dead_end.pop() {
+                    if live[v as usize] > 0 {
+                        fanning = Some(v as usize);
+                        break;
+                    }
+                }
+                if fanning.is_none() {
+                    while cursor < num_vertices && live[cursor] == 0 {
+                        cursor += 1;
+                    }
+                    if cursor == num_vertices {
+                        break;
+                    }
+                    clusters.push(output.len() / 3);
+                    fanning = Some(cursor);
+                }
+                fanning.unwrap()
+            }
+        };
+
+        let mut candidates = vec![];
+        for t in &adjacency[offsets[f]..offsets[f + 1]] {
+            if emitted[*t] {
+                continue;
+            }
+            emitted[*t] = true;
+            for v in &indices[t * 3..t * 3 + 3] {
+                output.push(*v);
+                dead_end.push(*v);
+                candidates.push(*v as usize);
+                live[*v as usize] -= 1;
+                if time - cache_time[*v as usize] > cache_size {
+                    cache_time[*v as usize] = time;
+                    time += 1;
+                }
+            }
+        }
+
+        // The next fanning vertex is the candidate that is oldest in
+        // the cache but will still be in it after its triangles
+        let mut best: Option<(usize, usize)> = None;
+        for v in candidates {
+            if live[v] > 0 {
+                let age = time - cache_time[v];
+                let priority = if age + 2 * live[v] <= cache_size {
+                    age
+                } else {
+                    0
+                };
+                match best {
+                    Some((_, p)) if p >= priority => (),
+                    _ => best = Some((v, priority)),
+                }
+            }
+        }
+        fanning = best.map(|(v, _)| v);
+    }
+    (output, clusters)
+}
+
+//fi sort_clusters
+/// Split the clusters of a list of triangles (ordered for the vertex
+/// cache) where their cache efficiency stays within `threshold` of the
+/// cluster's, and sort the resulting clusters to reduce overdraw
+///
+/// Clusters are drawn in order of how far they face away from the
+/// centroid of the mesh, so that outer surfaces tend to be drawn before
+/// those that they occlude
+fn sort_clusters(
+    indices: &[u32],
+    clusters: &[usize],
+    positions: &[[f32; 3]],
+    cache_size: usize,
+    threshold: f32,
+) -> Vec<u32> {
+    let num_triangles = indices.len() / 3;
+    let triangle = |t: usize| &indices[t * 3..t * 3 + 3];
+
+    let mut cache = FifoCache::new(cache_size);
+    let mut starts = vec![];
+    for (i, start) in clusters.iter().enumerate() {
+        let end = clusters.get(i + 1).copied().unwrap_or(num_triangles);
+        cache.clear();
+        let misses: usize = (*start..end).map(|t| cache.use_triangle(triangle(t))).sum();
+        let limit = threshold * misses as f32 / (end - start) as f32;
+        starts.push(*start);
+        cache.clear();
+        let (mut running_misses, mut running_triangles) = (0, 0);
+        for t in *start..end {
+            running_misses += cache.use_triangle(triangle(t));
+            running_triangles += 1;
+            if t + 1 < end && running_misses as f32 / running_triangles as f32 <= limit {
+                starts.push(t + 1);
+                cache.clear();
+                (running_misses, running_triangles) = (0, 0);
+            }
+        }
+    }
+
+    let corner = |i: &u32| positions[*i as usize];
+    let mut mesh_centroid = [0_f32; 3];
+    for p in indices.iter().map(corner) {
+        (0..3).for_each(|i| mesh_centroid[i] += p[i]);
+    }
+    (0..3).for_each(|i| mesh_centroid[i] /= indices.len().max(1) as f32);
+
+    let mut keyed = vec![];
+    for (i, start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(num_triangles);
+        let mut centroid = [0_f32; 3];
+        let mut normal = [0_f32; 3];
+        let mut area = 0.;
+        for t in *start..end {
+            let [a, b, c] = [0, 1, 2].map(|i| corner(&triangle(t)[i]));
+            let u = [0, 1, 2].map(|i| b[i] - a[i]);
+            let v = [0, 1, 2].map(|i| c[i] - a[i]);
+            let n = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            let twice_area = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            for i in 0..3 {
+                centroid[i] += (a[i] + b[i] + c[i]) / 3. * twice_area;
+                normal[i] += n[i];
+            }
+            area += twice_area;
+        }
+        let key = if area > 0. {
+            (0..3)
+                .map(|i| (centroid[i] / area - mesh_centroid[i]) * normal[i])
+                .sum::<f32>()
+                / (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2])
+                    .sqrt()
+                    .max(f32::MIN_POSITIVE)
+        } else {
+            0.
+        };
+        keyed.push((key, *start, end));
+    }
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+    keyed
+        .into_iter()
+        .flat_map(|(_, start, end)| indices[start * 3..end * 3].iter().copied())
+        .collect()
+}
+
+//fi fetch_order
+/// Remap the indices so that vertices are numbered in the order of
+/// their first use, returning the original vertex of each new vertex
+/// (with unused vertices last)
+fn fetch_order(indices: &mut [u32], num_vertices: usize) -> Vec<u32> {
+    let mut remap = vec![u32::MAX; num_vertices];
+    let mut order = vec![];
+    for i in indices.iter_mut() {
+        if remap[*i as usize] == u32::MAX {
+            remap[*i as usize] = order.len() as u32;
+            order.push(*i);
+        }
+        *i = remap[*i as usize];
+    }
+    order.extend((0..num_vertices as u32).filter(|v| remap[*v as usize] == u32::MAX));
+    order
+}
+
+//a Optimization
+//tp Optimization
+/// The parameters for optimizing a [Vertices] and its primitives
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Optimization {
+    /// The number of vertices in the vertex cache to optimize for
+    pub cache_size: usize,
+    /// The largest ratio of the cache miss ratio of a cluster split for
+    /// overdraw to that of the cluster it came from; `None` to not
+    /// optimize for overdraw
+    pub overdraw: Option<f32>,
+    /// The layout of the optimized vertex buffer
+    pub layout: VertexLayout,
+}
+
+//ip Default for Optimization
+impl Default for Optimization {
+    fn default() -> Self {
+        Self {
+            cache_size: 16,
+            overdraw: Some(1.05),
+            layout: VertexLayout::default(),
+        }
+    }
+}
+
+//ip Optimization
+impl Optimization {
+    //fp new
+    /// Create a new [Optimization] for a 16-vertex cache, with overdraw
+    /// optimization allowing a 5% loss in cache efficiency
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with_cache_size
+    /// Set the size of the vertex cache
+    pub fn with_cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size = cache_size;
+        self
+    }
+
+    //cp with_overdraw
+    /// Set the threshold for overdraw optimization, or disable it
+    pub fn with_overdraw(mut self, threshold: Option<f32>) -> Self {
+        self.overdraw = threshold;
+        self
+    }
+
+    //cp with_layout
+    /// Set the layout of the optimized vertex buffer
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+}
+
+//a Optimized
+//tp Optimized
+/// The result of optimizing a [Vertices]
+#[derive(Debug)]
+pub struct Optimized<'b, R: Renderable> {
+    /// The optimized vertices
+    pub vertices: Vertices<'b, R>,
+    /// The primitives, in the same order as those that were optimized,
+    /// using the new indices
+    pub primitives: Vec<Primitive>,
+    /// The statistics for the triangle lists before optimization
+    pub before: CacheStatistics,
+    /// The statistics for the triangle lists after optimization
+    pub after: CacheStatistics,
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp cache_statistics
+    /// Get the vertex cache statistics for drawing the
+    /// [PrimitiveType::Triangles] primitives of a set that use this
+    /// [Vertices], in order
+    pub fn cache_statistics(
+        &self,
+        primitives: &[Primitive],
+        cache_size: usize,
+    ) -> Result<CacheStatistics, AccessorError> {
+        let mut triangles = vec![];
+        for p in primitives {
+            if p.primitive_type() == PrimitiveType::Triangles {
+                triangles.extend(self.primitive_indices(p)?);
+            }
+        }
+        Ok(CacheStatistics::of_triangles(&triangles, cache_size))
+    }
+
+    //mp optimize
+    /// Create a new [Vertices], with new indices and vertex data, and
+    /// new primitives, optimized for drawing the primitives (which must
+    /// use this [Vertices])
+    ///
+    /// The indices of each primitive are copied into a new index buffer
+    /// (with the same element type) in order, with those of triangle
+    /// lists reordered
+    ///
+    /// The vertices are reordered, so any [crate::Meshlets] are not
+    /// kept; they may be built again for the new primitives
+    ///
+    /// An error is returned if the data cannot be read, or if an index
+    /// is beyond the vertices of the position accessor
+    pub fn optimize<'b>(
+        &self,
+        primitives: &[Primitive],
+        optimization: &Optimization,
+    ) -> Result<Optimized<'b, R>, AccessorError> {
+        let position = self.borrow_position();
+        let n = position.count as usize;
+        let cache_size = optimization.cache_size.max(1);
+        let positions: Vec<[f32; 3]> = match optimization.overdraw {
+            Some(_) => position.iter_elements(n)?.collect(),
+            None => vec![],
+        };
+
+        let mut new_indices = vec![];
+        let mut ranges = vec![];
+        let mut before = vec![];
+        let mut after = vec![];
+        for p in primitives {
+            if !p.is_indexed() {
+                ranges.push(None);
+                continue;
+            }
+            let mut indices = self.primitive_indices(p)?;
+            if let Some(i) = indices.iter().find(|i| **i as usize >= n) {
+                return Err(AccessorError::BadIndex(*i, n));
+            }
+            if p.primitive_type() == PrimitiveType::Triangles {
+                let (mut triangles, clusters) = tipsify(&indices, n, cache_size);
+                if let Some(threshold) = optimization.overdraw {
+                    triangles =
+                        sort_clusters(&triangles, &clusters, &positions, cache_size, threshold);
+                }
+                before.extend(&indices[0..triangles.len()]);
+                after.extend(&triangles);
+                indices.splice(0..triangles.len(), triangles);
+            }
+            ranges.push(Some((new_indices.len(), indices.len())));
+            new_indices.extend(indices);
+        }
+
+        let order = {
+            if ranges.iter().all(|r| r.is_some()) {
+                fetch_order(&mut new_indices, n)
+            } else {
+                (0..n as u32).collect()
+            }
+        };
+        let indices = self
+            .borrow_indices()
+            .map(|i| Shared::new(BufferAccessor::of_indices(&new_indices, i.ele_type)));
+        let index_size = self
+            .borrow_indices()
+            .map_or(4, |i| i.ele_type.byte_length());
+        let primitives = primitives
+            .iter()
+            .zip(ranges)
+            .map(|(p, range)| match range {
+                Some((start, count)) => Primitive::new(
+                    p.primitive_type(),
+                    p.vertices_index(),
+                    (start * index_size) as u32,
+                    count as u32,
+                    p.material_index(),
+                ),
+                None => p.clone(),
+            })
+            .collect();
+        let vertices = self.repack_vertices(optimization.layout, &order, indices)?;
+        Ok(Optimized {
+            vertices,
+            primitives,
+            before: CacheStatistics::of_triangles(&before, cache_size),
+            after: CacheStatistics::of_triangles(&after, cache_size),
+        })
+    }
+
+    //zz All done
+}
@@ -1,10 +1,13 @@
 //a Imports
 use crate::{PrimitiveType, ShortIndex};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a Primitive
 //tp Primitive
 /// A primitive consisting of a material and a subset of
-/// vertices using a particular range of indices
+/// vertices using a particular range of indices - or, for non-indexed
+/// drawing, a particular range of the vertices
 ///
 /// This might be, for example, the arm of a robot.
 ///
@@ -18,13 +21,21 @@ use crate::{PrimitiveType, ShortIndex};
 /// uses bones?
 /// index type (u8, u16, u32) - is this part of indices?
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Primitive {
     /// Byte offset to first index to use
     ///
-    /// If vertices_index is None then the first PrimitiveType to draw in the array
+    /// If the primitive is a vertex range then the first vertex to draw
     byte_offset: u32,
-    /// Number of indices to use
+    /// Number of indices (or vertices, for a vertex range) to use
     index_count: u32,
+    /// True if the primitive draws a range of vertices rather than a
+    /// range of indices
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    vertex_range: bool,
     /// Material to be used in drawing - index within the [crate::Object]
     material_index: ShortIndex,
     /// Vertices index within the [crate::Object]
@@ -53,6 +64,27 @@ impl Primitive {
         Self {
             byte_offset,
             index_count,
+            vertex_range: false,
+            material_index,
+            vertices_index,
+            primitive_type,
+        }
+    }
+
+    //fp of_vertex_range
+    /// Create a new Primitive that draws a range of the vertices of a
+    /// [crate::Vertices] without indices
+    pub fn of_vertex_range(
+        primitive_type: PrimitiveType,
+        vertices_index: ShortIndex,
+        first_vertex: u32,
+        vertex_count: u32,
+        material_index: ShortIndex,
+    ) -> Self {
+        Self {
+            byte_offset: first_vertex,
+            index_count: vertex_count,
+            vertex_range: true,
             material_index,
             vertices_index,
             primitive_type,
@@ -62,12 +94,15 @@ impl Primitive {
     //mp vertices
     /// Retrieve the data for the vertices in the primitive
     ///
-    /// This is the vertices index, the offset index, and the count
+    /// This is the vertices index, the byte offset to the first index
+    /// (as for `byte_offset`, so zero for a vertex range), and the
+    /// count; the first vertex of a vertex range is given by
+    /// `vertex_range`
     #[inline]
     pub fn vertices(&self) -> (Option<usize>, u32, u32) {
         (
             self.vertices_index.into(),
-            self.byte_offset,
+            self.byte_offset(),
             self.index_count,
         )
     }
@@ -101,7 +136,8 @@ impl Primitive {
     }
 
     //mp index_count
-    /// Get the number of indices required to draw this primitive
+    /// Get the number of indices required to draw this primitive - or
+    /// the number of vertices, if it is a vertex range
     pub fn index_count(&self) -> u32 {
         self.index_count
     }
@@ -109,8 +145,29 @@ impl Primitive {
     //mp byte_offset
     /// Get the byte offset within the indices buffer view to the
     /// first byte used by this primitive
+    ///
+    /// This is zero if the primitive is a vertex range
     pub fn byte_offset(&self) -> u32 {
-        self.byte_offset
+        if self.vertex_range {
+            0
+        } else {
+            self.byte_offset
+        }
+    }
+
+    //ap is_indexed
+    /// Return true if the primitive draws a range of indices, false if
+    /// it draws a range of vertices
+    pub fn is_indexed(&self) -> bool {
+        !self.vertex_range
+    }
+
+    //ap vertex_range
+    /// Get the first vertex and number of vertices, if the primitive
+    /// draws a range of vertices rather than of indices
+    pub fn vertex_range(&self) -> Option<(u32, u32)> {
+        self.vertex_range
+            .then_some((self.byte_offset, self.index_count))
     }
 
     //zz All done
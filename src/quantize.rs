@@ -0,0 +1,453 @@
+//a Documentation
+/*!
+
+This provides quantization of the vertex attributes of a [Vertices],
+in the style of the glTF `KHR_mesh_quantization` extension, and the
+reverse conversion back to [f32].
+
+Positions, normals, tangents and texture coordinates can each be
+converted to normalized [BufferElementType::Int16] or
+[BufferElementType::Int8] (or their unsigned equivalents, for
+positions and texture coordinates), or to
+[BufferElementType::Float16].
+
+Positions quantized to integers are first mapped to the range of the
+type, by subtracting an offset and dividing by a (uniform) scale; the
+dequantization - scaling by the scale and adding the offset - is
+returned as a [Transformation], which can be folded into the
+[Component] that uses the [Vertices] so that the rendered result does
+not change. Normals, tangents and texture coordinates are quantized
+directly, with texture coordinates being clamped to the range of the
+type.
+
+The deltas of any morph targets are kept as [f32], except that if the
+positions are quantized to integers then the position deltas are
+divided by the same scale, so that the morphed positions are
+dequantized correctly by the same [Transformation].
+
+The largest error introduced in any component of each quantized
+attribute (in the space of the original data) is reported, and the
+quantized [Vertices] can be converted back to [f32] attributes with
+`dequantize`.
+
+!*/
+
+//a Imports
+use std::sync::Arc;
+
+use crate::{
+    AccessorError, BufferAccessor, BufferData, BufferElementType, Component, Element, MorphTarget,
+    Renderable, Transformation, VertexAttr, VertexLayout, Vertices,
+};
+
+//a Quantization
+//tp Quantization
+/// The element types to quantize attributes of a [Vertices] to
+///
+/// Each is one of [BufferElementType::Int8], [BufferElementType::Int16],
+/// [BufferElementType::UInt8], [BufferElementType::UInt16] (normalized)
+/// or [BufferElementType::Float16]; or `None` to leave the attribute
+/// unchanged. Normals and tangents must use signed or [Float16] types.
+///
+/// [Float16]: BufferElementType::Float16
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Quantization {
+    /// The type for positions
+    pub position: Option<BufferElementType>,
+    /// The type for normals
+    pub normal: Option<BufferElementType>,
+    /// The type for tangents
+    pub tangent: Option<BufferElementType>,
+    /// The type for all the texture coordinates
+    pub tex_coords: Option<BufferElementType>,
+    /// The layout of the quantized vertex buffer
+    pub layout: VertexLayout,
+}
+
+//ip Quantization
+impl Quantization {
+    //fp new
+    /// Create a new [Quantization] that leaves all attributes unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with_position
+    /// Set the type to quantize positions to
+    pub fn with_position(mut self, ele_type: BufferElementType) -> Self {
+        self.position = Some(ele_type);
+        self
+    }
+
+    //cp with_normal
+    /// Set the type to quantize normals to
+    pub fn with_normal(mut self, ele_type: BufferElementType) -> Self {
+        self.normal = Some(ele_type);
+        self
+    }
+
+    //cp with_tangent
+    /// Set the type to quantize tangents to
+    pub fn with_tangent(mut self, ele_type: BufferElementType) -> Self {
+        self.tangent = Some(ele_type);
+        self
+    }
+
+    //cp with_tex_coords
+    /// Set the type to quantize texture coordinates to
+    pub fn with_tex_coords(mut self, ele_type: BufferElementType) -> Self {
+        self.tex_coords = Some(ele_type);
+        self
+    }
+
+    //cp with_layout
+    /// Set the layout of the quantized vertex buffer
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    //mi type_of_attr
+    /// Get the type (if any) to quantize an attribute to, checking
+    /// that it is valid for the attribute
+    fn type_of_attr(&self, attr: VertexAttr) -> Result<Option<BufferElementType>, AccessorError> {
+        use BufferElementType::*;
+        let (ele_type, signed) = match attr {
+            VertexAttr::Position => (self.position, false),
+            VertexAttr::Normal => (self.normal, true),
+            VertexAttr::Tangent => (self.tangent, true),
+            VertexAttr::TexCoords0 | VertexAttr::TexCoords1 | VertexAttr::TexCoords2 => {
+                (self.tex_coords, false)
+            }
+            _ => (None, false),
+        };
+        match ele_type {
+            None | Some(Float16 | Int8 | Int16) => Ok(ele_type),
+            Some(UInt8 | UInt16) if !signed => Ok(ele_type),
+            Some(t) => Err(AccessorError::Unsupported(t, "a quantized attribute")),
+        }
+    }
+}
+
+//a Encoding
+//fi encode
+/// Encode a value as a component of a quantized element type, returning
+/// the value stored (for bounds) and the value it will be read as
+fn encode(ele_type: BufferElementType, value: f32, bytes: &mut Vec<u8>) -> (f64, f32) {
+    let start = bytes.len();
+    let normalized = ele_type.is_integer();
+    value.write_bytes(ele_type, normalized, bytes);
+    let written = &bytes[start..];
+    let stored = f32::of_bytes(ele_type, written, false);
+    let read = f32::of_bytes(ele_type, written, normalized);
+    (stored as f64, read)
+}
+
+//fi quantize_accessor
+/// Quantize the first `n` elements of an accessor, with an optional
+/// offset and scale (mapping the values to the range of the type) -
+/// returning the new accessor and the largest error introduced
+fn quantize_accessor<'b, R: Renderable>(
+    accessor: &BufferAccessor<R>,
+    n: usize,
+    ele_type: BufferElementType,
+    offset_scale: Option<([f32; 3], f32)>,
+) -> Result<(BufferAccessor<'b, R>, f32), AccessorError> {
+    let components = (accessor.elements_per_data as usize).min(4);
+    let (offset, scale) = offset_scale.unwrap_or(([0.; 3], 1.));
+    let mut bytes = vec![];
+    let mut min = vec![f64::MAX; components];
+    let mut max = vec![f64::MIN; components];
+    let mut error: f32 = 0.;
+    for element in accessor.iter_elements::<f32, 4>(n)? {
+        for (i, v) in element.iter().take(components).enumerate() {
+            let o = offset.get(i).copied().unwrap_or(0.);
+            let (stored, read) = encode(ele_type, (v - o) / scale, &mut bytes);
+            min[i] = min[i].min(stored);
+            max[i] = max[i].max(stored);
+            error = error.max((read * scale + o - v).abs());
+        }
+    }
+    let normalized = ele_type.is_integer();
+    let data = Arc::new(BufferData::of_bytes(bytes, 0, 0));
+    let quantized = BufferAccessor::new(data, components as u32, ele_type, 0, 0)
+        .with_normalized(normalized)
+        .with_count(n as u32);
+    let quantized = if n > 0 {
+        quantized.with_bounds(min, max)
+    } else {
+        quantized
+    };
+    Ok((quantized, error))
+}
+
+//fi float_accessor
+/// Convert the first `n` elements of an accessor to [f32], applying a
+/// transformation to them if they are positions, and calculating their
+/// bounds if required
+fn float_accessor<'b, R: Renderable>(
+    accessor: &BufferAccessor<R>,
+    n: usize,
+    transformation: Option<&Transformation>,
+    bounds: bool,
+) -> Result<BufferAccessor<'b, R>, AccessorError> {
+    let components = (accessor.elements_per_data as usize).min(4);
+    let mut bytes = vec![];
+    let mut min = vec![f64::MAX; components];
+    let mut max = vec![f64::MIN; components];
+    for element in accessor.iter_elements::<f32, 4>(n)? {
+        let element = match transformation {
+            Some(t) => {
+                let p = t.transform_point(&[element[0], element[1], element[2]]);
+                [p[0], p[1], p[2], element[3]]
+            }
+            None => element,
+        };
+        for (i, v) in element.iter().take(components).enumerate() {
+            bytes.extend(v.to_le_bytes());
+            min[i] = min[i].min(*v as f64);
+            max[i] = max[i].max(*v as f64);
+        }
+    }
+    let data = Arc::new(BufferData::of_bytes(bytes, 0, 0));
+    let float = BufferAccessor::new(data, components as u32, BufferElementType::Float32, 0, 0)
+        .with_count(n as u32);
+    if n > 0 && bounds {
+        Ok(float.with_bounds(min, max))
+    } else {
+        Ok(float)
+    }
+}
+
+//fi position_offset_scale
+/// Determine the offset and uniform scale that map positions into the
+/// range of a normalized integer type (-1 to 1 for signed, 0 to 1 for
+/// unsigned)
+fn position_offset_scale<R: Renderable>(
+    accessor: &BufferAccessor<R>,
+    n: usize,
+    ele_type: BufferElementType,
+) -> Result<([f32; 3], f32), AccessorError> {
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    for p in accessor.iter_elements::<f32, 3>(n)? {
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+    }
+    if n == 0 {
+        return Ok(([0.; 3], 1.));
+    }
+    let extent = (0..3).map(|i| max[i] - min[i]).fold(0., f32::max);
+    let extent = if extent > 0. { extent } else { 1. };
+    if ele_type.is_unsigned() {
+        Ok((min, extent))
+    } else {
+        let center = [0, 1, 2].map(|i| (min[i] + max[i]) / 2.);
+        Ok((center, extent / 2.))
+    }
+}
+
+//a Quantized
+//tp Quantized
+/// The result of quantizing a [Vertices]
+#[derive(Debug)]
+pub struct Quantized<'b, R: Renderable> {
+    /// The quantized vertices
+    pub vertices: Vertices<'b, R>,
+    /// The transformation that maps the quantized positions back to
+    /// the original positions
+    pub dequantize: Transformation,
+    /// The largest error introduced in any component of each
+    /// quantized attribute
+    pub errors: Vec<(VertexAttr, f32)>,
+}
+
+//ip Quantized
+impl<'b, R: Renderable> Quantized<'b, R> {
+    //ap error
+    /// Get the largest error introduced in an attribute, if it was
+    /// quantized
+    pub fn error(&self, attr: VertexAttr) -> Option<f32> {
+        self.errors
+            .iter()
+            .find(|(a, _)| *a == attr)
+            .map(|(_, e)| *e)
+    }
+
+    //mp fold_into
+    /// Fold the dequantization of positions into the transformation of
+    /// a [Component] that uses the quantized vertices, so that it is
+    /// rendered as it was before quantization
+    pub fn fold_into(&self, component: &mut Component) {
+        component.transformation = Some(fold_dequantize(
+            component.transformation.as_ref(),
+            &self.dequantize,
+        ));
+    }
+}
+
+//fp fold_dequantize
+/// Combine a transformation (if any) with a dequantization (a uniform
+/// scale then a translation) applied before it
+///
+/// As the dequantization scale is uniform the result is also a
+/// [Transformation]
+fn fold_dequantize(
+    transformation: Option<&Transformation>,
+    dequantize: &Transformation,
+) -> Transformation {
+    let mut dq = *dequantize;
+    let Some(t) = transformation else {
+        return dq;
+    };
+    let mut t = *t;
+    let k = dq.scale()[0];
+    let translation = t.transform_point(&dq.translation());
+    let scale = t.scale().map(|s| s * k);
+    t.with_translation(translation).with_scale(scale)
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp quantize
+    /// Create a new [Vertices] with the attributes quantized as
+    /// requested, and the other attributes (and the indices) copied
+    ///
+    /// Every attribute is given the number of vertices of the position
+    /// accessor (its `count`)
+    ///
+    /// The morph targets are copied, with the position deltas scaled
+    /// to match the quantized positions; an error is returned if the
+    /// deltas of a target cannot be read
+    pub fn quantize<'b>(
+        &self,
+        quantization: &Quantization,
+    ) -> Result<Quantized<'b, R>, AccessorError> {
+        let position = self.borrow_position();
+        let n = position.count as usize;
+        let mut errors = vec![];
+        let mut dequantize = Transformation::new();
+        let mut delta_scale = None;
+
+        let mut quantize = |attr: VertexAttr, accessor: &BufferAccessor<'vertices, R>| {
+            let Some(ele_type) = quantization.type_of_attr(attr)? else {
+                return Ok(None);
+            };
+            let offset_scale = if attr == VertexAttr::Position && ele_type.is_integer() {
+                let (offset, scale) = position_offset_scale(accessor, n, ele_type)?;
+                dequantize = Transformation::new()
+                    .with_translation(offset)
+                    .with_scale([scale; 3]);
+                delta_scale = Some(Transformation::new().with_scale([1. / scale; 3]));
+                Some((offset, scale))
+            } else {
+                None
+            };
+            let (accessor, error) = quantize_accessor(accessor, n, ele_type, offset_scale)?;
+            errors.push((attr, error));
+            Ok::<_, AccessorError>(Some(Arc::new(accessor)))
+        };
+
+        let mut vertices = match quantize(VertexAttr::Position, position)? {
+            Some(p) => Vertices::of_indices(self.shared_indices(), p),
+            None => Vertices::of_indices(self.shared_indices(), position),
+        };
+        for (attr, accessor) in self.iter_attrs() {
+            match quantize(attr, accessor)? {
+                Some(a) => vertices.add_attr(attr, a),
+                None => vertices.add_attr(attr, accessor),
+            }
+        }
+        for target in self.iter_morph_targets() {
+            let mut quantized = MorphTarget::new();
+            for (attr, delta) in target.iter_deltas() {
+                match (attr, &delta_scale) {
+                    (VertexAttr::Position, Some(t)) => {
+                        let delta = float_accessor(delta, n, Some(t), false)?;
+                        quantized.add_delta(attr, Arc::new(delta));
+                    }
+                    _ => quantized.add_delta(attr, delta),
+                }
+            }
+            vertices.add_morph_target(quantized);
+        }
+        for meshlets in self.iter_meshlets() {
+            vertices.add_meshlets(meshlets.clone());
+        }
+        let vertices = vertices.repack(quantization.layout)?;
+        Ok(Quantized {
+            vertices,
+            dequantize,
+            errors,
+        })
+    }
+
+    //mp dequantize
+    /// Create a new [Vertices] with the quantized (non-[f32])
+    /// positions, normals, tangents and texture coordinates converted
+    /// back to [f32], and the other attributes (and the indices)
+    /// copied
+    ///
+    /// If a dequantization transformation is given it is applied to the
+    /// positions; the positions are always converted to [f32], and
+    /// their bounds recalculated
+    ///
+    /// The morph targets are copied, with their deltas converted in the
+    /// same way as their attributes (without the translation of the
+    /// dequantization for position deltas); an error is returned if
+    /// the deltas of a target cannot be read
+    pub fn dequantize<'b>(
+        &self,
+        dequantize: Option<&Transformation>,
+        layout: VertexLayout,
+    ) -> Result<Vertices<'b, R>, AccessorError> {
+        let position = self.borrow_position();
+        let n = position.count as usize;
+        let needs_float = |attr: VertexAttr, accessor: &BufferAccessor<R>| {
+            let quantizable = matches!(
+                attr,
+                VertexAttr::Normal
+                    | VertexAttr::Tangent
+                    | VertexAttr::TexCoords0
+                    | VertexAttr::TexCoords1
+                    | VertexAttr::TexCoords2
+            );
+            quantizable && accessor.ele_type != BufferElementType::Float32
+        };
+
+        let position: Arc<BufferAccessor<R>> =
+            Arc::new(float_accessor(position, n, dequantize, true)?);
+        let mut vertices = Vertices::of_indices(self.shared_indices(), position);
+        for (attr, accessor) in self.iter_attrs() {
+            if needs_float(attr, accessor) {
+                vertices.add_attr(attr, Arc::new(float_accessor(accessor, n, None, false)?));
+            } else {
+                vertices.add_attr(attr, accessor);
+            }
+        }
+        let delta_dequantize = dequantize.map(|t| t.with_translation([0.; 3]));
+        for target in self.iter_morph_targets() {
+            let mut dequantized = MorphTarget::new();
+            for (attr, delta) in target.iter_deltas() {
+                if attr == VertexAttr::Position {
+                    let delta = float_accessor(delta, n, delta_dequantize.as_ref(), false)?;
+                    dequantized.add_delta(attr, Arc::new(delta));
+                } else if needs_float(attr, delta) {
+                    dequantized.add_delta(attr, Arc::new(float_accessor(delta, n, None, false)?));
+                } else {
+                    dequantized.add_delta(attr, delta);
+                }
+            }
+            vertices.add_morph_target(dequantized);
+        }
+        for meshlets in self.iter_meshlets() {
+            vertices.add_meshlets(meshlets.clone());
+        }
+        vertices.repack(layout)
+    }
+
+    //zz All done
+}
@@ -4,8 +4,9 @@ use hierarchy::{Hierarchy, NodeEnumOp};
 
 use geo_nd::matrix;
 
+use crate::transformation::mat4_compose;
 use crate::Mat4;
-use crate::{Component, Primitive};
+use crate::{Component, LodMesh, MorphMesh, Primitive};
 
 //a RenderRecipe
 //tp RenderRecipe
@@ -14,6 +15,14 @@ use crate::{Component, Primitive};
 /// Created from a hierarchy of components, this is an array of
 /// transformation matrices, an array of [Primitive]s, and an array of
 /// pairs (matrix index, primitive index) of what needs to be drawn
+///
+/// Each matrix is that of a component with its transformation applied
+/// within those of its ancestors (the parent's after the child's)
+///
+/// The primitives are those of the full detail of the meshes; the
+/// meshes that have levels of detail are also kept, to select the
+/// primitives to draw for each frame, as are the weights of the meshes
+/// that have morph target weights
 #[derive(Debug)]
 pub struct RenderRecipe {
     /// Matrices to use (the first is the identity matrix)
@@ -22,6 +31,12 @@ pub struct RenderRecipe {
     pub primitives: Vec<Primitive>,
     /// Draw requirements - matrix index for the associated primitive index
     pub matrix_for_primitives: Vec<usize>,
+    /// The meshes that have levels of detail, in the order of their
+    /// primitives
+    pub lod_meshes: Vec<LodMesh>,
+    /// The meshes that have morph target weights, in the order of
+    /// their primitives
+    pub morph_meshes: Vec<MorphMesh>,
 }
 
 //ip Default RenderRecipe
@@ -39,10 +54,14 @@ impl RenderRecipe {
         let matrices = Vec::new();
         let primitives = Vec::new();
         let matrix_for_primitives = Vec::new();
+        let lod_meshes = Vec::new();
+        let morph_meshes = Vec::new();
         Self {
             matrices,
             primitives,
             matrix_for_primitives,
+            lod_meshes,
+            morph_meshes,
         }
     }
 
@@ -65,10 +84,8 @@ impl RenderRecipe {
                     NodeEnumOp::Push((n, comp), _has_children) => {
                         mesh_stack.push(trans_index);
                         if let Some(transformation) = comp.transformation {
-                            let transformation = matrix::multiply4(
-                                &recipe.matrices[trans_index],
-                                &transformation.mat4(),
-                            );
+                            let transformation =
+                                mat4_compose(&recipe.matrices[trans_index], &transformation.mat4());
                             trans_index = recipe.matrices.len();
                             recipe.matrices.push(transformation);
                         } // else keep same trans_index as its parent
@@ -83,7 +100,23 @@ impl RenderRecipe {
 
         // Copy out the mesh primitives paired with the matrix index
         for (n, trans_index) in meshes {
-            for p in &components.borrow_node(n).mesh.primitives {
+            let mesh = &components.borrow_node(n).mesh;
+            if !mesh.lods.is_empty() {
+                recipe.lod_meshes.push(LodMesh {
+                    first: recipe.primitives.len(),
+                    count: mesh.primitives.len(),
+                    matrix: trans_index,
+                    mesh: mesh.clone(),
+                });
+            }
+            if !mesh.weights.is_empty() {
+                recipe.morph_meshes.push(MorphMesh {
+                    first: recipe.primitives.len(),
+                    count: mesh.primitives.len(),
+                    weights: mesh.weights.clone(),
+                });
+            }
+            for p in &mesh.primitives {
                 recipe.primitives.push(p.clone());
                 recipe.matrix_for_primitives.push(trans_index);
             }
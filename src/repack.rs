@@ -0,0 +1,220 @@
+//a Documentation
+/*!
+
+This provides repacking of the data of a [Vertices] into a new vertex
+buffer with a given [VertexLayout] - interleaved (one buffer with a
+stride of the vertex size) or planar (one contiguous stream per
+attribute) - and a new index buffer.
+
+The elements of each attribute are copied unchanged (keeping their
+element type, normalization and bounds); each attribute starts on a
+4-byte boundary, and each element is padded to a multiple of 4 bytes,
+as required for vertex attributes by (for example) glTF and most GPU
+APIs.
+
+The repacked [Vertices] own their data, so they can have any lifetime;
+they have no clients, so the clients must be created afterwards.
+
+!*/
+
+//a Imports
+use std::sync::Arc;
+
+use crate::{
+    AccessorError, BufferAccessor, BufferData, BufferElementType, MorphTarget, Renderable, Shared,
+    VertexLayout, Vertices,
+};
+
+//a Constants
+/// The alignment of each attribute (and of each element within an
+/// attribute) in a repacked vertex buffer
+pub const VERTEX_ALIGNMENT: usize = 4;
+
+//fi align
+/// Round up a byte size or offset to the vertex alignment
+fn align(n: usize) -> usize {
+    n.div_ceil(VERTEX_ALIGNMENT) * VERTEX_ALIGNMENT
+}
+
+//a BufferAccessor
+//ip BufferAccessor
+impl<'a, R: Renderable> BufferAccessor<'a, R> {
+    //mp element_bytes
+    /// Get the bytes of element `n` of the accessor, which must have
+    /// been range-checked
+    pub(crate) fn element_bytes(&self, n: usize) -> &[u8] {
+        let start = self.byte_offset as usize + n * self.element_stride();
+        &self.data.as_slice()[start..start + self.element_size()]
+    }
+
+    //mi repacked
+    /// Create a copy of the accessor using different data, byte offset
+    /// and stride
+    fn repacked<'b>(
+        &self,
+        data: Arc<BufferData<'b, R>>,
+        byte_offset: usize,
+        stride: usize,
+        count: u32,
+    ) -> BufferAccessor<'b, R> {
+        let accessor = BufferAccessor::new(
+            data,
+            self.elements_per_data,
+            self.ele_type,
+            byte_offset as u32,
+            stride as u32,
+        )
+        .with_normalized(self.normalized)
+        .with_count(count);
+        match (&self.min, &self.max) {
+            (Some(min), Some(max)) => accessor.with_bounds(min.clone(), max.clone()),
+            _ => accessor,
+        }
+    }
+
+    //fp of_indices
+    /// Create an indices accessor that owns its data, from indices
+    /// that must fit within the element type
+    pub(crate) fn of_indices(indices: &[u32], ele_type: BufferElementType) -> Self {
+        let index_bytes: Vec<u8> = match ele_type.byte_length() {
+            1 => indices.iter().map(|i| *i as u8).collect(),
+            2 => indices
+                .iter()
+                .flat_map(|i| (*i as u16).to_le_bytes())
+                .collect(),
+            _ => indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+        };
+        let index_data = Arc::new(BufferData::of_bytes(index_bytes, 0, 0));
+        BufferAccessor::new(index_data, indices.len() as u32, ele_type, 0, 0)
+    }
+
+    //mi repacked_indices
+    /// Create a copy of an indices accessor with its own data
+    fn repacked_indices<'b>(&self) -> Result<Shared<'b, BufferAccessor<'b, R>>, AccessorError> {
+        let index_size = self.ele_type.byte_length();
+        self.check_range(1, index_size, self.elements_per_data as usize)?;
+        let start = self.byte_offset as usize;
+        let end = start + index_size * self.elements_per_data as usize;
+        let index_bytes = self.data.as_slice()[start..end].to_vec();
+        let index_data = Arc::new(BufferData::of_bytes(index_bytes, 0, 0));
+        Ok(Shared::new(self.repacked(index_data, 0, 0, self.count)))
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp repack
+    /// Create a new [Vertices] with a copy of the indices (if any) and
+    /// of all the attributes (and the deltas of the morph targets), in
+    /// a new index buffer and a new vertex buffer with the given layout
+    ///
+    /// Every attribute is given the number of vertices of the position
+    /// accessor (its `count`); an error is returned if any of the
+    /// accessors do not lie within their [BufferData]
+    ///
+    /// As the vertices and indices are unchanged, the [crate::Meshlets]
+    /// (if any) are copied too
+    pub fn repack<'b>(&self, layout: VertexLayout) -> Result<Vertices<'b, R>, AccessorError> {
+        let indices = match self.borrow_indices() {
+            Some(indices) => Some(indices.repacked_indices()?),
+            None => None,
+        };
+        let order: Vec<u32> = (0..self.borrow_position().count).collect();
+        let mut vertices = self.repack_vertices(layout, &order, indices)?;
+        for meshlets in self.iter_meshlets() {
+            vertices.add_meshlets(meshlets.clone());
+        }
+        Ok(vertices)
+    }
+
+    //mp repack_vertices
+    /// Create a new [Vertices] with the given indices whose vertex `i`
+    /// is a copy of vertex `order[i]` of this [Vertices], in a new
+    /// vertex buffer with the given layout
+    ///
+    /// The entries of `order` must be less than the number of vertices
+    /// of the position accessor (its `count`); the new [Vertices] has
+    /// no [crate::Meshlets], as they would refer to the old vertices
+    pub(crate) fn repack_vertices<'b>(
+        &self,
+        layout: VertexLayout,
+        order: &[u32],
+        indices: Option<Shared<'b, BufferAccessor<'b, R>>>,
+    ) -> Result<Vertices<'b, R>, AccessorError> {
+        let position = self.borrow_position();
+        let count = order.len() as u32;
+        let n = order.len();
+
+        let mut accessors = vec![position];
+        accessors.extend(self.iter_attrs().map(|(_, a)| a));
+        for target in self.iter_morph_targets() {
+            accessors.extend(target.iter_deltas().map(|(_, a)| a));
+        }
+        for a in &accessors {
+            let stride = a.element_stride();
+            a.check_range(
+                a.elements_per_data as usize,
+                stride,
+                position.count as usize,
+            )?;
+        }
+
+        // The byte offset and stride of each attribute in the new buffer
+        let sizes: Vec<usize> = accessors.iter().map(|a| align(a.element_size())).collect();
+        let mut layout_offsets = vec![];
+        let mut offset = 0;
+        for size in &sizes {
+            layout_offsets.push(offset);
+            offset += match layout {
+                VertexLayout::Interleaved => *size,
+                VertexLayout::Planar => *size * n,
+            };
+        }
+        let (vertex_size, buffer_size) = match layout {
+            VertexLayout::Interleaved => (offset, offset * n),
+            VertexLayout::Planar => (0, offset),
+        };
+
+        let mut vertex_bytes = vec![0_u8; buffer_size];
+        for (i, a) in accessors.iter().enumerate() {
+            let stride = match layout {
+                VertexLayout::Interleaved => vertex_size,
+                VertexLayout::Planar => sizes[i],
+            };
+            let size = a.element_size();
+            for (e, v) in order.iter().enumerate() {
+                let dest = layout_offsets[i] + e * stride;
+                vertex_bytes[dest..dest + size].copy_from_slice(a.element_bytes(*v as usize));
+            }
+        }
+
+        let vertex_data = Arc::new(BufferData::of_bytes(vertex_bytes, 0, 0));
+        let repacked = |i: usize| {
+            let a = accessors[i];
+            let stride = match layout {
+                VertexLayout::Interleaved => vertex_size,
+                VertexLayout::Planar if sizes[i] == a.element_size() => 0,
+                VertexLayout::Planar => sizes[i],
+            };
+            Arc::new(a.repacked(vertex_data.clone(), layout_offsets[i], stride, count))
+        };
+        let mut vertices = Vertices::of_indices(indices, repacked(0));
+        let mut i = 1;
+        for (attr, _) in self.iter_attrs() {
+            vertices.add_attr(attr, repacked(i));
+            i += 1;
+        }
+        for target in self.iter_morph_targets() {
+            let mut repacked_target = MorphTarget::new();
+            for (attr, _) in target.iter_deltas() {
+                repacked_target.add_delta(attr, repacked(i));
+                i += 1;
+            }
+            vertices.add_morph_target(repacked_target);
+        }
+        Ok(vertices)
+    }
+
+    //zz All done
+}
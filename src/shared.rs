@@ -0,0 +1,241 @@
+//a Documentation
+/*!
+
+This provides the storage types that permit the object model to
+either borrow its data or own it.
+
+A [Shared] is either a borrow of a descriptor (such as a [BufferData]
+or [Vertices]) or an [Arc] of one; the descriptors that use other
+descriptors hold [Shared]s, and hence an [Object] can be built using
+borrows (with a lifetime limited by the data it borrows) or using
+[Arc]s (in which case it can be `'static`).
+
+A [ByteData] is similarly either a borrow of a byte slice or a
+reference-counted owner of the bytes - an `Arc<[u8]>`, or any
+`AsRef<[u8]>` (such as a `Vec<u8>` or a memory mapping of a file).
+Cloning a [ByteData] or a [Shared] is cheap, so many descriptors can
+share the same data.
+
+!*/
+
+//a Imports
+use std::ops::Deref;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(doc)]
+use crate::{BufferData, Object, Vertices};
+
+//a Shared
+//tp Shared
+/// A reference to a descriptor that is either borrowed or shared
+/// through an [Arc]
+pub enum Shared<'a, T: ?Sized> {
+    /// A borrow of the descriptor
+    Borrowed(&'a T),
+    /// A reference-counted descriptor
+    Owned(Arc<T>),
+}
+
+//ip Shared
+impl<'a, T> Shared<'a, T> {
+    //fp new
+    /// Create a [Shared] that owns a descriptor
+    pub fn new(value: T) -> Self {
+        Self::Owned(Arc::new(value))
+    }
+}
+
+//ip Shared
+impl<'a, T: ?Sized> Shared<'a, T> {
+    //ap is_owned
+    /// Return true if the [Shared] owns (a reference to) its descriptor
+    pub fn is_owned(&self) -> bool {
+        matches!(self, Self::Owned(_))
+    }
+
+    //ap ptr_eq
+    /// Return true if two [Shared]s refer to the same descriptor
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::addr_eq(&**self as *const T, &**other as *const T)
+    }
+}
+
+//ip Deref for Shared
+impl<'a, T: ?Sized> Deref for Shared<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        match self {
+            Self::Borrowed(r) => r,
+            Self::Owned(a) => a,
+        }
+    }
+}
+
+//ip Clone for Shared
+impl<'a, T: ?Sized> Clone for Shared<'a, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Borrowed(r) => Self::Borrowed(r),
+            Self::Owned(a) => Self::Owned(a.clone()),
+        }
+    }
+}
+
+//ip Debug for Shared
+impl<'a, T: std::fmt::Debug + ?Sized> std::fmt::Debug for Shared<'a, T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        (**self).fmt(fmt)
+    }
+}
+
+//ip Display for Shared
+impl<'a, T: std::fmt::Display + ?Sized> std::fmt::Display for Shared<'a, T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        (**self).fmt(fmt)
+    }
+}
+
+//ip From<&T> for Shared
+impl<'a, T: ?Sized> From<&'a T> for Shared<'a, T> {
+    fn from(r: &'a T) -> Self {
+        Self::Borrowed(r)
+    }
+}
+
+//ip From<Arc<T>> for Shared
+impl<'a, T: ?Sized> From<Arc<T>> for Shared<'a, T> {
+    fn from(a: Arc<T>) -> Self {
+        Self::Owned(a)
+    }
+}
+
+//a ByteData
+//tp ByteData
+/// The bytes used by a [BufferData] or a texture - either borrowed, or
+/// a range of some reference-counted data
+#[derive(Clone)]
+pub enum ByteData<'a> {
+    /// A borrow of the bytes
+    Borrowed(&'a [u8]),
+    /// A range of reference-counted data
+    Owned(Arc<dyn AsRef<[u8]> + Send + Sync>, usize, usize),
+}
+
+//ip ByteData
+impl<'a> ByteData<'a> {
+    //fp owned
+    /// Create a [ByteData] that owns all of some data - such as a
+    /// `Vec<u8>`, `Arc<[u8]>`, or a memory mapping of a file
+    pub fn owned<B: AsRef<[u8]> + Send + Sync + 'static>(data: B) -> Self {
+        let end = data.as_ref().len();
+        Self::Owned(Arc::new(data), 0, end)
+    }
+
+    //mp slice
+    /// Get a [ByteData] for a range of the bytes, sharing the data
+    ///
+    /// Panics if the range is not within the bytes
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Self {
+        match self {
+            Self::Borrowed(b) => Self::Borrowed(&b[range]),
+            Self::Owned(data, start, end) => {
+                assert!(
+                    range.start <= range.end && start + range.end <= *end,
+                    "Range {range:?} is not within the {} bytes",
+                    end - start
+                );
+                Self::Owned(data.clone(), start + range.start, start + range.end)
+            }
+        }
+    }
+
+    //ap is_owned
+    /// Return true if the [ByteData] owns (a reference to) its data
+    pub fn is_owned(&self) -> bool {
+        matches!(self, Self::Owned(..))
+    }
+}
+
+//ip Deref for ByteData
+impl<'a> Deref for ByteData<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(b) => b,
+            Self::Owned(data, start, end) => &(**data).as_ref()[*start..*end],
+        }
+    }
+}
+
+//ip Debug for ByteData
+impl<'a> std::fmt::Debug for ByteData<'a> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let kind = if self.is_owned() { "Owned" } else { "Borrowed" };
+        write!(fmt, "{kind}[{:?}#{}]", self.as_ptr(), self.len())
+    }
+}
+
+//ip From<&[u8]> for ByteData
+impl<'a> From<&'a [u8]> for ByteData<'a> {
+    fn from(b: &'a [u8]) -> Self {
+        Self::Borrowed(b)
+    }
+}
+
+//ip From<Arc<[u8]>> for ByteData
+impl<'a> From<Arc<[u8]>> for ByteData<'a> {
+    fn from(b: Arc<[u8]>) -> Self {
+        Self::owned(b)
+    }
+}
+
+//ip From<Vec<u8>> for ByteData
+impl<'a> From<Vec<u8>> for ByteData<'a> {
+    fn from(b: Vec<u8>) -> Self {
+        Self::owned(b)
+    }
+}
+
+//a ClientCell
+//tp ClientCell
+/// The cell that holds the client of a descriptor
+///
+/// The client is created after the descriptor is shared, so it must be
+/// interior-mutable; it is held in an [RwLock] so that descriptors
+/// (and hence objects) can be sent between threads if the clients can
+pub(crate) struct ClientCell<T>(RwLock<T>);
+
+//ip ClientCell
+impl<T> ClientCell<T> {
+    //fp new
+    /// Create a new [ClientCell]
+    pub(crate) fn new(client: T) -> Self {
+        Self(RwLock::new(client))
+    }
+
+    //ap borrow
+    /// Borrow the client
+    pub(crate) fn borrow(&self) -> RwLockReadGuard<'_, T> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    //ap borrow_mut
+    /// Borrow the client mutably
+    pub(crate) fn borrow_mut(&self) -> RwLockWriteGuard<'_, T> {
+        self.0.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+//ip Default for ClientCell
+impl<T: Default> Default for ClientCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+//ip Debug for ClientCell
+impl<T: std::fmt::Debug> std::fmt::Debug for ClientCell<T> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.borrow().fmt(fmt)
+    }
+}
@@ -0,0 +1,794 @@
+//a Documentation
+/*!
+
+This provides simplification of the triangles of a [Primitive] by edge
+collapses ordered by quadric error metrics (Garland and Heckbert,
+1997), and the generation from it of a chain of levels of detail that
+share the vertex data of a [Vertices].
+
+Each collapse moves one vertex onto another along an edge, removing
+the triangles that use the edge; no vertices are created or moved, so
+the simplified indices use the original vertex data. Each vertex has a
+quadric - the sum of the squared distances to the planes of the
+triangles using it (weighted by their areas) - and the error of a
+collapse is the (weighted mean) squared distance of the vertex
+position it moves to from the planes of the vertex it moves; the
+quadrics of collapsed vertices are summed, so the error is measured
+against the original surface. The collapses with least error are made
+first, until the number of indices reaches the target or the next
+collapse would have an error beyond the target error.
+
+Vertices with the same position but different attributes (such as
+texture coordinates or normals) are on an attribute seam. The edges
+of the mesh used by only one triangle are on its border. A vertex on a
+border may only move along the border, and a vertex on a seam only
+along the seam (with its copies on the other side of the seam moved
+with it); vertices where seams or borders meet or branch, or that are
+otherwise complicated, are never moved. Extra quadrics are added for
+the edges of borders and seams, so that they keep their shape.
+Optionally vertices on borders may be locked in place, so that a mesh
+keeps its outline (for example, where it meets other meshes).
+
+Vertices are only considered the same vertex if they have the same
+index; a mesh whose triangles each have their own vertices (such as one
+imported from STL) should be welded (see [Vertices::weld]) first.
+
+Errors are distances in the units of the positions; the error of a
+simplification is the largest error of any of its collapses.
+
+!*/
+
+//a Imports
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    list_indices, AccessorError, BoundingBox, BufferAccessor, BufferElementType, Primitive,
+    PrimitiveType, Renderable, Shared, Vec3, Vertices,
+};
+
+//a Constants
+/// Marker for no vertex, in the edge loops
+const NONE: u32 = u32::MAX;
+
+/// The weight of the quadrics of border edges, relative to the
+/// triangles
+const BORDER_WEIGHT: f32 = 10.;
+
+/// The weight of the quadrics of seam edges, relative to the triangles
+const SEAM_WEIGHT: f32 = 1.;
+
+//a Vector functions
+//fi sub
+fn sub(a: &Vec3, b: &Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+//fi dot
+fn dot(a: &Vec3, b: &Vec3) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+//fi cross
+fn cross(a: &Vec3, b: &Vec3) -> Vec3 {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+//fi normalize
+/// Normalize a vector, returning it and its length, or `None` if it
+/// has zero length
+fn normalize(a: &Vec3) -> Option<(Vec3, f32)> {
+    let l = dot(a, a).sqrt();
+    (l > 0.).then(|| (a.map(|c| c / l), l))
+}
+
+//a Quadric
+//ti Quadric
+/// A weighted sum of the squared distances of a point from some planes
+#[derive(Debug, Default, Clone, Copy)]
+struct Quadric {
+    /// The symmetric matrix of the quadratic term - xx, xy, xz, yy, yz
+    /// and zz
+    a: [f32; 6],
+    /// The linear term
+    b: Vec3,
+    /// The constant term
+    c: f32,
+    /// The total weight of the planes
+    w: f32,
+}
+
+//ii Quadric
+impl Quadric {
+    //fi of_plane
+    /// Create a [Quadric] for the plane of points `p` with `n.p + d =
+    /// 0`, for a unit normal `n`
+    fn of_plane(n: &Vec3, d: f32, w: f32) -> Self {
+        Self {
+            a: [
+                n[0] * n[0] * w,
+                n[0] * n[1] * w,
+                n[0] * n[2] * w,
+                n[1] * n[1] * w,
+                n[1] * n[2] * w,
+                n[2] * n[2] * w,
+            ],
+            b: n.map(|c| c * d * w),
+            c: d * d * w,
+            w,
+        }
+    }
+
+    //fi of_triangle
+    /// Create a [Quadric] for the plane of a triangle, weighted by its
+    /// area, or `None` if it is degenerate
+    fn of_triangle(p0: &Vec3, p1: &Vec3, p2: &Vec3) -> Option<Self> {
+        let (n, l) = normalize(&cross(&sub(p1, p0), &sub(p2, p0)))?;
+        Some(Self::of_plane(&n, -dot(&n, p0), l / 2.))
+    }
+
+    //fi of_edge
+    /// Create a [Quadric] for the plane through the edge p0-p1 of a
+    /// triangle (whose other vertex is p2) perpendicular to the
+    /// triangle, weighted by the length of the edge
+    fn of_edge(p0: &Vec3, p1: &Vec3, p2: &Vec3, weight: f32) -> Option<Self> {
+        let (e, l) = normalize(&sub(p1, p0))?;
+        let p20 = sub(p2, p0);
+        let k = dot(&p20, &e);
+        let (n, _) = normalize(&[0, 1, 2].map(|i| p20[i] - e[i] * k))?;
+        Some(Self::of_plane(&n, -dot(&n, p0), l * weight))
+    }
+
+    //mi add
+    /// Add another [Quadric] to this
+    fn add(&mut self, other: &Self) {
+        for i in 0..6 {
+            self.a[i] += other.a[i];
+        }
+        for i in 0..3 {
+            self.b[i] += other.b[i];
+        }
+        self.c += other.c;
+        self.w += other.w;
+    }
+
+    //mi error
+    /// Get the weighted mean squared distance of a point from the
+    /// planes
+    fn error(&self, p: &Vec3) -> f32 {
+        let [xx, xy, xz, yy, yz, zz] = self.a;
+        let r = p[0] * (xx * p[0] + 2. * (xy * p[1] + xz * p[2]))
+            + p[1] * (yy * p[1] + 2. * yz * p[2])
+            + zz * p[2] * p[2]
+            + 2. * dot(&self.b, p)
+            + self.c;
+        if self.w > 0. {
+            r.abs() / self.w
+        } else {
+            r.abs()
+        }
+    }
+}
+
+//a Simplifier
+//ti Kind
+/// The kind of a vertex, which determines how it may be moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    /// A vertex with no other vertices at its position, inside the
+    /// mesh; it can be moved onto any vertex it shares an edge with
+    Manifold,
+    /// A vertex on a single border of the mesh; it can be moved along
+    /// the border
+    Border,
+    /// A vertex with one other vertex at its position, on a single
+    /// seam; it can be moved along the seam
+    Seam,
+    /// A vertex that is never moved
+    Locked,
+}
+
+//ti Collapse
+/// A collapse of an edge, moving one vertex onto another
+#[derive(Debug, Clone, Copy)]
+struct Collapse {
+    /// The vertex that is moved
+    from: u32,
+    /// The vertex it is moved onto
+    to: u32,
+    /// The error of the collapse (squared, in the scaled positions)
+    error: f32,
+}
+
+//ti Simplifier
+/// The state for simplifying the triangles that use a set of vertices
+///
+/// Vertices at the same position share the quadric of the first of
+/// them, and are locked together
+#[derive(Debug, Clone)]
+struct Simplifier {
+    /// The scale of the positions
+    scale: f32,
+    /// The positions, scaled to the unit cube
+    positions: Vec<Vec3>,
+    /// The first vertex with the same position as each vertex
+    remap: Vec<u32>,
+    /// The next vertex with the same position as each vertex, in a
+    /// cycle
+    wedge: Vec<u32>,
+    /// The kind of each vertex
+    kind: Vec<Kind>,
+    /// For each vertex, the vertex at the end of its edge that is used
+    /// by only one triangle, if it has one; the vertex itself if it
+    /// has more than one
+    loop_out: Vec<u32>,
+    /// For each vertex, the vertex at the start of its edge that is
+    /// used by only one triangle, if it has one; the vertex itself if
+    /// it has more than one
+    loop_in: Vec<u32>,
+    /// The quadric of each vertex (only the first of those with each
+    /// position is used)
+    quadrics: Vec<Quadric>,
+}
+
+//ii Simplifier
+impl Simplifier {
+    //fi new
+    /// Create a new [Simplifier] for triangles using some positions
+    fn new(positions: &[Vec3], indices: &[u32], lock_border: bool) -> Self {
+        let n = positions.len();
+        let (min, scale) = match BoundingBox::of_points(positions) {
+            Some(bbox) => (bbox.min, bbox.size().into_iter().fold(0_f32, f32::max)),
+            None => ([0.; 3], 1.),
+        };
+        let scale = if scale > 0. { scale } else { 1. };
+        let positions: Vec<Vec3> = positions
+            .iter()
+            .map(|p| [0, 1, 2].map(|i| (p[i] - min[i]) / scale))
+            .collect();
+
+        let mut first = HashMap::new();
+        let remap: Vec<u32> = positions
+            .iter()
+            .enumerate()
+            .map(|(v, p)| *first.entry(p.map(f32::to_bits)).or_insert(v as u32))
+            .collect();
+        let mut wedge: Vec<u32> = (0..n as u32).collect();
+        for v in 0..n {
+            let r = remap[v] as usize;
+            if r != v {
+                wedge[v] = wedge[r];
+                wedge[r] = v as u32;
+            }
+        }
+
+        let edges: HashSet<(u32, u32)> = triangle_edges(indices).map(|(a, b, _)| (a, b)).collect();
+        let position_edges: HashSet<(u32, u32)> = edges
+            .iter()
+            .map(|(a, b)| (remap[*a as usize], remap[*b as usize]))
+            .collect();
+        let mut loop_out = vec![NONE; n];
+        let mut loop_in = vec![NONE; n];
+        let mut quadrics = vec![Quadric::default(); n];
+        for t in indices.chunks_exact(3) {
+            let [p0, p1, p2] = [0, 1, 2].map(|i| &positions[t[i] as usize]);
+            if let Some(q) = Quadric::of_triangle(p0, p1, p2) {
+                for v in t {
+                    quadrics[remap[*v as usize] as usize].add(&q);
+                }
+            }
+        }
+        for (a, b, c) in triangle_edges(indices) {
+            if edges.contains(&(b, a)) {
+                continue;
+            }
+            let (a, b) = (a as usize, b as usize);
+            loop_out[a] = if loop_out[a] == NONE {
+                b as u32
+            } else {
+                a as u32
+            };
+            loop_in[b] = if loop_in[b] == NONE {
+                a as u32
+            } else {
+                b as u32
+            };
+            let (ra, rb) = (remap[a], remap[b]);
+            let weight = if position_edges.contains(&(rb, ra)) {
+                SEAM_WEIGHT
+            } else {
+                BORDER_WEIGHT
+            };
+            let [p0, p1, p2] = [a, b, c as usize].map(|i| &positions[i]);
+            if let Some(q) = Quadric::of_edge(p0, p1, p2, weight) {
+                quadrics[ra as usize].add(&q);
+                quadrics[rb as usize].add(&q);
+            }
+        }
+
+        let mut simplifier = Self {
+            scale,
+            positions,
+            remap,
+            wedge,
+            kind: vec![],
+            loop_out,
+            loop_in,
+            quadrics,
+        };
+        simplifier.kind = (0..n)
+            .map(|v| simplifier.classify(v, lock_border))
+            .collect();
+        simplifier
+    }
+
+    //mi has_single_loop
+    /// Return true if a vertex has exactly one edge in and one edge out
+    /// that are each used by only one triangle
+    fn has_single_loop(&self, v: usize) -> bool {
+        let (o, i) = (self.loop_out[v], self.loop_in[v]);
+        o != NONE && i != NONE && o as usize != v && i as usize != v
+    }
+
+    //mi classify
+    /// Find the [Kind] of a vertex
+    fn classify(&self, v: usize, lock_border: bool) -> Kind {
+        let v = self.remap[v] as usize;
+        let w = self.wedge[v] as usize;
+        let remap = |v: u32| self.remap[v as usize];
+        if w == v {
+            if self.loop_out[v] == NONE && self.loop_in[v] == NONE {
+                Kind::Manifold
+            } else if !lock_border
+                && self.has_single_loop(v)
+                && remap(self.loop_out[v]) != remap(self.loop_in[v])
+            {
+                Kind::Border
+            } else {
+                Kind::Locked
+            }
+        } else if self.wedge[w] as usize == v
+            && self.has_single_loop(v)
+            && self.has_single_loop(w)
+            && remap(self.loop_in[v]) == remap(self.loop_out[w])
+            && remap(self.loop_out[v]) == remap(self.loop_in[w])
+            && remap(self.loop_in[v]) != remap(self.loop_out[v])
+        {
+            Kind::Seam
+        } else {
+            Kind::Locked
+        }
+    }
+
+    //mi can_collapse
+    /// Return true if one vertex may be moved onto another that it
+    /// shares an edge with
+    fn can_collapse(&self, from: u32, to: u32) -> bool {
+        let (f, t) = (from as usize, to as usize);
+        let on_loop = self.loop_out[f] == to || self.loop_in[f] == to;
+        match (self.kind[f], self.kind[t]) {
+            (Kind::Manifold, _) => true,
+            (Kind::Border, Kind::Border | Kind::Locked) => on_loop,
+            (Kind::Seam, Kind::Seam | Kind::Locked) => on_loop,
+            _ => false,
+        }
+    }
+
+    //mi collapse_error
+    /// Get the error of moving one vertex onto another
+    fn collapse_error(&self, from: u32, to: u32) -> f32 {
+        let r = self.remap[from as usize] as usize;
+        self.quadrics[r].error(&self.positions[to as usize])
+    }
+
+    //mi collapses
+    /// Get the best collapse of each edge of some triangles that can be
+    /// collapsed, in order of increasing error
+    fn collapses(&self, indices: &[u32]) -> Vec<Collapse> {
+        let mut seen = HashSet::new();
+        let mut collapses = vec![];
+        for (a, b, _) in triangle_edges(indices) {
+            let (ra, rb) = (self.remap[a as usize], self.remap[b as usize]);
+            if !seen.insert((ra.min(rb), ra.max(rb))) {
+                continue;
+            }
+            let ab = self.can_collapse(a, b).then(|| self.collapse_error(a, b));
+            let ba = self.can_collapse(b, a).then(|| self.collapse_error(b, a));
+            let (from, to, error) = match (ab, ba) {
+                (Some(e), Some(f)) if f < e => (b, a, f),
+                (Some(e), _) => (a, b, e),
+                (None, Some(f)) => (b, a, f),
+                (None, None) => continue,
+            };
+            collapses.push(Collapse { from, to, error });
+        }
+        collapses.sort_by(|a, b| a.error.total_cmp(&b.error));
+        collapses
+    }
+
+    //mi adjacency
+    /// Get, for the first vertex at each position, the other corners of
+    /// each triangle using the position
+    fn adjacency(&self, indices: &[u32]) -> Vec<Vec<(u32, u32)>> {
+        let mut adjacency = vec![vec![]; self.remap.len()];
+        for (a, b, c) in triangle_edges(indices) {
+            let [a, b, c] = [a, b, c].map(|v| self.remap[v as usize]);
+            adjacency[a as usize].push((b, c));
+        }
+        adjacency
+    }
+
+    //mi has_flips
+    /// Return true if moving a position (the first vertex at it) onto
+    /// another would flip any of the triangles using it that remain
+    fn has_flips(&self, adjacent: &[(u32, u32)], collapse_remap: &[u32], r0: u32, r1: u32) -> bool {
+        let p0 = &self.positions[r0 as usize];
+        let p1 = &self.positions[r1 as usize];
+        for (a, b) in adjacent {
+            let (a, b) = (collapse_remap[*a as usize], collapse_remap[*b as usize]);
+            let (ra, rb) = (self.remap[a as usize], self.remap[b as usize]);
+            if ra == r1 || rb == r1 || ra == rb {
+                continue;
+            }
+            let pa = &self.positions[a as usize];
+            let ab = sub(&self.positions[b as usize], pa);
+            let before = cross(&ab, &sub(p0, pa));
+            let after = cross(&ab, &sub(p1, pa));
+            if dot(&before, &after) <= 0. {
+                return true;
+            }
+        }
+        false
+    }
+
+    //mi remap_loops
+    /// Update the edge loops after the vertices have been collapsed
+    fn remap_loops(&mut self, collapse_remap: &[u32]) {
+        for edges in [&mut self.loop_out, &mut self.loop_in] {
+            for v in 0..edges.len() {
+                let l = edges[v];
+                if l == NONE || l as usize == v {
+                    continue;
+                }
+                let r = collapse_remap[l as usize];
+                edges[v] = if r as usize != v {
+                    r
+                } else {
+                    // The edge was collapsed onto this vertex
+                    match edges[l as usize] {
+                        NONE => NONE,
+                        n => collapse_remap[n as usize],
+                    }
+                };
+            }
+        }
+    }
+
+    //mi simplify
+    /// Simplify triangles to at most a number of indices, with
+    /// collapses of at most an error, returning the new triangles and
+    /// the largest error of any collapse
+    fn simplify(
+        &mut self,
+        mut indices: Vec<u32>,
+        target: usize,
+        error_limit: f32,
+    ) -> (Vec<u32>, f32) {
+        let n = self.remap.len();
+        let mut result_error = 0_f32;
+        while indices.len() > target {
+            let collapses = self.collapses(&indices);
+            if collapses.is_empty() {
+                break;
+            }
+            let adjacency = self.adjacency(&indices);
+            let triangle_goal = (indices.len() - target).div_ceil(3);
+            // Many collapses are blocked by earlier ones in each pass,
+            // so allow some more error than the best collapses need
+            let error_goal = collapses
+                .get(triangle_goal / 2)
+                .map_or(f32::MAX, |c| c.error * 1.5);
+
+            let mut collapse_remap: Vec<u32> = (0..n as u32).collect();
+            let mut locked = vec![false; n];
+            let mut triangles_collapsed = 0;
+            let mut edges_collapsed = 0;
+            for c in collapses {
+                let (i0, i1) = (c.from as usize, c.to);
+                let (r0, r1) = (self.remap[i0], self.remap[i1 as usize]);
+                if locked[r0 as usize] || locked[r1 as usize] {
+                    continue;
+                }
+                if c.error > error_limit || triangles_collapsed >= triangle_goal {
+                    break;
+                }
+                if c.error > error_goal && triangles_collapsed > triangle_goal / 6 {
+                    break;
+                }
+                if self.has_flips(&adjacency[r0 as usize], &collapse_remap, r0, r1) {
+                    continue;
+                }
+                if self.kind[i0] == Kind::Seam {
+                    let s0 = self.wedge[i0] as usize;
+                    let s1 = if self.loop_out[i0] == i1 {
+                        self.loop_in[s0]
+                    } else {
+                        self.loop_out[s0]
+                    };
+                    if s1 == NONE || s1 as usize == s0 {
+                        continue;
+                    }
+                    collapse_remap[s0] = s1;
+                }
+                collapse_remap[i0] = i1;
+                let q = self.quadrics[r0 as usize];
+                self.quadrics[r1 as usize].add(&q);
+                locked[r0 as usize] = true;
+                locked[r1 as usize] = true;
+                triangles_collapsed += if self.kind[i0] == Kind::Border { 1 } else { 2 };
+                edges_collapsed += 1;
+                result_error = result_error.max(c.error);
+            }
+            if edges_collapsed == 0 {
+                break;
+            }
+
+            self.remap_loops(&collapse_remap);
+            indices = indices
+                .chunks_exact(3)
+                .map(|t| [0, 1, 2].map(|i| collapse_remap[t[i] as usize]))
+                .filter(|t| {
+                    let [a, b, c] = t.map(|v| self.remap[v as usize]);
+                    a != b && b != c && c != a
+                })
+                .flatten()
+                .collect();
+        }
+        (indices, result_error)
+    }
+}
+
+//fi triangle_edges
+/// Iterate over the edges of triangles, as the vertices at the start
+/// and end of each edge and the other vertex of its triangle
+fn triangle_edges(indices: &[u32]) -> impl Iterator<Item = (u32, u32, u32)> + '_ {
+    indices
+        .chunks_exact(3)
+        .flat_map(|t| [(t[0], t[1], t[2]), (t[1], t[2], t[0]), (t[2], t[0], t[1])])
+}
+
+//a Simplification
+//tp Simplification
+/// The parameters for simplifying the triangles of a [Primitive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Simplification {
+    /// The number of indices to reduce the triangles to (if the error
+    /// permits)
+    pub target_index_count: usize,
+    /// The largest error (a distance in the units of the positions) of
+    /// any collapse
+    pub target_error: f32,
+    /// If true then vertices on the border of the mesh are never moved
+    pub lock_border: bool,
+}
+
+//ip Default for Simplification
+impl Default for Simplification {
+    fn default() -> Self {
+        Self {
+            target_index_count: 0,
+            target_error: f32::MAX,
+            lock_border: false,
+        }
+    }
+}
+
+//ip Simplification
+impl Simplification {
+    //fp new
+    /// Create a new [Simplification] that reduces the triangles as far
+    /// as possible, whatever the error
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with_target_index_count
+    /// Set the number of indices to reduce the triangles to
+    pub fn with_target_index_count(mut self, target_index_count: usize) -> Self {
+        self.target_index_count = target_index_count;
+        self
+    }
+
+    //cp with_target_error
+    /// Set the largest error of any collapse
+    pub fn with_target_error(mut self, target_error: f32) -> Self {
+        self.target_error = target_error;
+        self
+    }
+
+    //cp with_lock_border
+    /// Set whether vertices on the border of the mesh are never moved
+    pub fn with_lock_border(mut self, lock_border: bool) -> Self {
+        self.lock_border = lock_border;
+        self
+    }
+}
+
+//a Simplified, LodChain
+//tp Simplified
+/// The result of simplifying the triangles of a [Primitive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simplified {
+    /// The indices of the simplified triangles, of the same vertices
+    pub indices: Vec<u32>,
+    /// The largest error of any collapse; a distance in the units of
+    /// the positions
+    pub error: f32,
+}
+
+//tp Lod
+/// A level of detail of a [LodChain]
+#[derive(Debug, Clone)]
+pub struct Lod {
+    /// The primitive for the level
+    pub primitive: Primitive,
+    /// The error of the level, relative to the original primitive; a
+    /// distance in the units of the positions
+    pub error: f32,
+}
+
+//tp LodChain
+/// A chain of levels of detail of a [Primitive], using a [Vertices]
+/// that shares the vertex data of the original
+#[derive(Debug)]
+pub struct LodChain<'b, R: Renderable> {
+    /// The vertices, with an index buffer holding the indices of all
+    /// of the levels
+    pub vertices: Vertices<'b, R>,
+    /// The levels, from the original primitive (with an error of
+    /// zero) in order of decreasing detail
+    pub lods: Vec<Lod>,
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mi simplifier
+    /// Get the list indices of a primitive that uses this [Vertices],
+    /// and a [Simplifier] for them if it is a triangle primitive
+    fn simplifier(
+        &self,
+        primitive: &Primitive,
+        lock_border: bool,
+    ) -> Result<(Vec<u32>, Option<Simplifier>), AccessorError> {
+        let position = self.borrow_position();
+        let n = position.count as usize;
+        let indices = self.primitive_indices(primitive)?;
+        if let Some(i) = indices.iter().find(|i| **i as usize >= n) {
+            return Err(AccessorError::BadIndex(*i, n));
+        }
+        let indices = list_indices(primitive.primitive_type(), &indices);
+        if primitive.primitive_type().list_type() != PrimitiveType::Triangles {
+            return Ok((indices, None));
+        }
+        let positions: Vec<Vec3> = position.iter_elements(n)?.collect();
+        let simplifier = Simplifier::new(&positions, &indices, lock_border);
+        Ok((indices, Some(simplifier)))
+    }
+
+    //mp simplify
+    /// Simplify the triangles of a [Primitive] that uses this
+    /// [Vertices], giving the indices of a triangle list of the same
+    /// vertices
+    ///
+    /// Triangle strips and fans are simplified as lists; other
+    /// primitives are not simplified, and give their (list) indices
+    ///
+    /// An error is returned if the data cannot be read, or if an index
+    /// is beyond the vertices of the position accessor
+    pub fn simplify(
+        &self,
+        primitive: &Primitive,
+        simplification: &Simplification,
+    ) -> Result<Simplified, AccessorError> {
+        let (indices, simplifier) = self.simplifier(primitive, simplification.lock_border)?;
+        let Some(simplifier) = simplifier else {
+            return Ok(Simplified { indices, error: 0. });
+        };
+        Ok(simplify_with(simplifier, indices, simplification))
+    }
+
+    //mp lod_chain
+    /// Create a chain of levels of detail for a [Primitive] that uses
+    /// this [Vertices], starting with the primitive itself (as a list)
+    ///
+    /// Each further level is simplified from the original to at most
+    /// `ratio` of the indices of the level before, down to the target
+    /// index count and within the target error of the
+    /// [Simplification]; the chain ends when a level cannot be reduced
+    /// further, or after `max_lods` levels
+    ///
+    /// The new [Vertices] shares the vertex data of this one, with an
+    /// index buffer holding the indices of all of the levels, with the
+    /// same element type as the indices of this [Vertices] (if any)
+    ///
+    /// An error is returned if the data cannot be read, or if an index
+    /// is beyond the vertices of the position accessor
+    pub fn lod_chain(
+        &self,
+        primitive: &Primitive,
+        simplification: &Simplification,
+        ratio: f32,
+        max_lods: usize,
+    ) -> Result<LodChain<'vertices, R>, AccessorError> {
+        let (indices, simplifier) = self.simplifier(primitive, simplification.lock_border)?;
+        let mut levels = vec![Simplified { indices, error: 0. }];
+        if let Some(simplifier) = simplifier {
+            while levels.len() < max_lods {
+                let count = levels.last().unwrap().indices.len();
+                let target = ((count as f32 * ratio) as usize / 3 * 3)
+                    .max(simplification.target_index_count);
+                if target >= count {
+                    break;
+                }
+                let level = simplify_with(
+                    simplifier.clone(),
+                    levels[0].indices.clone(),
+                    &simplification.with_target_index_count(target),
+                );
+                if level.indices.len() >= count {
+                    break;
+                }
+                levels.push(level);
+            }
+        }
+
+        let ele_type = match self.borrow_indices() {
+            Some(indices) => indices.ele_type,
+            None => BufferElementType::for_indices(self.borrow_position().count as usize),
+        };
+        let size = ele_type.byte_length();
+        let mut indices = vec![];
+        let mut lods = vec![];
+        for level in levels {
+            lods.push(Lod {
+                primitive: Primitive::new(
+                    primitive.primitive_type().list_type(),
+                    primitive.vertices_index(),
+                    (indices.len() * size) as u32,
+                    level.indices.len() as u32,
+                    primitive.material_index(),
+                ),
+                error: level.error,
+            });
+            indices.extend(level.indices);
+        }
+        let indices = Shared::new(BufferAccessor::of_indices(&indices, ele_type));
+        Ok(LodChain {
+            vertices: self.reindexed(Some(indices)),
+            lods,
+        })
+    }
+
+    //zz All done
+}
+
+//fi simplify_with
+/// Simplify triangles with a [Simplifier]
+fn simplify_with(
+    mut simplifier: Simplifier,
+    indices: Vec<u32>,
+    simplification: &Simplification,
+) -> Simplified {
+    let scale = simplifier.scale;
+    let error_limit = (simplification.target_error / scale).powi(2);
+    let (indices, error) =
+        simplifier.simplify(indices, simplification.target_index_count, error_limit);
+    Simplified {
+        indices,
+        error: error.sqrt() * scale,
+    }
+}
@@ -5,6 +5,8 @@ use crate::hierarchy;
 use crate::Bone;
 use crate::Mat4;
 use crate::Transformation;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 //a Skeleton
 //tp Skeleton
@@ -12,15 +14,22 @@ use crate::Transformation;
 ///
 /// This corresponds to a skeleton (or a number thereof), with each
 /// bone appearing once in each skeleton. The bones form a hierarchy.
-#[derive(Debug)]
+///
+/// When serialized only the bones are included; a deserialized
+/// [Skeleton] must be resolved (and its matrices derived) before use.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Skeleton {
     /// The bones that make up the set, with the hierarchical relationships
     pub skeleton: hierarchy::Hierarchy<Bone>,
     /// The roots of the bones and hierarchical recipes for traversal
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub roots: Vec<(usize, hierarchy::Recipe)>,
     /// An array of matrices long enough for the one per level of traversal
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub temp_mat4s: Vec<Mat4>,
     /// Max bone index
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub max_index: usize,
 }
 
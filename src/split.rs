@@ -0,0 +1,172 @@
+//a Documentation
+/*!
+
+This provides the splitting of the vertices of a [Vertices] for an
+attribute that is generated for each corner of each triangle (such as
+generated normals or tangents): the corners that use a vertex and have
+the same value share it, and a new vertex is added for each further
+value.
+
+!*/
+
+//a Imports
+use crate::{
+    list_indices, AccessorError, BufferAccessor, BufferElementType, Primitive, PrimitiveType,
+    Renderable, Shared, VertexAttr, VertexLayout, Vertices,
+};
+
+//a Triangulated
+//tp Triangulated
+/// The indices of the primitives that use a [Vertices], with those of
+/// triangle strips and fans converted to triangle lists, and all of
+/// their triangles
+pub(crate) struct Triangulated {
+    /// The number of vertices of the position accessor
+    pub(crate) num_vertices: usize,
+    /// The (list) indices of each primitive
+    pub(crate) indices: Vec<Vec<u32>>,
+    /// The triangles of all of the primitives, in order
+    pub(crate) triangles: Vec<[u32; 3]>,
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp triangulate
+    /// Get the [Triangulated] indices of primitives that use this
+    /// [Vertices]
+    ///
+    /// An error is returned if the indices cannot be read, or if an
+    /// index is beyond the vertices of the position accessor
+    pub(crate) fn triangulate(
+        &self,
+        primitives: &[Primitive],
+    ) -> Result<Triangulated, AccessorError> {
+        let num_vertices = self.borrow_position().count as usize;
+        let mut indices = vec![];
+        let mut triangles = vec![];
+        for p in primitives {
+            let mut p_indices = self.primitive_indices(p)?;
+            if let Some(i) = p_indices.iter().find(|i| **i as usize >= num_vertices) {
+                return Err(AccessorError::BadIndex(*i, num_vertices));
+            }
+            if p.primitive_type().list_type() == PrimitiveType::Triangles {
+                p_indices = list_indices(p.primitive_type(), &p_indices);
+                triangles.extend(p_indices.chunks_exact(3).map(|t| [t[0], t[1], t[2]]));
+            }
+            indices.push(p_indices);
+        }
+        Ok(Triangulated {
+            num_vertices,
+            indices,
+            triangles,
+        })
+    }
+
+    //mp split_by_corners
+    /// Create a new [Vertices] with an attribute (replacing any it
+    /// already has) given by a value for each corner of each of the
+    /// [Triangulated] triangles, and the primitives to use with it
+    ///
+    /// The first value of each vertex uses the vertex itself; any
+    /// other values use new vertices, that are copies of it, added
+    /// after the original vertices. If vertices are added then the
+    /// primitives use a new index buffer, in which triangle strips and
+    /// fans become triangle lists; otherwise the indices (if any) are
+    /// copied and the primitives are unchanged. Vertices that are not
+    /// used by any triangle are given the default value.
+    ///
+    /// The new [Vertices] has no [crate::Meshlets]
+    pub(crate) fn split_by_corners<'b, const N: usize>(
+        &self,
+        primitives: &[Primitive],
+        triangulated: Triangulated,
+        attr: VertexAttr,
+        corner_values: &[[f32; N]],
+        default: [f32; N],
+        layout: VertexLayout,
+    ) -> Result<(Vertices<'b, R>, Vec<Primitive>), AccessorError> {
+        let n = triangulated.num_vertices;
+        let mut order: Vec<u32> = (0..n as u32).collect();
+        let mut values = vec![default; n];
+        let mut vertex_values: Vec<Vec<([f32; N], u32)>> = vec![vec![]; n];
+        let mut corner_vertex = Vec::with_capacity(corner_values.len());
+        for (v, value) in triangulated.triangles.iter().flatten().zip(corner_values) {
+            let v = *v as usize;
+            let existing = vertex_values[v].iter().find(|(x, _)| x == value);
+            corner_vertex.push(match existing {
+                Some((_, w)) => *w,
+                None if vertex_values[v].is_empty() => {
+                    values[v] = *value;
+                    vertex_values[v].push((*value, v as u32));
+                    v as u32
+                }
+                None => {
+                    let w = order.len() as u32;
+                    order.push(v as u32);
+                    values.push(*value);
+                    vertex_values[v].push((*value, w));
+                    w
+                }
+            });
+        }
+
+        let (indices, primitives) = if order.len() == n {
+            let indices = match self.borrow_indices() {
+                Some(indices) => {
+                    let i: Vec<u32> = indices.iter_indices()?.collect();
+                    Some(BufferAccessor::of_indices(&i, indices.ele_type))
+                }
+                None => None,
+            };
+            (indices, primitives.to_vec())
+        } else {
+            let ele_type = match self.borrow_indices() {
+                Some(i) if order.len() as u64 <= 1 << (8 * i.ele_type.byte_length()) => i.ele_type,
+                _ => BufferElementType::for_indices(order.len()),
+            };
+            let mut new_indices: Vec<u32> = vec![];
+            let mut new_primitives = vec![];
+            let mut corner = 0;
+            for (p, indices) in primitives.iter().zip(triangulated.indices) {
+                let mut primitive_type = p.primitive_type();
+                let first = new_indices.len();
+                if primitive_type.list_type() == PrimitiveType::Triangles {
+                    primitive_type = PrimitiveType::Triangles;
+                    new_indices.extend(&corner_vertex[corner..corner + indices.len()]);
+                    corner += indices.len();
+                } else {
+                    new_indices.extend(indices);
+                }
+                new_primitives.push(Primitive::new(
+                    primitive_type,
+                    p.vertices_index(),
+                    (first * ele_type.byte_length()) as u32,
+                    (new_indices.len() - first) as u32,
+                    p.material_index(),
+                ));
+            }
+            (
+                Some(BufferAccessor::of_indices(&new_indices, ele_type)),
+                new_primitives,
+            )
+        };
+
+        let mut source = Vertices::non_indexed(self.borrow_position());
+        for (a, accessor) in self.iter_attrs() {
+            if a != attr {
+                source.add_attr(a, accessor);
+            }
+        }
+        for target in self.iter_morph_targets() {
+            source.add_morph_target(target.clone());
+        }
+        let mut vertices =
+            source.repack_vertices(VertexLayout::Planar, &order, indices.map(Shared::new))?;
+        let values = BufferAccessor::of_elements(&values, BufferElementType::Float32, false)?;
+        vertices.add_attr(attr, Shared::new(values));
+        Ok((vertices.repack(layout)?, primitives))
+    }
+
+    //zz All done
+}
@@ -0,0 +1,253 @@
+//a Documentation
+/*!
+
+This provides conversion of the indices of [Primitive]s to the list
+types - triangle strips and fans to [PrimitiveType::Triangles], and
+line strips and loops to [PrimitiveType::Lines] - so that consumers
+that only support lists (such as exporters, CPU picking, or some
+rendering APIs) need not expand them themselves; and the reverse
+conversion of triangle lists to triangle strips.
+
+Alternate triangles of a triangle strip have the opposite winding, so
+the first two indices of every odd triangle are swapped to keep the
+winding of the first. Degenerate triangles (and lines) - those that
+use the same index more than once - are dropped from the lists; hence
+the strips produced from triangle lists can join separate strips with
+repeated indices.
+
+!*/
+
+//a Imports
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    AccessorError, BufferAccessor, BufferElementType, Primitive, PrimitiveType, Renderable, Shared,
+    Vertices,
+};
+
+//a Lists
+//fp list_indices
+/// Convert the indices of a primitive of a given type to those of its
+/// list type (see [PrimitiveType::list_type])
+///
+/// Degenerate triangles and lines are dropped, as are any indices that
+/// do not form a complete triangle or line
+pub fn list_indices(primitive_type: PrimitiveType, indices: &[u32]) -> Vec<u32> {
+    use PrimitiveType::*;
+    let n = indices.len();
+    let i = |n: usize| indices[n];
+    let elements: Vec<Vec<u32>> = match primitive_type {
+        Points => return indices.to_vec(),
+        Lines => indices.chunks_exact(2).map(|l| l.to_vec()).collect(),
+        LineStrip => (1..n).map(|k| vec![i(k - 1), i(k)]).collect(),
+        LineLoop if n < 2 => vec![],
+        LineLoop => (0..n).map(|k| vec![i(k), i((k + 1) % n)]).collect(),
+        Triangles => indices.chunks_exact(3).map(|t| t.to_vec()).collect(),
+        TriangleStrip => (2..n)
+            .map(|k| {
+                if k % 2 == 0 {
+                    vec![i(k - 2), i(k - 1), i(k)]
+                } else {
+                    vec![i(k - 1), i(k - 2), i(k)]
+                }
+            })
+            .collect(),
+        TriangleFan => (2..n).map(|k| vec![i(0), i(k - 1), i(k)]).collect(),
+    };
+    elements
+        .into_iter()
+        .filter(|e| (1..e.len()).all(|k| !e[0..k].contains(&e[k])))
+        .flatten()
+        .collect()
+}
+
+//a Strips
+//tp Stripifier
+/// The state for building triangle strips from a triangle list
+struct Stripifier {
+    /// The non-degenerate triangles
+    triangles: Vec<[u32; 3]>,
+    /// The triangles (and their third vertex) with each directed edge
+    edges: HashMap<(u32, u32), Vec<(usize, u32)>>,
+    /// True for each triangle that is in a strip
+    used: Vec<bool>,
+}
+
+//ip Stripifier
+impl Stripifier {
+    //fp new
+    /// Create a new [Stripifier] for a triangle list
+    fn new(indices: &[u32]) -> Self {
+        let triangles: Vec<[u32; 3]> = indices
+            .chunks_exact(3)
+            .map(|t| [t[0], t[1], t[2]])
+            .filter(|[a, b, c]| a != b && b != c && c != a)
+            .collect();
+        let mut edges: HashMap<(u32, u32), Vec<(usize, u32)>> = HashMap::new();
+        for (n, t) in triangles.iter().enumerate() {
+            for r in 0..3 {
+                let edge = (t[r], t[(r + 1) % 3]);
+                edges.entry(edge).or_default().push((n, t[(r + 2) % 3]));
+            }
+        }
+        let used = vec![false; triangles.len()];
+        Self {
+            triangles,
+            edges,
+            used,
+        }
+    }
+
+    //mp follow
+    /// Build a strip starting with a triangle (rotated so that it
+    /// starts with vertex `r`), returning the strip and the triangles
+    /// it uses
+    fn follow(&self, start: usize, r: usize) -> (Vec<u32>, Vec<usize>) {
+        let t = self.triangles[start];
+        let mut strip = vec![t[r], t[(r + 1) % 3], t[(r + 2) % 3]];
+        let mut taken = HashSet::from([start]);
+        let mut order = vec![start];
+        loop {
+            let k = strip.len() - 2;
+            let (p, q) = (strip[k], strip[k + 1]);
+            // The next triangle must be wound as (p, q, r) for an even
+            // position in the strip, and as (q, p, r) for an odd one
+            let edge = if k % 2 == 0 { (p, q) } else { (q, p) };
+            let next = self.edges.get(&edge).and_then(|candidates| {
+                candidates
+                    .iter()
+                    .find(|(n, _)| !self.used[*n] && !taken.contains(n))
+            });
+            let Some((n, third)) = next else {
+                break;
+            };
+            strip.push(*third);
+            taken.insert(*n);
+            order.push(*n);
+        }
+        (strip, order)
+    }
+
+    //mp strip
+    /// Build strips of all the triangles, greedily starting each from
+    /// the first unused triangle (at the rotation that gives the
+    /// longest strip), and join them with degenerate triangles
+    fn strip(mut self) -> Vec<u32> {
+        let mut result: Vec<u32> = vec![];
+        for start in 0..self.triangles.len() {
+            if self.used[start] {
+                continue;
+            }
+            let (strip, order) = (0..3)
+                .map(|r| self.follow(start, r))
+                .max_by_key(|(_, order)| order.len())
+                .unwrap();
+            for n in order {
+                self.used[n] = true;
+            }
+            if let Some(last) = result.last().copied() {
+                // The strip must start at an even position
+                if result.len() % 2 == 1 {
+                    result.push(last);
+                }
+                result.push(last);
+                result.push(strip[0]);
+            }
+            result.extend(strip);
+        }
+        result
+    }
+}
+
+//fp strip_indices
+/// Convert the indices of a triangle list to those of a single
+/// triangle strip with the same triangles (and windings)
+///
+/// Triangles are joined into strips greedily, following shared edges;
+/// separate strips are joined by repeating indices, forming degenerate
+/// triangles. Degenerate triangles in the list are dropped.
+pub fn strip_indices(indices: &[u32]) -> Vec<u32> {
+    Stripifier::new(indices).strip()
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mi rewrite_primitives
+    /// Create a new [Vertices], sharing the vertex data of this one,
+    /// with a new index buffer holding the indices of each primitive
+    /// rewritten by a function
+    ///
+    /// The index buffer has the same element type as the indices of
+    /// this [Vertices] (if any)
+    fn rewrite_primitives<F>(
+        &self,
+        primitives: &[Primitive],
+        rewrite: F,
+    ) -> Result<(Vertices<'vertices, R>, Vec<Primitive>), AccessorError>
+    where
+        F: Fn(PrimitiveType, &[u32]) -> (PrimitiveType, Vec<u32>),
+    {
+        let ele_type = match self.borrow_indices() {
+            Some(indices) => indices.ele_type,
+            None => BufferElementType::for_indices(self.borrow_position().count as usize),
+        };
+        let size = ele_type.byte_length();
+        let mut indices = vec![];
+        let mut rewritten = vec![];
+        for p in primitives {
+            let (primitive_type, new_indices) =
+                rewrite(p.primitive_type(), &self.primitive_indices(p)?);
+            rewritten.push(Primitive::new(
+                primitive_type,
+                p.vertices_index(),
+                (indices.len() * size) as u32,
+                new_indices.len() as u32,
+                p.material_index(),
+            ));
+            indices.extend(new_indices);
+        }
+        let indices = Shared::new(BufferAccessor::of_indices(&indices, ele_type));
+        Ok((self.reindexed(Some(indices)), rewritten))
+    }
+
+    //mp to_lists
+    /// Create a new [Vertices], sharing the vertex data of this one
+    /// with a new index buffer, and new primitives of the list types
+    /// for the primitives (which must use this [Vertices])
+    ///
+    /// An error is returned if the indices of a primitive cannot be read
+    pub fn to_lists(
+        &self,
+        primitives: &[Primitive],
+    ) -> Result<(Vertices<'vertices, R>, Vec<Primitive>), AccessorError> {
+        self.rewrite_primitives(primitives, |primitive_type, indices| {
+            (
+                primitive_type.list_type(),
+                list_indices(primitive_type, indices),
+            )
+        })
+    }
+
+    //mp to_strips
+    /// Create a new [Vertices], sharing the vertex data of this one
+    /// with a new index buffer, and new primitives in which each
+    /// triangle list is a triangle strip (other primitives are
+    /// unchanged)
+    ///
+    /// An error is returned if the indices of a primitive cannot be read
+    pub fn to_strips(
+        &self,
+        primitives: &[Primitive],
+    ) -> Result<(Vertices<'vertices, R>, Vec<Primitive>), AccessorError> {
+        self.rewrite_primitives(primitives, |primitive_type, indices| {
+            if primitive_type == PrimitiveType::Triangles {
+                (PrimitiveType::TriangleStrip, strip_indices(indices))
+            } else {
+                (primitive_type, indices.to_vec())
+            }
+        })
+    }
+
+    //zz All done
+}
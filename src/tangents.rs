@@ -0,0 +1,465 @@
+//a Documentation
+/*!
+
+This provides generation of tangents for a [Vertices], from its
+positions, normals and first texture coordinates, for normal mapping
+of meshes whose assets lack [VertexAttr::Tangent].
+
+The tangents match those of the MikkTSpace algorithm (as used by
+Blender, Unity, Unreal and the glTF sample viewer, and which normal
+maps are commonly baked with), with its default angular threshold (so
+tangent spaces are only split where the texture coordinates are
+mirrored, or at seams in the texture coordinates).
+
+The tangents have four components: a unit vector in the direction of
+increasing `u` texture coordinate, orthogonal to the normal, and a
+sign (1.0 or -1.0) giving the handedness of the tangent space; the
+bitangent is `sign * cross(normal, tangent)`, as in glTF.
+
+The tangent space is generated for each corner of each triangle, so
+vertices are split where the triangles that use them have different
+tangents for them. Vertices are identified by their position, normal
+and texture coordinates (rather than by index), as in MikkTSpace.
+
+!*/
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{AccessorError, Primitive, Renderable, VertexAttr, VertexLayout, Vertices};
+
+//a Vector functions
+//fi not_zero
+/// MikkTSpace's test for a non-zero value
+fn not_zero(x: f32) -> bool {
+    x.abs() > f32::MIN_POSITIVE
+}
+
+//fi v_not_zero
+fn v_not_zero(v: &[f32; 3]) -> bool {
+    v.iter().any(|c| not_zero(*c))
+}
+
+//fi sub
+fn sub(a: &[f32; 3], b: &[f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+//fi scale
+fn scale(s: f32, a: &[f32; 3]) -> [f32; 3] {
+    a.map(|c| c * s)
+}
+
+//fi dot
+fn dot(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+//fi length
+fn length(a: &[f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+//fi normalize
+/// Normalize a vector if it is not zero
+fn normalize(a: &[f32; 3]) -> [f32; 3] {
+    if v_not_zero(a) {
+        scale(1. / length(a), a)
+    } else {
+        *a
+    }
+}
+
+//fi project
+/// Project a vector onto the plane with a (unit) normal, and normalize
+/// it if it is not zero
+fn project(n: &[f32; 3], v: &[f32; 3]) -> [f32; 3] {
+    normalize(&sub(v, &scale(dot(n, v), n)))
+}
+
+//a MikkTSpace
+//tp TriInfo
+/// The information for a (non-degenerate) triangle
+#[derive(Debug, Default, Clone)]
+struct TriInfo {
+    /// The (unit) direction of increasing `u`, or zero
+    os: [f32; 3],
+    /// The (unit) direction of increasing `v`, or zero
+    ot: [f32; 3],
+    /// True if the texture coordinates have the same winding as the
+    /// positions
+    orient_preserving: bool,
+    /// True if the texture coordinates are degenerate, so the triangle
+    /// can join any group
+    group_with_any: bool,
+    /// The neighbouring triangle across each edge (from corner `i` to
+    /// corner `i+1`)
+    neighbors: [Option<usize>; 3],
+    /// The group of each corner
+    groups: [Option<usize>; 3],
+}
+
+//tp Group
+/// A group of triangles sharing a vertex, connected across edges that
+/// use the vertex, and with the same orientation
+#[derive(Debug)]
+struct Group {
+    /// The vertex
+    vertex: u32,
+    /// The orientation of the triangles
+    orient_preserving: bool,
+    /// The triangles
+    faces: Vec<usize>,
+}
+
+//tp TSpace
+/// The tangent space for a corner
+#[derive(Debug, Clone, Copy)]
+struct TSpace {
+    /// The tangent
+    os: [f32; 3],
+    /// The orientation
+    orient_preserving: bool,
+}
+
+//ip Default for TSpace
+impl Default for TSpace {
+    fn default() -> Self {
+        Self {
+            os: [1., 0., 0.],
+            orient_preserving: false,
+        }
+    }
+}
+
+//tp MikkTSpace
+/// The state of the MikkTSpace algorithm for a set of triangles, whose
+/// vertices are (shared) indices into the positions, normals and
+/// texture coordinates
+struct MikkTSpace<'a> {
+    positions: &'a [[f32; 3]],
+    normals: &'a [[f32; 3]],
+    triangles: Vec<[u32; 3]>,
+    infos: Vec<TriInfo>,
+    groups: Vec<Group>,
+}
+
+//ip MikkTSpace
+impl<'a> MikkTSpace<'a> {
+    //fp new
+    /// Create the state, with the information for each triangle
+    fn new(
+        positions: &'a [[f32; 3]],
+        normals: &'a [[f32; 3]],
+        tex_coords: &[[f32; 2]],
+        triangles: Vec<[u32; 3]>,
+    ) -> Self {
+        let infos = triangles
+            .iter()
+            .map(|t| {
+                let [p1, p2, p3] = t.map(|v| positions[v as usize]);
+                let [t1, t2, t3] = t.map(|v| tex_coords[v as usize]);
+                let (t21x, t21y) = (t2[0] - t1[0], t2[1] - t1[1]);
+                let (t31x, t31y) = (t3[0] - t1[0], t3[1] - t1[1]);
+                let d1 = sub(&p2, &p1);
+                let d2 = sub(&p3, &p1);
+                let signed_area_x2 = t21x * t31y - t21y * t31x;
+                let os = sub(&scale(t31y, &d1), &scale(t21y, &d2));
+                let ot = sub(&scale(t21x, &d2), &scale(t31x, &d1));
+                let mut info = TriInfo {
+                    orient_preserving: signed_area_x2 > 0.,
+                    group_with_any: true,
+                    ..Default::default()
+                };
+                if not_zero(signed_area_x2) {
+                    let abs_area = signed_area_x2.abs();
+                    let (len_os, len_ot) = (length(&os), length(&ot));
+                    let s = if info.orient_preserving { 1. } else { -1. };
+                    if not_zero(len_os) {
+                        info.os = scale(s / len_os, &os);
+                    }
+                    if not_zero(len_ot) {
+                        info.ot = scale(s / len_ot, &ot);
+                    }
+                    // The rates of change of position with u and v
+                    let mag_s = len_os / abs_area;
+                    let mag_t = len_ot / abs_area;
+                    if not_zero(mag_s) && not_zero(mag_t) {
+                        info.group_with_any = false;
+                    }
+                }
+                info
+            })
+            .collect();
+        Self {
+            positions,
+            normals,
+            triangles,
+            infos,
+            groups: vec![],
+        }
+    }
+
+    //mp build_neighbors
+    /// Find the neighbouring triangle across each edge; each edge is
+    /// paired with the first unpaired edge in the opposite direction
+    /// (in order of triangle)
+    fn build_neighbors(&mut self) {
+        let mut edges: HashMap<(u32, u32), Vec<(usize, usize)>> = HashMap::new();
+        for (f, t) in self.triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (i0, i1) = (t[i], t[(i + 1) % 3]);
+                edges
+                    .entry((i0.min(i1), i0.max(i1)))
+                    .or_default()
+                    .push((f, i));
+            }
+        }
+        for entries in edges.values() {
+            for (a, (f, i)) in entries.iter().enumerate() {
+                if self.infos[*f].neighbors[*i].is_some() {
+                    continue;
+                }
+                let t = &self.triangles[*f];
+                let (i0, i1) = (t[*i], t[(*i + 1) % 3]);
+                let found = entries[a + 1..].iter().find(|(g, j)| {
+                    let u = &self.triangles[*g];
+                    (u[*j], u[(*j + 1) % 3]) == (i1, i0) && self.infos[*g].neighbors[*j].is_none()
+                });
+                if let Some((g, j)) = found.copied() {
+                    self.infos[*f].neighbors[*i] = Some(g);
+                    self.infos[g].neighbors[j] = Some(*f);
+                }
+            }
+        }
+    }
+
+    //mi corner_of
+    /// Find the corner of a triangle that uses a vertex
+    fn corner_of(&self, f: usize, vertex: u32) -> usize {
+        self.triangles[f].iter().position(|v| *v == vertex).unwrap()
+    }
+
+    //mi assign_recur
+    /// Add a triangle (and recursively its neighbours sharing the
+    /// group's vertex) to a group, if it is not in a group for that
+    /// vertex and has the group's orientation
+    fn assign_recur(&mut self, f: usize, g: usize) {
+        let i = self.corner_of(f, self.groups[g].vertex);
+        let info = &mut self.infos[f];
+        if info.groups[i].is_some() {
+            return;
+        }
+        // The first group to include a triangle that can join any group
+        // determines its orientation
+        if info.group_with_any && info.groups.iter().all(|g| g.is_none()) {
+            info.orient_preserving = self.groups[g].orient_preserving;
+        }
+        if info.orient_preserving != self.groups[g].orient_preserving {
+            return;
+        }
+        self.groups[g].faces.push(f);
+        info.groups[i] = Some(g);
+        let (left, right) = (info.neighbors[i], info.neighbors[(i + 2) % 3]);
+        for neighbor in [left, right].into_iter().flatten() {
+            self.assign_recur(neighbor, g);
+        }
+    }
+
+    //mp build_groups
+    /// Build the groups of triangles for each vertex
+    fn build_groups(&mut self) {
+        for f in 0..self.triangles.len() {
+            for i in 0..3 {
+                let info = &self.infos[f];
+                if info.group_with_any || info.groups[i].is_some() {
+                    continue;
+                }
+                let g = self.groups.len();
+                self.groups.push(Group {
+                    vertex: self.triangles[f][i],
+                    orient_preserving: info.orient_preserving,
+                    faces: vec![f],
+                });
+                self.infos[f].groups[i] = Some(g);
+                let info = &self.infos[f];
+                let (left, right) = (info.neighbors[i], info.neighbors[(i + 2) % 3]);
+                for neighbor in [left, right].into_iter().flatten() {
+                    self.assign_recur(neighbor, g);
+                }
+            }
+        }
+    }
+
+    //mi eval_tspace
+    /// Evaluate the tangent of a vertex for a set of triangles, as the
+    /// average of the tangents of the triangles (projected onto the
+    /// plane of the vertex normal), weighted by their angles at the
+    /// vertex
+    fn eval_tspace(&self, faces: &[usize], vertex: u32) -> [f32; 3] {
+        let mut os = [0.; 3];
+        for f in faces {
+            let info = &self.infos[*f];
+            if info.group_with_any {
+                continue;
+            }
+            let t = &self.triangles[*f];
+            let i = self.corner_of(*f, vertex);
+            let n = &self.normals[vertex as usize];
+            let vos = project(n, &info.os);
+            let p0 = &self.positions[t[(i + 2) % 3] as usize];
+            let p1 = &self.positions[t[i] as usize];
+            let p2 = &self.positions[t[(i + 1) % 3] as usize];
+            let v1 = project(n, &sub(p0, p1));
+            let v2 = project(n, &sub(p2, p1));
+            let angle = dot(&v1, &v2).clamp(-1., 1.).acos();
+            os = [0, 1, 2].map(|k| os[k] + angle * vos[k]);
+        }
+        normalize(&os)
+    }
+
+    //mp generate_tspaces
+    /// Generate the tangent space for each corner of each triangle
+    ///
+    /// Within each group the triangles are split into subgroups whose
+    /// tangents and bitangents (projected at the vertex) are within the
+    /// angular threshold; with the default threshold of 180 degrees
+    /// only exactly opposite directions are split
+    fn generate_tspaces(&self) -> Vec<TSpace> {
+        const THRESHOLD_COS: f32 = -1.;
+        let mut tspaces = vec![TSpace::default(); self.triangles.len() * 3];
+        for (g, group) in self.groups.iter().enumerate() {
+            let mut subgroups: Vec<(Vec<usize>, [f32; 3])> = vec![];
+            let n = &self.normals[group.vertex as usize];
+            for f in &group.faces {
+                let info = &self.infos[*f];
+                let i = info.groups.iter().position(|x| *x == Some(g)).unwrap();
+                let vos = project(n, &info.os);
+                let vot = project(n, &info.ot);
+                let mut members: Vec<usize> = group
+                    .faces
+                    .iter()
+                    .copied()
+                    .filter(|t| {
+                        let other = &self.infos[*t];
+                        let any = info.group_with_any || other.group_with_any;
+                        let cos_s = dot(&vos, &project(n, &other.os));
+                        let cos_t = dot(&vot, &project(n, &other.ot));
+                        any || t == f || (cos_s > THRESHOLD_COS && cos_t > THRESHOLD_COS)
+                    })
+                    .collect();
+                members.sort_unstable();
+                let os = match subgroups.iter().find(|(m, _)| *m == members) {
+                    Some((_, os)) => *os,
+                    None => {
+                        let os = self.eval_tspace(&members, group.vertex);
+                        subgroups.push((members, os));
+                        os
+                    }
+                };
+                tspaces[f * 3 + i] = TSpace {
+                    os,
+                    orient_preserving: group.orient_preserving,
+                };
+            }
+        }
+        tspaces
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mp generate_tangents
+    /// Create a new [Vertices] with MikkTSpace tangents generated from
+    /// the triangles of the primitives (which must use this
+    /// [Vertices]), replacing any existing tangents, and the primitives
+    /// to use with it
+    ///
+    /// Where vertices must be split, the new vertices are added after
+    /// the original ones, and the primitives use a new index buffer, in
+    /// which triangle strips and fans become triangle lists; otherwise
+    /// the indices (if any) are copied and the primitives are
+    /// unchanged. Vertices that are not used by any triangle are given
+    /// a tangent of `[1., 0., 0., 1.]`. Any [crate::Meshlets] are not
+    /// kept.
+    ///
+    /// An error is returned if the [Vertices] has no normals or texture
+    /// coordinates, if the attributes or indices cannot be read, or if
+    /// an index is beyond the vertices of the position accessor
+    pub fn generate_tangents<'b>(
+        &self,
+        primitives: &[Primitive],
+        layout: VertexLayout,
+    ) -> Result<(Vertices<'b, R>, Vec<Primitive>), AccessorError> {
+        let triangulated = self.triangulate(primitives)?;
+        let n = triangulated.num_vertices;
+        let attr = |attr| {
+            self.borrow_attr(attr)
+                .ok_or(AccessorError::MissingAttr(attr))
+        };
+        let positions: Vec<[f32; 3]> = self.borrow_position().iter_elements(n)?.collect();
+        let normals: Vec<[f32; 3]> = attr(VertexAttr::Normal)?.iter_elements(n)?.collect();
+        let tex_coords: Vec<[f32; 2]> = attr(VertexAttr::TexCoords0)?.iter_elements(n)?.collect();
+
+        // Vertices with the same position, normal and texture
+        // coordinates are the same vertex (with -0.0 equal to 0.0)
+        let mut shared: HashMap<Vec<u32>, u32> = HashMap::new();
+        let shared_vertex: Vec<u32> = (0..n)
+            .map(|v| {
+                let key = positions[v]
+                    .iter()
+                    .chain(normals[v].iter())
+                    .chain(tex_coords[v].iter())
+                    .map(|c| (c + 0.).to_bits())
+                    .collect();
+                *shared.entry(key).or_insert(v as u32)
+            })
+            .collect();
+
+        // Degenerate triangles take the tangent space of the first
+        // corner of a good triangle that uses the same vertex
+        let triangles: Vec<[u32; 3]> = triangulated
+            .triangles
+            .iter()
+            .map(|t| t.map(|v| shared_vertex[v as usize]))
+            .collect();
+        let is_good = |t: &[u32; 3]| t[0] != t[1] && t[1] != t[2] && t[2] != t[0];
+        let good: Vec<[u32; 3]> = triangles.iter().copied().filter(is_good).collect();
+        let mut mikk = MikkTSpace::new(&positions, &normals, &tex_coords, good);
+        mikk.build_neighbors();
+        mikk.build_groups();
+        let tspaces = mikk.generate_tspaces();
+
+        let mut first_corner = HashMap::new();
+        for (c, v) in mikk.triangles.iter().flatten().enumerate() {
+            first_corner.entry(*v).or_insert(c);
+        }
+        let mut good_corners = tspaces.iter();
+        let mut corner_tangents = Vec::with_capacity(triangles.len() * 3);
+        for t in &triangles {
+            for v in t {
+                let tspace = if is_good(t) {
+                    *good_corners.next().unwrap()
+                } else {
+                    first_corner
+                        .get(v)
+                        .map_or_else(TSpace::default, |c| tspaces[*c])
+                };
+                let sign = if tspace.orient_preserving { 1. } else { -1. };
+                let [x, y, z] = tspace.os;
+                corner_tangents.push([x, y, z, sign]);
+            }
+        }
+        self.split_by_corners(
+            primitives,
+            triangulated,
+            VertexAttr::Tangent,
+            &corner_tangents,
+            [1., 0., 0., 1.],
+            layout,
+        )
+    }
+
+    //zz All done
+}
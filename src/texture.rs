@@ -1,7 +1,8 @@
 //a Imports
-use std::cell::{Ref, RefCell};
+use std::sync::RwLockReadGuard;
 
-use crate::{BufferElementType, Renderable};
+use crate::shared::ClientCell;
+use crate::{BufferElementType, ByteData, Element, Renderable};
 
 //a Texture
 //tp Texture
@@ -18,8 +19,8 @@ use crate::{BufferElementType, Renderable};
 /// this must be easily Cloned, particuarly if the texture is used in
 /// more than one instantiable object
 pub struct Texture<'texture, R: Renderable + ?Sized> {
-    /// The underlying data for the texture
-    pub data: &'texture [u8],
+    /// The underlying data for the texture, borrowed or owned
+    pub data: ByteData<'texture>,
     /// Width, height, and depth of the texture - width must be
     /// non-zero
     ///
@@ -33,10 +34,10 @@ pub struct Texture<'texture, R: Renderable + ?Sized> {
     pub elements_per_data: u32,
     /// The type of each element
     ///
-    /// For most image textures this is Int8
+    /// For most image textures this is UInt8
     pub ele_type: BufferElementType,
     /// Client handle/value
-    rc_client: RefCell<R::Texture>,
+    rc_client: ClientCell<R::Texture>,
 }
 
 //ip Debug for Texture
@@ -76,6 +77,18 @@ impl<'texture, R: Renderable> Texture<'texture, R> {
         ele_type: BufferElementType,
         elements_per_data: u32,
     ) -> Self {
+        Self::of_bytes(data, dims, ele_type, elements_per_data)
+    }
+
+    //cp of_bytes
+    /// Create a new [Texture] object given borrowed or owned bytes
+    pub fn of_bytes<D: Into<ByteData<'texture>>>(
+        data: D,
+        dims: (usize, usize, usize),
+        ele_type: BufferElementType,
+        elements_per_data: u32,
+    ) -> Self {
+        let data = data.into();
         let rc_client = Default::default();
         Self {
             data,
@@ -86,6 +99,26 @@ impl<'texture, R: Renderable> Texture<'texture, R> {
         }
     }
 
+    //cp of_floats
+    /// Create a new [Texture] object that owns its data, from [f32]
+    /// components converted to the element type
+    ///
+    /// Integer element types are normalized (so 0.0 to 1.0 maps to 0
+    /// to 255 for UInt8); [BufferElementType::Float16] is rounded to
+    /// nearest
+    pub fn of_floats(
+        values: &[f32],
+        dims: (usize, usize, usize),
+        ele_type: BufferElementType,
+        elements_per_data: u32,
+    ) -> Self {
+        let mut bytes = Vec::with_capacity(values.len() * ele_type.byte_length());
+        for v in values {
+            v.write_bytes(ele_type, true, &mut bytes);
+        }
+        Self::of_bytes(bytes, dims, ele_type, elements_per_data)
+    }
+
     //ap dims
     /// Get the dimensions of the texture
     pub fn dims(&self) -> &(usize, usize, usize) {
@@ -95,7 +128,17 @@ impl<'texture, R: Renderable> Texture<'texture, R> {
     //ap data
     /// Get the data slice for the texture
     pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    //ap to_floats
+    /// Get all the components of the texture as [f32], with integer
+    /// element types normalized
+    pub fn to_floats(&self) -> Vec<f32> {
         self.data
+            .chunks_exact(self.ele_type.byte_length())
+            .map(|b| f32::of_bytes(self.ele_type, b, true))
+            .collect()
     }
 
     //ap data_type
@@ -112,7 +155,7 @@ impl<'texture, R: Renderable> Texture<'texture, R> {
 
     //ap borrow_client
     /// Borrow the client
-    pub fn borrow_client(&self) -> Ref<R::Texture> {
+    pub fn borrow_client(&self) -> RwLockReadGuard<'_, R::Texture> {
         self.rc_client.borrow()
     }
 }
@@ -70,8 +70,8 @@ pub trait Renderable: Sized {
     /// to be an extraction of the aspects of a material that the
     /// renderer pipelines can apply.
     type Material: MaterialClient;
-    /// The renderer's type that reflects a [BufferAccessor] of indices
-    /// and the associated [BufferAccessor]s of attributes supported by a
+    /// The renderer's type that reflects an optional [BufferAccessor]
+    /// of indices and the associated [BufferAccessor]s of attributes supported by a
     /// particular pipeline within the renderer
     type Vertices: VerticesClient;
     // type Instantiable : ;
@@ -88,7 +88,30 @@ pub trait Renderable: Sized {
         buffer_view: &BufferAccessor<Self>,
         attr: VertexAttr,
     );
-    /// Create a client
+    /// Initialize a buffer view client for the deltas of an attribute
+    /// of a morph target (by index) of a [Vertices]
+    ///
+    /// By default this initializes it as for any other buffer view
+    fn init_morph_target_client(
+        &mut self,
+        client: &mut Self::Accessor,
+        buffer_view: &BufferAccessor<Self>,
+        _target: usize,
+        attr: VertexAttr,
+    ) {
+        self.init_buffer_view_client(client, buffer_view, attr);
+    }
+    /// Create a client for a [Vertices]; the [BufferAccessor] clients
+    /// of its indices (if any), position, attributes and morph targets
+    /// will have been initialized
+    ///
+    /// A [Vertices] may have no indices, in which case the
+    /// [crate::Primitive]s that use it describe a vertex range (see
+    /// [crate::Primitive::vertex_range]) and should be drawn without
+    /// indices
+    ///
+    /// Any [crate::Meshlets] of the [Vertices] (see
+    /// [Vertices::iter_meshlets]) may be uploaded here too
     fn create_vertices_client(&mut self, vertices: &Vertices<Self>) -> Self::Vertices;
     /// Create a client
     fn create_texture_client(&mut self, texture: &Texture<Self>) -> Self::Texture;
@@ -1,8 +1,33 @@
 //a Imports
 use geo_nd::{matrix, quat, vector};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{Mat4, Quat, Vec3};
 
+//a Matrix functions
+//fp mat4_transform_point
+/// Apply a [Mat4] (column-major, with the translation in elements 12
+/// to 14) to a point
+pub(crate) fn mat4_transform_point(m: &Mat4, point: &Vec3) -> Vec3 {
+    let mut result = [m[12], m[13], m[14]];
+    for (i, r) in result.iter_mut().enumerate() {
+        *r += m[i] * point[0] + m[4 + i] * point[1] + m[8 + i] * point[2];
+    }
+    result
+}
+
+//fp mat4_compose
+/// Get the [Mat4] that applies `child` and then `parent` - such as the
+/// world matrix of a node in a hierarchy from that of its parent and
+/// its own transformation
+///
+/// The `geo_nd` matrix product is of row-major matrices; for the
+/// column-major [Mat4]s here `multiply4(a, b)` applies `a` first
+pub(crate) fn mat4_compose(parent: &Mat4, child: &Mat4) -> Mat4 {
+    matrix::multiply4(child, parent)
+}
+
 //a Transformation
 //tp Transformation
 /// A transformation corresponds to a translation of a rotation of a
@@ -10,6 +35,7 @@ use crate::{Mat4, Quat, Vec3};
 ///
 /// The rotation here is encoded by a [Quat]ernion
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transformation {
     /// Translation to apply after rotation
     translation: Vec3,
@@ -187,6 +213,13 @@ impl Transformation {
         self.rotation = quat::of_rotation(&rotation);
     }
 
+    //mp transform_point
+    /// Apply the transformation to a point - scaling, then rotation,
+    /// then translation
+    pub fn transform_point(&self, point: &Vec3) -> Vec3 {
+        mat4_transform_point(&self.mat4(), point)
+    }
+
     //mp mat4_after
     /// Calculate a Mat4 of this transformation premultiplied by another Mat4
     pub fn mat4_after(&self, pre_mat: &Mat4) -> Mat4 {
@@ -27,17 +27,24 @@ pub type Quat = [f32; 4];
 //tp BufferElementType
 /// The type of an element in a buffer
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BufferElementType {
     /// 32-bit floating point
     Float32,
     /// 16-bit floating point
     Float16,
-    /// 8-bit integers
+    /// 8-bit signed integers
     Int8,
-    /// 16-bit integers
+    /// 16-bit signed integers
     Int16,
-    /// 32-bit integers
+    /// 32-bit signed integers
     Int32,
+    /// 8-bit unsigned integers
+    UInt8,
+    /// 16-bit unsigned integers
+    UInt16,
+    /// 32-bit unsigned integers
+    UInt32,
 }
 
 //ip BufferElementType
@@ -48,9 +55,31 @@ impl BufferElementType {
         match self {
             Float32 => 4,
             Float16 => 2,
-            Int8 => 1,
-            Int16 => 2,
-            Int32 => 4,
+            Int8 | UInt8 => 1,
+            Int16 | UInt16 => 2,
+            Int32 | UInt32 => 4,
+        }
+    }
+
+    /// Return true if the element type is an integer type (signed or
+    /// unsigned)
+    pub fn is_integer(self) -> bool {
+        !matches!(self, Self::Float32 | Self::Float16)
+    }
+
+    /// Return true if the element type is an unsigned integer type
+    /// (such as is required for indices)
+    pub fn is_unsigned(self) -> bool {
+        matches!(self, Self::UInt8 | Self::UInt16 | Self::UInt32)
+    }
+
+    /// Get the index type to use for indices of a number of vertices -
+    /// [Self::UInt16] if it is large enough, else [Self::UInt32]
+    pub fn for_indices(num_vertices: usize) -> Self {
+        if num_vertices <= 1 << 16 {
+            Self::UInt16
+        } else {
+            Self::UInt32
         }
     }
 }
@@ -89,6 +118,7 @@ pub enum VertexAttr {
 ///
 /// This is set to match the GLTF
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u8)]
 pub enum PrimitiveType {
     /// Points (of an indeterminate size?)
@@ -108,9 +138,25 @@ pub enum PrimitiveType {
     TriangleFan,
 }
 
+//ip PrimitiveType
+impl PrimitiveType {
+    /// Get the list type that the primitive type can be converted to -
+    /// [Self::Lines] for line strips and loops, [Self::Triangles] for
+    /// triangle strips and fans, and otherwise itself
+    pub fn list_type(self) -> Self {
+        use PrimitiveType::*;
+        match self {
+            LineLoop | LineStrip => Lines,
+            TriangleStrip | TriangleFan => Triangles,
+            _ => self,
+        }
+    }
+}
+
 //tp MaterialAspect
 /// The aspect of a material
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MaterialAspect {
     /// Color (notionally RGBA as 4xf32)
     Color,
@@ -213,3 +259,25 @@ impl<I: Into<usize>> From<Option<I>> for ShortIndex {
         }
     }
 }
+
+//ip Serialize for ShortIndex
+/// A [ShortIndex] is serialized as an optional integer
+#[cfg(feature = "serde")]
+impl Serialize for ShortIndex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Option::<usize>::from(*self).serialize(serializer)
+    }
+}
+
+//ip Deserialize for ShortIndex
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ShortIndex {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Option::<u16>::deserialize(deserializer)? {
+            Some(65535) => Err(serde::de::Error::custom(
+                "short index must be less than 65535",
+            )),
+            opt_index => Ok(Self(opt_index.unwrap_or(65535))),
+        }
+    }
+}
@@ -1,7 +1,8 @@
 //a Imports
-use std::cell::{Ref, RefCell};
+use std::sync::RwLockReadGuard;
 
-use crate::BufferAccessor;
+use crate::shared::ClientCell;
+use crate::{AccessorError, BufferAccessor, Meshlets, MorphTarget, Primitive, Shared};
 use crate::{Renderable, VertexAttr};
 
 //a Vertices
@@ -24,12 +25,25 @@ use crate::{Renderable, VertexAttr};
 /// primitives; rendering the instance with a shader will require
 /// enabling the [Vertices] client for that shader, setting
 /// appropriate render options (uniforms in OpenGL)
+///
+/// The [BufferAccessor]s may be borrowed, or shared through `Arc`s
+///
+/// The indices are optional; [Vertices] without indices are for
+/// non-indexed drawing (such as of point clouds, line lists or
+/// triangle soups), where each primitive using them draws a range of
+/// the vertices
+///
+/// A [Vertices] may also have a number of [MorphTarget]s, with the
+/// displacements of some of the attributes of each vertex, and the
+/// [Meshlets] of some of the primitives that use it
 #[derive(Debug)]
 pub struct Vertices<'vertices, R: Renderable + ?Sized> {
-    indices: &'vertices BufferAccessor<'vertices, R>,
-    position: &'vertices BufferAccessor<'vertices, R>,
-    rc_client: RefCell<R::Vertices>,
-    attrs: Vec<(VertexAttr, &'vertices BufferAccessor<'vertices, R>)>,
+    indices: Option<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+    position: Shared<'vertices, BufferAccessor<'vertices, R>>,
+    rc_client: ClientCell<R::Vertices>,
+    attrs: Vec<(VertexAttr, Shared<'vertices, BufferAccessor<'vertices, R>>)>,
+    targets: Vec<MorphTarget<'vertices, R>>,
+    meshlets: Vec<Meshlets>,
 }
 
 //ip Display for Vertices
@@ -44,6 +58,11 @@ where
         for (n, a) in &self.attrs {
             writeln!(fmt, "  {n:?}: {a:?}")?;
         }
+        for (i, t) in self.targets.iter().enumerate() {
+            for (n, a) in t.iter_deltas() {
+                writeln!(fmt, "  target {i} {n:?}: {a:?}")?;
+            }
+        }
         Ok(())
     }
 }
@@ -52,17 +71,46 @@ where
 impl<'vertices, R: Renderable> Vertices<'vertices, R> {
     //fp new
     /// Create a new [Vertices] object with no additional attributes
-    pub fn new(
-        indices: &'vertices BufferAccessor<'vertices, R>,
-        position: &'vertices BufferAccessor<'vertices, R>,
-    ) -> Self {
+    pub fn new<A, B>(indices: A, position: B) -> Self
+    where
+        A: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+        B: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+    {
+        Self::of_indices(Some(indices.into()), position)
+    }
+
+    //fp non_indexed
+    /// Create a new [Vertices] object with no indices and no
+    /// additional attributes, for non-indexed drawing
+    pub fn non_indexed<B>(position: B) -> Self
+    where
+        B: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+    {
+        Self::of_indices(None, position)
+    }
+
+    //fp of_indices
+    /// Create a new [Vertices] object with optional indices and no
+    /// additional attributes
+    pub fn of_indices<B>(
+        indices: Option<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+        position: B,
+    ) -> Self
+    where
+        B: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+    {
+        let position = position.into();
         let attrs = Vec::new();
-        let rc_client = RefCell::new(R::Vertices::default());
+        let targets = Vec::new();
+        let meshlets = Vec::new();
+        let rc_client = ClientCell::default();
         Self {
             indices,
             position,
             rc_client,
             attrs,
+            targets,
+            meshlets,
         }
     }
 
@@ -72,24 +120,70 @@ impl<'vertices, R: Renderable> Vertices<'vertices, R> {
     /// On creation the [Vertices] will have views for indices and
     /// positions; this provides a means to add views for things such
     /// as normal, tex coords, etc
-    pub fn add_attr(
+    pub fn add_attr<A: Into<Shared<'vertices, BufferAccessor<'vertices, R>>>>(
         &mut self,
         attr: VertexAttr,
-        accessor: &'vertices BufferAccessor<'vertices, R>,
+        accessor: A,
     ) {
-        self.attrs.push((attr, accessor));
+        self.attrs.push((attr, accessor.into()));
+    }
+
+    //mp add_morph_target
+    /// Add a [MorphTarget], returning its index
+    pub fn add_morph_target(&mut self, target: MorphTarget<'vertices, R>) -> usize {
+        self.targets.push(target);
+        self.targets.len() - 1
+    }
+
+    //mp add_meshlets
+    /// Add the [Meshlets] of a primitive that uses this [Vertices],
+    /// returning their index
+    ///
+    /// Meshlets are kept by `repack`, `quantize` and `dequantize`, which
+    /// keep the vertices and indices; they are not kept by the other
+    /// ways of creating a [Vertices] from this one (such as `reindexed`,
+    /// `weld` or `optimize`)
+    pub fn add_meshlets(&mut self, meshlets: Meshlets) -> usize {
+        self.meshlets.push(meshlets);
+        self.meshlets.len() - 1
+    }
+
+    //mp reindexed
+    /// Create a new [Vertices] that shares the position, attribute and
+    /// morph target [BufferAccessor]s of this one, but with different
+    /// (optional) indices
+    pub fn reindexed(
+        &self,
+        indices: Option<Shared<'vertices, BufferAccessor<'vertices, R>>>,
+    ) -> Self {
+        let mut vertices = Self::of_indices(indices, self.position.clone());
+        vertices.attrs = self.attrs.clone();
+        vertices.targets = self.targets.clone();
+        vertices
+    }
+
+    //ap is_indexed
+    /// Return true if the [Vertices] has indices
+    pub fn is_indexed(&self) -> bool {
+        self.indices.is_some()
     }
 
     //mp borrow_indices
-    /// Borrow the indices [BufferAccessor]
-    pub fn borrow_indices<'a>(&'a self) -> &'a BufferAccessor<'vertices, R> {
-        self.indices
+    /// Borrow the indices [BufferAccessor], if the [Vertices] has them
+    pub fn borrow_indices<'a>(&'a self) -> Option<&'a BufferAccessor<'vertices, R>> {
+        self.indices.as_deref()
+    }
+
+    //mp shared_indices
+    /// Get the (shared) indices [BufferAccessor], if the [Vertices] has them
+    pub fn shared_indices(&self) -> Option<Shared<'vertices, BufferAccessor<'vertices, R>>> {
+        self.indices.clone()
     }
 
     //mp borrow_position
     /// Borrow the position [BufferAccessor]
     pub fn borrow_position<'a>(&'a self) -> &'a BufferAccessor<'vertices, R> {
-        self.position
+        &self.position
     }
 
     //mp borrow_attr
@@ -97,7 +191,7 @@ impl<'vertices, R: Renderable> Vertices<'vertices, R> {
     pub fn borrow_attr<'a>(&'a self, attr: VertexAttr) -> Option<&'a BufferAccessor<'vertices, R>> {
         for i in 0..self.attrs.len() {
             if self.attrs[i].0 == attr {
-                return Some(self.attrs[i].1);
+                return Some(&self.attrs[i].1);
             }
         }
         None
@@ -105,24 +199,94 @@ impl<'vertices, R: Renderable> Vertices<'vertices, R> {
 
     //mp iter_attrs
     /// Iterate through attributes
-    pub fn iter_attrs(&self) -> std::slice::Iter<(VertexAttr, &BufferAccessor<'vertices, R>)> {
-        self.attrs.iter()
+    pub fn iter_attrs(
+        &self,
+    ) -> impl Iterator<Item = (VertexAttr, &BufferAccessor<'vertices, R>)> + '_ {
+        self.attrs
+            .iter()
+            .map(|(attr, accessor)| (*attr, &**accessor))
+    }
+
+    //ap num_morph_targets
+    /// Get the number of [MorphTarget]s
+    pub fn num_morph_targets(&self) -> usize {
+        self.targets.len()
+    }
+
+    //mp borrow_morph_target
+    /// Borrow a [MorphTarget] if the [Vertices] has it
+    pub fn borrow_morph_target(&self, n: usize) -> Option<&MorphTarget<'vertices, R>> {
+        self.targets.get(n)
+    }
+
+    //mp iter_morph_targets
+    /// Iterate through the [MorphTarget]s
+    pub fn iter_morph_targets(&self) -> impl Iterator<Item = &MorphTarget<'vertices, R>> + '_ {
+        self.targets.iter()
+    }
+
+    //mp borrow_meshlets
+    /// Borrow some [Meshlets] (by index) if the [Vertices] has them
+    pub fn borrow_meshlets(&self, n: usize) -> Option<&Meshlets> {
+        self.meshlets.get(n)
+    }
+
+    //mp iter_meshlets
+    /// Iterate through the [Meshlets]
+    pub fn iter_meshlets(&self) -> impl Iterator<Item = &Meshlets> + '_ {
+        self.meshlets.iter()
+    }
+
+    //mp primitive_indices
+    /// Get the indices of the vertices drawn by a [Primitive] that uses
+    /// this [Vertices] - the range of its indices, or for a vertex range
+    /// the vertices themselves
+    ///
+    /// An error is returned if the range is not within the indices
+    pub fn primitive_indices(&self, primitive: &Primitive) -> Result<Vec<u32>, AccessorError> {
+        if let Some((first, count)) = primitive.vertex_range() {
+            return Ok((first..first + count).collect());
+        }
+        let size = self
+            .indices
+            .as_ref()
+            .map_or(1, |i| i.ele_type.byte_length());
+        let first = primitive.byte_offset() as usize / size;
+        let count = primitive.index_count() as usize;
+        let available = self.indices.as_ref().map_or(0, |i| i.elements_per_data) as usize;
+        match &self.indices {
+            Some(indices) if first + count <= available => {
+                Ok(indices.iter_indices()?.skip(first).take(count).collect())
+            }
+            _ => Err(AccessorError::OutOfRange(
+                (first + count).saturating_sub(1),
+                (first + count) * size,
+                available * size,
+            )),
+        }
     }
 
     //mp create_client
     /// Create the render buffer required by the BufferAccessor
     pub fn create_client(&self, renderer: &mut R) {
-        self.indices.create_client(VertexAttr::Indices, renderer);
+        if let Some(indices) = &self.indices {
+            indices.create_client(VertexAttr::Indices, renderer);
+        }
         self.position.create_client(VertexAttr::Position, renderer);
         for (attr, view) in self.iter_attrs() {
-            view.create_client(*attr, renderer);
+            view.create_client(attr, renderer);
+        }
+        for (i, target) in self.targets.iter().enumerate() {
+            for (attr, view) in target.iter_deltas() {
+                view.create_morph_target_client(i, attr, renderer);
+            }
         }
         *(self.rc_client.borrow_mut()) = renderer.create_vertices_client(self);
     }
 
     //ap borrow_client
     /// Borrow the client
-    pub fn borrow_client(&self) -> Ref<R::Vertices> {
+    pub fn borrow_client(&self) -> RwLockReadGuard<'_, R::Vertices> {
         self.rc_client.borrow()
     }
 
@@ -0,0 +1,363 @@
+//a Documentation
+/*!
+
+This provides welding of the vertices of a [Vertices] - merging
+vertices that are equal in all of their attributes, and rewriting the
+indices to use the merged vertices. Meshes imported from STL files, or
+exported with flat shading, often repeat each vertex for every face
+that uses it; welding removes the duplicates.
+
+By default vertices are merged only if the bytes of all of their
+attributes are identical. A tolerance may be given for any attribute,
+in which case two vertices are merged if every component of each such
+attribute differs by no more than its tolerance, and the other
+attributes are equal in value; each welded vertex takes the attributes
+of the first of the vertices merged into it. The deltas of any morph
+targets are compared in the same way as the attributes (with the
+tolerance of their attribute), so that vertices which morph differently
+are not merged.
+
+The welded [Vertices] own a new vertex buffer (with a given
+[VertexLayout]) and a new index buffer. If the [Vertices] has indices
+then these are rewritten, keeping their element type and order, so that
+the [Primitive]s that use them are unchanged; otherwise it is given
+indices of each of its original vertices in order, and a vertex range
+[Primitive] must become an indexed one (see
+[Welded::reindex_primitive]).
+
+!*/
+
+//a Imports
+use std::collections::HashMap;
+
+use crate::{
+    AccessorError, BufferAccessor, BufferElementType, Primitive, Renderable, Shared, VertexAttr,
+    VertexLayout, Vertices,
+};
+
+//a Welding
+//tp Welding
+/// The tolerances for welding the vertices of a [Vertices]
+///
+/// With no tolerances the welding is exact, merging only vertices
+/// whose attributes are bitwise identical
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Welding {
+    /// The largest difference in any component of each attribute for
+    /// which vertices may be merged; attributes without a tolerance
+    /// must be equal
+    pub tolerances: Vec<(VertexAttr, f32)>,
+    /// The layout of the welded vertex buffer
+    pub layout: VertexLayout,
+}
+
+//ip Welding
+impl Welding {
+    //fp new
+    /// Create a new [Welding] that merges only identical vertices
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //cp with_tolerance
+    /// Set the tolerance for an attribute
+    pub fn with_tolerance(mut self, attr: VertexAttr, tolerance: f32) -> Self {
+        self.tolerances.retain(|(a, _)| *a != attr);
+        self.tolerances.push((attr, tolerance));
+        self
+    }
+
+    //cp with_layout
+    /// Set the layout of the welded vertex buffer
+    pub fn with_layout(mut self, layout: VertexLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    //ap tolerance
+    /// Get the tolerance for an attribute, if it has one
+    pub fn tolerance(&self, attr: VertexAttr) -> Option<f32> {
+        self.tolerances
+            .iter()
+            .find(|(a, _)| *a == attr)
+            .map(|(_, t)| *t)
+    }
+
+    //ap is_exact
+    /// Return true if only bitwise identical vertices are merged
+    pub fn is_exact(&self) -> bool {
+        self.tolerances.is_empty()
+    }
+}
+
+//a Welded
+//tp Welded
+/// The result of welding a [Vertices]
+#[derive(Debug)]
+pub struct Welded<'b, R: Renderable> {
+    /// The welded vertices
+    pub vertices: Vertices<'b, R>,
+    /// The welded vertex that each original vertex was merged into
+    pub remap: Vec<u32>,
+}
+
+//ip Welded
+impl<'b, R: Renderable> Welded<'b, R> {
+    //ap original_count
+    /// The number of vertices before welding
+    pub fn original_count(&self) -> usize {
+        self.remap.len()
+    }
+
+    //ap welded_count
+    /// The number of vertices after welding
+    pub fn welded_count(&self) -> usize {
+        self.vertices.borrow_position().count as usize
+    }
+
+    //ap reduction_ratio
+    /// The ratio of the number of vertices before welding to the
+    /// number after (1.0 if nothing was merged)
+    pub fn reduction_ratio(&self) -> f32 {
+        if self.welded_count() == 0 {
+            1.
+        } else {
+            self.original_count() as f32 / self.welded_count() as f32
+        }
+    }
+
+    //mp reindex_primitive
+    /// Get the [Primitive] to use with the welded vertices in place of
+    /// one that used the original vertices
+    ///
+    /// Indexed primitives are unchanged; a vertex range becomes the
+    /// same range of the new indices
+    pub fn reindex_primitive(&self, primitive: &Primitive) -> Primitive {
+        let Some((first, count)) = primitive.vertex_range() else {
+            return primitive.clone();
+        };
+        let index_size = self
+            .vertices
+            .borrow_indices()
+            .map_or(4, |i| i.ele_type.byte_length()) as u32;
+        Primitive::new(
+            primitive.primitive_type(),
+            primitive.vertices_index(),
+            first * index_size,
+            count,
+            primitive.material_index(),
+        )
+    }
+}
+
+//a Merging
+//fi cell
+/// The cell of the spatial hash containing a position; for a zero
+/// tolerance this is the position itself (with -0.0 equal to 0.0)
+fn cell(p: &[f32], tolerance: f32) -> [i64; 3] {
+    if tolerance > 0. {
+        [0, 1, 2].map(|i| (p[i] / tolerance).floor() as i64)
+    } else {
+        [0, 1, 2].map(|i| (p[i] + 0.).to_bits() as i64)
+    }
+}
+
+//fi within
+/// Return true if every component of two attribute values differs by
+/// no more than the tolerance
+fn within(a: &[f32], b: &[f32], tolerance: f32) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .all(|(a, b)| (a - b).abs() <= tolerance)
+}
+
+//tp AttrValues
+/// The values of the first `n` elements of an attribute as [f32], with
+/// the tolerance for comparing them
+struct AttrValues {
+    components: usize,
+    tolerance: f32,
+    values: Vec<f32>,
+}
+
+//ip AttrValues
+impl AttrValues {
+    //fp new
+    /// Read the values of an attribute
+    fn new<R: Renderable>(
+        accessor: &BufferAccessor<R>,
+        n: usize,
+        tolerance: f32,
+    ) -> Result<Self, AccessorError> {
+        let components = (accessor.elements_per_data as usize).min(4);
+        let values = accessor
+            .iter_elements::<f32, 4>(n)?
+            .flat_map(|e| e.into_iter().take(components))
+            .collect();
+        Ok(Self {
+            components,
+            tolerance,
+            values,
+        })
+    }
+
+    //mp get
+    /// Get the value of element `n`
+    fn get(&self, n: usize) -> &[f32] {
+        &self.values[n * self.components..(n + 1) * self.components]
+    }
+
+    //mp matches
+    /// Return true if elements `a` and `b` are within the tolerance
+    fn matches(&self, a: usize, b: usize) -> bool {
+        within(self.get(a), self.get(b), self.tolerance)
+    }
+}
+
+//a Vertices
+//ip Vertices
+impl<'vertices, R: Renderable> Vertices<'vertices, R> {
+    //mi accessors
+    /// Get the position and attribute accessors, followed by the deltas
+    /// of each morph target, with their attributes
+    fn accessors(&self) -> Vec<(VertexAttr, &BufferAccessor<'vertices, R>)> {
+        std::iter::once((VertexAttr::Position, self.borrow_position()))
+            .chain(self.iter_attrs())
+            .chain(self.iter_morph_targets().flat_map(|t| t.iter_deltas()))
+            .collect()
+    }
+
+    //mi weld_exact
+    /// Determine the welded vertex for each of the first `n` vertices,
+    /// merging bitwise identical vertices; the result is the welded
+    /// vertex of each vertex, and the first vertex of each welded vertex
+    fn weld_exact(&self, n: usize) -> Result<(Vec<u32>, Vec<u32>), AccessorError> {
+        let accessors = self.accessors();
+        for (_, a) in &accessors {
+            a.check_range(a.elements_per_data as usize, a.element_stride(), n)?;
+        }
+        let mut welded: HashMap<Vec<u8>, u32> = HashMap::new();
+        let mut remap = Vec::with_capacity(n);
+        let mut order = vec![];
+        for v in 0..n {
+            let key: Vec<u8> = accessors
+                .iter()
+                .flat_map(|(_, a)| a.element_bytes(v).iter().copied())
+                .collect();
+            let w = *welded.entry(key).or_insert_with(|| {
+                order.push(v as u32);
+                (order.len() - 1) as u32
+            });
+            remap.push(w);
+        }
+        Ok((remap, order))
+    }
+
+    //mi weld_within
+    /// Determine the welded vertex for each of the first `n` vertices,
+    /// merging vertices whose attributes are within the tolerances of
+    /// the first vertex of a welded vertex
+    ///
+    /// The candidates are found by hashing the positions into cells the
+    /// size of the position tolerance
+    fn weld_within(
+        &self,
+        welding: &Welding,
+        n: usize,
+    ) -> Result<(Vec<u32>, Vec<u32>), AccessorError> {
+        let mut attrs = vec![];
+        for (attr, a) in self.accessors() {
+            let tolerance = welding.tolerance(attr).unwrap_or(0.);
+            attrs.push(AttrValues::new(a, n, tolerance)?);
+        }
+        let position = &attrs[0];
+        let tolerance = position.tolerance;
+        let offsets: &[i64] = if tolerance > 0. { &[-1, 0, 1] } else { &[0] };
+
+        let mut cells: HashMap<[i64; 3], Vec<u32>> = HashMap::new();
+        let mut remap = Vec::with_capacity(n);
+        let mut order: Vec<u32> = vec![];
+        for v in 0..n {
+            let c = cell(position.get(v), tolerance);
+            let mut found = None;
+            'search: for dx in offsets {
+                for dy in offsets {
+                    for dz in offsets {
+                        // Cells of huge or infinite positions saturate
+                        let neighbour = [
+                            c[0].saturating_add(*dx),
+                            c[1].saturating_add(*dy),
+                            c[2].saturating_add(*dz),
+                        ];
+                        let Some(welded) = cells.get(&neighbour) else {
+                            continue;
+                        };
+                        for w in welded {
+                            let first = order[*w as usize] as usize;
+                            if attrs.iter().all(|a| a.matches(first, v)) {
+                                found = Some(*w);
+                                break 'search;
+                            }
+                        }
+                    }
+                }
+            }
+            let w = found.unwrap_or_else(|| {
+                order.push(v as u32);
+                let w = (order.len() - 1) as u32;
+                cells.entry(c).or_default().push(w);
+                w
+            });
+            remap.push(w);
+        }
+        Ok((remap, order))
+    }
+
+    //mp weld
+    /// Create a new [Vertices] in which vertices that are equal (or,
+    /// with tolerances, close) in all of their attributes are merged
+    ///
+    /// The welding is exact if the [Welding] has no tolerances; with
+    /// tolerances, a welded vertex may absorb a vertex that is within
+    /// the tolerances of the first vertex merged into it, so the result
+    /// depends on the order of the vertices.
+    ///
+    /// As the vertices are renumbered, the new [Vertices] has none of
+    /// the [crate::Meshlets] of this one
+    ///
+    /// An error is returned if the attributes cannot be read, or if an
+    /// index is beyond the vertices of the position accessor
+    pub fn weld<'b>(&self, welding: &Welding) -> Result<Welded<'b, R>, AccessorError> {
+        let n = self.borrow_position().count as usize;
+        let (remap, order) = {
+            if welding.is_exact() {
+                self.weld_exact(n)?
+            } else {
+                self.weld_within(welding, n)?
+            }
+        };
+
+        let indices = match self.borrow_indices() {
+            Some(indices) => {
+                let welded = indices
+                    .iter_indices()?
+                    .map(|i| {
+                        remap
+                            .get(i as usize)
+                            .copied()
+                            .ok_or(AccessorError::BadIndex(i, n))
+                    })
+                    .collect::<Result<Vec<u32>, _>>()?;
+                BufferAccessor::of_indices(&welded, indices.ele_type)
+            }
+            None => {
+                let ele_type = BufferElementType::for_indices(order.len());
+                BufferAccessor::of_indices(&remap, ele_type)
+            }
+        };
+        let vertices = self.repack_vertices(welding.layout, &order, Some(Shared::new(indices)))?;
+        Ok(Welded { vertices, remap })
+    }
+
+    //zz All done
+}
@@ -0,0 +1,171 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, BaseMaterial, BoundingBox, BoundingSphere, Bounds, Mesh, MeshBuilder,
+    MeshVertex, Object, Primitive, PrimitiveType, Transformation, Vertices,
+};
+
+//fi build
+/// Build a mesh with a triangle in the unit square and a line from
+/// the origin to (4, 2, 1)
+fn build() -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for p in [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [4., 2., 1.]] {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    builder.push_triangles(&[0, 1, 2], 0.into());
+    builder.push_lines(&[0, 3], 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi assert_near
+fn assert_near(a: [f32; 3], b: [f32; 3]) {
+    assert!((0..3).all(|i| (a[i] - b[i]).abs() < 1e-5), "{a:?} {b:?}");
+}
+
+//ft test_bounds_volumes
+#[test]
+fn test_bounds_volumes() {
+    let points = [[1., 2., 3.], [-1., 0., 5.], [0., 4., 4.]];
+    let bbox = BoundingBox::of_points(&points).unwrap();
+    assert_eq!(bbox, BoundingBox::new([-1., 0., 3.], [1., 4., 5.]));
+    assert_eq!(bbox.center(), [0., 2., 4.]);
+    assert_eq!(bbox.size(), [2., 4., 2.]);
+    assert!(points.iter().all(|p| bbox.contains(p)));
+    assert!(!bbox.contains(&[0., 0., 0.]));
+    assert_eq!(BoundingBox::of_points(&[]), None);
+
+    let other = BoundingBox::new([0., -1., 0.], [2., 1., 1.]);
+    assert_eq!(
+        bbox.union(&other),
+        BoundingBox::new([-1., -1., 0.], [2., 4., 5.])
+    );
+
+    let bounds = Bounds::of_points(&points).unwrap();
+    assert_eq!(bounds.sphere.center, [0., 2., 4.]);
+    assert!(points.iter().all(|p| bounds.sphere.contains(p)));
+
+    // A sphere within another, and two apart
+    let a = BoundingSphere::new([0., 0., 0.], 2.);
+    let b = BoundingSphere::new([1., 0., 0.], 0.5);
+    assert_eq!(a.union(&b), a);
+    assert_eq!(b.union(&a), a);
+    let c = BoundingSphere::new([5., 0., 0.], 1.);
+    let u = a.union(&c);
+    assert_near(u.center, [2., 0., 0.]);
+    assert_eq!(u.radius, 4.);
+
+    // Translate and scale
+    let m = Transformation::new()
+        .with_scale([2., 1., 3.])
+        .with_translation([1., 1., 1.])
+        .mat4();
+    let t = bounds.transformed(&m);
+    assert_eq!(t.bbox, BoundingBox::new([-1., 1., 10.], [3., 5., 16.]));
+    assert_near(t.sphere.center, [1., 3., 13.]);
+    assert_eq!(t.sphere.radius, bounds.sphere.radius * 3.);
+}
+
+//ft test_bounds_vertices
+#[test]
+fn test_bounds_vertices() {
+    let (v, primitives) = build();
+    let all = v.bounds().unwrap().unwrap();
+    assert_eq!(all.bbox, BoundingBox::new([0., 0., 0.], [4., 2., 1.]));
+
+    let triangle = v.primitive_bounds(&primitives[0]).unwrap().unwrap();
+    assert_eq!(triangle.bbox, BoundingBox::new([0., 0., 0.], [1., 1., 0.]));
+    assert_near(triangle.sphere.center, [0.5, 0.5, 0.]);
+    assert!((triangle.sphere.radius - 0.5_f32.sqrt()).abs() < 1e-6);
+
+    let line = v.primitive_bounds(&primitives[1]).unwrap().unwrap();
+    assert_eq!(line.bbox, all.bbox);
+
+    let range = Primitive::of_vertex_range(PrimitiveType::Points, 0.into(), 1, 2, 0.into());
+    let range = v.primitive_bounds(&range).unwrap().unwrap();
+    assert_eq!(range.bbox, BoundingBox::new([0., 0., 0.], [1., 1., 0.]));
+
+    // Indices beyond the vertices are errors
+    let bad = Primitive::of_vertex_range(PrimitiveType::Points, 0.into(), 3, 3, 0.into());
+    assert_eq!(
+        v.primitive_bounds(&bad).err(),
+        Some(AccessorError::BadIndex(4, 4))
+    );
+}
+
+//ft test_bounds_object
+#[test]
+fn test_bounds_object() {
+    let (v, primitives) = build();
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    let v_id = obj.add_vertices(&v);
+    let m_id = obj.add_material(&material);
+    assert_eq!(v_id, 0.into());
+    assert_eq!(m_id, 0.into());
+
+    // The triangle alone at the root, moved by 10 in x; and a child
+    // of that with the line scaled by 2
+    let mut triangle = Mesh::default();
+    triangle.add_primitive(primitives[0].clone());
+    let mut line = Mesh::default();
+    line.add_primitive(primitives[1].clone());
+    let root = obj.add_component(
+        None,
+        Some(Transformation::new().with_translation([10., 0., 0.])),
+        triangle,
+    );
+    obj.add_component(
+        Some(root),
+        Some(Transformation::new().with_scale([2., 2., 2.])),
+        line,
+    );
+    obj.analyze();
+
+    let bounds = obj.bounds().unwrap().unwrap();
+    assert_eq!(bounds.bbox, BoundingBox::new([10., 0., 0.], [18., 4., 2.]));
+    assert!(bounds.sphere.contains(&[18., 4., 2.]));
+    assert!(bounds.sphere.contains(&[10., 1., 0.]));
+
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .unwrap();
+    assert_eq!(inst.bounds, Some(bounds));
+
+    let mut instance = inst.instantiate();
+    instance.transformation = Transformation::new().with_translation([0., 0., -5.]);
+    let world = instance.world_bounds().unwrap();
+    assert_eq!(world.bbox, BoundingBox::new([10., 0., -5.], [18., 4., -3.]));
+    assert_eq!(world.sphere.radius, bounds.sphere.radius);
+}
+
+//ft test_bounds_unreadable
+#[test]
+fn test_bounds_unreadable() {
+    // An object whose bounds cannot be found still instantiates,
+    // without bounds
+    let (v, _) = build();
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&v);
+    obj.add_material(&material);
+    let mut mesh = Mesh::default();
+    mesh.add_primitive(Primitive::of_vertex_range(
+        PrimitiveType::Points,
+        0.into(),
+        3,
+        3,
+        0.into(),
+    ));
+    obj.add_component(None, None, mesh);
+    obj.analyze();
+    assert_eq!(obj.bounds().err(), Some(AccessorError::BadIndex(4, 4)));
+
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .unwrap();
+    assert_eq!(inst.bounds, None);
+    assert!(inst.instantiate().world_bounds().is_none());
+}
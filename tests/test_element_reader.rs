@@ -0,0 +1,181 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{AccessorError, BufferAccessor, BufferData, BufferElementType};
+
+//fi bytes_of_f32
+/// Get the little-endian bytes of some floats
+fn bytes_of_f32(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+//ft test_packed_and_interleaved
+#[test]
+fn test_packed_and_interleaved() {
+    // Three vertices of position (3 floats) and UV (2 floats)
+    let bytes = bytes_of_f32(&[
+        0., 1., 2., 0.5, 0.25, //
+        3., 4., 5., 0.75, 1.0, //
+        6., 7., 8., 0.0, 0.5,
+    ]);
+    let data = BufferData::<Renderable>::new(&bytes, 0, bytes.len() as u32);
+    let position = BufferAccessor::new(&data, 3, BufferElementType::Float32, 0, 20);
+    let uv = BufferAccessor::new(&data, 2, BufferElementType::Float32, 12, 20);
+    assert_eq!(position.element_size(), 12);
+    assert_eq!(position.element_stride(), 20);
+
+    let p: Vec<[f32; 3]> = position.iter_elements(3).unwrap().collect();
+    assert_eq!(p, vec![[0., 1., 2.], [3., 4., 5.], [6., 7., 8.]]);
+    let t: Vec<[f32; 2]> = uv.iter_elements(3).unwrap().collect();
+    assert_eq!(t, vec![[0.5, 0.25], [0.75, 1.0], [0.0, 0.5]]);
+
+    // Extra components are zero, missing ones are dropped
+    assert_eq!(position.read_element::<f32, 4>(1), Ok([3., 4., 5., 0.]));
+    assert_eq!(position.read_element::<f32, 2>(2), Ok([6., 7.]));
+
+    // The same data read tightly packed as 5-float elements
+    let packed = BufferAccessor::new(&data, 5, BufferElementType::Float32, 0, 0);
+    assert_eq!(packed.element_stride(), 20);
+    let mut elements = packed.iter_elements::<f32, 5>(3).unwrap();
+    assert_eq!(elements.len(), 3);
+    assert_eq!(elements.nth(2), Some([6., 7., 8., 0.0, 0.5]));
+    assert_eq!(elements.next(), None);
+
+    assert_eq!(
+        position.iter_elements::<f32, 3>(4).err(),
+        Some(AccessorError::OutOfRange(3, 72, 60))
+    );
+    assert!(uv.read_element::<f32, 2>(3).is_err());
+}
+
+//ft test_float16
+#[test]
+fn test_float16() {
+    let halves: [u16; 8] = [
+        0x3c00, // 1.0
+        0xc000, // -2.0
+        0x3555, // 0.333251953125
+        0x0001, // smallest subnormal
+        0x7bff, // 65504.0
+        0x7c00, // +inf
+        0x8000, // -0.0
+        0x0000,
+    ];
+    let bytes: Vec<u8> = halves.iter().flat_map(|h| h.to_le_bytes()).collect();
+    let data = BufferData::<Renderable>::new(&bytes, 0, bytes.len() as u32);
+    let accessor = BufferAccessor::new(&data, 4, BufferElementType::Float16, 0, 0);
+    let e: Vec<[f32; 4]> = accessor.iter_elements(2).unwrap().collect();
+    assert_eq!(e[0], [1.0, -2.0, 0.333_251_95, 2.0_f32.powi(-24)]);
+    assert_eq!(e[1][0], 65504.0);
+    assert_eq!(e[1][1], f32::INFINITY);
+    assert_eq!(e[1][2].to_bits(), 0x8000_0000);
+    assert_eq!(
+        accessor.iter_elements::<u32, 4>(2).err(),
+        Some(AccessorError::Unsupported(
+            BufferElementType::Float16,
+            "u32"
+        ))
+    );
+}
+
+//ft test_integers
+#[test]
+fn test_integers() {
+    let bytes: Vec<u8> = vec![0, 255, 51, 128, 0xff, 0xff, 0x00, 0x80];
+    let data = BufferData::<Renderable>::new(&bytes, 0, bytes.len() as u32);
+
+    let colors = BufferAccessor::new(&data, 4, BufferElementType::UInt8, 0, 0);
+    assert_eq!(
+        colors.read_element::<f32, 4>(0),
+        Ok([0.0, 255.0, 51.0, 128.0])
+    );
+    assert_eq!(colors.read_element::<u16, 4>(1), Ok([255, 255, 0, 128]));
+    let colors = colors.with_normalized(true);
+    assert_eq!(
+        colors.read_element::<f32, 4>(0),
+        Ok([0.0, 1.0, 0.2, 128.0 / 255.0])
+    );
+
+    let shorts = BufferAccessor::new(&data, 2, BufferElementType::UInt16, 4, 0);
+    assert_eq!(shorts.read_element::<u32, 2>(0), Ok([65535, 32768]));
+    let shorts = shorts.with_normalized(true);
+    assert_eq!(
+        shorts.read_element::<f32, 2>(0),
+        Ok([1.0, 32768.0 / 65535.0])
+    );
+
+    let signed = BufferAccessor::new(&data, 2, BufferElementType::Int16, 4, 0);
+    assert_eq!(signed.read_element::<i32, 2>(0), Ok([-1, -32768]));
+    assert_eq!(signed.read_element::<f32, 2>(0), Ok([-1.0, -32768.0]));
+    let signed = signed.with_normalized(true);
+    assert_eq!(signed.read_element::<f32, 2>(0), Ok([-1.0 / 32767.0, -1.0]));
+    assert_eq!(
+        signed.read_element::<u32, 2>(0),
+        Err(AccessorError::Unsupported(BufferElementType::Int16, "u32"))
+    );
+
+    let ints = BufferAccessor::new(&data, 1, BufferElementType::UInt32, 4, 0);
+    assert_eq!(ints.read_element::<u32, 1>(0), Ok([0x8000_ffff]));
+    assert_eq!(
+        ints.read_element::<u16, 1>(0),
+        Err(AccessorError::Unsupported(BufferElementType::UInt32, "u16"))
+    );
+}
+
+//ft test_validate
+#[test]
+fn test_validate() {
+    let bytes = bytes_of_f32(&[0., 1., 2., 3., 4., 5., 6., 7., 8., 9.]);
+    let data = BufferData::<Renderable>::new(&bytes, 0, bytes.len() as u32);
+
+    // Three elements of three floats fit (with one spare float)
+    let position = BufferAccessor::new(&data, 3, BufferElementType::Float32, 0, 0);
+    assert_eq!(position.count, 3);
+    assert_eq!(position.validate(), Ok(()));
+    let strided = BufferAccessor::new(&data, 3, BufferElementType::Float32, 4, 16);
+    assert_eq!(strided.count, 2);
+    assert_eq!(
+        strided.iter_all::<f32, 3>().unwrap().last(),
+        Some([5., 6., 7.])
+    );
+
+    let position = position.with_count(4);
+    assert_eq!(
+        position.validate(),
+        Err(AccessorError::OutOfRange(3, 48, 40))
+    );
+    let position = position
+        .with_count(2)
+        .with_bounds(vec![0., 1., 2.], vec![3., 4., 5.]);
+    assert_eq!(position.validate(), Ok(()));
+    assert_eq!(position.iter_all::<f32, 3>().unwrap().len(), 2);
+    let position = position.with_bounds(vec![0.], vec![3.]);
+    assert_eq!(position.validate(), Err(AccessorError::BadBounds(1, 3)));
+    let position = position.with_normalized(true);
+    assert_eq!(
+        position.validate(),
+        Err(AccessorError::NotNormalizable(BufferElementType::Float32))
+    );
+}
+
+//ft test_indices
+#[test]
+fn test_indices() {
+    let indices: [u16; 6] = [0, 1, 2, 2, 1, 300];
+    let bytes: Vec<u8> = indices.iter().flat_map(|i| i.to_le_bytes()).collect();
+    let data = BufferData::<Renderable>::new(&bytes, 0, bytes.len() as u32);
+
+    let accessor = BufferAccessor::new(&data, 6, BufferElementType::UInt16, 0, 0);
+    let i: Vec<u32> = accessor.iter_indices().unwrap().collect();
+    assert_eq!(i, vec![0, 1, 2, 2, 1, 300]);
+    assert_eq!(accessor.read_index(5), Ok(300));
+    assert!(accessor.read_index(6).is_err());
+
+    let offset = BufferAccessor::new(&data, 4, BufferElementType::UInt16, 4, 0);
+    let i: Vec<u32> = offset.iter_indices().unwrap().collect();
+    assert_eq!(i, vec![2, 2, 1, 300]);
+
+    let too_many = BufferAccessor::new(&data, 7, BufferElementType::UInt16, 0, 0);
+    assert_eq!(
+        too_many.iter_indices().err(),
+        Some(AccessorError::OutOfRange(6, 14, 12))
+    );
+}
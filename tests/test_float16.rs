@@ -0,0 +1,123 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    f16_bytes_to_f32s, f16_to_f32, f32_to_f16, f32s_to_f16_bytes, AccessorError, BufferAccessor,
+    BufferElementType, Texture,
+};
+
+//ft test_round_trip
+#[test]
+fn test_round_trip() {
+    for h in 0..=u16::MAX {
+        let f = f16_to_f32(h);
+        if f.is_nan() {
+            assert!(f16_to_f32(f32_to_f16(f)).is_nan(), "{h:04x}");
+        } else {
+            assert_eq!(f32_to_f16(f), h, "{h:04x} {f}");
+        }
+    }
+}
+
+//ft test_conversions
+#[test]
+fn test_conversions() {
+    assert_eq!(f32_to_f16(1.0), 0x3c00);
+    assert_eq!(f32_to_f16(-2.0), 0xc000);
+    assert_eq!(f32_to_f16(-0.0), 0x8000);
+    assert_eq!(f32_to_f16(65504.), 0x7bff);
+    assert_eq!(f16_to_f32(0x7bff), 65504.);
+
+    // Round to nearest, ties to even
+    let ulp = 2.0_f32.powi(-10);
+    assert_eq!(f32_to_f16(1. + ulp * 0.5), 0x3c00);
+    assert_eq!(f32_to_f16(1. + ulp * 0.51), 0x3c01);
+    assert_eq!(f32_to_f16(1. + ulp * 1.5), 0x3c02);
+    assert_eq!(f32_to_f16(65519.), 0x7bff);
+
+    // Overflow to infinity
+    assert_eq!(f32_to_f16(65520.), 0x7c00);
+    assert_eq!(f32_to_f16(f32::INFINITY), 0x7c00);
+    assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+    assert_eq!(f32_to_f16(1e10), 0x7c00);
+    assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+
+    // Subnormals
+    let min = 2.0_f32.powi(-24);
+    assert_eq!(f32_to_f16(min), 0x0001);
+    assert_eq!(f16_to_f32(0x0001), min);
+    assert_eq!(f32_to_f16(min * 0.5), 0x0000);
+    assert_eq!(f32_to_f16(min * 0.51), 0x0001);
+    assert_eq!(f32_to_f16(min * 1.5), 0x0002);
+    assert_eq!(f32_to_f16(-min * 3.), 0x8003);
+    assert_eq!(f32_to_f16(2.0_f32.powi(-14)), 0x0400);
+    assert_eq!(f32_to_f16(2.0_f32.powi(-14) - min * 0.5), 0x0400);
+    assert_eq!(f32_to_f16(1e-10), 0x0000);
+
+    // NaN
+    assert!(f16_to_f32(0x7e00).is_nan());
+    assert_eq!(f32_to_f16(f32::NAN) & 0x7e00, 0x7e00);
+
+    let values = [0.5, -1.25, 1024., 0.1];
+    let bytes = f32s_to_f16_bytes(&values);
+    assert_eq!(bytes.len(), 8);
+    let back = f16_bytes_to_f32s(&bytes);
+    assert_eq!(&back[0..3], &values[0..3]);
+    assert!((back[3] - 0.1).abs() < 0.1 / 1024.);
+}
+
+//ft test_accessor
+#[test]
+fn test_accessor() {
+    let weights = [[0.25_f32, 0.75, 0., 0.], [1. / 3., 1. / 3., 1. / 3., 0.]];
+    let accessor =
+        BufferAccessor::<Renderable>::of_elements(&weights, BufferElementType::Float16, false)
+            .unwrap();
+    assert_eq!(accessor.data.as_slice().len(), 16);
+    assert_eq!(accessor.count, 2);
+    assert_eq!(accessor.validate(), Ok(()));
+    assert_eq!(accessor.read_element::<f32, 4>(0), Ok(weights[0]));
+    let third = accessor.read_element::<f32, 4>(1).unwrap();
+    assert!((third[0] - 1. / 3.).abs() < 1. / 2048.);
+
+    let accessor =
+        BufferAccessor::<Renderable>::of_elements(&weights, BufferElementType::Int16, true)
+            .unwrap();
+    assert_eq!(accessor.read_element::<i32, 2>(0), Ok([8192, 24575]));
+    assert_eq!(
+        accessor.read_element::<f32, 2>(1).unwrap()[0],
+        10922. / 32767.
+    );
+
+    let clamped =
+        BufferAccessor::<Renderable>::of_elements(&[[-3_i32, 300]], BufferElementType::Int8, false)
+            .unwrap();
+    assert_eq!(clamped.read_element::<i32, 2>(0), Ok([-3, 127]));
+
+    assert_eq!(
+        BufferAccessor::<Renderable>::of_elements(&[[1_u32]], BufferElementType::Float32, false)
+            .err(),
+        Some(AccessorError::Unsupported(
+            BufferElementType::Float32,
+            "u32"
+        ))
+    );
+    assert_eq!(
+        BufferAccessor::<Renderable>::of_elements(&[[1_f32]], BufferElementType::Float16, true)
+            .err(),
+        Some(AccessorError::NotNormalizable(BufferElementType::Float16))
+    );
+}
+
+//ft test_texture
+#[test]
+fn test_texture() {
+    let pixels = [0.5_f32, 0.25, 1.0, 1.0, 100.0, -2.0, 0.0, 1.0];
+    let texture =
+        Texture::<Renderable>::of_floats(&pixels, (2, 0, 0), BufferElementType::Float16, 4);
+    assert!(texture.data.is_owned());
+    assert_eq!(texture.data().len(), 16);
+    assert_eq!(texture.to_floats(), pixels);
+
+    let texture = Texture::<Renderable>::of_floats(&pixels, (2, 0, 0), BufferElementType::UInt8, 4);
+    assert_eq!(texture.data(), &[128, 64, 255, 255, 255, 0, 0, 255]);
+    assert_eq!(texture.to_floats()[0], 128. / 255.);
+}
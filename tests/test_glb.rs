@@ -0,0 +1,106 @@
+#![cfg(feature = "gltf")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::gltf::{Error, Glb, GltfAsset, ObjectExporter, GLB_MAGIC};
+use mod3d_base::{BufferElementType, Material, PrimitiveType, Transformation, VertexAttr};
+
+/// Export a tetrahedron (translated) as GLB
+fn tetrahedron_glb() -> Vec<u8> {
+    let mut tetrahedron = mod3d_base::ExampleVertices::new();
+    mod3d_base::example_objects::tetrahedron::new::<Renderable>(&mut tetrahedron, 1.0);
+
+    let material = mod3d_base::BaseMaterial::of_rgba(0xff0000ff);
+    let mut obj: mod3d_base::Object<mod3d_base::BaseMaterial, Renderable> =
+        mod3d_base::Object::new();
+    let v_id = obj.add_vertices(tetrahedron.borrow_vertices(0.into()));
+    let m_id = obj.add_material(&material);
+    let mesh = mod3d_base::example_objects::tetrahedron::mesh(v_id, m_id);
+    let transformation = Transformation::new().with_translation([1., 2., 3.]);
+    obj.add_component(None, Some(transformation), mesh);
+    obj.analyze();
+
+    let mut exporter = ObjectExporter::new();
+    assert_eq!(exporter.add_object(&obj).expect("Failed to export"), 0);
+    exporter.into_glb().expect("Failed to create GLB")
+}
+
+#[test]
+fn test_round_trip() {
+    let glb = tetrahedron_glb();
+    assert_eq!(u32::from_le_bytes(glb[0..4].try_into().unwrap()), GLB_MAGIC);
+    assert_eq!(glb.len() % 4, 0);
+
+    let asset = GltfAsset::from_glb(glb, None).expect("Failed to load GLB");
+    assert_eq!(asset.gltf().buffers.len(), 1);
+    assert_eq!(asset.gltf().accessors[0].min.len(), 3, "POSITION has min");
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let mut object = asset.make_object(&vertices);
+    object.analyze();
+
+    assert_eq!(data.len(), 2, "Indices and shared vertex data");
+    assert_eq!(vertices.len(), 1);
+    let v = object.vertices(0.into());
+    let indices = v.borrow_indices().unwrap();
+    assert_eq!(indices.elements_per_data, 6);
+    assert_eq!(indices.ele_type, BufferElementType::UInt8);
+    let position = v.borrow_position();
+    assert_eq!(position.elements_per_data, 3);
+    assert_eq!(position.stride, 24);
+    assert_eq!(position.byte_offset, 0);
+    let normal = v
+        .borrow_attr(VertexAttr::Normal)
+        .expect("Normals should survive");
+    assert_eq!(normal.stride, 24);
+    assert_eq!(normal.byte_offset, 12);
+    assert!(position.data.ptr_eq(&normal.data), "Shared data");
+
+    assert_eq!(object.components.len(), 1);
+    let mut t = object.components.borrow_node(0).transformation.unwrap();
+    assert_eq!(t.translation(), [1., 2., 3.]);
+    let mesh = &object.components.borrow_node(0).mesh;
+    assert_eq!(mesh.primitives.len(), 1);
+    let p = &mesh.primitives[0];
+    assert_eq!(p.primitive_type(), PrimitiveType::TriangleStrip);
+    assert_eq!(p.byte_offset(), 0);
+    assert_eq!(p.index_count(), 6);
+    let m = object.material(p.material_index());
+    assert_eq!(m.base_data().rgba_tuple(), (255, 0, 0, 255));
+}
+
+#[test]
+fn test_glb_errors() {
+    let glb = tetrahedron_glb();
+    assert!(Glb::from_bytes(glb.clone()).unwrap().has_bin());
+
+    let mut bad_magic = glb.clone();
+    bad_magic[0] = b'G';
+    assert!(matches!(
+        Glb::from_bytes(bad_magic),
+        Err(Error::Malformed(_))
+    ));
+
+    let mut bad_version = glb.clone();
+    bad_version[4] = 1;
+    assert!(matches!(
+        Glb::from_bytes(bad_version),
+        Err(Error::Unsupported(_))
+    ));
+
+    let truncated = glb[0..glb.len() - 4].to_vec();
+    assert!(matches!(
+        Glb::from_bytes(truncated),
+        Err(Error::BadRange(_))
+    ));
+
+    let json_only = Glb::write("{\"asset\": {\"version\": \"2.0\"}}", &[]);
+    let glb = Glb::from_bytes(json_only).unwrap();
+    assert!(!glb.has_bin());
+    assert!(glb.bin().is_empty());
+    assert!(GltfAsset::from_glb(glb_bytes_of(&glb), None).is_ok());
+}
+
+/// Rebuild the bytes of a JSON-only GLB
+fn glb_bytes_of(glb: &Glb) -> Vec<u8> {
+    Glb::write(glb.json_str().unwrap(), glb.bin())
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "gltf")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::gltf::{Error, GltfAsset};
+use mod3d_base::{BufferElementType, Material, MaterialAspect, PrimitiveType, VertexAttr};
+
+/// A triangle: three u16 indices (padded to 8 bytes) then three VEC3 positions
+const TRIANGLE_DATA: &str =
+    "data:application/octet-stream;base64,AAABAAIAAAAAAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAAAAAACAPwAAAAA=";
+
+fn triangle_json(accessors: &str, views: &str) -> String {
+    format!(
+        r#"{{
+  "asset": {{"version": "2.0"}},
+  "scene": 0,
+  "scenes": [{{"nodes": [0]}}],
+  "nodes": [
+    {{"children": [1, 2], "translation": [1.0, 2.0, 3.0]}},
+    {{"mesh": 0, "skin": 0}},
+    {{"children": [3], "translation": [0.0, 1.0, 0.0]}},
+    {{"translation": [0.0, 0.5, 0.0]}}
+  ],
+  "skins": [{{"joints": [2, 3]}}],
+  "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 1}}, "indices": 0, "material": 0}}]}}],
+  "materials": [{{
+     "pbrMetallicRoughness": {{"baseColorFactor": [1.0, 0.0, 0.0, 1.0], "baseColorTexture": {{"index": 0}}}},
+     "emissiveFactor": [0.0, 1.0, 0.0]
+  }}],
+  "textures": [{{"source": 0}}],
+  "images": [{{"uri": "image.png"}}],
+  "accessors": {accessors},
+  "bufferViews": {views},
+  "buffers": [{{"byteLength": 44, "uri": "{TRIANGLE_DATA}"}}]
+}}"#
+    )
+}
+
+const ACCESSORS: &str = r#"[
+    {"bufferView": 0, "componentType": 5123, "count": 3, "type": "SCALAR"},
+    {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3",
+     "min": [0, 0, 0], "max": [1, 1, 0]}
+  ]"#;
+
+const VIEWS: &str = r#"[
+    {"buffer": 0, "byteOffset": 0, "byteLength": 6},
+    {"buffer": 0, "byteOffset": 8, "byteLength": 36}
+  ]"#;
+
+#[test]
+fn test_triangle() {
+    let json = triangle_json(ACCESSORS, VIEWS);
+    let asset = GltfAsset::from_json(&json, None).expect("Failed to load glTF");
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let mut object = asset.make_object(&vertices);
+    object.analyze();
+
+    assert_eq!(data.len(), 2, "One buffer data per buffer view");
+    assert_eq!(vertices.len(), 1);
+    let v = object.vertices(0.into());
+    let indices = v.borrow_indices().unwrap();
+    assert_eq!(indices.elements_per_data, 3, "Three indices");
+    assert_eq!(indices.ele_type, BufferElementType::UInt16);
+    let position = v.borrow_position();
+    assert_eq!(position.elements_per_data, 3);
+    assert_eq!(position.ele_type, BufferElementType::Float32);
+    assert_eq!(position.data.byte_offset, 8);
+    assert_eq!(position.count, 3);
+    assert!(!position.normalized);
+    assert_eq!(position.min, Some(vec![0., 0., 0.]));
+    assert_eq!(position.max, Some(vec![1., 1., 0.]));
+    assert_eq!(position.validate(), Ok(()));
+    assert!(v.borrow_attr(VertexAttr::Normal).is_none());
+
+    assert_eq!(object.components.len(), 4, "One component per node");
+    assert_eq!(object.components.borrow_roots(), &[0]);
+    let mesh = &object.components.borrow_node(1).mesh;
+    assert_eq!(mesh.primitives.len(), 1);
+    let p = &mesh.primitives[0];
+    assert_eq!(p.primitive_type(), PrimitiveType::Triangles);
+    assert_eq!(p.index_count(), 3);
+    assert_eq!(p.material_index().as_usize(), 0);
+    let mut t = object.components.borrow_node(0).transformation.unwrap();
+    assert_eq!(t.translation(), [1.0, 2.0, 3.0]);
+
+    let m = object.material(0.into());
+    assert_eq!(m.base_data().rgba_tuple(), (255, 0, 0, 255));
+    assert_eq!(m.base_data().emissive_rgb, 0xff00);
+    assert_eq!(m.texture(MaterialAspect::Color).as_usize(), 0);
+    assert!(m.texture(MaterialAspect::Normal).is_none());
+
+    let skeleton = object
+        .skeleton
+        .as_ref()
+        .expect("Skin should give a skeleton");
+    assert_eq!(skeleton.skeleton.len(), 2);
+    assert_eq!(skeleton.iter_roots().collect::<Vec<_>>(), vec![0]);
+    assert_eq!(skeleton.max_index, 2);
+
+    let inst = object
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .expect("Failed to make the object instantiable");
+    assert_eq!(inst.render_recipe.primitives.len(), 1);
+    assert_eq!(
+        inst.render_recipe.matrices[inst.render_recipe.matrix_for_primitives[0]][12..15],
+        [1.0, 2.0, 3.0]
+    );
+}
+
+#[test]
+fn test_errors() {
+    let bad_accessor = r#"[
+    {"bufferView": 0, "componentType": 5123, "count": 3, "type": "SCALAR"},
+    {"bufferView": 3, "componentType": 5126, "count": 3, "type": "VEC3"}
+  ]"#;
+    let json = triangle_json(bad_accessor, VIEWS);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::BadIndex("buffer view", 3))
+    ));
+
+    let short_view = r#"[
+    {"buffer": 0, "byteOffset": 0, "byteLength": 6},
+    {"buffer": 0, "byteOffset": 8, "byteLength": 24}
+  ]"#;
+    let json = triangle_json(ACCESSORS, short_view);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::BadRange(_))
+    ));
+
+    let outside_buffer = r#"[
+    {"buffer": 0, "byteOffset": 0, "byteLength": 6},
+    {"buffer": 0, "byteOffset": 12, "byteLength": 36}
+  ]"#;
+    let json = triangle_json(ACCESSORS, outside_buffer);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::BadRange(_))
+    ));
+
+    // Ranges that overflow must be rejected, not wrap
+    let overflow_view = r#"[
+    {"buffer": 0, "byteOffset": 0, "byteLength": 6},
+    {"buffer": 0, "byteOffset": 18446744073709551615, "byteLength": 36}
+  ]"#;
+    let json = triangle_json(ACCESSORS, overflow_view);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::BadRange(_))
+    ));
+
+    let overflow_accessor = r#"[
+    {"bufferView": 0, "componentType": 5123, "count": 3, "type": "SCALAR"},
+    {"bufferView": 1, "componentType": 5126, "count": 1537228672809129302, "type": "VEC3"}
+  ]"#;
+    let json = triangle_json(overflow_accessor, VIEWS);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::BadRange(_))
+    ));
+
+    let float_indices = r#"[
+    {"bufferView": 1, "componentType": 5126, "count": 3, "type": "SCALAR"},
+    {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3",
+     "min": [0, 0, 0], "max": [1, 1, 0]}
+  ]"#;
+    let json = triangle_json(float_indices, VIEWS);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::Malformed(_))
+    ));
+
+    // A joint whose ancestors form a cycle outside the scene
+    let json = triangle_json(ACCESSORS, VIEWS)
+        .replace(
+            r#"{"translation": [0.0, 0.5, 0.0]}"#,
+            r#"{"translation": [0.0, 0.5, 0.0]}, {"children": [5]}, {"children": [4, 6]}, {}"#,
+        )
+        .replace(r#""joints": [2, 3]"#, r#""joints": [2, 3, 6]"#);
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::Malformed(_))
+    ));
+
+    let json = triangle_json(ACCESSORS, VIEWS).replace("\"2.0\"", "\"1.0\"");
+    assert!(matches!(
+        GltfAsset::from_json(&json, None),
+        Err(Error::Unsupported(_))
+    ));
+
+    assert!(matches!(
+        GltfAsset::from_json("{\"asset\": ", None),
+        Err(Error::Json(_))
+    ));
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "gltf")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::gltf::{base64_decode, base64_encode, GltfAsset, ObjectExporter};
+use mod3d_base::{
+    BufferElementType, Material, MaterialAspect, PbrMaterial, Primitive, PrimitiveType, Skeleton,
+    Texture, Transformation, VertexAttr,
+};
+
+/// Export a textured triangle skinned to a two-bone skeleton as glTF
+/// JSON with an embedded buffer
+fn skinned_triangle_json() -> String {
+    let mut eg = mod3d_base::ExampleVertices::<Renderable>::new();
+    let positions = [0.0_f32, 0., 0., 1., 0., 0., 0., 1., 0.];
+    let joints = [0_u8, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0];
+    let weights = [1.0_f32, 0., 0., 0., 1., 0., 0., 0., 1., 0., 0., 0.];
+    let data_indices = eg.push_byte_buffer(Box::new([0_u8, 1, 2]));
+    let data_positions = eg.push_byte_buffer(Box::new(positions));
+    let data_joints = eg.push_byte_buffer(Box::new(joints));
+    let data_weights = eg.push_byte_buffer(Box::new(weights));
+    let indices = eg.push_accessor(data_indices, 3, BufferElementType::UInt8, 0, 0);
+    let positions = eg.push_accessor(data_positions, 3, BufferElementType::Float32, 0, 0);
+    let joints = eg.push_accessor(data_joints, 4, BufferElementType::UInt8, 0, 0);
+    let weights = eg.push_accessor(data_weights, 4, BufferElementType::Float32, 0, 0);
+    eg.push_vertices(
+        indices,
+        positions,
+        &[(VertexAttr::Joints, joints), (VertexAttr::Weights, weights)],
+    );
+
+    let pixels = [
+        255_u8, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 0, 0, 0, 255,
+    ];
+    let texture = Texture::new(&pixels, (2, 2, 0), BufferElementType::UInt8, 4);
+    let mut material = PbrMaterial::of_rgba(0xff00ff00);
+    material.set_texture(MaterialAspect::Color, 0.into());
+
+    let mut skeleton = Skeleton::new();
+    let b0 = skeleton.add_bone(Transformation::new(), 0);
+    let b1 = skeleton.add_bone(Transformation::new().with_translation([0., 1., 0.]), 1);
+    skeleton.relate(b0, b1);
+
+    let mut obj: mod3d_base::Object<PbrMaterial, Renderable> = mod3d_base::Object::new();
+    let v_id = obj.add_vertices(eg.borrow_vertices(0.into()));
+    obj.add_texture(&texture);
+    let m_id = obj.add_material(&material);
+    let mesh = mod3d_base::example_objects::triangle::mesh(v_id, m_id);
+    let root = obj.add_component(None, Some(Transformation::new().with_scale([2.; 3])), mesh);
+    obj.add_component(Some(root), None, Default::default());
+    obj.skeleton = Some(skeleton);
+    obj.analyze();
+
+    let mut exporter = ObjectExporter::new();
+    exporter.add_object(&obj).expect("Failed to export");
+    let (json, _) = exporter.into_gltf(None).expect("Failed to create glTF");
+    json
+}
+
+#[test]
+fn test_base64() {
+    for n in 0..8 {
+        let bytes: Vec<u8> = (0..n).map(|i| (i * 97 + 13) as u8).collect();
+        let s = base64_encode(&bytes);
+        assert_eq!(s.len() % 4, 0);
+        assert_eq!(base64_decode(&s).unwrap(), bytes);
+    }
+    assert_eq!(base64_encode(b"glTF"), "Z2xURg==");
+}
+
+#[test]
+fn test_export() {
+    let json = skinned_triangle_json();
+    let asset = GltfAsset::from_json(&json, None).expect("Failed to load exported glTF");
+    let gltf = asset.gltf();
+
+    assert_eq!(
+        gltf.scenes[0].nodes,
+        vec![0, 2],
+        "Component root then bone root"
+    );
+    assert_eq!(gltf.nodes.len(), 4);
+    assert_eq!(gltf.nodes[0].children, vec![1]);
+    assert_eq!(gltf.nodes[0].scale, Some([2., 2., 2.]));
+    assert_eq!(gltf.nodes[0].skin, Some(0));
+    assert_eq!(gltf.nodes[2].children, vec![3]);
+    assert_eq!(gltf.nodes[3].translation, Some([0., 1., 0.]));
+
+    let primitive = &gltf.meshes[0].primitives[0];
+    assert_eq!(primitive.mode, 4, "Triangles");
+    for semantic in ["POSITION", "JOINTS_0", "WEIGHTS_0"] {
+        assert!(primitive.attributes.contains_key(semantic), "{semantic}");
+    }
+    let joints = &gltf.accessors[primitive.attributes["JOINTS_0"]];
+    assert_eq!(joints.component_type, 5121);
+    assert!(!joints.normalized);
+
+    let image = &gltf.images[0];
+    assert_eq!(image.mime_type.as_deref(), Some("image/png"));
+    assert!(image.buffer_view.is_some());
+    assert_eq!(gltf.textures[0].source, Some(0));
+
+    let skin = &gltf.skins[0];
+    assert_eq!(skin.joints, vec![2, 3]);
+    assert_eq!(skin.skeleton, Some(2));
+    let ibm = &gltf.accessors[skin.inverse_bind_matrices.unwrap()];
+    assert_eq!((ibm.type_.as_str(), ibm.count), ("MAT4", 2));
+
+    let m = &asset.materials()[0];
+    assert_eq!(m.base_data().rgba_tuple(), (0, 255, 0, 255));
+    assert_eq!(m.texture(MaterialAspect::Color).as_usize(), 0);
+    assert!(m.texture(MaterialAspect::Normal).is_none());
+
+    let skeleton = asset.skeleton().expect("Skin should give a skeleton");
+    assert_eq!(skeleton.skeleton.len(), 2);
+    assert_eq!(skeleton.iter_roots().collect::<Vec<_>>(), vec![0]);
+}
+
+#[test]
+fn test_export_layout() {
+    // Positions with a stride of 14 bytes, which glTF does not allow,
+    // and vertices with no indices at all
+    let mut eg = mod3d_base::ExampleVertices::<Renderable>::new();
+    let mut bytes = [0_u8; 40];
+    for (i, p) in [[0.0_f32, 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+        .iter()
+        .enumerate()
+    {
+        for (j, c) in p.iter().enumerate() {
+            bytes[i * 14 + j * 4..][..4].copy_from_slice(&c.to_le_bytes());
+        }
+    }
+    let data_indices = eg.push_byte_buffer(Box::new([0_u8, 1, 2]));
+    let data_positions = eg.push_byte_buffer(Box::new(bytes));
+    let indices = eg.push_accessor(data_indices, 3, BufferElementType::UInt8, 0, 0);
+    let no_indices = eg.push_accessor(data_indices, 0, BufferElementType::UInt8, 0, 0);
+    let positions = eg.push_accessor(data_positions, 3, BufferElementType::Float32, 0, 14);
+    eg.push_vertices(indices, positions, &[]);
+    eg.push_vertices(no_indices, positions, &[]);
+
+    let material = PbrMaterial::of_rgba(0xff0000ff);
+    let mut obj: mod3d_base::Object<PbrMaterial, Renderable> = mod3d_base::Object::new();
+    let v_id = obj.add_vertices(eg.borrow_vertices(0.into()));
+    let empty_id = obj.add_vertices(eg.borrow_vertices(1.into()));
+    let m_id = obj.add_material(&material);
+    let mesh = mod3d_base::example_objects::triangle::mesh(v_id, m_id);
+    let root = obj.add_component(None, None, mesh);
+    let mut empty = mod3d_base::Mesh::default();
+    empty.add_primitive(Primitive::new(
+        PrimitiveType::Triangles,
+        empty_id,
+        0,
+        0,
+        m_id,
+    ));
+    obj.add_component(Some(root), None, empty);
+    obj.analyze();
+
+    let mut exporter = ObjectExporter::new();
+    exporter.add_object(&obj).expect("Failed to export");
+    let (json, _) = exporter.into_gltf(None).expect("Failed to create glTF");
+    let asset = GltfAsset::from_json(&json, None).expect("Failed to load exported glTF");
+    let gltf = asset.gltf();
+    for view in &gltf.buffer_views {
+        if let Some(stride) = view.byte_stride {
+            assert!((4..=252).contains(&stride) && stride % 4 == 0, "{stride}");
+        }
+    }
+    assert!(gltf.accessors.iter().all(|a| a.count > 0));
+    assert_eq!(gltf.meshes.len(), 1, "The empty mesh is omitted");
+    assert_eq!(gltf.nodes[1].mesh, None);
+
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let positions: Vec<[f32; 3]> = vertices[0]
+        .borrow_position()
+        .iter_elements(3)
+        .unwrap()
+        .collect();
+    assert_eq!(positions, vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]);
+}
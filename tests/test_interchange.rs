@@ -0,0 +1,244 @@
+#![cfg(feature = "interchange")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::interchange::{off, ply, stl, Error, TriangleAsset, TriangleMesh};
+use mod3d_base::{
+    BufferAccessor, BufferData, BufferElementType, Primitive, PrimitiveType, VertexAttr, Vertices,
+};
+
+/// Positions and normals of the corners of a tetrahedron
+const POSITION_NORMALS: [f32; 24] = [
+    0., 0., 0., 0., 0., -1., //
+    1., 0., 0., 0., 0., -1., //
+    0., 1., 0., 0., 0., -1., //
+    0., 0., 1., 0., 0., 1., //
+];
+
+/// Colors of the corners of the tetrahedron
+const COLORS: [u8; 16] = [
+    255, 0, 0, 255, //
+    0, 255, 0, 255, //
+    0, 0, 255, 255, //
+    255, 255, 255, 0, //
+];
+
+/// Indices of the tetrahedron as a triangle strip, preceded by a
+/// single unused index
+const INDICES: [u8; 7] = [3, 0, 1, 2, 3, 0, 1];
+
+//fi tetrahedron
+/// Convert the tetrahedron (as a strip using the Vertices) into a
+/// TriangleMesh
+fn tetrahedron() -> TriangleMesh {
+    let index_data = BufferData::<Renderable>::new(&INDICES, 0, 0);
+    let vertex_data = BufferData::new(&POSITION_NORMALS, 0, 0);
+    let color_data = BufferData::new(&COLORS, 0, 0);
+    let indices = BufferAccessor::new(&index_data, 7, BufferElementType::UInt8, 0, 0);
+    let position = BufferAccessor::new(&vertex_data, 3, BufferElementType::Float32, 0, 24);
+    let normal = BufferAccessor::new(&vertex_data, 3, BufferElementType::Float32, 12, 24);
+    let color =
+        BufferAccessor::new(&color_data, 4, BufferElementType::UInt8, 0, 0).with_normalized(true);
+    let mut vertices = Vertices::new(&indices, &position);
+    vertices.add_attr(VertexAttr::Normal, &normal);
+    vertices.add_attr(VertexAttr::Color, &color);
+
+    let strip = Primitive::new(PrimitiveType::TriangleStrip, 0.into(), 1, 6, 0.into());
+    TriangleMesh::of_primitive(&vertices, &strip).expect("Failed to convert strip")
+}
+
+#[test]
+fn test_of_primitive() {
+    let mesh = tetrahedron();
+    assert_eq!(
+        mesh.triangles,
+        vec![[0, 1, 2], [2, 1, 3], [2, 3, 0], [0, 3, 1]],
+        "Strip expanded with alternate triangles reversed, vertices in order of use"
+    );
+    assert_eq!(mesh.positions[0], [0., 0., 0.]);
+    assert_eq!(mesh.positions[1], [1., 0., 0.]);
+    assert_eq!(mesh.positions[3], [0., 0., 1.]);
+    assert_eq!(mesh.normals.as_ref().unwrap()[3], [0., 0., 1.]);
+    let colors = mesh.colors.as_ref().unwrap();
+    assert_eq!(colors[1], [0., 1., 0., 1.]);
+    assert_eq!(colors[3], [1., 1., 1., 0.]);
+    assert_eq!(mesh.face_normal(0), [0., 0., 1.]);
+    assert!(mesh.validate().is_ok());
+}
+
+#[test]
+fn test_stl() {
+    let mesh = tetrahedron();
+    for bytes in [
+        stl::write_binary(&mesh).unwrap(),
+        stl::write_ascii(&mesh, "tetrahedron").unwrap().into_bytes(),
+    ] {
+        let read = stl::read(&bytes).expect("Failed to read STL");
+        assert_eq!(read.positions.len(), 12, "STL has three vertices per facet");
+        assert_eq!(read.triangles.len(), 4);
+        assert!(read.colors.is_none());
+        for (i, t) in read.triangles.iter().enumerate() {
+            let original = mesh.triangles[i];
+            for c in 0..3 {
+                assert_eq!(
+                    read.positions[t[c] as usize],
+                    mesh.positions[original[c] as usize]
+                );
+                assert_eq!(read.normals.as_ref().unwrap()[t[c] as usize], {
+                    mesh.face_normal(i)
+                });
+            }
+        }
+    }
+    assert_eq!(stl::write_binary(&mesh).unwrap().len(), 84 + 4 * 50);
+}
+
+#[test]
+fn test_ply() {
+    let mesh = tetrahedron();
+    let ascii = ply::write_ascii(&mesh).unwrap();
+    assert!(ascii.starts_with("ply\nformat ascii 1.0\n"));
+    for bytes in [ascii.into_bytes(), ply::write_binary(&mesh).unwrap()] {
+        let read = ply::read(&bytes).expect("Failed to read PLY");
+        assert_eq!(read, mesh, "Colors are exact as they were 8-bit");
+    }
+
+    let quad = r#"ply
+format ascii 1.0
+comment A quad with a face color
+element vertex 4
+property double x
+property double y
+property double z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_index
+property uchar red
+element edge 1
+property int vertex1
+property int vertex2
+end_header
+0 0 0 255 0 0
+1 0 0 255 0 0
+1 1 0 0 0 255
+0 1 0 0 0 255
+4 0 1 2 3 7
+0 1
+"#;
+    let read = ply::read(quad.as_bytes()).expect("Failed to read PLY");
+    assert_eq!(read.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+    assert!(read.normals.is_none());
+    assert_eq!(read.colors.as_ref().unwrap()[2], [0., 0., 1., 1.]);
+}
+
+#[test]
+fn test_off() {
+    let mesh = tetrahedron();
+    let text = off::write(&mesh).unwrap();
+    assert!(text.starts_with("CNOFF\n4 4 0\n"));
+    let read = off::read(text.as_bytes()).expect("Failed to read OFF");
+    assert_eq!(read, mesh);
+
+    let quad = "OFF 4 1 0\n# comment\n0 0 0\n1 0 0\n1 1 0\n0 1 0\n4 0 1 2 3\n";
+    let read = off::read(quad.as_bytes()).expect("Failed to read OFF");
+    assert_eq!(read.positions.len(), 4);
+    assert_eq!(read.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+
+    let colored = "COFF\n3 1 0\n0 0 0 255 0 0\n1 0 0 0 255 0\n0 1 0 0 0 255 128\n3 0 1 2\n";
+    let read = off::read(colored.as_bytes()).expect("Failed to read OFF");
+    let colors = read.colors.unwrap();
+    assert_eq!(colors[0], [1., 0., 0., 1.]);
+    assert_eq!(colors[2][3], 128. / 255.);
+}
+
+#[test]
+fn test_asset() {
+    let mesh = tetrahedron();
+    let asset = TriangleAsset::new(&mesh).unwrap();
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let mut object = asset.make_object(&vertices);
+    object.analyze();
+
+    assert_eq!(object.components.len(), 1);
+    let primitives = &object.components.borrow_node(0).mesh.primitives;
+    assert_eq!(primitives.len(), 1);
+    assert_eq!(primitives[0].primitive_type(), PrimitiveType::Triangles);
+    assert_eq!(primitives[0].index_count(), 12);
+
+    let v = object.vertices(0.into());
+    assert_eq!(
+        v.borrow_indices().unwrap().ele_type,
+        BufferElementType::UInt32
+    );
+    assert_eq!(v.borrow_position().stride, 40, "Position, normal and color");
+    assert_eq!(v.borrow_attr(VertexAttr::Color).unwrap().byte_offset, 24);
+
+    let copy = TriangleMesh::of_primitive(v, &primitives[0]).unwrap();
+    assert_eq!(copy, mesh);
+}
+
+#[test]
+fn test_errors() {
+    let mut mesh = tetrahedron();
+    mesh.triangles.push([0, 1, 4]);
+    assert!(matches!(mesh.validate(), Err(Error::BadIndex("vertex", 4))));
+    assert!(matches!(
+        TriangleAsset::new(&mesh),
+        Err(Error::BadIndex(..))
+    ));
+
+    assert!(matches!(
+        stl::read(b"solid x\n  facet normal 0 0 1\n    vertex 0 0\n"),
+        Err(Error::Syntax(_))
+    ));
+    assert!(matches!(
+        stl::read(&[&[0_u8; 80][..], &[1, 0, 0, 0]].concat()),
+        Err(Error::BadRange(_))
+    ));
+    assert!(matches!(
+        ply::read(b"ply\nformat binary_middle_endian 1.0\nend_header\n"),
+        Err(Error::Unsupported(_))
+    ));
+    assert!(matches!(
+        off::read(b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0 1 3\n"),
+        Err(Error::BadIndex("vertex", 3))
+    ));
+    assert!(matches!(
+        off::read(b"OFF\n3 1 0\n0 0 0\n"),
+        Err(Error::BadRange(_))
+    ));
+
+    // Counts and indices from the file must be non-negative integers,
+    // and are not trusted for preallocation
+    for bad in [
+        &b"NOFF\n1e30 0 0\n"[..],
+        b"OFF\n-1 0 0\n",
+        b"OFF\n3 1.5 0\n",
+        b"OFF\ninf 0 0\n",
+        b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 -1 1 2\n",
+        b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n3 0.5 1 2\n",
+    ] {
+        assert!(matches!(off::read(bad), Err(Error::BadRange(_))));
+    }
+    assert!(matches!(
+        off::read(b"OFF\n3 1 0\n0 0 0\n1 0 0\n0 1 0\n1e30 0 1 2\n"),
+        Err(Error::Syntax(_))
+    ));
+    let ply_header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1 0\n";
+    for face in ["3 -1 1 2\n", "3 0 1.5 2\n"] {
+        let ply = format!("{ply_header}{face}");
+        assert!(matches!(ply::read(ply.as_bytes()), Err(Error::BadRange(_))));
+    }
+    let huge = b"ply\nformat binary_little_endian 1.0\nelement vertex 2305843009213693951\nproperty float x\nproperty float y\nproperty float z\nproperty float nx\nproperty float ny\nproperty float nz\nend_header\n";
+    assert!(ply::read(huge).is_err());
+    assert!(matches!(
+        TriangleAsset::from_file("model.3ds"),
+        Err(Error::Unsupported(_))
+    ));
+    assert!(matches!(
+        TriangleAsset::from_file("/nonexistent/model.stl"),
+        Err(Error::Io(_))
+    ));
+}
@@ -0,0 +1,268 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BaseMaterial, BoundingSphere, Bounds, LodSelection, LodThreshold, Mat4, Mesh, MeshBuilder,
+    MeshLod, MeshVertex, Object, Primitive, PrimitiveType, SelectedPrimitive, Simplification,
+    Transformation,
+};
+
+const IDENTITY: Mat4 = [
+    1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1., 0., 0., 0., 0., 1.,
+];
+
+//fi projection
+/// A projection with a vertical field of view of 90 degrees
+fn projection() -> Mat4 {
+    let mut projection = [0.; 16];
+    projection[0] = 1.;
+    projection[5] = 1.;
+    projection
+}
+
+//fi points
+/// A primitive of a number of points, to tell levels apart
+fn points(count: u32) -> Primitive {
+    Primitive::of_vertex_range(PrimitiveType::Points, 0.into(), 0, count, 0.into())
+}
+
+//fi mesh
+/// A mesh of 3 points, with levels of 2 and 1 points
+fn mesh(thresholds: [LodThreshold; 2]) -> Mesh {
+    let mut mesh = Mesh::default();
+    mesh.add_primitive(points(3));
+    mesh.add_lod(MeshLod::new(vec![points(2)], thresholds[0]).with_error(0.01));
+    mesh.add_lod(MeshLod::new(vec![points(1)], thresholds[1]).with_error(0.1));
+    mesh
+}
+
+//fi counts
+fn counts(primitives: &[SelectedPrimitive]) -> Vec<(usize, u32)> {
+    primitives
+        .iter()
+        .map(|p| (p.matrix, p.primitive.index_count()))
+        .collect()
+}
+
+//ft test_lod_mesh
+#[test]
+fn test_lod_mesh() {
+    let mesh = mesh([LodThreshold::Distance(10.), LodThreshold::Distance(20.)]);
+    assert_eq!(mesh.num_lods(), 3);
+    assert_eq!(mesh.lod_primitives(0)[0].index_count(), 3);
+    assert_eq!(mesh.lod_primitives(2)[0].index_count(), 1);
+    assert_eq!(mesh.lod_primitives(5)[0].index_count(), 1);
+    assert_eq!(Mesh::default().lod_primitives(1).len(), 0);
+}
+
+//ft test_lod_distance
+#[test]
+fn test_lod_distance() {
+    let mesh = mesh([LodThreshold::Distance(10.), LodThreshold::Distance(20.)]);
+    let selection = LodSelection::new([0.; 3], &projection());
+    let select = |z: f32, current| {
+        let selection = LodSelection {
+            camera: [0., 0., z],
+            ..selection
+        };
+        mesh.select_lod(&selection, &IDENTITY, current)
+    };
+    assert_eq!(mesh.select_lod(&selection, &IDENTITY, 2), 0);
+    assert_eq!(select(5., 0), 0);
+    assert_eq!(select(25., 0), 2);
+
+    // Moving away, the level changes once the threshold is passed by
+    // 10%; moving back, once it is missed by 10%
+    assert_eq!(select(10.5, 0), 0);
+    assert_eq!(select(11.5, 0), 1);
+    assert_eq!(select(9.5, 1), 1);
+    assert_eq!(select(8.5, 1), 0);
+    assert_eq!(select(21., 1), 1);
+    assert_eq!(select(19., 2), 2);
+    assert_eq!(select(17., 2), 1);
+
+    // Without hysteresis
+    let selection = selection.with_hysteresis(0.);
+    let select = |z: f32, current| {
+        let selection = LodSelection {
+            camera: [0., 0., z],
+            ..selection
+        };
+        mesh.select_lod(&selection, &IDENTITY, current)
+    };
+    assert_eq!(select(10.5, 0), 1);
+    assert_eq!(select(9.5, 1), 0);
+}
+
+//ft test_lod_screen_size
+#[test]
+fn test_lod_screen_size() {
+    // With a radius of 1 at a distance of 10, the screen size is 0.1
+    let mut mesh = mesh([
+        LodThreshold::ScreenSize(0.2),
+        LodThreshold::ScreenSize(0.05),
+    ]);
+    let selection = LodSelection::new([0., 0., 10.], &projection());
+    assert_eq!(mesh.select_lod(&selection, &IDENTITY, 0), 2);
+    mesh.bounds = Bounds::of_points(&[[-1., 0., 0.], [1., 0., 0.]]);
+    assert_eq!(
+        mesh.bounds.unwrap().sphere,
+        BoundingSphere::new([0.; 3], 1.)
+    );
+    assert_eq!(mesh.select_lod(&selection, &IDENTITY, 0), 1);
+
+    // Scaled by 2 the screen size is 0.2
+    let scaled = Transformation::new().with_scale([2., 2., 2.]).mat4();
+    assert_eq!(mesh.select_lod(&selection, &scaled, 0), 0);
+    assert_eq!(mesh.select_lod(&selection, &scaled, 1), 1);
+
+    // Within the bounds the full detail is used
+    let selection = LodSelection::new([0., 0., 0.5], &projection());
+    assert_eq!(mesh.select_lod(&selection, &IDENTITY, 2), 0);
+}
+
+//ft test_lod_screen_error
+#[test]
+fn test_lod_screen_error() {
+    // At a distance of 10, the errors of the levels are 0.0005 and
+    // 0.005 of the height of the viewport
+    let mesh = mesh([LodThreshold::Distance(1000.), LodThreshold::Distance(1000.)]);
+    let selection = LodSelection::new([0., 0., 10.], &projection());
+    assert_eq!(mesh.select_lod(&selection, &IDENTITY, 0), 0);
+    for (max, level) in [(0.0001, 0), (0.001, 1), (0.01, 2)] {
+        let selection = selection.with_max_screen_error(Some(max));
+        assert_eq!(mesh.select_lod(&selection, &IDENTITY, 0), level);
+    }
+}
+
+//ft test_lod_recipe
+#[test]
+fn test_lod_recipe() {
+    // A grid, with a chain of levels from simplifying it
+    let mut builder = MeshBuilder::new();
+    for y in 0..5 {
+        for x in 0..5 {
+            let (x, y) = (x as f32, y as f32);
+            builder.push_vertex(MeshVertex::new([x / 4., y / 4., (x * y).sin() * 0.1]));
+        }
+    }
+    let mut indices = vec![];
+    for y in 0..4 {
+        for x in 0..4 {
+            let v = y * 5 + x;
+            indices.extend([v, v + 1, v + 6, v, v + 6, v + 5]);
+        }
+    }
+    builder.push_triangles(&indices, 0.into());
+    let (v, grid) = builder.build::<Renderable>(0.into()).unwrap();
+    let chain = v
+        .lod_chain(&grid.primitives[0], &Simplification::new(), 0.5, 3)
+        .unwrap();
+    assert_eq!(chain.lods.len(), 3);
+
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&chain.vertices);
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    obj.add_material(&material);
+    let mut lod_mesh = Mesh::default();
+    lod_mesh.add_primitive(chain.lods[0].primitive.clone());
+    for (lod, distance) in chain.lods[1..].iter().zip([10., 20.]) {
+        lod_mesh.add_lod(MeshLod::of_lod(lod, LodThreshold::Distance(distance)));
+    }
+    let mut plain = Mesh::default();
+    plain.add_primitive(points(3));
+    let root = obj.add_component(None, None, plain.clone());
+    obj.add_component(
+        Some(root),
+        Some(Transformation::new().with_translation([0., 0., -5.])),
+        lod_mesh,
+    );
+    obj.add_component(Some(root), None, plain);
+    obj.analyze();
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .unwrap();
+    let recipe = &inst.render_recipe;
+    assert_eq!(recipe.lod_meshes.len(), 1);
+    assert_eq!(recipe.lod_meshes[0].first, 1);
+    assert_eq!(recipe.lod_meshes[0].count, 1);
+    let counts_of_lods: Vec<u32> = chain
+        .lods
+        .iter()
+        .map(|lod| lod.primitive.index_count())
+        .collect();
+
+    let selection = LodSelection::new([0., 0., 0.], &projection());
+    let mut instance = inst.instantiate();
+    let primitives = instance.select_primitives(&selection);
+    assert_eq!(
+        counts(&primitives),
+        vec![(0, 3), (1, counts_of_lods[0]), (0, 3)]
+    );
+    let indices: Vec<usize> = primitives.iter().map(|p| p.index).collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+    assert_eq!(primitives[0].lod_index, None);
+    assert_eq!((primitives[1].level, primitives[1].lod_index), (0, Some(0)));
+    assert_eq!(instance.lod_levels, vec![0]);
+
+    instance.transformation = Transformation::new().with_translation([0., 0., -10.]);
+    let primitives = instance.select_primitives(&selection);
+    assert_eq!(counts(&primitives)[1], (1, counts_of_lods[1]));
+    assert_eq!((primitives[1].index, primitives[1].level), (1, 1));
+    assert_eq!(primitives[2].index, 2);
+    assert_eq!(instance.lod_levels, vec![1]);
+
+    instance.transformation = Transformation::new().with_translation([0., 0., -100.]);
+    let primitives = instance.select_primitives(&selection);
+    assert_eq!(counts(&primitives)[1], (1, counts_of_lods[2]));
+    assert_eq!(instance.lod_levels, vec![2]);
+}
+
+//ft test_lod_serde
+#[cfg(feature = "serde")]
+#[test]
+fn test_lod_serde() {
+    let mut mesh = mesh([LodThreshold::Distance(10.), LodThreshold::ScreenSize(0.1)]);
+    let json = serde_json::to_string(&Mesh::default()).unwrap();
+    assert_eq!(json, r#"{"primitives":[]}"#);
+    mesh.bounds = Bounds::of_points(&[[0., 0., 0.], [1., 1., 1.]]);
+    let json = serde_json::to_string(&mesh).unwrap();
+    let mesh: Mesh = serde_json::from_str(&json).unwrap();
+    assert_eq!(mesh.num_lods(), 3);
+    assert_eq!(mesh.lods[1].threshold, LodThreshold::ScreenSize(0.1));
+    assert_eq!(mesh.lods[1].error, 0.1);
+    assert_eq!(mesh.bounds.unwrap().bbox.max, [1., 1., 1.]);
+}
+
+//ft test_lod_instance_scale
+#[test]
+fn test_lod_instance_scale() {
+    // A mesh scaled by 2 by its component, in an instance 15 from the
+    // camera: the instance translation is not scaled, so the mesh is at
+    // a distance of 15
+    let mut builder = MeshBuilder::new();
+    for p in [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]] {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    let (v, _) = builder.build::<Renderable>(0.into()).unwrap();
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&v);
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    obj.add_material(&material);
+    obj.add_component(
+        None,
+        Some(Transformation::new().with_scale([2., 2., 2.])),
+        mesh([LodThreshold::Distance(10.), LodThreshold::Distance(20.)]),
+    );
+    obj.analyze();
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .unwrap();
+
+    let selection = LodSelection::new([0., 0., 0.], &projection());
+    let mut instance = inst.instantiate();
+    instance.transformation = Transformation::new().with_translation([0., 0., -15.]);
+    let primitives = instance.select_primitives(&selection);
+    assert_eq!(primitives[0].primitive.index_count(), 2);
+    assert_eq!(instance.lod_levels, vec![1]);
+}
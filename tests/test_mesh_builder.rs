@@ -0,0 +1,147 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BaseMaterial, BufferElementType, MeshBuilder, MeshBuilderError, MeshVertex, Object,
+    PrimitiveType, Shared, VertexAttr, VertexLayout,
+};
+
+//fi quad
+/// Create a builder with a colored quad as two triangles, and its
+/// outline as lines
+fn quad(layout: VertexLayout, index_type: BufferElementType) -> MeshBuilder {
+    let mut builder = MeshBuilder::new()
+        .with_layout(layout)
+        .with_index_type(index_type);
+    for (x, y) in [(0., 0.), (1., 0.), (1., 1.), (0., 1.)] {
+        builder.push_vertex(
+            MeshVertex::new([x, y, 0.])
+                .with_normal([0., 0., 1.])
+                .with_tex_coords([x, y]),
+        );
+    }
+    builder.push_vertex(MeshVertex::new([0.5, 0.5, 2.]).with_color([1., 0., 0., 1.]));
+    builder.push_triangles(&[0, 1, 2, 0, 2, 3], 0.into());
+    builder.push_lines(&[0, 1, 1, 2, 2, 3, 3, 0], 1.into());
+    builder
+}
+
+//ft test_layouts
+#[test]
+fn test_layouts() {
+    for layout in [VertexLayout::Interleaved, VertexLayout::Planar] {
+        let builder = quad(layout, BufferElementType::UInt16);
+        assert_eq!(
+            builder.attrs(),
+            vec![
+                VertexAttr::Position,
+                VertexAttr::Normal,
+                VertexAttr::TexCoords0,
+                VertexAttr::Color
+            ]
+        );
+        let (vertices, mesh) = builder.build::<Renderable>(3.into()).unwrap();
+
+        let position = vertices.borrow_position();
+        assert_eq!(position.count, 5);
+        assert_eq!(position.validate(), Ok(()));
+        assert_eq!(position.min, Some(vec![0., 0., 0.]));
+        assert_eq!(position.max, Some(vec![1., 1., 2.]));
+        assert_eq!(position.read_element::<f32, 3>(2), Ok([1., 1., 0.]));
+        let expected_stride = match layout {
+            VertexLayout::Interleaved => 48,
+            VertexLayout::Planar => 12,
+        };
+        assert_eq!(position.element_stride(), expected_stride, "{layout:?}");
+        assert_eq!(position.data.byte_length, 5 * 48);
+
+        let uv = vertices.borrow_attr(VertexAttr::TexCoords0).unwrap();
+        assert_eq!(uv.read_element::<f32, 2>(3), Ok([0., 1.]));
+        let color = vertices.borrow_attr(VertexAttr::Color).unwrap();
+        assert!(color.data.ptr_eq(&position.data));
+        assert_eq!(color.read_element::<f32, 4>(0), Ok([0.; 4]));
+        assert_eq!(color.read_element::<f32, 4>(4), Ok([1., 0., 0., 1.]));
+
+        let indices = vertices.borrow_indices().unwrap();
+        assert_eq!(indices.ele_type, BufferElementType::UInt16);
+        assert_eq!(indices.elements_per_data, 14);
+        let all: Vec<u32> = indices.iter_indices().unwrap().collect();
+        assert_eq!(&all[0..6], &[0, 1, 2, 0, 2, 3]);
+
+        assert_eq!(mesh.primitives.len(), 2);
+        let lines = &mesh.primitives[1];
+        assert_eq!(lines.primitive_type(), PrimitiveType::Lines);
+        assert_eq!(lines.vertices_index().as_usize(), 3);
+        assert_eq!(lines.material_index().as_usize(), 1);
+        assert_eq!(lines.byte_offset(), 12);
+        assert_eq!(lines.index_count(), 8);
+    }
+}
+
+//ft test_object
+#[test]
+fn test_object() {
+    let mut builder = MeshBuilder::new();
+    let a = builder.push_vertex(MeshVertex::new([0., 0., 0.]).with_skin([1, 0, 0, 0], [1.; 4]));
+    let b = builder.push_vertex(MeshVertex::new([1., 0., 0.]));
+    let c = builder.push_vertex(MeshVertex::new([0., 1., 0.]));
+    builder.push_strip(&[a, b, c], 0.into());
+
+    let mut obj: Object<'static, BaseMaterial, Renderable> = Object::new();
+    obj.add_material(Shared::new(BaseMaterial::of_rgba(0xff0000ff)));
+    let mesh = builder.add_to_object(&mut obj).unwrap();
+    obj.add_component(None, None, mesh);
+    obj.analyze();
+
+    let v = obj.vertices(0.into());
+    assert!(obj.vertices[0].is_owned());
+    assert_eq!(
+        v.borrow_indices().unwrap().ele_type,
+        BufferElementType::UInt32
+    );
+    let joints = v.borrow_attr(VertexAttr::Joints).unwrap();
+    assert_eq!(joints.ele_type, BufferElementType::UInt16);
+    assert_eq!(joints.read_element::<u32, 4>(0), Ok([1, 0, 0, 0]));
+    assert_eq!(joints.read_element::<u32, 4>(2), Ok([0; 4]));
+
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .expect("Failed to make the object instantiable");
+    assert_eq!(inst.render_recipe.primitives.len(), 1);
+}
+
+//ft test_errors
+#[test]
+fn test_errors() {
+    let mut builder = quad(VertexLayout::Planar, BufferElementType::UInt32);
+    assert_eq!(builder.validate(), Ok(()));
+    builder.push_triangles(&[0, 1, 5], 0.into());
+    assert_eq!(
+        builder.validate(),
+        Err(MeshBuilderError::IndexOutOfRange(5, 5))
+    );
+
+    let mut builder = quad(VertexLayout::Planar, BufferElementType::UInt32);
+    builder.push_triangles(&[0, 1], 0.into());
+    assert_eq!(
+        builder.validate(),
+        Err(MeshBuilderError::BadIndexCount(PrimitiveType::Triangles, 2))
+    );
+
+    let builder = quad(VertexLayout::Planar, BufferElementType::Float32);
+    assert_eq!(
+        builder.validate(),
+        Err(MeshBuilderError::BadIndexType(BufferElementType::Float32))
+    );
+
+    let mut builder = MeshBuilder::new().with_index_type(BufferElementType::UInt8);
+    for i in 0..257 {
+        builder.push_vertex(MeshVertex::new([i as f32, 0., 0.]));
+    }
+    assert!(matches!(
+        builder.build::<Renderable>(0.into()),
+        Err(MeshBuilderError::TooManyVertices(
+            257,
+            BufferElementType::UInt8
+        ))
+    ));
+}
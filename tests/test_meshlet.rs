@@ -0,0 +1,237 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, MeshBuilder, MeshVertex, MeshletBuilder, Meshlets, Primitive, PrimitiveType,
+    Quantization, VertexLayout, Vertices,
+};
+
+//fi grid
+/// Build a grid of n by n squares in the unit square of the xy plane,
+/// each of two triangles facing +z
+fn grid(n: u32) -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for y in 0..=n {
+        for x in 0..=n {
+            builder.push_vertex(MeshVertex::new([
+                x as f32 / n as f32,
+                y as f32 / n as f32,
+                0.,
+            ]));
+        }
+    }
+    let mut indices = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let v = y * (n + 1) + x;
+            indices.extend([v, v + 1, v + n + 2, v, v + n + 2, v + n + 1]);
+        }
+    }
+    builder.push_triangles(&indices, 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi sphere
+/// Build a unit sphere of a number of rings and segments, with its
+/// triangles facing outwards
+fn sphere(rings: u32, segments: u32) -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for r in 0..=rings {
+        let theta = std::f32::consts::PI * r as f32 / rings as f32;
+        for s in 0..=segments {
+            let phi = 2. * std::f32::consts::PI * s as f32 / segments as f32;
+            let p = [
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            ];
+            builder.push_vertex(MeshVertex::new(p));
+        }
+    }
+    let mut indices = vec![];
+    for r in 0..rings {
+        for s in 0..segments {
+            let v = r * (segments + 1) + s;
+            let w = v + segments + 1;
+            if r > 0 {
+                indices.extend([v, w, v + 1]);
+            }
+            if r + 1 < rings {
+                indices.extend([v + 1, w, w + 1]);
+            }
+        }
+    }
+    builder.push_triangles(&indices, 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi positions
+fn positions(v: &Vertices<Renderable>) -> Vec<[f32; 3]> {
+    v.borrow_position().iter_all().unwrap().collect()
+}
+
+//fi sorted_triangles
+/// The triangles of some indices, each rotated to start with its
+/// smallest index, sorted
+fn sorted_triangles(indices: &[u32]) -> Vec<[u32; 3]> {
+    let mut triangles: Vec<[u32; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| {
+            let m = (0..3).min_by_key(|i| t[*i]).unwrap();
+            [t[m], t[(m + 1) % 3], t[(m + 2) % 3]]
+        })
+        .collect();
+    triangles.sort();
+    triangles
+}
+
+//fi check_meshlets
+/// Check that meshlets are within the limits of a builder, and that
+/// their bounding spheres contain their vertices
+fn check_meshlets(meshlets: &Meshlets, builder: &MeshletBuilder, positions: &[[f32; 3]]) {
+    for m in &meshlets.meshlets {
+        let vertices = meshlets.vertices_of(m);
+        let triangles = meshlets.triangles_of(m);
+        assert!(m.vertex_count as usize <= builder.max_vertices);
+        assert!(m.triangle_count as usize <= builder.max_triangles);
+        assert!(m.triangle_count > 0);
+        assert_eq!(triangles.len(), 3 * m.triangle_count as usize);
+        assert!(triangles.iter().all(|i| (*i as u32) < m.vertex_count));
+        let mut unique = vertices.to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), vertices.len());
+        for v in vertices {
+            let p = positions[*v as usize];
+            let d: f32 = (0..3).map(|i| (p[i] - m.sphere.center[i]).powi(2)).sum();
+            assert!(d.sqrt() <= m.sphere.radius * 1.0001);
+        }
+    }
+}
+
+//ft test_meshlet_grid
+#[test]
+fn test_meshlet_grid() {
+    let (v, primitives) = grid(16);
+    let positions = positions(&v);
+    let builder = MeshletBuilder::new();
+    let meshlets = v.build_meshlets(&primitives[0], &builder).unwrap();
+    check_meshlets(&meshlets, &builder, &positions);
+    assert_eq!(meshlets.primitive.index_count(), 16 * 16 * 6);
+    let original = v.primitive_indices(&primitives[0]).unwrap();
+    assert_eq!(
+        sorted_triangles(&meshlets.indices()),
+        sorted_triangles(&original)
+    );
+    // 512 triangles of at most 64 vertices each need at least 6
+    // meshlets, and good clusters need not many more
+    assert!(meshlets.meshlets.len() >= 6);
+    assert!(meshlets.meshlets.len() <= 10, "{}", meshlets.meshlets.len());
+
+    // The triangles are all flat, facing +z
+    for m in &meshlets.meshlets {
+        assert_eq!(m.cone.axis, [0., 0., 1.]);
+        assert!(m.cone.cutoff < 1e-3);
+        let c = m.sphere.center;
+        assert!(m.cone.is_backfacing(&[c[0], c[1], -1.]));
+        assert!(m.cone.is_backfacing(&[5., -3., -0.1]));
+        assert!(!m.cone.is_backfacing(&[c[0], c[1], 1.]));
+        assert!(!m.cone.is_backfacing(&[5., -3., 0.1]));
+    }
+
+    // With at most 4 vertices and 2 triangles, each square is a meshlet
+    let builder = MeshletBuilder::new()
+        .with_max_vertices(4)
+        .with_max_triangles(2);
+    let meshlets = v.build_meshlets(&primitives[0], &builder).unwrap();
+    check_meshlets(&meshlets, &builder, &positions);
+    assert_eq!(meshlets.meshlets.len(), 16 * 16);
+}
+
+//ft test_meshlet_cones
+#[test]
+fn test_meshlet_cones() {
+    let (v, primitives) = sphere(16, 32);
+    let positions = positions(&v);
+    let builder = MeshletBuilder::new()
+        .with_max_vertices(32)
+        .with_max_triangles(32);
+    let meshlets = v.build_meshlets(&primitives[0], &builder).unwrap();
+    check_meshlets(&meshlets, &builder, &positions);
+    let original = v.primitive_indices(&primitives[0]).unwrap();
+    assert_eq!(
+        sorted_triangles(&meshlets.indices()),
+        sorted_triangles(&original)
+    );
+
+    // A meshlet culled as backfacing must have all its triangles facing
+    // away from the camera; and from a distance some are culled
+    let cameras = [[3., 0., 0.], [0., -2., 1.], [1.5, 1.5, -1.5], [0., 0., 10.]];
+    for camera in cameras {
+        let mut culled = 0;
+        for m in &meshlets.meshlets {
+            if !m.cone.is_backfacing(&camera) {
+                continue;
+            }
+            culled += 1;
+            let vertices = meshlets.vertices_of(m);
+            for t in meshlets.triangles_of(m).chunks_exact(3) {
+                let [a, b, c] = [0, 1, 2].map(|i| positions[vertices[t[i] as usize] as usize]);
+                let e0 = [0, 1, 2].map(|i| b[i] - a[i]);
+                let e1 = [0, 1, 2].map(|i| c[i] - a[i]);
+                let n = [
+                    e0[1] * e1[2] - e0[2] * e1[1],
+                    e0[2] * e1[0] - e0[0] * e1[2],
+                    e0[0] * e1[1] - e0[1] * e1[0],
+                ];
+                let d: f32 = (0..3).map(|i| n[i] * (camera[i] - a[i])).sum();
+                assert!(d <= 1e-6, "{camera:?} {t:?}");
+            }
+        }
+        assert!(culled > 0, "{camera:?}");
+    }
+}
+
+//ft test_meshlet_primitives
+#[test]
+fn test_meshlet_primitives() {
+    let (mut v, primitives) = grid(2);
+    // Points and lines have no meshlets
+    let lines = Primitive::of_vertex_range(PrimitiveType::Lines, 0.into(), 0, 4, 0.into());
+    let meshlets = v.build_meshlets(&lines, &MeshletBuilder::new()).unwrap();
+    assert!(meshlets.meshlets.is_empty());
+    assert_eq!(meshlets.indices(), Vec::<u32>::new());
+
+    // A triangle strip is split as a list
+    let strip = Primitive::of_vertex_range(PrimitiveType::TriangleStrip, 0.into(), 0, 4, 0.into());
+    let meshlets = v.build_meshlets(&strip, &MeshletBuilder::new()).unwrap();
+    assert_eq!(meshlets.meshlets.len(), 1);
+    assert_eq!(meshlets.indices(), vec![0, 1, 2, 2, 1, 3]);
+
+    // Indices beyond the vertices are errors
+    let bad = Primitive::of_vertex_range(PrimitiveType::Triangles, 0.into(), 6, 6, 0.into());
+    assert_eq!(
+        v.build_meshlets(&bad, &MeshletBuilder::new()).err(),
+        Some(AccessorError::BadIndex(9, 9))
+    );
+
+    // Meshlets are attached to the vertices
+    let meshlets = v
+        .build_meshlets(&primitives[0], &MeshletBuilder::new())
+        .unwrap();
+    assert_eq!(v.add_meshlets(meshlets), 0);
+    assert_eq!(v.iter_meshlets().count(), 1);
+    assert_eq!(v.borrow_meshlets(0).unwrap().meshlets.len(), 1);
+    assert!(v.borrow_meshlets(1).is_none());
+    assert_eq!(v.reindexed(None).iter_meshlets().count(), 0);
+
+    // Repacking and quantizing keep the vertices, and so the meshlets
+    let repacked = v.repack(VertexLayout::Planar).unwrap();
+    assert_eq!(repacked.borrow_meshlets(0).unwrap().meshlets.len(), 1);
+    let quantized = v.quantize(&Quantization::new()).unwrap().vertices;
+    assert_eq!(quantized.iter_meshlets().count(), 1);
+    let dequantized = quantized
+        .dequantize(None, VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(dequantized.iter_meshlets().count(), 1);
+}
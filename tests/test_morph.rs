@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, BaseMaterial, BufferAccessor, BufferElementType, Mesh, MeshBuilder, MeshVertex,
+    MorphTarget, Object, Primitive, Quantization, VertexAttr, VertexLayout, Vertices, Welding,
+};
+
+//fi build
+/// Build a triangle in the xy plane with two morph targets: the first
+/// moves the top vertex up by 1 and tilts its normal towards y, the
+/// second moves all the vertices by 1 in z
+fn build() -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for p in [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]] {
+        builder.push_vertex(MeshVertex::new(p).with_normal([0., 0., 1.]));
+    }
+    builder.push_triangles(&[0, 1, 2], 0.into());
+    let (mut v, mesh) = builder.build::<Renderable>(0.into()).unwrap();
+
+    let deltas = |d: [[f32; 3]; 3]| {
+        Arc::new(BufferAccessor::of_elements(&d, BufferElementType::Float32, false).unwrap())
+    };
+    let mut up = MorphTarget::new();
+    up.add_delta(
+        VertexAttr::Position,
+        deltas([[0., 0., 0.], [0., 0., 0.], [0., 1., 0.]]),
+    );
+    up.add_delta(
+        VertexAttr::Normal,
+        deltas([[0., 0., 0.], [0., 0., 0.], [0., 1., -1.]]),
+    );
+    let mut lift = MorphTarget::new();
+    lift.add_delta(VertexAttr::Position, deltas([[0., 0., 1.]; 3]));
+    assert_eq!(v.add_morph_target(up), 0);
+    assert_eq!(v.add_morph_target(lift), 1);
+    (v, mesh.primitives)
+}
+
+//ft test_morph_vertices
+#[test]
+fn test_morph_vertices() {
+    let (v, _) = build();
+    assert_eq!(v.num_morph_targets(), 2);
+    let up = v.borrow_morph_target(0).unwrap();
+    assert!(up.borrow_delta(VertexAttr::Tangent).is_none());
+    assert_eq!(up.iter_deltas().count(), 2);
+    assert!(v.borrow_morph_target(2).is_none());
+
+    assert_eq!(
+        v.morphed_positions(&[]).unwrap(),
+        vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+    );
+    assert_eq!(
+        v.morphed_positions(&[0.5, 2.]).unwrap(),
+        vec![[0., 0., 2.], [1., 0., 2.], [0., 1.5, 2.]]
+    );
+    // Weights beyond the targets are ignored
+    assert_eq!(v.morphed_positions(&[1., 0., 5.]).unwrap()[2], [0., 2., 0.]);
+
+    let normals = v.morphed_normals(&[1., 1.]).unwrap();
+    assert_eq!(normals[0], [0., 0., 1.]);
+    assert_eq!(normals[2], [0., 1., 0.]);
+    let normals = v.morphed_normals(&[0.5]).unwrap();
+    let s = 0.5_f32.sqrt();
+    assert!((normals[2][1] - s).abs() < 1e-6 && (normals[2][2] - s).abs() < 1e-6);
+
+    // Without normals
+    let position = v.borrow_position();
+    let mut bare = Vertices::non_indexed(position);
+    bare.add_morph_target(v.borrow_morph_target(0).unwrap().clone());
+    assert_eq!(bare.morphed_positions(&[1.]).unwrap()[2], [0., 2., 0.]);
+    assert_eq!(
+        bare.morphed_normals(&[1.]).err(),
+        Some(AccessorError::MissingAttr(VertexAttr::Normal))
+    );
+}
+
+//ft test_morph_repack
+#[test]
+fn test_morph_repack() {
+    let (v, _) = build();
+    let weights = [0.25, 1.];
+    let expected = v.morphed_positions(&weights).unwrap();
+
+    // Reindexing shares the targets; repacking copies them
+    let reindexed = v.reindexed(None);
+    assert_eq!(reindexed.num_morph_targets(), 2);
+    assert_eq!(reindexed.morphed_positions(&weights).unwrap(), expected);
+    for layout in [VertexLayout::Interleaved, VertexLayout::Planar] {
+        let repacked = v.repack(layout).unwrap();
+        assert_eq!(repacked.num_morph_targets(), 2);
+        assert_eq!(repacked.morphed_positions(&weights).unwrap(), expected);
+        assert_eq!(
+            repacked.morphed_normals(&weights).unwrap(),
+            v.morphed_normals(&weights).unwrap()
+        );
+    }
+}
+
+//ft test_morph_weld
+#[test]
+fn test_morph_weld() {
+    // A square of two triangles with each vertex repeated for each
+    // triangle; the target moves the corners of the second triangle
+    // apart from those of the first at the origin only
+    let mut builder = MeshBuilder::new();
+    let corners = [
+        [0., 0., 0.],
+        [1., 0., 0.],
+        [1., 1., 0.],
+        [0., 0., 0.],
+        [1., 1., 0.],
+        [0., 1., 0.],
+    ];
+    for p in corners {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    builder.push_triangles(&[0, 1, 2, 3, 4, 5], 0.into());
+    let (mut v, _) = builder.build::<Renderable>(0.into()).unwrap();
+    let mut deltas = [[0_f32, 0., 1.]; 6];
+    deltas[3] = [0., 0., -1.];
+    let mut target = MorphTarget::new();
+    target.add_delta(
+        VertexAttr::Position,
+        Arc::new(BufferAccessor::of_elements(&deltas, BufferElementType::Float32, false).unwrap()),
+    );
+    v.add_morph_target(target);
+    let morphed = v.morphed_positions(&[1.]).unwrap();
+
+    for welding in [
+        Welding::new(),
+        Welding::new().with_tolerance(VertexAttr::Position, 0.01),
+    ] {
+        let welded = v.weld(&welding).unwrap();
+        assert_eq!(welded.welded_count(), 5);
+        assert_ne!(welded.remap[0], welded.remap[3]);
+        assert_eq!(welded.remap[2], welded.remap[4]);
+        let w = &welded.vertices;
+        assert_eq!(w.num_morph_targets(), 1);
+        let welded_morphed = w.morphed_positions(&[1.]).unwrap();
+        for (i, r) in welded.remap.iter().enumerate() {
+            assert_eq!(welded_morphed[*r as usize], morphed[i]);
+        }
+    }
+}
+
+//ft test_morph_quantize
+#[test]
+fn test_morph_quantize() {
+    let (v, _) = build();
+    let weights = [0.5, 1.];
+    let expected = v.morphed_positions(&weights).unwrap();
+    let close = |a: &[[f32; 3]], b: &[[f32; 3]]| {
+        a.iter()
+            .zip(b.iter())
+            .all(|(a, b)| (0..3).all(|i| (a[i] - b[i]).abs() < 1e-3))
+    };
+
+    // The position deltas are scaled with the positions, so the
+    // dequantization maps the morphed positions back
+    let quantization = Quantization::new()
+        .with_position(BufferElementType::Int16)
+        .with_normal(BufferElementType::Int8);
+    let q = v.quantize(&quantization).unwrap();
+    assert_eq!(q.vertices.num_morph_targets(), 2);
+    let morphed: Vec<[f32; 3]> = q
+        .vertices
+        .morphed_positions(&weights)
+        .unwrap()
+        .iter()
+        .map(|p| q.dequantize.transform_point(p))
+        .collect();
+    assert!(close(&morphed, &expected));
+    assert!(close(
+        &q.vertices.morphed_normals(&weights).unwrap(),
+        &v.morphed_normals(&weights).unwrap()
+    ));
+
+    let d = q
+        .vertices
+        .dequantize(Some(&q.dequantize), VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(d.num_morph_targets(), 2);
+    assert!(close(&d.morphed_positions(&weights).unwrap(), &expected));
+}
+
+//ft test_morph_instance
+#[test]
+fn test_morph_instance() {
+    let (v, primitives) = build();
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&v);
+    obj.add_material(&material);
+    let mut plain = Mesh::default();
+    plain.add_primitive(primitives[0].clone());
+    let mut morphed = plain.clone();
+    morphed.weights = vec![0.5, 0.];
+    let root = obj.add_component(None, None, plain);
+    obj.add_component(Some(root), None, morphed);
+    obj.analyze();
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .unwrap();
+
+    let recipe = &inst.render_recipe;
+    assert_eq!(recipe.morph_meshes.len(), 1);
+    assert_eq!(recipe.morph_meshes[0].first, 1);
+    assert_eq!(recipe.morph_mesh_of_primitive(0), None);
+    assert_eq!(recipe.morph_mesh_of_primitive(1), Some(0));
+
+    let mut a = inst.instantiate();
+    let b = inst.instantiate();
+    a.morph_weights[0][1] = 1.;
+    assert_eq!(a.primitive_weights(0), None);
+    assert_eq!(a.primitive_weights(1), Some(&[0.5, 1.][..]));
+    assert_eq!(b.primitive_weights(1), Some(&[0.5, 0.][..]));
+}
+
+//ft test_morph_description
+#[cfg(feature = "serde")]
+#[test]
+fn test_morph_description() {
+    use mod3d_base::ObjectDescription;
+
+    let (v, _) = build();
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&v);
+    let description = ObjectDescription::of_object(&obj);
+    assert_eq!(description.vertices[0].targets.len(), 2);
+    assert_eq!(description.vertices[0].targets[0].len(), 2);
+    assert_eq!(description.validate(), Ok(()));
+    let obj: Object<BaseMaterial, Renderable> = description.into_object();
+    let vertices = obj.vertices(0.into());
+    assert_eq!(
+        vertices.morphed_positions(&[1., 1.]).unwrap(),
+        v.morphed_positions(&[1., 1.]).unwrap()
+    );
+}
+
+//ft test_morph_gltf
+#[cfg(feature = "gltf")]
+#[test]
+fn test_morph_gltf() {
+    use mod3d_base::gltf::{GltfAsset, ObjectExporter};
+
+    let (v, primitives) = build();
+    let material = BaseMaterial::of_rgba(0xff0000ff);
+    let mut obj: Object<BaseMaterial, Renderable> = Object::new();
+    obj.add_vertices(&v);
+    obj.add_material(&material);
+    let mut mesh = Mesh::default();
+    mesh.add_primitive(primitives[0].clone());
+    mesh.weights = vec![0.5, 0.25];
+    obj.add_component(None, None, mesh);
+    obj.analyze();
+
+    let mut exporter = ObjectExporter::new();
+    exporter.add_object(&obj).unwrap();
+    let (json, _) = exporter.into_gltf(None).unwrap();
+    let asset = GltfAsset::from_json(&json, None).unwrap();
+    let gltf = asset.gltf();
+    assert_eq!(gltf.meshes[0].weights, vec![0.5, 0.25]);
+    let targets = &gltf.meshes[0].primitives[0].targets;
+    assert_eq!(targets.len(), 2);
+    assert!(targets[0].contains_key("NORMAL"));
+    let position = &gltf.accessors[targets[1]["POSITION"]];
+    assert_eq!(position.min, vec![0., 0., 1.]);
+
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    assert_eq!(vertices[0].num_morph_targets(), 2);
+    assert_eq!(
+        vertices[0].morphed_positions(&[1., 1.]).unwrap(),
+        v.morphed_positions(&[1., 1.]).unwrap()
+    );
+    let object = asset.make_object(&vertices);
+    assert_eq!(
+        object.components.borrow_node(0).mesh.weights,
+        vec![0.5, 0.25]
+    );
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "native")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::native::{self, Error, NativeFile, HEADER_SIZE, REGION_ALIGNMENT};
+use mod3d_base::{
+    BufferElementType, MaterialAspect, Object, ObjectDescription, PbrMaterial, Skeleton, Texture,
+    Transformation,
+};
+
+/// A 2x1 RGBA texture
+const TEXTURE: [u8; 8] = [255, 0, 0, 255, 0, 255, 0, 255];
+
+//fi description
+/// Create the description of an object with a tetrahedron, a textured
+/// material and a single-bone skeleton
+fn description() -> ObjectDescription<PbrMaterial> {
+    let mut eg = mod3d_base::ExampleVertices::new();
+    mod3d_base::example_objects::tetrahedron::new::<Renderable>(&mut eg, 1.0);
+    let texture = Texture::new(&TEXTURE, (2, 1, 0), BufferElementType::UInt8, 4);
+    let mut material = PbrMaterial::of_rgba(0xff0000ff);
+    material.set_texture(MaterialAspect::Color, 0.into());
+    let mut skeleton = Skeleton::new();
+    skeleton.add_bone(Transformation::new(), 0);
+
+    let mut obj: Object<PbrMaterial, Renderable> = Object::new();
+    let v_id = obj.add_vertices(eg.borrow_vertices(0.into()));
+    obj.add_texture(&texture);
+    let m_id = obj.add_material(&material);
+    obj.add_component(
+        None,
+        None,
+        mod3d_base::example_objects::tetrahedron::mesh(v_id, m_id),
+    );
+    obj.skeleton = Some(skeleton);
+    obj.analyze();
+    ObjectDescription::of_object(&obj)
+}
+
+#[test]
+fn test_round_trip() {
+    let description = description();
+    let bytes = native::write(&description).unwrap();
+    assert_eq!(&bytes[0..8], b"MOD3DNAT");
+
+    let file = NativeFile::<_, PbrMaterial>::new(bytes).unwrap();
+    assert_eq!(file.header().version, native::NATIVE_VERSION);
+    assert_eq!(file.regions().len(), 3, "Indices, vertex data and texture");
+    for r in file.regions() {
+        assert_eq!(r.offset as usize % REGION_ALIGNMENT, 0);
+    }
+    assert_eq!(file.region(2), TEXTURE);
+    file.verify().unwrap();
+    assert!(file.description().buffers.is_empty());
+
+    let data = file.make_buffer_data::<Renderable>();
+    let accessors = file.make_accessors(&data);
+    let vertices = file.make_vertices(&accessors);
+    let textures = file.make_textures::<Renderable>();
+    let obj = file.make_object(&vertices, &textures);
+    assert_eq!(obj.texture(0.into()).data(), TEXTURE);
+    assert_eq!(obj.skeleton.as_ref().unwrap().iter_roots().count(), 1);
+
+    for (d, bd) in data.iter().zip(description.buffer_data.iter()) {
+        let start = bd.byte_offset as usize;
+        let end = start + bd.byte_length as usize;
+        assert_eq!(d.as_slice(), &description.buffers[bd.buffer][start..end]);
+    }
+    let rebuilt = ObjectDescription::of_object(&obj);
+    assert_eq!(rebuilt.accessors, description.accessors);
+    assert_eq!(rebuilt.vertices, description.vertices);
+    assert_eq!(
+        serde_json::to_string(&rebuilt.materials).unwrap(),
+        serde_json::to_string(&description.materials).unwrap(),
+    );
+    assert_eq!(
+        serde_json::to_string(&rebuilt.components).unwrap(),
+        serde_json::to_string(&description.components).unwrap(),
+    );
+}
+
+#[test]
+fn test_file() {
+    let path = std::env::temp_dir().join(format!("mod3d_test_native_{}.m3d", std::process::id()));
+    native::write_file(&description(), &path).unwrap();
+    let file = NativeFile::<_, PbrMaterial>::read_file(&path).unwrap();
+    assert_eq!(file.description().vertices.len(), 1);
+    #[cfg(feature = "mmap")]
+    {
+        let file = NativeFile::<_, PbrMaterial>::map_file(&path).unwrap();
+        let data = file.make_buffer_data::<Renderable>();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].as_slice(), &[0, 1, 2, 3, 0, 1]);
+        drop(data);
+
+        // The object owns (and shares) the mapping of the file
+        let obj: Object<'static, PbrMaterial, Renderable> = file.into_object();
+        let indices = obj.vertices(0.into()).borrow_indices().unwrap();
+        assert!(indices.data.data().is_owned());
+        assert_eq!(indices.data.as_slice(), &[0, 1, 2, 3, 0, 1]);
+        assert_eq!(obj.texture(0.into()).data(), TEXTURE);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_errors() {
+    let bytes = native::write(&description()).unwrap();
+    let load = |bytes: Vec<u8>| NativeFile::<_, PbrMaterial>::new(bytes).map(|_| ());
+
+    assert!(matches!(
+        load(bytes[0..10].to_vec()),
+        Err(Error::Truncated(64, 10))
+    ));
+    let length = bytes.len() as u64;
+    assert!(matches!(
+        load(bytes[0..bytes.len() - 16].to_vec()),
+        Err(Error::Truncated(l, _)) if l == length
+    ));
+
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert!(matches!(load(bad), Err(Error::BadMagic)));
+
+    let mut bad = bytes.clone();
+    bad[8] = 2;
+    assert!(matches!(load(bad), Err(Error::Version(2))));
+
+    let mut bad = bytes.clone();
+    let last = bad.len() - 1;
+    bad[HEADER_SIZE + 20] ^= 1;
+    bad[last] ^= 1;
+    assert!(matches!(load(bad), Err(Error::Checksum(..))));
+
+    // The data of the regions is only checked when verified
+    let mut bad = bytes.clone();
+    let texture = NativeFile::<_, PbrMaterial>::new(bytes.clone())
+        .unwrap()
+        .regions()[2];
+    bad[texture.offset as usize] ^= 1;
+    let file = NativeFile::<_, PbrMaterial>::new(bad).unwrap();
+    file.verify_region(0).unwrap();
+    assert!(matches!(file.verify_region(2), Err(Error::Checksum(..))));
+    assert!(matches!(file.verify(), Err(Error::Checksum(..))));
+
+    assert!(matches!(
+        NativeFile::<_, PbrMaterial>::read_file("/nonexistent/model.m3d"),
+        Err(Error::Io(_))
+    ));
+}
@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BufferAccessor, BufferData, BufferElementType, Mesh, Object, PbrMaterial, Primitive,
+    PrimitiveType, Shared, VertexLayout, Vertices,
+};
+
+//fi lines
+/// Create an object with four vertices and no indices, drawn as a
+/// line strip of all the vertices and (optionally) as points of the
+/// middle two
+fn lines(points: bool) -> Object<'static, PbrMaterial, Renderable> {
+    let floats: Vec<u8> = [0_f32, 0., 0., 1., 0., 0., 1., 1., 0., 0., 1., 0.]
+        .iter()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let data = Arc::new(BufferData::of_bytes(floats, 0, 0));
+    let position = BufferAccessor::new(data, 3, BufferElementType::Float32, 0, 0)
+        .with_bounds(vec![0.; 3], vec![1., 1., 0.]);
+    let vertices = Vertices::non_indexed(Arc::new(position));
+
+    let mut obj = Object::new();
+    let v_id = obj.add_vertices(Arc::new(vertices));
+    let m_id = obj.add_material(Shared::new(PbrMaterial::of_rgba(0xff0000ff)));
+    let mut mesh = Mesh::default();
+    mesh.add_primitive(Primitive::of_vertex_range(
+        PrimitiveType::LineStrip,
+        v_id,
+        0,
+        4,
+        m_id,
+    ));
+    if points {
+        mesh.add_primitive(Primitive::of_vertex_range(
+            PrimitiveType::Points,
+            v_id,
+            1,
+            2,
+            m_id,
+        ));
+    }
+    obj.add_component(None, None, mesh);
+    obj.analyze();
+    obj
+}
+
+//ft test_non_indexed
+#[test]
+fn test_non_indexed() {
+    let obj = lines(true);
+    let v = obj.vertices(0.into());
+    assert!(!v.is_indexed());
+    assert!(v.borrow_indices().is_none());
+
+    let primitives = &obj.components.borrow_node(0).mesh.primitives;
+    assert!(!primitives[1].is_indexed());
+    assert_eq!(primitives[1].vertex_range(), Some((1, 2)));
+    assert_eq!(primitives[1].byte_offset(), 0);
+    assert_eq!(primitives[1].vertices(), (Some(0), 0, 2));
+    assert_eq!(primitives[1].index_count(), 2);
+    assert!(Primitive::new(PrimitiveType::Points, 0.into(), 4, 2, 0.into()).is_indexed());
+
+    let planar = v.repack(VertexLayout::Planar).unwrap();
+    assert!(!planar.is_indexed());
+    assert_eq!(
+        planar.borrow_position().read_element::<f32, 3>(2),
+        Ok([1., 1., 0.])
+    );
+
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .expect("Failed to make the object instantiable");
+    assert_eq!(inst.render_recipe.primitives.len(), 2);
+}
+
+//ft test_description
+#[cfg(feature = "serde")]
+#[test]
+fn test_description() {
+    let description = mod3d_base::ObjectDescription::of_object(&lines(true));
+    let json = serde_json::to_string(&description).unwrap();
+    assert!(!json.contains("\"indices\""));
+    let description: mod3d_base::ObjectDescription<PbrMaterial> =
+        serde_json::from_str(&json).unwrap();
+    description.validate().unwrap();
+    let obj = description.into_object::<Renderable>();
+    assert!(!obj.vertices(0.into()).is_indexed());
+    let primitives = &obj.components.borrow_node(0).mesh.primitives;
+    assert_eq!(primitives[0].vertex_range(), Some((0, 4)));
+    assert_eq!(primitives[1].vertex_range(), Some((1, 2)));
+}
+
+//ft test_gltf
+#[cfg(feature = "gltf")]
+#[test]
+fn test_gltf() {
+    use mod3d_base::gltf::{Error, GltfAsset, ObjectExporter};
+
+    // A glTF primitive without indices draws all of the vertices
+    let mut exporter = ObjectExporter::new();
+    assert!(matches!(
+        exporter.add_object(&lines(true)),
+        Err(Error::Unsupported(_))
+    ));
+
+    let obj = lines(false);
+    let mut exporter = ObjectExporter::new();
+    exporter.add_object(&obj).expect("Failed to export");
+    let (json, _) = exporter.into_gltf(None).expect("Failed to create glTF");
+    let asset = GltfAsset::from_json(&json, None).expect("Failed to load exported glTF");
+    assert_eq!(asset.gltf().meshes[0].primitives[0].indices, None);
+
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let object = asset.make_object(&vertices);
+    assert!(!object.vertices(0.into()).is_indexed());
+    let primitive = &object.components.borrow_node(0).mesh.primitives[0];
+    assert_eq!(primitive.primitive_type(), PrimitiveType::LineStrip);
+    assert_eq!(primitive.vertex_range(), Some((0, 4)));
+}
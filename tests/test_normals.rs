@@ -0,0 +1,180 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, MeshBuilder, MeshVertex, NormalGeneration, NormalWeighting, Primitive,
+    PrimitiveType, VertexAttr, Vertices,
+};
+
+//fi cube
+/// Create a unit cube of 8 vertices, with the triangles of each face
+/// wound counterclockwise seen from outside, and with wrong normals
+fn cube() -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for v in 0..8 {
+        let p = [v & 1, (v >> 1) & 1, (v >> 2) & 1].map(|c| c as f32);
+        builder.push_vertex(MeshVertex::new(p).with_normal([1., 0., 0.]));
+    }
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    let indices: Vec<u32> = faces
+        .iter()
+        .flat_map(|[a, b, c, d]| [*a, *b, *c, *a, *c, *d])
+        .collect();
+    builder.push_triangles(&indices, 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi corner_normals
+/// Get the positions and normals of the vertices drawn by a primitive
+fn corner_normals(v: &Vertices<Renderable>, p: &Primitive) -> Vec<([f32; 3], [f32; 3])> {
+    let position = v.borrow_position();
+    let normal = v.borrow_attr(VertexAttr::Normal).unwrap();
+    v.primitive_indices(p)
+        .unwrap()
+        .into_iter()
+        .map(|i| {
+            let i = i as usize;
+            (
+                position.read_element(i).unwrap(),
+                normal.read_element(i).unwrap(),
+            )
+        })
+        .collect()
+}
+
+//fi assert_near
+fn assert_near(a: [f32; 3], b: [f32; 3]) {
+    assert!((0..3).all(|i| (a[i] - b[i]).abs() < 1e-5), "{a:?} {b:?}");
+}
+
+//ft test_normals_smooth
+#[test]
+fn test_normals_smooth() {
+    let (v, primitives) = cube();
+    let (smooth, p) = v
+        .generate_normals(&primitives, &NormalGeneration::new())
+        .unwrap();
+    assert_eq!(format!("{p:?}"), format!("{primitives:?}"));
+    assert_eq!(smooth.borrow_position().count, 8);
+    assert_eq!(smooth.iter_attrs().count(), 1);
+    let k = 1. / 3_f32.sqrt();
+    for (position, normal) in corner_normals(&smooth, &p[0]) {
+        assert_near(normal, position.map(|c| if c > 0.5 { k } else { -k }));
+    }
+
+    // A crease angle larger than the angle between faces has no effect
+    let generation = NormalGeneration::new().with_crease_angle(Some(1.6));
+    let (v_crease, _) = v.generate_normals(&primitives, &generation).unwrap();
+    assert_eq!(v_crease.borrow_position().count, 8);
+}
+
+//ft test_normals_flat
+#[test]
+fn test_normals_flat() {
+    let (v, primitives) = cube();
+    for generation in [
+        NormalGeneration::flat(),
+        NormalGeneration::new().with_crease_angle(Some(1.)),
+    ] {
+        let (flat, p) = v.generate_normals(&primitives, &generation).unwrap();
+        assert_eq!(flat.borrow_position().count, 24);
+        assert_eq!(p[0].index_count(), 36);
+        let corners = corner_normals(&flat, &p[0]);
+        for t in corners.chunks_exact(3) {
+            let face = (0..3)
+                .map(|i| {
+                    let mut c: Vec<f32> = t.iter().map(|(p, _)| p[i]).collect();
+                    c.dedup();
+                    if c.len() > 1 {
+                        0.
+                    } else {
+                        c[0] * 2. - 1.
+                    }
+                })
+                .collect::<Vec<f32>>();
+            for (_, normal) in t {
+                assert_eq!(normal.as_slice(), face.as_slice());
+            }
+        }
+    }
+}
+
+//ft test_normals_weighting
+#[test]
+fn test_normals_weighting() {
+    // Vertex 0 is used by a large triangle facing +z and a small one
+    // facing +x, each with a right angle at vertex 0
+    let mut builder = MeshBuilder::new();
+    for p in [
+        [0., 0., 0.],
+        [10., 0., 0.],
+        [0., 10., 0.],
+        [0., 0., 1.],
+        [0., -1., 0.],
+    ] {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    builder.push_triangles(&[0, 1, 2, 0, 3, 4], 0.into());
+    let (v, mesh) = builder.build::<Renderable>(0.into()).unwrap();
+    let normal_0 = |weighting| {
+        let generation = NormalGeneration::new().with_weighting(weighting);
+        let (v, _) = v.generate_normals(&mesh.primitives, &generation).unwrap();
+        let normal = v.borrow_attr(VertexAttr::Normal).unwrap();
+        normal.read_element::<f32, 3>(0).unwrap()
+    };
+    let k = 1. / 2_f32.sqrt();
+    assert_near(normal_0(NormalWeighting::Angle), [k, 0., k]);
+    let area = normal_0(NormalWeighting::Area);
+    assert!(area[2] > 0.99 && area[0] > 0., "{area:?}");
+}
+
+//ft test_normals_primitives
+#[test]
+fn test_normals_primitives() {
+    // A strip folded along the edge 1-2, a line, and an unused vertex
+    let mut builder = MeshBuilder::new();
+    for p in [
+        [0., 0., 0.],
+        [1., 0., 0.],
+        [0., 1., 0.],
+        [1., 1., 1.],
+        [5., 5., 5.],
+    ] {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    builder.push_strip(&[0, 1, 2, 3], 0.into());
+    builder.push_lines(&[0, 3], 1.into());
+    let (v, mesh) = builder.build::<Renderable>(0.into()).unwrap();
+
+    let generation = NormalGeneration::flat();
+    let (flat, p) = v.generate_normals(&mesh.primitives, &generation).unwrap();
+    assert_eq!(flat.borrow_position().count, 7);
+    assert_eq!(p[0].primitive_type(), PrimitiveType::Triangles);
+    assert_eq!(p[1].primitive_type(), PrimitiveType::Lines);
+    assert_eq!(p[1].material_index(), 1.into());
+    assert_eq!(
+        flat.primitive_indices(&p[0]).unwrap(),
+        vec![0, 1, 2, 5, 6, 3]
+    );
+    assert_eq!(flat.primitive_indices(&p[1]).unwrap(), vec![0, 3]);
+    let normal = flat.borrow_attr(VertexAttr::Normal).unwrap();
+    let normals: Vec<[f32; 3]> = normal.iter_all().unwrap().collect();
+    assert_near(normals[4], [0., 0., 1.]);
+    let k = 1. / 3_f32.sqrt();
+    for (i, n) in [(0, [0., 0., 1.]), (3, [-k, -k, k]), (5, [-k, -k, k])] {
+        assert_near(normals[i], n);
+    }
+
+    // Indices beyond the vertices are errors
+    let bad = Primitive::of_vertex_range(PrimitiveType::Points, 0.into(), 3, 3, 0.into());
+    assert_eq!(
+        v.generate_normals(&[bad], &generation).err(),
+        Some(AccessorError::BadIndex(5, 5))
+    );
+}
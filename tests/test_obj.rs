@@ -0,0 +1,160 @@
+#![cfg(feature = "obj")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::obj::{Error, MtlLibrary, ObjAsset};
+use mod3d_base::{BufferElementType, Material, MaterialAspect, PrimitiveType, VertexAttr};
+
+const MTL: &str = r#"
+# Two materials
+newmtl red
+Kd 1.0 0.0 0.0
+d 0.5
+map_Kd -s 1 1 1 red.png
+
+newmtl glow
+Kd 0 0 1
+Ke 0 1 0
+Tr 0.0
+map_Ke glow.png
+map_Kd red.png
+"#;
+
+const OBJ: &str = r#"
+mtllib materials.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vn 0 0 1
+
+o quad
+usemtl red
+# A quad, to be triangulated, with mixed tuples
+f 1/1/1 2/2/1 3/3/1 4//1
+g triangle
+usemtl glow
+f -4/-3/-1 -3/-2/-1 -2/-1/-1
+usemtl unknown
+f 1 2 3
+"#;
+
+#[test]
+fn test_obj() {
+    let mut library = MtlLibrary::new();
+    library.add_mtl(MTL).expect("Failed to parse MTL");
+    assert_eq!(library.len(), 2);
+    assert_eq!(library.textures(), &["red.png", "glow.png"]);
+
+    let asset = ObjAsset::new(OBJ, &library).expect("Failed to parse OBJ");
+    let data = asset.make_buffer_data::<Renderable>();
+    let accessors = asset.make_accessors(&data);
+    let vertices = asset.make_vertices(&accessors);
+    let mut object = asset.make_object(&vertices);
+    object.analyze();
+
+    assert_eq!(vertices.len(), 1);
+    let v = object.vertices(0.into());
+    let indices = v.borrow_indices().unwrap();
+    assert_eq!(indices.elements_per_data, 12, "Four triangles");
+    assert_eq!(indices.ele_type, BufferElementType::UInt32);
+    let position = v.borrow_position();
+    assert_eq!(
+        position.stride, 32,
+        "Position, normal and texture coordinates"
+    );
+    assert_eq!(v.borrow_attr(VertexAttr::Normal).unwrap().byte_offset, 12);
+    assert_eq!(
+        v.borrow_attr(VertexAttr::TexCoords0).unwrap().byte_offset,
+        24
+    );
+    assert_eq!(
+        position.data.byte_length,
+        7 * 32,
+        "Four quad vertices (reused by the glow face) and three from '1 2 3'"
+    );
+
+    assert_eq!(object.components.len(), 2, "One component per group");
+    assert_eq!(object.components.borrow_roots(), &[0, 1]);
+    let quad = &object.components.borrow_node(0).mesh.primitives;
+    assert_eq!(quad.len(), 1);
+    assert_eq!(quad[0].primitive_type(), PrimitiveType::Triangles);
+    assert_eq!((quad[0].byte_offset(), quad[0].index_count()), (0, 6));
+    assert_eq!(quad[0].material_index().as_usize(), 0);
+    let triangle = &object.components.borrow_node(1).mesh.primitives;
+    assert_eq!(triangle.len(), 2, "One primitive per material");
+    assert_eq!(
+        (triangle[0].byte_offset(), triangle[0].index_count()),
+        (24, 3)
+    );
+    assert_eq!(triangle[0].material_index().as_usize(), 1);
+    assert_eq!(
+        (triangle[1].byte_offset(), triangle[1].index_count()),
+        (36, 3)
+    );
+    assert_eq!(
+        triangle[1].material_index().as_usize(),
+        2,
+        "Unknown material uses the default"
+    );
+
+    assert_eq!(object.materials.len(), 3);
+    let red = object.material(0.into());
+    assert_eq!(red.base_data().rgba_tuple(), (255, 0, 0, 128));
+    assert_eq!(red.texture(MaterialAspect::Color).as_usize(), 0);
+    assert!(red.texture(MaterialAspect::Emission).is_none());
+    let glow = object.material(1.into());
+    assert_eq!(glow.base_data().rgba_tuple(), (0, 0, 255, 255));
+    assert_eq!(glow.base_data().emissive_rgb, 0xff00);
+    assert_eq!(glow.texture(MaterialAspect::Color).as_usize(), 0);
+    assert_eq!(glow.texture(MaterialAspect::Emission).as_usize(), 1);
+}
+
+#[test]
+fn test_obj_file() {
+    let dir = std::env::temp_dir().join(format!("mod3d_obj_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("materials.mtl"), MTL).unwrap();
+    std::fs::write(dir.join("model.obj"), OBJ).unwrap();
+    let asset = ObjAsset::from_file(dir.join("model.obj"));
+    std::fs::remove_dir_all(&dir).unwrap();
+    let asset = asset.expect("Failed to read OBJ file");
+    assert_eq!(asset.materials().len(), 3);
+    assert_eq!(asset.textures().len(), 2);
+
+    let asset = ObjAsset::from_obj(OBJ, None).unwrap();
+    assert_eq!(asset.materials().len(), 1, "Just the default material");
+}
+
+#[test]
+fn test_errors() {
+    let library = MtlLibrary::new();
+    assert!(matches!(
+        ObjAsset::new("v 0 0 0\nv 1 0 0\nf 1 2 3\n", &library),
+        Err(Error::BadIndex(3, "vertex", 3))
+    ));
+    assert!(matches!(
+        ObjAsset::new("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2 3\n", &library),
+        Err(Error::BadIndex(4, "texture coordinate", 1))
+    ));
+    assert!(matches!(
+        ObjAsset::new("v 0 0 0\nv 1 0 0\nf -3 1 2\n", &library),
+        Err(Error::BadIndex(3, "vertex", -3))
+    ));
+    assert!(matches!(
+        ObjAsset::new("v 0 0\n", &library),
+        Err(Error::Syntax(1, _))
+    ));
+    assert!(matches!(
+        ObjAsset::new("v 0 0 0\nf 1 1\n", &library),
+        Err(Error::Syntax(2, _))
+    ));
+    assert!(matches!(
+        MtlLibrary::new().add_mtl("Kd 1 1 1\n"),
+        Err(Error::Syntax(1, _))
+    ));
+
+    let asset = ObjAsset::new("v 0 0 0\n", &library).unwrap();
+    assert!(asset.make_buffer_data::<Renderable>().is_empty());
+}
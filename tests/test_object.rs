@@ -20,7 +20,7 @@ fn test0() {
     obj.add_component(None, None, mesh);
     obj.analyze();
     let x = obj.vertices(v_id).borrow_client();
-    let _p: &BufferAccessor<Renderable> = obj.vertices(v_id).borrow_indices();
+    let _p: &BufferAccessor<Renderable> = obj.vertices(v_id).borrow_indices().unwrap();
     let _p = obj.material(m_id).texture(MaterialAspect::Normal);
 
     drop(x); // so we can desconstruct obj
@@ -0,0 +1,152 @@
+#![cfg(feature = "serde")]
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BufferElementType, Material, MaterialAspect, Object, ObjectDescription, PbrMaterial,
+    ShortIndex, Skeleton, Texture, Transformation,
+};
+
+/// A 2x1 RGBA texture
+const TEXTURE: [u8; 8] = [255, 0, 0, 255, 0, 255, 0, 255];
+
+//fi to_json
+/// Create an object with a tetrahedron and a triangle (sharing no
+/// data), a textured material and a two-bone skeleton, and return
+/// its description as JSON
+fn to_json() -> String {
+    let mut eg = mod3d_base::ExampleVertices::new();
+    mod3d_base::example_objects::tetrahedron::new::<Renderable>(&mut eg, 1.0);
+    mod3d_base::example_objects::triangle::new::<Renderable>(&mut eg, 0.5);
+    let texture = Texture::new(&TEXTURE, (2, 1, 0), BufferElementType::UInt8, 4);
+
+    let mut material = PbrMaterial::of_rgba(0xff00ff00);
+    material.set_mr(0.5, 0.25);
+    material.set_texture(MaterialAspect::Color, 0.into());
+
+    let mut skeleton = Skeleton::new();
+    let b0 = skeleton.add_bone(Transformation::new(), 0);
+    let b1 = skeleton.add_bone(Transformation::new().with_translation([1., 0., 0.]), 1);
+    skeleton.relate(b0, b1);
+    skeleton.resolve();
+
+    let mut obj: Object<PbrMaterial, Renderable> = Object::new();
+    let t_id = obj.add_vertices(eg.borrow_vertices(0.into()));
+    let v_id = obj.add_vertices(eg.borrow_vertices(1.into()));
+    obj.add_texture(&texture);
+    let m_id = obj.add_material(&material);
+    let root = obj.add_component(
+        None,
+        Some(Transformation::new().with_scale([2.; 3])),
+        mod3d_base::example_objects::tetrahedron::mesh(t_id, m_id),
+    );
+    obj.add_component(
+        Some(root),
+        None,
+        mod3d_base::example_objects::triangle::mesh(v_id, ShortIndex::none()),
+    );
+    obj.skeleton = Some(skeleton);
+    obj.analyze();
+
+    let description = ObjectDescription::of_object(&obj);
+    assert_eq!(
+        description.buffers.len(),
+        5,
+        "Two each for the meshes, one texture"
+    );
+    assert_eq!(description.buffer_data.len(), 4);
+    assert_eq!(description.accessors.len(), 6);
+    assert_eq!(description.vertices.len(), 2);
+    assert_eq!(
+        description.vertices[0].attrs.len(),
+        1,
+        "Tetrahedron has normals"
+    );
+    assert_eq!(description.textures[0].buffer, 4);
+    assert_eq!(description.buffers[4], TEXTURE);
+    serde_json::to_string_pretty(&description).unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    let json = to_json();
+    let description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json).unwrap();
+    description.validate().unwrap();
+
+    let data = description.make_buffer_data::<Renderable>();
+    let accessors = description.make_accessors(&data);
+    let vertices = description.make_vertices(&accessors);
+    let textures = description.make_textures::<Renderable>();
+    let obj = description.make_object(&vertices, &textures);
+
+    assert_eq!(obj.vertices.len(), 2);
+    assert_eq!(
+        obj.vertices(0.into())
+            .borrow_indices()
+            .unwrap()
+            .elements_per_data,
+        6
+    );
+    assert_eq!(obj.texture(0.into()).data(), TEXTURE);
+    let material = obj.material(0.into());
+    assert_eq!(material.base_data().rgba, 0xff00ff00);
+    assert_eq!(material.texture(MaterialAspect::Color), 0.into());
+    assert!(material.texture(MaterialAspect::Normal).is_none());
+
+    assert_eq!(obj.components.len(), 2);
+    assert_eq!(obj.components.borrow_roots(), &[0]);
+    let triangle = &obj.components.borrow_node(1).mesh.primitives[0];
+    assert!(triangle.material_index().is_none());
+    let skeleton = obj.skeleton.as_ref().unwrap();
+    assert_eq!(skeleton.iter_roots().count(), 1);
+    assert_eq!(skeleton.max_index, 2);
+
+    let again = serde_json::to_string_pretty(&ObjectDescription::of_object(&obj)).unwrap();
+    assert_eq!(again, json, "Description of rebuilt object must match");
+}
+
+#[test]
+fn test_short_index() {
+    assert_eq!(serde_json::to_string(&ShortIndex::none()).unwrap(), "null");
+    assert_eq!(serde_json::to_string(&ShortIndex::from(3)).unwrap(), "3");
+    let index: ShortIndex = serde_json::from_str("null").unwrap();
+    assert!(index.is_none());
+    let index: ShortIndex = serde_json::from_str("7").unwrap();
+    assert_eq!(index.as_usize(), 7);
+    assert!(serde_json::from_str::<ShortIndex>("65535").is_err());
+    assert!(serde_json::from_str::<ShortIndex>("65536").is_err());
+}
+
+#[test]
+fn test_validate() {
+    let json = to_json();
+    let mut description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json).unwrap();
+    description.buffer_data[0].byte_length += 1;
+    assert!(description.validate().is_err());
+
+    let mut description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json).unwrap();
+    description.vertices[1].position = 6;
+    assert!(description.validate().is_err());
+
+    let mut description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json).unwrap();
+    description.materials.clear();
+    assert!(description.validate().is_err());
+
+    let mut description: ObjectDescription<PbrMaterial> = serde_json::from_str(&json).unwrap();
+    description.textures[0].dims = (3, 1, 0);
+    assert!(description.validate().is_err());
+    description.textures[0].dims = (usize::MAX, 2, 0);
+    assert!(description.validate().is_err());
+
+    // The component hierarchy must be a forest of the components
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    for (path, replacement) in [
+        ("/components/roots", "[2]"),
+        ("/components/elements/0/children", "[2]"),
+        ("/components/elements/1/children", "[1]"),
+        ("/components/elements/1/children", "[0]"),
+    ] {
+        let mut value = value.clone();
+        *value.pointer_mut(path).unwrap() = serde_json::from_str(replacement).unwrap();
+        let description: ObjectDescription<PbrMaterial> = serde_json::from_value(value).unwrap();
+        assert!(description.validate().is_err(), "{path} of {replacement}");
+    }
+}
@@ -0,0 +1,200 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    CacheStatistics, Mesh, MeshBuilder, MeshVertex, Optimization, Primitive, VertexLayout, Vertices,
+};
+
+//fi scrambled
+/// A deterministic permutation of 0..n
+fn scrambled(n: usize) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut seed = 12345_u64;
+    for i in (1..n).rev() {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        order.swap(i, (seed >> 33) as usize % (i + 1));
+    }
+    order
+}
+
+//fi grid
+/// Create a grid of `n` by `n` squares (two triangles each) with the
+/// vertices and triangles in a scrambled order, followed by a strip
+fn grid(n: usize) -> (Vertices<'static, Renderable>, Mesh) {
+    let w = n + 1;
+    let vertex_order = scrambled(w * w);
+    let mut vertex_of = vec![0; w * w];
+    let mut builder = MeshBuilder::new();
+    for v in vertex_order {
+        let (x, y) = ((v % w) as f32, (v / w) as f32);
+        vertex_of[v] = builder.push_vertex(MeshVertex::new([x, y, 0.]).with_tex_coords([x, y]));
+    }
+    let mut triangles = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let v = |dx: usize, dy: usize| vertex_of[(y + dy) * w + x + dx];
+            triangles.push([v(0, 0), v(1, 0), v(1, 1)]);
+            triangles.push([v(0, 0), v(1, 1), v(0, 1)]);
+        }
+    }
+    let indices: Vec<u32> = scrambled(triangles.len())
+        .into_iter()
+        .flat_map(|t| triangles[t])
+        .collect();
+    builder.push_triangles(&indices, 0.into());
+    builder.push_strip(
+        &[vertex_of[0], vertex_of[1], vertex_of[w], vertex_of[w + 1]],
+        0.into(),
+    );
+    builder.build(0.into()).unwrap()
+}
+
+//fi positions
+/// Get the positions of the vertices drawn by a primitive
+fn positions(v: &Vertices<Renderable>, p: &Primitive) -> Vec<[f32; 3]> {
+    let position = v.borrow_position();
+    v.primitive_indices(p)
+        .unwrap()
+        .into_iter()
+        .map(|i| position.read_element(i as usize).unwrap())
+        .collect()
+}
+
+//fi triangle_set
+/// Get the sorted triangles (as positions) of a triangle list
+fn triangle_set(v: &Vertices<Renderable>, p: &Primitive) -> Vec<Vec<[u32; 3]>> {
+    let mut triangles: Vec<_> = positions(v, p)
+        .chunks_exact(3)
+        .map(|t| {
+            let mut t: Vec<[u32; 3]> = t.iter().map(|p| p.map(f32::to_bits)).collect();
+            let first = (0..3).min_by_key(|i| t[*i]).unwrap();
+            t.rotate_left(first);
+            t
+        })
+        .collect();
+    triangles.sort();
+    triangles
+}
+
+//fi check_optimized
+/// Check that an optimization keeps the primitives' triangles and
+/// strips, and numbers the vertices in order of first use
+fn check_optimized(
+    v: &Vertices<Renderable>,
+    mesh: &Mesh,
+    optimization: &Optimization,
+) -> (CacheStatistics, CacheStatistics) {
+    let optimized = v.optimize(&mesh.primitives, optimization).unwrap();
+    let o = &optimized.vertices;
+    let (before, after) = (&mesh.primitives, &optimized.primitives);
+    assert_eq!(triangle_set(v, &before[0]), triangle_set(o, &after[0]));
+    assert_eq!(positions(v, &before[1]), positions(o, &after[1]));
+    assert_eq!(o.borrow_position().count, v.borrow_position().count);
+
+    let mut next = 0;
+    for i in o.borrow_indices().unwrap().iter_indices().unwrap() {
+        assert!(i <= next);
+        if i == next {
+            next += 1;
+        }
+    }
+    assert_eq!(
+        o.cache_statistics(after, optimization.cache_size),
+        Ok(optimized.after)
+    );
+    (optimized.before, optimized.after)
+}
+
+//ft test_cache_statistics
+#[test]
+fn test_cache_statistics() {
+    let s = CacheStatistics::of_triangles(&[0, 1, 2, 2, 1, 3], 16);
+    assert_eq!((s.triangles, s.vertices, s.misses), (2, 4, 4));
+    assert_eq!((s.acmr(), s.atvr()), (2., 1.));
+
+    // With a cache of 3 vertices, vertex 0 is evicted by vertex 3, and
+    // then vertex 1 by vertex 0 - but vertex 3 is still in the cache
+    let indices = [0, 1, 2, 1, 2, 3, 0, 1, 3];
+    assert_eq!(CacheStatistics::of_triangles(&indices, 3).misses, 6);
+    assert_eq!(CacheStatistics::of_triangles(&indices, 4).misses, 4);
+    assert_eq!(CacheStatistics::of_triangles(&[], 4).acmr(), 0.);
+}
+
+//ft test_optimize_cache
+#[test]
+fn test_optimize_cache() {
+    let (v, mesh) = grid(16);
+    let optimization = Optimization::new().with_overdraw(None);
+    let (before, after) = check_optimized(&v, &mesh, &optimization);
+    assert_eq!(before.triangles, 512);
+    assert_eq!(after.vertices, 289);
+    assert!(before.acmr() > 2., "{}", before.acmr());
+    assert!(after.acmr() < 0.8, "{}", after.acmr());
+    assert!(after.atvr() < 1.4, "{}", after.atvr());
+
+    let optimization = optimization
+        .with_cache_size(32)
+        .with_layout(VertexLayout::Planar);
+    let (_, after_32) = check_optimized(&v, &mesh, &optimization);
+    assert!(after_32.acmr() <= after.acmr());
+}
+
+//fi spheres
+/// Create two concentric spheres, of radius 1 and 2, with their
+/// triangles (facing outwards) mixed in a scrambled order, followed by
+/// a strip
+fn spheres() -> (Vertices<'static, Renderable>, Mesh) {
+    let (rings, segments) = (8, 12);
+    let mut builder = MeshBuilder::new();
+    let mut triangles = vec![];
+    for radius in [1_f32, 2.] {
+        let base = builder.num_vertices() as u32;
+        for r in 0..=rings {
+            let theta = std::f32::consts::PI * r as f32 / rings as f32;
+            for s in 0..segments {
+                let phi = std::f32::consts::TAU * s as f32 / segments as f32;
+                let p = [
+                    theta.sin() * phi.cos(),
+                    theta.sin() * phi.sin(),
+                    theta.cos(),
+                ];
+                builder.push_vertex(MeshVertex::new(p.map(|c| c * radius)));
+            }
+        }
+        let v = |r: u32, s: u32| base + r * segments + s % segments;
+        for r in 0..rings {
+            for s in 0..segments {
+                triangles.push([v(r, s), v(r + 1, s), v(r + 1, s + 1)]);
+                triangles.push([v(r, s), v(r + 1, s + 1), v(r, s + 1)]);
+            }
+        }
+    }
+    let indices: Vec<u32> = scrambled(triangles.len())
+        .into_iter()
+        .flat_map(|t| triangles[t])
+        .collect();
+    builder.push_triangles(&indices, 0.into());
+    builder.push_strip(&[0, 1, 2, 3], 0.into());
+    builder.build(0.into()).unwrap()
+}
+
+//ft test_optimize_overdraw
+#[test]
+fn test_optimize_overdraw() {
+    let (v, mesh) = spheres();
+    let optimization = Optimization::new();
+    let (before, after) = check_optimized(&v, &mesh, &optimization);
+    let (_, cache_only) = check_optimized(&v, &mesh, &optimization.with_overdraw(None));
+    assert!(after.acmr() < before.acmr() / 2.);
+    assert!(after.acmr() < cache_only.acmr() * 1.2);
+
+    // The outer sphere is drawn before the inner sphere it occludes
+    let optimized = v.optimize(&mesh.primitives, &optimization).unwrap();
+    let radii: Vec<f32> = positions(&optimized.vertices, &optimized.primitives[0])
+        .iter()
+        .map(|p| (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt())
+        .collect();
+    let first_inner = radii.iter().position(|r| *r < 1.5).unwrap();
+    assert_eq!(first_inner, radii.len() / 2);
+    assert!(radii[first_inner..].iter().all(|r| *r < 1.5));
+}
@@ -0,0 +1,151 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, BufferElementType, Component, MeshBuilder, MeshVertex, Quantization,
+    Transformation, VertexAttr, VertexLayout, Vertices,
+};
+
+//fi vertices
+/// Create vertices with positions, normals and texture coordinates
+fn vertices() -> Vertices<'static, Renderable> {
+    let mut builder = MeshBuilder::new();
+    for i in 0..8 {
+        let f = i as f32;
+        let n = [f.cos(), f.sin(), 0.];
+        builder.push_vertex(
+            MeshVertex::new([f * 0.37 - 1., 3. - f * 0.11, f * f * 0.05])
+                .with_normal(n)
+                .with_tex_coords([f / 7., 1. - f / 9.]),
+        );
+    }
+    builder.push_strip(&[0, 1, 2, 3, 4, 5, 6, 7], 0.into());
+    builder.build(0.into()).unwrap().0
+}
+
+//fi positions
+/// Read the positions of a [Vertices], applying a transformation
+fn positions(v: &Vertices<Renderable>, t: &Transformation) -> Vec<[f32; 3]> {
+    v.borrow_position()
+        .iter_all::<f32, 3>()
+        .unwrap()
+        .map(|p| t.transform_point(&p))
+        .collect()
+}
+
+//fi max_difference
+/// The largest difference between any components of two sets of elements
+fn max_difference<const N: usize>(a: &[[f32; N]], b: &[[f32; N]]) -> f32 {
+    assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b.iter())
+        .flat_map(|(a, b)| a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs()))
+        .fold(0., f32::max)
+}
+
+//ft test_quantize
+#[test]
+fn test_quantize() {
+    let v = vertices();
+    let quantization = Quantization::new()
+        .with_position(BufferElementType::Int16)
+        .with_normal(BufferElementType::Int8)
+        .with_tex_coords(BufferElementType::UInt16)
+        .with_layout(VertexLayout::Planar);
+    let q = v.quantize(&quantization).unwrap();
+
+    let position = q.vertices.borrow_position();
+    assert_eq!(position.ele_type, BufferElementType::Int16);
+    assert!(position.normalized);
+    assert_eq!(position.validate(), Ok(()));
+    let normal = q.vertices.borrow_attr(VertexAttr::Normal).unwrap();
+    assert_eq!(
+        (normal.ele_type, normal.element_stride()),
+        (BufferElementType::Int8, 4)
+    );
+    let uv = q.vertices.borrow_attr(VertexAttr::TexCoords0).unwrap();
+    assert_eq!(uv.ele_type, BufferElementType::UInt16);
+    let indices = |v: &Vertices<Renderable>| -> Vec<u32> {
+        v.borrow_indices()
+            .unwrap()
+            .iter_indices()
+            .unwrap()
+            .collect()
+    };
+    assert_eq!(indices(&v), indices(&q.vertices));
+
+    // The error is at most half a step of the quantized range
+    let original = positions(&v, &Transformation::new());
+    let extent = original
+        .iter()
+        .flat_map(|p| p.iter())
+        .fold(0_f32, |m, c| m.max(c.abs()));
+    let error = q.error(VertexAttr::Position).unwrap();
+    assert!(error > 0. && error < extent / 32767.);
+    let dequantized = positions(&q.vertices, &q.dequantize);
+    assert!(max_difference(&original, &dequantized) <= error * 1.001);
+    assert!(q.error(VertexAttr::Normal).unwrap() <= 0.5 / 127. + 1e-6);
+    assert!(q.error(VertexAttr::TexCoords0).unwrap() <= 0.5 / 65535. + 1e-6);
+    assert_eq!(q.error(VertexAttr::Color), None);
+
+    // Folding the dequantization into a component leaves the result unchanged
+    let t = Transformation::new()
+        .with_translation([1., 2., 3.])
+        .with_scale([2., 3., 4.]);
+    let mut component = Component::new(Some(t), Default::default());
+    q.fold_into(&mut component);
+    let folded = positions(&q.vertices, component.transformation.as_ref().unwrap());
+    assert!(max_difference(&positions(&v, &t), &folded) <= error * 4.01);
+
+    // Dequantizing gives f32 data close to the original
+    let f = q
+        .vertices
+        .dequantize(Some(&q.dequantize), VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(f.borrow_position().ele_type, BufferElementType::Float32);
+    assert!(max_difference(&original, &positions(&f, &Transformation::new())) <= error * 1.001);
+    let uv = f.borrow_attr(VertexAttr::TexCoords0).unwrap();
+    assert_eq!(
+        (uv.ele_type, uv.normalized),
+        (BufferElementType::Float32, false)
+    );
+    assert_eq!(f.borrow_position().validate(), Ok(()));
+}
+
+//ft test_quantize_float16
+#[test]
+fn test_quantize_float16() {
+    let v = vertices();
+    let quantization = Quantization::new()
+        .with_position(BufferElementType::Float16)
+        .with_tex_coords(BufferElementType::Float16);
+    let mut q = v.quantize(&quantization).unwrap();
+    assert_eq!(q.dequantize.translation(), [0.; 3]);
+    assert_eq!(q.dequantize.scale(), [1.; 3]);
+    let position = q.vertices.borrow_position();
+    assert_eq!(position.ele_type, BufferElementType::Float16);
+    assert!(!position.normalized);
+    let error = q.error(VertexAttr::Position).unwrap();
+    assert!(error < 4. / 2048.);
+    let original = positions(&v, &Transformation::new());
+    assert!(max_difference(&original, &positions(&q.vertices, &q.dequantize)) <= error);
+    assert!(q.error(VertexAttr::TexCoords0).unwrap() < 1. / 2048.);
+    assert_eq!(
+        q.vertices.borrow_attr(VertexAttr::Normal).unwrap().ele_type,
+        BufferElementType::Float32
+    );
+}
+
+//ft test_quantize_errors
+#[test]
+fn test_quantize_errors() {
+    let v = vertices();
+    for quantization in [
+        Quantization::new().with_normal(BufferElementType::UInt8),
+        Quantization::new().with_position(BufferElementType::Float32),
+        Quantization::new().with_tex_coords(BufferElementType::UInt32),
+    ] {
+        assert!(matches!(
+            v.quantize(&quantization),
+            Err(AccessorError::Unsupported(..))
+        ));
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BufferAccessor, BufferData, BufferElementType, MeshBuilder, MeshVertex, VertexAttr,
+    VertexLayout, Vertices, VERTEX_ALIGNMENT,
+};
+
+//fi assert_same
+/// Assert that two [Vertices] have the same indices and attribute values
+fn assert_same(a: &Vertices<Renderable>, b: &Vertices<Renderable>) {
+    let indices = |v: &Vertices<Renderable>| -> Vec<u32> {
+        v.borrow_indices()
+            .unwrap()
+            .iter_indices()
+            .unwrap()
+            .collect()
+    };
+    assert_eq!(indices(a), indices(b));
+    assert_eq!(
+        a.borrow_indices().unwrap().ele_type,
+        b.borrow_indices().unwrap().ele_type
+    );
+    let pa = a.borrow_position();
+    let pb = b.borrow_position();
+    assert_eq!(pa.count, pb.count);
+    assert_eq!((&pa.min, &pa.max), (&pb.min, &pb.max));
+    let pa: Vec<_> = pa.iter_all::<f32, 3>().unwrap().collect();
+    let pb: Vec<_> = pb.iter_all::<f32, 3>().unwrap().collect();
+    assert_eq!(pa, pb);
+    let attrs = |v: &Vertices<Renderable>| -> Vec<_> {
+        v.iter_attrs()
+            .map(|(attr, acc)| {
+                let values: Vec<_> = acc.iter_all::<f32, 4>().unwrap().collect();
+                (attr, acc.ele_type, acc.normalized, values)
+            })
+            .collect()
+    };
+    assert_eq!(attrs(a), attrs(b));
+}
+
+//fi check_alignment
+/// Check that all the attributes of a [Vertices] are aligned
+fn check_alignment(v: &Vertices<Renderable>) {
+    let position = v.borrow_position();
+    for acc in std::iter::once(position).chain(v.iter_attrs().map(|(_, a)| a)) {
+        assert_eq!(acc.byte_offset as usize % VERTEX_ALIGNMENT, 0);
+        assert_eq!(acc.element_stride() % VERTEX_ALIGNMENT, 0);
+        assert!(acc.data.ptr_eq(&position.data));
+        assert_eq!(acc.validate(), Ok(()));
+    }
+}
+
+//ft test_repack_builder
+#[test]
+fn test_repack_builder() {
+    let mut builder = MeshBuilder::new().with_index_type(BufferElementType::UInt16);
+    for i in 0..4 {
+        let f = i as f32;
+        builder.push_vertex(
+            MeshVertex::new([f, f * 2., 0.])
+                .with_tex_coords([f, 1. - f])
+                .with_color([f / 4., 0., 0., 1.]),
+        );
+    }
+    builder.push_strip(&[0, 1, 2, 3], 0.into());
+    let (interleaved, _) = builder.build::<Renderable>(0.into()).unwrap();
+
+    let planar = interleaved.repack(VertexLayout::Planar).unwrap();
+    assert_same(&interleaved, &planar);
+    check_alignment(&planar);
+    assert!(!planar
+        .borrow_position()
+        .data
+        .ptr_eq(&interleaved.borrow_position().data));
+    let uv = planar.borrow_attr(VertexAttr::TexCoords0).unwrap();
+    assert_eq!((uv.byte_offset, uv.stride), (4 * 12, 0));
+
+    let again = planar.repack(VertexLayout::Interleaved).unwrap();
+    assert_same(&interleaved, &again);
+    assert_eq!(
+        again.borrow_position().data.as_slice(),
+        interleaved.borrow_position().data.as_slice()
+    );
+}
+
+//fi rgb_triangle
+/// Create a triangle with three positions followed by three RGB
+/// colors (of 3 bytes each) at a byte offset in the same buffer
+fn rgb_triangle(color_offset: u32) -> Vertices<'static, Renderable> {
+    let mut bytes: Vec<u8> = [0_f32, 0., 0., 1., 0., 0., 0., 1., 0.]
+        .iter()
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    bytes.extend([255, 0, 0, 0, 255, 0, 0, 0, 255]);
+    let data = Arc::new(BufferData::of_bytes(bytes, 0, 0));
+    let indices = Arc::new(BufferData::of_bytes(vec![0_u8, 1, 2], 0, 0));
+    let indices = BufferAccessor::new(indices, 3, BufferElementType::UInt8, 0, 0);
+    let position =
+        BufferAccessor::new(data.clone(), 3, BufferElementType::Float32, 0, 0).with_count(3);
+    let color = BufferAccessor::new(data, 3, BufferElementType::UInt8, color_offset, 0)
+        .with_normalized(true)
+        .with_count(3);
+    let mut vertices = Vertices::new(Arc::new(indices), Arc::new(position));
+    vertices.add_attr(VertexAttr::Color, Arc::new(color));
+    vertices
+}
+
+//ft test_repack_padding
+#[test]
+fn test_repack_padding() {
+    let vertices = rgb_triangle(36);
+    for (layout, stride) in [(VertexLayout::Interleaved, 16), (VertexLayout::Planar, 4)] {
+        let repacked = vertices.repack(layout).unwrap();
+        assert_same(&vertices, &repacked);
+        check_alignment(&repacked);
+        let color = repacked.borrow_attr(VertexAttr::Color).unwrap();
+        assert_eq!(color.element_stride(), stride, "{layout:?}");
+        assert_eq!(color.read_element::<f32, 3>(2), Ok([0., 0., 1.]));
+    }
+
+    // Colors that extend beyond the buffer data cannot be repacked
+    assert!(rgb_triangle(40).repack(VertexLayout::Planar).is_err());
+}
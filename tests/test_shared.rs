@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    BaseMaterial, BufferAccessor, BufferData, BufferElementType, ByteData, Object, Shared, Texture,
+    VertexAttr, Vertices,
+};
+
+//fi owned_triangle
+/// Create a triangle object that owns all of its data, from data
+/// created at run time
+fn owned_triangle(scale: f32) -> Object<'static, BaseMaterial, Renderable> {
+    let indices: Arc<[u8]> = Arc::from(&[0_u8, 1, 2][..]);
+    let floats: Vec<u8> = [0., 0., 0., 1., 0., 0., 0., 1., 0.]
+        .iter()
+        .flat_map(|f: &f32| (f * scale).to_le_bytes())
+        .collect();
+
+    let index_data = Arc::new(BufferData::of_bytes(indices, 0, 0));
+    let vertex_data = Arc::new(BufferData::of_bytes(floats, 0, 0));
+    let indices = BufferAccessor::new(index_data, 3, BufferElementType::UInt8, 0, 0);
+    let position = Arc::new(BufferAccessor::new(
+        vertex_data,
+        3,
+        BufferElementType::Float32,
+        0,
+        0,
+    ));
+    let mut vertices = Vertices::new(Arc::new(indices), position.clone());
+    vertices.add_attr(VertexAttr::Normal, position);
+    let texture = Texture::of_bytes(vec![255_u8; 4], (1, 0, 0), BufferElementType::UInt8, 4);
+
+    let mut obj = Object::new();
+    let v_id = obj.add_vertices(Arc::new(vertices));
+    obj.add_texture(Arc::new(texture));
+    let m_id = obj.add_material(Shared::new(BaseMaterial::of_rgba(0xff0000ff)));
+    obj.add_component(
+        None,
+        None,
+        mod3d_base::example_objects::triangle::mesh(v_id, m_id),
+    );
+    obj
+}
+
+//fi assert_send
+fn assert_send<T: Send + 'static>(_: &T) {}
+
+//ft test_owned_object
+#[test]
+fn test_owned_object() {
+    let mut obj = owned_triangle(2.0);
+    assert_send(&obj);
+
+    // The object can be moved to (and back from) another thread
+    let obj_thread = std::thread::spawn(move || {
+        obj.analyze();
+        obj
+    });
+    obj = obj_thread.join().unwrap();
+
+    let v = obj.vertices(0.into());
+    assert!(obj.vertices[0].is_owned());
+    assert!(v.borrow_position().data.is_owned());
+    assert!(
+        v.borrow_position()
+            .data
+            .ptr_eq(&v.borrow_attr(VertexAttr::Normal).unwrap().data),
+        "Accessors share their buffer data"
+    );
+    assert_eq!(
+        v.borrow_indices().unwrap().iter_indices().unwrap().max(),
+        Some(2)
+    );
+    assert_eq!(
+        v.borrow_position().read_element::<f32, 3>(1),
+        Ok([2., 0., 0.])
+    );
+    assert_eq!(obj.texture(0.into()).data(), &[255; 4]);
+
+    let inst = obj
+        .into_instantiable(&mut Default::default())
+        .map_err(|(_, e)| e)
+        .expect("Failed to make the object instantiable");
+    assert_eq!(inst.render_recipe.primitives.len(), 1);
+}
+
+//ft test_byte_data
+#[test]
+fn test_byte_data() {
+    let bytes = [1_u8, 2, 3, 4, 5, 6];
+    let borrowed = ByteData::from(&bytes[..]);
+    assert!(!borrowed.is_owned());
+    assert_eq!(&borrowed.slice(2..4)[..], &[3, 4]);
+
+    let owned = ByteData::owned(bytes.to_vec());
+    let slice = owned.slice(1..5);
+    assert!(slice.is_owned());
+    assert_eq!(&slice[..], &[2, 3, 4, 5]);
+    assert_eq!(&slice.slice(1..3)[..], &[3, 4]);
+    drop(owned);
+    assert_eq!(&slice[..], &[2, 3, 4, 5], "Slices share the data");
+
+    let data = BufferData::<Renderable>::of_bytes(slice, 1, 2);
+    assert_eq!(data.as_slice(), &[3, 4]);
+}
+
+//ft test_description_into_object
+#[cfg(feature = "serde")]
+#[test]
+fn test_description_into_object() {
+    let obj = owned_triangle(1.0);
+    let description = mod3d_base::ObjectDescription::of_object(&obj);
+    drop(obj);
+    assert_eq!(description.buffers.len(), 3);
+
+    let obj: Object<'static, BaseMaterial, Renderable> = description.clone().into_object();
+    assert_send(&obj);
+    let v = obj.vertices(0.into());
+    assert_eq!(v.borrow_position().count, 3);
+    assert_eq!(
+        v.borrow_position().read_element::<f32, 3>(2),
+        Ok([0., 1., 0.])
+    );
+    assert_eq!(obj.texture(0.into()).data(), &description.buffers[2][..]);
+    assert_eq!(
+        mod3d_base::ObjectDescription::of_object(&obj).accessors,
+        description.accessors
+    );
+}
@@ -0,0 +1,236 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, MeshBuilder, MeshVertex, Primitive, PrimitiveType, Simplification, VertexAttr,
+    Vertices,
+};
+
+//fi grid
+/// Build a grid of n by n squares in the unit square of the xy plane,
+/// with a height for each position
+///
+/// If `seam` is true then the vertices at x = 0.5 are repeated, for
+/// the squares on the right, with different texture coordinates
+fn grid(
+    n: usize,
+    seam: bool,
+    height: impl Fn(f32, f32) -> f32,
+) -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    let mut vertex = vec![vec![(0, 0); n + 1]; n + 1];
+    for (y, row) in vertex.iter_mut().enumerate() {
+        for (x, v) in row.iter_mut().enumerate() {
+            let (px, py) = (x as f32 / n as f32, y as f32 / n as f32);
+            let p = [px, py, height(px, py)];
+            let left = builder.push_vertex(MeshVertex::new(p).with_tex_coords([px, py]));
+            let right = if seam && x * 2 == n {
+                builder.push_vertex(MeshVertex::new(p).with_tex_coords([px + 1., py]))
+            } else {
+                left
+            };
+            *v = (left, right);
+        }
+    }
+    let mut indices = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let v = |dx: usize, dy: usize| {
+                let (left, right) = vertex[y + dy][x + dx];
+                if x * 2 < n {
+                    left
+                } else {
+                    right
+                }
+            };
+            indices.extend([v(0, 0), v(1, 0), v(1, 1), v(0, 0), v(1, 1), v(0, 1)]);
+        }
+    }
+    builder.push_triangles(&indices, 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi positions
+fn positions(v: &Vertices<Renderable>) -> Vec<[f32; 3]> {
+    let position = v.borrow_position();
+    position.iter_all().unwrap().collect()
+}
+
+//fi area
+/// The total area of triangles, projected on to the xy plane
+fn area(positions: &[[f32; 3]], indices: &[u32]) -> f32 {
+    indices
+        .chunks_exact(3)
+        .map(|t| {
+            let [a, b, c] = [0, 1, 2].map(|i| positions[t[i] as usize]);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.
+        })
+        .sum()
+}
+
+//ft test_simplify_plane
+#[test]
+fn test_simplify_plane() {
+    let (v, primitives) = grid(8, false, |_, _| 0.);
+    let positions = positions(&v);
+    let simplification = Simplification::new().with_target_error(1e-4);
+    let simplified = v.simplify(&primitives[0], &simplification).unwrap();
+    assert_eq!(simplified.indices.len(), 6);
+    assert!(simplified.error < 1e-4);
+    // The corners remain, and all the triangles face the same way
+    assert!((area(&positions, &simplified.indices) - 1.).abs() < 1e-5);
+    let mut corners: Vec<[f32; 3]> = simplified
+        .indices
+        .iter()
+        .map(|i| positions[*i as usize])
+        .collect();
+    corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    corners.dedup();
+    assert_eq!(
+        corners,
+        vec![[0., 0., 0.], [0., 1., 0.], [1., 0., 0.], [1., 1., 0.]]
+    );
+
+    // With a target index count
+    let simplification = simplification.with_target_index_count(200);
+    let simplified = v.simplify(&primitives[0], &simplification).unwrap();
+    assert!(simplified.indices.len() <= 200);
+    assert!(simplified.indices.len() > 100);
+    assert!((area(&positions, &simplified.indices) - 1.).abs() < 1e-5);
+}
+
+//ft test_simplify_lock_border
+#[test]
+fn test_simplify_lock_border() {
+    let (v, primitives) = grid(8, false, |_, _| 0.);
+    let positions = positions(&v);
+    let simplification = Simplification::new()
+        .with_target_error(1e-4)
+        .with_lock_border(true);
+    let simplified = v.simplify(&primitives[0], &simplification).unwrap();
+    // The 32 border vertices remain, in 30 triangles
+    assert_eq!(simplified.indices.len(), 90);
+    assert!((area(&positions, &simplified.indices) - 1.).abs() < 1e-5);
+    for (i, p) in positions.iter().enumerate() {
+        if p[0..2].iter().any(|c| *c == 0. || *c == 1.) {
+            assert!(simplified.indices.contains(&(i as u32)), "{p:?}");
+        }
+    }
+}
+
+//ft test_simplify_seam
+#[test]
+fn test_simplify_seam() {
+    let (v, primitives) = grid(8, true, |_, _| 0.);
+    let positions = positions(&v);
+    let tex_coords: Vec<[f32; 2]> = v
+        .borrow_attr(VertexAttr::TexCoords0)
+        .unwrap()
+        .iter_all()
+        .unwrap()
+        .collect();
+    let simplification = Simplification::new().with_target_error(1e-4);
+    let simplified = v.simplify(&primitives[0], &simplification).unwrap();
+    // The corners and the ends of the seam remain
+    assert_eq!(simplified.indices.len(), 12);
+    assert!((area(&positions, &simplified.indices) - 1.).abs() < 1e-5);
+    // Each triangle remains on one side of the seam, using the
+    // vertices with the texture coordinates for that side
+    for t in simplified.indices.chunks_exact(3) {
+        let left = t.iter().all(|i| tex_coords[*i as usize][0] <= 0.5);
+        let right = t.iter().all(|i| tex_coords[*i as usize][0] > 0.5);
+        assert!(left || right, "{t:?}");
+    }
+}
+
+//ft test_simplify_error
+#[test]
+fn test_simplify_error() {
+    // A bump, which cannot be simplified without error
+    let (v, primitives) = grid(16, false, |x, y| {
+        let (dx, dy) = (x - 0.5, y - 0.5);
+        (-(dx * dx + dy * dy) * 8.).exp() * 0.5
+    });
+    let count = primitives[0].index_count() as usize;
+    let none = v
+        .simplify(&primitives[0], &Simplification::new().with_target_error(0.))
+        .unwrap();
+    assert!(none.indices.len() < count);
+    assert!(none.error < 1e-6);
+
+    let mut last = (count, 0.);
+    for target_error in [1e-3, 1e-2, 1e-1] {
+        let simplification = Simplification::new().with_target_error(target_error);
+        let simplified = v.simplify(&primitives[0], &simplification).unwrap();
+        assert!(simplified.error <= target_error);
+        assert!(simplified.indices.len() <= last.0);
+        assert!(simplified.error >= last.1);
+        last = (simplified.indices.len(), simplified.error);
+    }
+    assert!(last.0 < count / 10, "{}", last.0);
+}
+
+//ft test_simplify_lod_chain
+#[test]
+fn test_simplify_lod_chain() {
+    let (v, primitives) = grid(16, false, |x, y| (x * 6.).sin() * (y * 6.).cos() * 0.1);
+    let count = primitives[0].index_count();
+    let chain = v
+        .lod_chain(&primitives[0], &Simplification::new(), 0.5, 4)
+        .unwrap();
+    assert_eq!(chain.lods.len(), 4);
+    assert_eq!(
+        chain.vertices.borrow_position().count,
+        v.borrow_position().count
+    );
+    assert_eq!(
+        chain.vertices.primitive_indices(&chain.lods[0].primitive),
+        v.primitive_indices(&primitives[0])
+    );
+    assert_eq!(chain.lods[0].error, 0.);
+    let mut last = (count * 2, 0.);
+    for lod in &chain.lods {
+        let p = &lod.primitive;
+        assert_eq!(p.primitive_type(), PrimitiveType::Triangles);
+        assert!(
+            p.index_count() <= last.0 / 2,
+            "{} {}",
+            p.index_count(),
+            last.0
+        );
+        assert!(lod.error >= last.1);
+        last = (p.index_count(), lod.error);
+    }
+
+    // The chain stops when the error budget is reached
+    let simplification = Simplification::new()
+        .with_target_error(1e-3)
+        .with_target_index_count(100);
+    let chain = v
+        .lod_chain(&primitives[0], &simplification, 0.5, 10)
+        .unwrap();
+    assert!(chain.lods.len() < 10);
+    assert!(chain.lods.iter().all(|lod| lod.error <= 1e-3));
+    assert!(chain
+        .lods
+        .iter()
+        .all(|lod| lod.primitive.index_count() >= 100));
+}
+
+//ft test_simplify_primitives
+#[test]
+fn test_simplify_primitives() {
+    let (v, _) = grid(2, false, |_, _| 0.);
+    // Lines are not simplified
+    let lines = Primitive::of_vertex_range(PrimitiveType::Lines, 0.into(), 0, 4, 0.into());
+    let simplified = v.simplify(&lines, &Simplification::new()).unwrap();
+    assert_eq!(simplified.indices, vec![0, 1, 2, 3]);
+    let chain = v.lod_chain(&lines, &Simplification::new(), 0.5, 4).unwrap();
+    assert_eq!(chain.lods.len(), 1);
+
+    // Indices beyond the vertices are errors
+    let bad = Primitive::of_vertex_range(PrimitiveType::Triangles, 0.into(), 6, 6, 0.into());
+    assert_eq!(
+        v.simplify(&bad, &Simplification::new()).err(),
+        Some(AccessorError::BadIndex(9, 9))
+    );
+}
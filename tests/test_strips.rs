@@ -0,0 +1,155 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{list_indices, strip_indices, MeshBuilder, MeshVertex, Primitive, PrimitiveType};
+
+//fi canonical
+/// Get the sorted triangles of a triangle list, each rotated to start
+/// with its smallest index (so keeping its winding)
+fn canonical(indices: &[u32]) -> Vec<[u32; 3]> {
+    let mut triangles: Vec<[u32; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| {
+            let mut t = [t[0], t[1], t[2]];
+            let first = (0..3).min_by_key(|i| t[*i]).unwrap();
+            t.rotate_left(first);
+            t
+        })
+        .collect();
+    triangles.sort();
+    triangles
+}
+
+//fi grid_triangles
+/// Create the triangles of a grid of `n` by `n` squares, two triangles
+/// each, with a degenerate triangle at the end
+fn grid_triangles(n: u32) -> Vec<u32> {
+    let w = n + 1;
+    let mut indices = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let v = |dx: u32, dy: u32| (y + dy) * w + x + dx;
+            indices.extend([v(0, 0), v(1, 0), v(1, 1)]);
+            indices.extend([v(0, 0), v(1, 1), v(0, 1)]);
+        }
+    }
+    indices.extend([0, 1, 1]);
+    indices
+}
+
+//ft test_list_indices
+#[test]
+fn test_list_indices() {
+    use PrimitiveType::*;
+    // Odd triangles of a strip are flipped to keep the winding
+    assert_eq!(
+        list_indices(TriangleStrip, &[0, 1, 2, 3, 4]),
+        vec![0, 1, 2, 2, 1, 3, 2, 3, 4]
+    );
+    // Degenerate triangles (joining strips) are dropped
+    assert_eq!(
+        list_indices(TriangleStrip, &[0, 1, 2, 2, 2, 5, 5, 6, 7]),
+        vec![0, 1, 2, 5, 6, 7]
+    );
+    assert_eq!(
+        list_indices(TriangleFan, &[0, 1, 2, 3, 3, 4]),
+        vec![0, 1, 2, 0, 2, 3, 0, 3, 4]
+    );
+    assert_eq!(
+        list_indices(Triangles, &[0, 1, 2, 3, 3, 4, 5]),
+        vec![0, 1, 2]
+    );
+    assert_eq!(list_indices(LineStrip, &[0, 1, 1, 2]), vec![0, 1, 1, 2]);
+    assert_eq!(list_indices(LineLoop, &[0, 1, 2]), vec![0, 1, 1, 2, 2, 0]);
+    assert_eq!(list_indices(LineLoop, &[0]), Vec::<u32>::new());
+    assert_eq!(list_indices(Lines, &[0, 1, 2, 2, 3]), vec![0, 1]);
+    assert_eq!(list_indices(Points, &[3, 3, 1]), vec![3, 3, 1]);
+    assert_eq!(list_indices(TriangleStrip, &[0, 1]), Vec::<u32>::new());
+}
+
+//ft test_strip_indices
+#[test]
+fn test_strip_indices() {
+    // A single quad is a single strip
+    let strip = strip_indices(&[0, 1, 2, 0, 2, 3]);
+    assert_eq!(strip.len(), 4);
+    assert_eq!(
+        canonical(&list_indices(PrimitiveType::TriangleStrip, &strip)),
+        canonical(&[0, 1, 2, 0, 2, 3])
+    );
+
+    // A grid keeps its triangles and their windings, in fewer indices
+    let triangles = grid_triangles(8);
+    let strip = strip_indices(&triangles);
+    let listed = list_indices(PrimitiveType::TriangleStrip, &strip);
+    assert_eq!(
+        canonical(&listed),
+        canonical(&triangles[0..triangles.len() - 3])
+    );
+    assert!(strip.len() < triangles.len() * 2 / 3, "{}", strip.len());
+
+    // Disjoint triangles are joined by degenerate triangles
+    let triangles = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+    let strip = strip_indices(&triangles);
+    assert_eq!(
+        canonical(&list_indices(PrimitiveType::TriangleStrip, &strip)),
+        canonical(&triangles)
+    );
+    assert_eq!(strip_indices(&[]), Vec::<u32>::new());
+}
+
+//ft test_vertices_lists_strips
+#[test]
+fn test_vertices_lists_strips() {
+    let mut builder = MeshBuilder::new();
+    for i in 0..9 {
+        builder.push_vertex(MeshVertex::new([(i % 3) as f32, (i / 3) as f32, 0.]));
+    }
+    builder.push_strip(&[0, 3, 1, 4, 2, 5], 0.into());
+    builder.push_primitive(PrimitiveType::TriangleFan, &[4, 5, 8, 7, 6], 1.into());
+    builder.push_primitive(PrimitiveType::LineLoop, &[0, 2, 8], 0.into());
+    let (v, mut mesh) = builder.build::<Renderable>(0.into()).unwrap();
+    mesh.add_primitive(Primitive::of_vertex_range(
+        PrimitiveType::TriangleStrip,
+        0.into(),
+        3,
+        4,
+        0.into(),
+    ));
+
+    let (lists, primitives) = v.to_lists(&mesh.primitives).unwrap();
+    let types: Vec<PrimitiveType> = primitives.iter().map(|p| p.primitive_type()).collect();
+    assert_eq!(
+        types,
+        vec![
+            PrimitiveType::Triangles,
+            PrimitiveType::Triangles,
+            PrimitiveType::Lines,
+            PrimitiveType::Triangles
+        ]
+    );
+    assert!(primitives.iter().all(|p| p.is_indexed()));
+    assert_eq!(
+        primitives[1].material_index(),
+        mesh.primitives[1].material_index()
+    );
+    let indices: Vec<Vec<u32>> = primitives
+        .iter()
+        .map(|p| lists.primitive_indices(p).unwrap())
+        .collect();
+    assert_eq!(indices[0], vec![0, 3, 1, 1, 3, 4, 1, 4, 2, 2, 4, 5]);
+    assert_eq!(indices[1], vec![4, 5, 8, 4, 8, 7, 4, 7, 6]);
+    assert_eq!(indices[2], vec![0, 2, 2, 8, 8, 0]);
+    assert_eq!(indices[3], vec![3, 4, 5, 5, 4, 6]);
+    assert_eq!(lists.borrow_position().count, v.borrow_position().count);
+
+    // Converting the lists back to strips keeps the triangles
+    let (strips, stripped) = lists.to_strips(&primitives).unwrap();
+    assert_eq!(stripped[0].primitive_type(), PrimitiveType::TriangleStrip);
+    assert_eq!(stripped[2].primitive_type(), PrimitiveType::Lines);
+    for (p, s) in primitives.iter().zip(stripped.iter()) {
+        let s = list_indices(s.primitive_type(), &strips.primitive_indices(s).unwrap());
+        assert_eq!(
+            canonical(&lists.primitive_indices(p).unwrap()),
+            canonical(&s)
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, MeshBuilder, MeshVertex, Primitive, VertexAttr, VertexLayout, Vertices,
+};
+
+//fi build
+/// Build a mesh of triangles from vertices of position, normal and
+/// texture coordinates
+fn build(
+    vertices: &[([f32; 3], [f32; 3], [f32; 2])],
+    indices: &[u32],
+) -> (Vertices<'static, Renderable>, Vec<Primitive>) {
+    let mut builder = MeshBuilder::new();
+    for (p, n, uv) in vertices {
+        builder.push_vertex(MeshVertex::new(*p).with_normal(*n).with_tex_coords(*uv));
+    }
+    builder.push_triangles(indices, 0.into());
+    let (v, mesh) = builder.build(0.into()).unwrap();
+    (v, mesh.primitives)
+}
+
+//fi corner_tangents
+/// Get the tangents of the corners of a primitive
+fn corner_tangents(v: &Vertices<Renderable>, p: &Primitive) -> Vec<[f32; 4]> {
+    let tangent = v.borrow_attr(VertexAttr::Tangent).unwrap();
+    v.primitive_indices(p)
+        .unwrap()
+        .into_iter()
+        .map(|i| tangent.read_element(i as usize).unwrap())
+        .collect()
+}
+
+//fi assert_near
+fn assert_near(a: [f32; 4], b: [f32; 4]) {
+    assert!((0..4).all(|i| (a[i] - b[i]).abs() < 1e-6), "{a:?} {b:?}");
+}
+
+const Z: [f32; 3] = [0., 0., 1.];
+
+//ft test_tangents_plane
+#[test]
+fn test_tangents_plane() {
+    // A unit square with texture coordinates of its position
+    let (v, primitives) = build(
+        &[
+            ([0., 0., 0.], Z, [0., 0.]),
+            ([1., 0., 0.], Z, [1., 0.]),
+            ([1., 1., 0.], Z, [1., 1.]),
+            ([0., 1., 0.], Z, [0., 1.]),
+        ],
+        &[0, 1, 2, 0, 2, 3],
+    );
+    let (t, p) = v
+        .generate_tangents(&primitives, VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(format!("{p:?}"), format!("{primitives:?}"));
+    assert_eq!(t.borrow_position().count, 4);
+    for tangent in corner_tangents(&t, &p[0]) {
+        assert_eq!(tangent, [1., 0., 0., 1.]);
+    }
+
+    // Texture coordinates skewed relative to the positions
+    let (v, primitives) = build(
+        &[
+            ([0., 0., 0.], Z, [0., 0.]),
+            ([1., 0., 0.], Z, [1., 1.]),
+            ([0., 1., 0.], Z, [0., 1.]),
+        ],
+        &[0, 1, 2],
+    );
+    let (t, p) = v
+        .generate_tangents(&primitives, VertexLayout::Planar)
+        .unwrap();
+    let k = 1. / 2_f32.sqrt();
+    for tangent in corner_tangents(&t, &p[0]) {
+        assert_near(tangent, [k, -k, 0., 1.]);
+    }
+}
+
+//ft test_tangents_mirrored
+#[test]
+fn test_tangents_mirrored() {
+    // Two squares side by side, with the texture mirrored about the
+    // edge they share; its vertices are split
+    let mut vertices = vec![];
+    for y in 0..2 {
+        for x in 0..3 {
+            let (x, y) = (x as f32, y as f32);
+            vertices.push(([x, y, 0.], Z, [(x - 1.).abs(), y]));
+        }
+    }
+    let (v, primitives) = build(&vertices, &[0, 1, 4, 0, 4, 3, 1, 2, 5, 1, 5, 4]);
+    let (t, p) = v
+        .generate_tangents(&primitives, VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(t.borrow_position().count, 8);
+    let indices = t.primitive_indices(&p[0]).unwrap();
+    assert_eq!(indices, vec![0, 1, 4, 0, 4, 3, 6, 2, 5, 6, 5, 7]);
+    for (n, tangent) in corner_tangents(&t, &p[0]).into_iter().enumerate() {
+        if n >= 6 {
+            assert_eq!(tangent, [1., 0., 0., 1.]);
+        } else {
+            assert_eq!(tangent, [-1., 0., 0., -1.]);
+        }
+    }
+}
+
+//ft test_tangents_smooth
+#[test]
+fn test_tangents_smooth() {
+    // A ridge along the y axis, with the texture coordinates of the x
+    // and y of the position; the tangents at the ridge (with a normal
+    // of +z) are the average of the faces' tangents projected
+    let k = 1. / 2_f32.sqrt();
+    let (na, nb) = ([-k, 0., k], [k, 0., k]);
+    let (v, primitives) = build(
+        &[
+            ([-1., 0., 0.], na, [-1., 0.]),
+            ([-1., 1., 0.], na, [-1., 1.]),
+            ([0., 0., 1.], Z, [0., 0.]),
+            ([0., 1., 1.], Z, [0., 1.]),
+            ([1., 0., 0.], nb, [1., 0.]),
+            ([1., 1., 0.], nb, [1., 1.]),
+        ],
+        &[0, 2, 3, 0, 3, 1, 2, 4, 5, 2, 5, 3],
+    );
+    let (t, p) = v
+        .generate_tangents(&primitives, VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(t.borrow_position().count, 6);
+    let tangent = t.borrow_attr(VertexAttr::Tangent).unwrap();
+    let tangents: Vec<[f32; 4]> = tangent.iter_all().unwrap().collect();
+    assert_near(tangents[0], [k, 0., k, 1.]);
+    assert_near(tangents[1], [k, 0., k, 1.]);
+    assert_near(tangents[2], [1., 0., 0., 1.]);
+    assert_near(tangents[3], [1., 0., 0., 1.]);
+    assert_near(tangents[4], [k, 0., -k, 1.]);
+    assert_eq!(p[0].index_count(), 12);
+}
+
+//ft test_tangents_degenerate
+#[test]
+fn test_tangents_degenerate() {
+    // The second triangle has degenerate texture coordinates, so takes
+    // the tangents of the first at the vertices they share; its other
+    // vertex has the default tangent space
+    let (v, primitives) = build(
+        &[
+            ([0., 0., 0.], Z, [0., 0.]),
+            ([1., 0., 0.], Z, [1., 0.]),
+            ([1., 1., 0.], Z, [1., 1.]),
+            ([0., 1., 0.], Z, [1., 1.]),
+        ],
+        &[0, 1, 2, 0, 2, 3],
+    );
+    let (t, p) = v
+        .generate_tangents(&primitives, VertexLayout::Interleaved)
+        .unwrap();
+    assert_eq!(t.borrow_position().count, 4);
+    let tangents = corner_tangents(&t, &p[0]);
+    assert_eq!(tangents[0..5], [[1., 0., 0., 1.]; 5]);
+    assert_eq!(tangents[5], [1., 0., 0., -1.]);
+
+    // Normals and texture coordinates are required
+    let mut builder = MeshBuilder::new();
+    for p in [[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]] {
+        builder.push_vertex(MeshVertex::new(p).with_tex_coords([0., 0.]));
+    }
+    builder.push_triangles(&[0, 1, 2], 0.into());
+    let (v, mesh) = builder.build::<Renderable>(0.into()).unwrap();
+    assert_eq!(
+        v.generate_tangents(&mesh.primitives, VertexLayout::Planar)
+            .err(),
+        Some(AccessorError::MissingAttr(VertexAttr::Normal))
+    );
+}
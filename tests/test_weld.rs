@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use mod3d_base::example_client::Renderable;
+use mod3d_base::{
+    AccessorError, BufferAccessor, BufferData, BufferElementType, MeshBuilder, MeshVertex,
+    Primitive, PrimitiveType, VertexAttr, VertexLayout, Vertices, Welding,
+};
+
+//fi corners
+/// Read the positions of the vertices used by the indices of a [Vertices]
+fn corners(v: &Vertices<Renderable>) -> Vec<[f32; 3]> {
+    let position = v.borrow_position();
+    v.borrow_indices()
+        .unwrap()
+        .iter_indices()
+        .unwrap()
+        .map(|i| position.read_element(i as usize).unwrap())
+        .collect()
+}
+
+//fi quad_soup
+/// Create a non-indexed unit square of two triangles, with every
+/// vertex repeated for each triangle that uses it
+fn quad_soup() -> Vertices<'static, Renderable> {
+    let corners = [[0_f32, 0., 0.], [1., 0., 0.], [1., 1., 0.], [0., 1., 0.]];
+    let floats: Vec<u8> = [0, 1, 2, 0, 2, 3]
+        .iter()
+        .flat_map(|i: &usize| corners[*i])
+        .flat_map(|f| f.to_le_bytes())
+        .collect();
+    let data = Arc::new(BufferData::of_bytes(floats, 0, 0));
+    let position = BufferAccessor::new(data, 3, BufferElementType::Float32, 0, 0);
+    Vertices::non_indexed(Arc::new(position))
+}
+
+//ft test_weld_exact
+#[test]
+fn test_weld_exact() {
+    let soup = quad_soup();
+    let welded = soup.weld(&Welding::new()).unwrap();
+    assert_eq!(welded.original_count(), 6);
+    assert_eq!(welded.welded_count(), 4);
+    assert_eq!(welded.reduction_ratio(), 1.5);
+    assert_eq!(welded.remap, vec![0, 1, 2, 0, 2, 3]);
+
+    let v = &welded.vertices;
+    let indices = v.borrow_indices().unwrap();
+    assert_eq!(indices.ele_type, BufferElementType::UInt16);
+    let original: Vec<[f32; 3]> = soup.borrow_position().iter_all().unwrap().collect();
+    assert_eq!(corners(v), original);
+
+    let p = Primitive::of_vertex_range(PrimitiveType::Triangles, 0.into(), 3, 3, 0.into());
+    let p = welded.reindex_primitive(&p);
+    assert!(p.is_indexed());
+    assert_eq!((p.byte_offset(), p.index_count()), (6, 3));
+
+    // Vertices differing in any attribute (or in the sign of a zero)
+    // are not merged by exact welding; indices keep their type
+    let mut builder = MeshBuilder::new().with_index_type(BufferElementType::UInt8);
+    builder.push_vertex(MeshVertex::new([0., 0., 0.]).with_normal([0., 0., 1.]));
+    builder.push_vertex(MeshVertex::new([0., 0., 0.]).with_normal([0., 1., 0.]));
+    builder.push_vertex(MeshVertex::new([-0., 0., 0.]).with_normal([0., 0., 1.]));
+    builder.push_vertex(MeshVertex::new([0., 0., 0.]).with_normal([0., 1., 0.]));
+    builder.push_triangles(&[0, 1, 2, 3, 2, 1], 0.into());
+    let (v, _) = builder.build::<Renderable>(0.into()).unwrap();
+    let welded = v
+        .weld(&Welding::new().with_layout(VertexLayout::Planar))
+        .unwrap();
+    assert_eq!(welded.remap, vec![0, 1, 2, 1]);
+    let indices = welded.vertices.borrow_indices().unwrap();
+    assert_eq!(indices.ele_type, BufferElementType::UInt8);
+    let indices: Vec<u32> = indices.iter_indices().unwrap().collect();
+    assert_eq!(indices, vec![0, 1, 2, 1, 2, 1]);
+    let normal = welded.vertices.borrow_attr(VertexAttr::Normal).unwrap();
+    assert_eq!(normal.read_element::<f32, 3>(1), Ok([0., 1., 0.]));
+    assert_eq!(normal.stride, 0);
+}
+
+//ft test_weld_tolerance
+#[test]
+fn test_weld_tolerance() {
+    let mut builder = MeshBuilder::new();
+    let vertices = [
+        ([0., 0., 0.], [0., 0., 1.], [0., 0.]),
+        ([1e-5, 0., -1e-5], [0., 0.01, 1.], [0., 0.]),
+        ([1., 0., 0.], [0., 0., 1.], [1., 0.]),
+        ([1., 1e-5, 0.], [0., 0., 1.], [0.5, 0.]),
+        ([1., 0., 0.], [0., 1., 0.], [1., 0.]),
+        ([-1e-5, 1e-5, 0.], [0., 0., 1.], [0., 0.]),
+    ];
+    for (p, n, uv) in vertices {
+        builder.push_vertex(MeshVertex::new(p).with_normal(n).with_tex_coords(uv));
+    }
+    builder.push_triangles(&[0, 2, 4, 1, 3, 5], 0.into());
+    let (v, _) = builder.build::<Renderable>(0.into()).unwrap();
+
+    // Exact welding finds no duplicates
+    let welded = v.weld(&Welding::new()).unwrap();
+    assert_eq!(welded.welded_count(), 6);
+    assert_eq!(welded.reduction_ratio(), 1.);
+
+    // Vertex 3 differs in texture coordinates, and vertex 4 in its
+    // normal by more than the tolerance
+    let welding = Welding::new()
+        .with_tolerance(VertexAttr::Position, 1e-4)
+        .with_tolerance(VertexAttr::Normal, 0.02);
+    let welded = v.weld(&welding).unwrap();
+    assert_eq!(welded.remap, vec![0, 0, 1, 2, 3, 0]);
+    assert_eq!(welded.reduction_ratio(), 1.5);
+    let position = welded.vertices.borrow_position();
+    assert_eq!(position.count, 4);
+    assert_eq!(position.read_element::<f32, 3>(2), Ok([1., 1e-5, 0.]));
+    let indices: Vec<u32> = welded
+        .vertices
+        .borrow_indices()
+        .unwrap()
+        .iter_indices()
+        .unwrap()
+        .collect();
+    assert_eq!(indices, vec![0, 1, 3, 0, 2, 0]);
+
+    // With a tolerance for texture coordinates vertex 3 is merged too
+    let welded = v
+        .weld(&welding.with_tolerance(VertexAttr::TexCoords0, 0.5))
+        .unwrap();
+    assert_eq!(welded.remap, vec![0, 0, 1, 1, 2, 0]);
+
+    // Huge and infinite positions fall in the last cells, without
+    // overflowing the search of the neighbouring cells
+    let mut builder = MeshBuilder::new();
+    for p in [
+        [f32::MAX, f32::MAX, f32::MAX],
+        [f32::INFINITY, f32::NEG_INFINITY, 0.],
+        [f32::MAX, f32::MAX, f32::MAX],
+    ] {
+        builder.push_vertex(MeshVertex::new(p));
+    }
+    builder.push_triangles(&[0, 1, 2], 0.into());
+    let (v, _) = builder.build::<Renderable>(0.into()).unwrap();
+    let welded = v
+        .weld(&Welding::new().with_tolerance(VertexAttr::Position, 1e-4))
+        .unwrap();
+    assert_eq!(welded.remap, vec![0, 1, 0]);
+}
+
+//ft test_weld_errors
+#[test]
+fn test_weld_errors() {
+    let data = Arc::new(BufferData::of_bytes(vec![0_u8, 1, 3], 0, 0));
+    let indices = BufferAccessor::new(data, 3, BufferElementType::UInt8, 0, 0);
+    let position = quad_soup().borrow_position().data.clone();
+    let position = BufferAccessor::new(position, 3, BufferElementType::Float32, 0, 0).with_count(3);
+    let v = Vertices::new(Arc::new(indices), Arc::new(position));
+    assert_eq!(
+        v.weld(&Welding::new()).err(),
+        Some(AccessorError::BadIndex(3, 3))
+    );
+}